@@ -8,31 +8,71 @@ use tracing_subscriber::FmtSubscriber;
 
 use zcad_core::entity::{Entity, EntityId};
 use zcad_core::geometry::{Arc, Circle, Geometry, Line, Point, Polyline};
-use zcad_core::history::{HistoryTree, OperationType, operations as hist_ops};
-use zcad_core::math::Point2;
+use zcad_core::grip::get_grips_for_geometry;
+use zcad_core::history::{HistoryStep, HistoryTree, OperationId, operations as hist_ops};
+use zcad_core::math::{BoundingBox2, Point2, Vector2};
+use zcad_core::pointstyle::PointMarker;
 use zcad_core::properties::Color;
-use zcad_core::snap::SnapType;
-use zcad_file::Document;
-use zcad_ui::state::{DrawingTool, EditState, UiState};
+use zcad_core::snap::{GridMode, SnapType};
+use zcad_file::{Document, DrawingStats, ViewState};
+use zcad_renderer::{arc_segment_count, Camera2D};
+use zcad_ui::action::{
+    apply_action_result, dispatch_input, Action, ActionContext, ActionResult, ActionType, MouseButton,
+};
+use zcad_ui::actions::{create_action, GripEditAction, PolylineBuilder};
+use zcad_ui::dynamic_input::DynamicField;
+use zcad_ui::keymap::ShortcutAction;
+use zcad_ui::scene_cache::{SceneCache, SceneKey};
+use zcad_ui::settings::Settings;
+use zcad_ui::actions::BoxSelectMode;
+use zcad_ui::state::{DrawLayer, DrawingTool, EditState, UiState};
+use zcad_ui::theme::{Theme, ThemePreset};
 
 /// 历史记录最大深度
 const HISTORY_MAX_DEPTH: usize = 500;
 
+/// 夹点拾取容差（屏幕像素），与夹点编辑 action 一致
+const GRIP_PICK_TOLERANCE: f64 = 10.0;
+
 /// ZCAD 应用程序
 struct ZcadApp {
     document: Document,
     ui_state: UiState,
     
     // 视图状态
-    camera_center: Point2,
-    camera_zoom: f64,
-    viewport_size: (f32, f32),
+    camera: Camera2D,
+    /// 拖动平移时的速度（屏幕像素/秒），松开后用于惯性滑动
+    pan_drag_velocity: Vector2,
+    /// 框选拖动起点（世界坐标）
+    box_select_start: Option<Point2>,
     
     // 文件操作状态
     pending_file_op: Option<FileOperation>,
     
     // 撤销/重做历史树
     history: HistoryTree,
+
+    // 当前运行的 action（夹点编辑、修改命令），事件经由 `drive_action` 分发
+    active_action: Option<Box<dyn Action>>,
+    // 运行 action 时键入的命令输入（子命令、数值、坐标），回车后交给 action
+    action_input: String,
+
+    // 剪贴板（Ctrl+C / Ctrl+V）
+    clipboard: Vec<Entity>,
+
+    // 多段线绘制中的顶点和圆弧模式（A/L 切换），与 `EditState::Drawing` 的点同步
+    polyline: PolylineBuilder,
+    // 矩形圆角半径（工具栏设置），为 0 时绘制普通矩形
+    rectangle_fillet_radius: f64,
+
+    // 图纸信息对话框
+    show_statistics: bool,
+    // 历史记录面板
+    show_history: bool,
+    // 统计信息缓存，文档有变更事件时失效
+    cached_stats: Option<DrawingStats>,
+    // 未选中实体的形状缓存，文档或视图变化时重建
+    scene_cache: SceneCache,
 }
 
 /// 文件操作类型
@@ -47,11 +87,27 @@ impl Default for ZcadApp {
         let mut app = Self {
             document: Document::new(),
             ui_state: UiState::default(),
-            camera_center: Point2::new(250.0, 100.0),
-            camera_zoom: 1.5,
-            viewport_size: (800.0, 600.0),
+            camera: {
+                let mut camera = Camera2D::new(800, 600);
+                camera.center = Point2::new(250.0, 100.0);
+                camera.zoom = 1.5;
+                camera.min_zoom = 0.01;
+                camera.max_zoom = 100.0;
+                camera
+            },
+            pan_drag_velocity: Vector2::zeros(),
+            box_select_start: None,
             pending_file_op: None,
             history: HistoryTree::new(HISTORY_MAX_DEPTH),
+            active_action: None,
+            action_input: String::new(),
+            clipboard: Vec::new(),
+            polyline: PolylineBuilder::new(),
+            rectangle_fillet_radius: 0.0,
+            show_statistics: false,
+            show_history: false,
+            cached_stats: None,
+            scene_cache: SceneCache::new(),
         };
         app.create_demo_content();
         app
@@ -101,14 +157,13 @@ impl ZcadApp {
             return;
         }
         
-        // 使用分组操作记录多个删除
-        let mut operations = Vec::new();
-        for id in &ids {
-            if let Some(entity) = self.document.remove_entity(id) {
-                let op = hist_ops::delete_entity(*id, Some(entity), "删除实体");
-                operations.push(op);
-            }
-        }
+        // 使用分组操作记录多个删除（锁定的实体会被跳过）
+        let removed = self.document.delete_entities(&ids);
+        let skipped = ids.len() - removed.len();
+        let mut operations: Vec<_> = removed
+            .into_iter()
+            .map(|entity| hist_ops::delete_entity(entity.id, Some(entity), "删除实体"))
+            .collect();
         
         if !operations.is_empty() {
             let count = operations.len();
@@ -125,9 +180,95 @@ impl ZcadApp {
             }
             self.ui_state.status_message = format!("已删除 {} 个实体", count);
         }
+        if skipped > 0 {
+            self.ui_state.status_message = format!("{} 个锁定的实体未被删除", skipped);
+        }
         self.ui_state.clear_selection();
     }
 
+    /// 锁定/解锁选中的实体：若有未锁定的则全部锁定，否则全部解锁
+    fn toggle_lock_selected(&mut self) {
+        let ids = self.ui_state.selected_entities.clone();
+        let lock = ids
+            .iter()
+            .filter_map(|id| self.document.get_entity(id))
+            .any(|e| !e.locked);
+        for id in &ids {
+            if let Some(entity) = self.document.get_entity_mut(id) {
+                entity.locked = lock;
+            }
+        }
+        if !ids.is_empty() {
+            let action = if lock { "锁定" } else { "解锁" };
+            self.ui_state.status_message = format!("已{} {} 个实体", action, ids.len());
+        }
+    }
+
+    /// 切换选中多段线的闭合状态（带撤销支持）
+    fn toggle_close_selected_polylines(&mut self) {
+        let mut operations = Vec::new();
+        let mut rejected = 0;
+        for id in self.ui_state.selected_entities.clone() {
+            let Some(entity) = self.document.get_entity(&id) else {
+                continue;
+            };
+            let Geometry::Polyline(polyline) = &entity.geometry else {
+                continue;
+            };
+            if entity.locked {
+                continue;
+            }
+            let mut toggled = polyline.clone();
+            if !toggled.toggle_closed() {
+                rejected += 1;
+                continue;
+            }
+            let mut modified = entity.clone();
+            modified.geometry = Geometry::Polyline(toggled);
+            operations.push(hist_ops::modify_entity(
+                id,
+                entity.geometry.clone(),
+                modified.geometry.clone(),
+                "闭合/打开多段线",
+            ));
+            self.document.update_entity(&id, modified);
+        }
+
+        let count = operations.len();
+        if count == 1 {
+            let _ = self.history.add_operation(operations.remove(0));
+        } else if count > 1 {
+            let group_op = hist_ops::group_operation("闭合/打开多段线", operations, format!("闭合/打开 {} 条多段线", count));
+            let _ = self.history.add_operation(group_op);
+        }
+        self.ui_state.status_message = if rejected > 0 {
+            format!("{} 条多段线顶点不足，无法闭合", rejected)
+        } else {
+            format!("已切换 {} 条多段线的闭合状态", count)
+        };
+    }
+
+    /// 由当前选择创建编组，自动命名为“编组N”
+    fn create_group_from_selection(&mut self) {
+        if self.ui_state.selected_entities.is_empty() {
+            self.ui_state.status_message = "请先选择要编组的对象".to_string();
+            return;
+        }
+        let name = (1..)
+            .map(|n| format!("编组{}", n))
+            .find(|name| !self.document.groups().contains_key(name))
+            .unwrap_or_default();
+        let count = self.document.add_group(name.clone(), self.ui_state.selected_entities.clone());
+        self.ui_state.status_message = format!("已创建 {}（{} 个对象）", name, count);
+    }
+
+    /// 选中编组中的对象
+    fn select_group(&mut self, name: &str) {
+        self.ui_state.selected_entities = self.document.select_group(name);
+        self.ui_state.status_message =
+            format!("已选择 {}（{} 个对象）", name, self.ui_state.selected_entities.len());
+    }
+
     /// 添加实体并记录历史（用于创建操作）
     fn add_entity_with_history(&mut self, entity: Entity, description: &str) -> EntityId {
         let id = self.document.add_entity(entity.clone());
@@ -141,7 +282,7 @@ impl ZcadApp {
         // 先获取操作并克隆，避免借用问题
         let op_type = self.history.undo().map(|op| (op.operation_type.clone(), op.description.clone()));
         if let Some((op_type, desc)) = op_type {
-            self.apply_undo_operation(&op_type);
+            self.document.undo_operation(&op_type);
             self.ui_state.status_message = format!("撤销: {}", desc);
         } else {
             self.ui_state.status_message = "没有可撤销的操作".to_string();
@@ -153,110 +294,288 @@ impl ZcadApp {
         // 先获取操作并克隆，避免借用问题
         let op_type = self.history.redo().map(|op| (op.operation_type.clone(), op.description.clone()));
         if let Some((op_type, desc)) = op_type {
-            self.apply_redo_operation(&op_type);
+            self.document.redo_operation(&op_type);
             self.ui_state.status_message = format!("重做: {}", desc);
         } else {
             self.ui_state.status_message = "没有可重做的操作".to_string();
         }
     }
 
-    /// 应用撤销操作（反向执行）
-    fn apply_undo_operation(&mut self, op_type: &OperationType) {
-        match op_type {
-            OperationType::CreateEntity { entity } => {
-                // 撤销创建：删除实体
-                self.document.remove_entity(&entity.id);
+    /// 撤销或重做到指定操作（历史面板点击）；`None` 表示撤销全部操作
+    fn goto_history(&mut self, target: Option<OperationId>) {
+        let Some(walk) = self.history.walk_to(target) else {
+            return;
+        };
+        // 逐步撤销/重做，文档随每一步更新
+        for step in walk {
+            match step {
+                HistoryStep::Undo => self.do_undo(),
+                HistoryStep::Redo => self.do_redo(),
             }
-            OperationType::DeleteEntity { previous_entity, .. } => {
-                // 撤销删除：恢复实体
-                if let Some(entity) = previous_entity {
-                    self.document.add_entity(entity.clone());
-                }
+        }
+    }
+
+    /// 复制选中的实体到剪贴板
+    ///
+    /// 同时向系统剪贴板写入一段文本：egui-winit 只在系统剪贴板有文本时才为 Ctrl+V 产生粘贴事件。
+    fn copy_selection(&mut self, ctx: &egui::Context) {
+        self.clipboard = self
+            .ui_state
+            .selected_entities
+            .iter()
+            .filter_map(|id| self.document.get_entity(id).cloned())
+            .collect();
+        self.ui_state.status_message = format!("已复制 {} 个实体", self.clipboard.len());
+        if !self.clipboard.is_empty() {
+            ctx.copy_text(format!("ZCAD: {} 个实体", self.clipboard.len()));
+        }
+    }
+
+    /// 原位粘贴剪贴板中的实体（带撤销支持）
+    fn paste_clipboard(&mut self) {
+        if self.clipboard.is_empty() {
+            self.ui_state.status_message = "剪贴板为空".to_string();
+            return;
+        }
+
+        self.ui_state.clear_selection();
+        let mut operations = Vec::new();
+        for source in &self.clipboard {
+            let entity = source.duplicate();
+            let id = self.document.add_entity(entity.clone());
+            self.ui_state.add_to_selection(id);
+            operations.push(hist_ops::create_entity(entity, "粘贴实体"));
+        }
+
+        let count = operations.len();
+        if count == 1 {
+            let _ = self.history.add_operation(operations.remove(0));
+        } else {
+            let group_op = hist_ops::group_operation(
+                "粘贴",
+                operations,
+                format!("粘贴 {} 个实体", count),
+            );
+            let _ = self.history.add_operation(group_op);
+        }
+        self.ui_state.status_message = format!("已粘贴 {} 个实体", count);
+    }
+
+    /// 执行快捷键对应的操作
+    fn handle_shortcut(&mut self, ctx: &egui::Context, shortcut: ShortcutAction) {
+        match shortcut {
+            ShortcutAction::NewDocument => {
+                self.document = Document::new();
+                self.cached_stats = None;
+                self.scene_cache.invalidate();
+                self.ui_state.clear_selection();
+                self.ui_state.status_message = "新文档".to_string();
             }
-            OperationType::ModifyEntity { entity_id, previous_geometry, .. } => {
-                // 撤销修改：恢复到之前的几何
-                if let Some(entity) = self.document.get_entity(entity_id) {
-                    let mut restored = entity.clone();
-                    restored.geometry = previous_geometry.clone();
-                    self.document.update_entity(entity_id, restored);
+            ShortcutAction::Open => self.show_open_dialog(),
+            ShortcutAction::Save => self.quick_save(),
+            ShortcutAction::SaveAs => self.show_save_dialog(),
+            ShortcutAction::Undo => self.do_undo(),
+            ShortcutAction::Redo => self.do_redo(),
+            ShortcutAction::Copy => self.copy_selection(ctx),
+            ShortcutAction::Paste => self.paste_clipboard(),
+            ShortcutAction::Delete => self.delete_selected_entities(),
+            ShortcutAction::Cancel => {
+                if self.active_action.take().is_some() {
+                    self.ui_state.status_message = "取消".to_string();
+                } else {
+                    self.ui_state.cancel();
                 }
             }
-            OperationType::MoveEntities { .. } => {
-                // TODO: 移动操作的撤销需要额外的几何体变换支持
-                // 目前先跳过
+            // 绘制多段线时 A/L 切换圆弧/直线模式，而不是切换工具
+            ShortcutAction::SetTool(tool @ (DrawingTool::Arc | DrawingTool::Line))
+                if matches!(self.ui_state.edit_state, EditState::Drawing { tool: DrawingTool::Polyline, .. }) =>
+            {
+                self.polyline.arc_mode = tool == DrawingTool::Arc;
+                self.ui_state.status_message = self.polyline_prompt().to_string();
             }
-            OperationType::RotateEntities { .. } => {
-                // TODO: 旋转操作的撤销需要额外的几何体变换支持
+            ShortcutAction::SetTool(tool) => {
+                self.active_action = None;
+                self.ui_state.set_tool(tool);
             }
-            OperationType::ScaleEntities { .. } => {
-                // TODO: 缩放操作的撤销需要额外的几何体变换支持
+            ShortcutAction::ZoomExtents => self.zoom_to_fit(),
+            ShortcutAction::ToggleGrid => {
+                self.ui_state.show_grid = !self.ui_state.show_grid;
             }
-            OperationType::GroupOperation { operations, .. } => {
-                // 反向撤销分组中的所有操作
-                for op in operations.iter().rev() {
-                    self.apply_undo_operation(&op.operation_type);
-                }
+            ShortcutAction::ToggleSnap => {
+                self.ui_state.snap_state.enabled = !self.ui_state.snap_state.enabled;
+                let status = if self.ui_state.snap_state.enabled { "捕捉已启用" } else { "捕捉已禁用" };
+                self.ui_state.status_message = status.to_string();
             }
-            _ => {
-                // 其他操作类型暂不支持
+            ShortcutAction::ToggleOrtho => {
+                self.ui_state.ortho_mode = !self.ui_state.ortho_mode;
+                let status = if self.ui_state.ortho_mode { "正交模式已启用" } else { "正交模式已禁用" };
+                self.ui_state.status_message = status.to_string();
             }
         }
     }
 
-    /// 应用重做操作（正向执行）
-    fn apply_redo_operation(&mut self, op_type: &OperationType) {
-        match op_type {
-            OperationType::CreateEntity { entity } => {
-                // 重做创建：添加实体
-                self.document.add_entity(entity.clone());
-            }
-            OperationType::DeleteEntity { entity_id, .. } => {
-                // 重做删除：删除实体
-                self.document.remove_entity(entity_id);
-            }
-            OperationType::ModifyEntity { entity_id, new_geometry, .. } => {
-                // 重做修改：应用新几何
-                if let Some(entity) = self.document.get_entity(entity_id) {
-                    let mut modified = entity.clone();
-                    modified.geometry = new_geometry.clone();
-                    self.document.update_entity(entity_id, modified);
-                }
-            }
-            OperationType::MoveEntities { .. } => {
-                // TODO: 移动操作的重做需要额外的几何体变换支持
-            }
-            OperationType::RotateEntities { .. } => {
-                // TODO: 旋转操作的重做需要额外的几何体变换支持
-            }
-            OperationType::ScaleEntities { .. } => {
-                // TODO: 缩放操作的重做需要额外的几何体变换支持
-            }
-            OperationType::GroupOperation { operations, .. } => {
-                // 正向重做分组中的所有操作
-                for op in operations {
-                    self.apply_redo_operation(&op.operation_type);
-                }
-            }
-            _ => {
-                // 其他操作类型暂不支持
+    // ========== Action 宿主 ==========
+
+    /// 构造传给 action 的上下文
+    fn action_context<'a>(
+        &self,
+        entities: &'a [Entity],
+        selected: &'a [EntityId],
+        ctrl_pressed: bool,
+        reference_point: Option<Point2>,
+    ) -> ActionContext<'a> {
+        ActionContext {
+            mouse_pos: self.ui_state.mouse_world_pos,
+            snap_pos: self.ui_state.current_snap().map(|snap| snap.point),
+            selected_entities: selected,
+            entities,
+            ortho_mode: self.ui_state.ortho_mode,
+            reference_point,
+            zoom: self.camera.zoom,
+            ctrl_pressed,
+        }
+    }
+
+    /// 视图内显示的实体副本，供 action 拾取和捕捉
+    fn entities_in_view(&self) -> Vec<Entity> {
+        self.document
+            .query_view(&self.camera.visible_bounds())
+            .into_iter()
+            .filter(|e| self.document.is_displayed(e))
+            .cloned()
+            .collect()
+    }
+
+    /// 把一个事件交给当前 action，应用其结果并提交历史
+    fn drive_action(&mut self, ctrl_pressed: bool, event: impl FnOnce(&mut dyn Action, &ActionContext) -> ActionResult) {
+        let Some(mut action) = self.active_action.take() else {
+            return;
+        };
+        let entities = self.entities_in_view();
+        let selected = self.ui_state.selected_entities.clone();
+        let ctx = self.action_context(&entities, &selected, ctrl_pressed, action.base_point());
+        let result = event(action.as_mut(), &ctx);
+
+        // 夹点编辑每次只拖动一个夹点，提交后回到选择状态
+        let one_shot = action.action_type() == ActionType::GripEdit && !matches!(result, ActionResult::Continue);
+        let running = apply_action_result(action.as_mut(), result, &mut self.document, &mut self.history) && !one_shot;
+
+        // 被删除或替换的实体不再保持选中
+        self.ui_state
+            .selected_entities
+            .retain(|id| self.document.get_entity(id).is_some());
+        if running {
+            self.ui_state.status_message = action.get_prompt().to_string();
+            self.active_action = Some(action);
+        } else {
+            self.ui_state.status_message.clear();
+        }
+    }
+
+    /// 运行 action 时把键入的文本作为命令输入
+    ///
+    /// 回车后依次按子命令、`宽,高`、坐标、数值交给 action（如圆角半径），
+    /// 退格删除最后一个字符，Esc 先清空输入。键入的字母和数字不再触发单键快捷键。
+    fn handle_action_input(&mut self, ui: &mut egui::Ui) {
+        if self.active_action.is_none() {
+            self.action_input.clear();
+            return;
+        }
+        if ui.ctx().wants_keyboard_input() {
+            return;
+        }
+
+        let typed: String = ui.input(|i| {
+            i.events
+                .iter()
+                .filter_map(|event| match event {
+                    egui::Event::Text(text) => Some(text.as_str()),
+                    _ => None,
+                })
+                .collect()
+        });
+        let typed: String = typed.chars().filter(|c| !c.is_whitespace()).collect();
+        if !typed.is_empty() {
+            self.action_input.push_str(&typed);
+            ui.input_mut(|i| {
+                i.events.retain(|event| {
+                    !matches!(event, egui::Event::Key { key, modifiers, .. }
+                        if modifiers.is_none() && key.symbol_or_name().chars().count() == 1)
+                })
+            });
+        }
+        if self.action_input.is_empty() {
+            return;
+        }
+
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Backspace)) {
+            self.action_input.pop();
+        }
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+            self.action_input.clear();
+        }
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter)) {
+            let input = std::mem::take(&mut self.action_input);
+            let mut error = None;
+            self.drive_action(false, |action, ctx| {
+                dispatch_input(action, ctx, &input).unwrap_or_else(|err| {
+                    error = Some(err);
+                    ActionResult::Continue
+                })
+            });
+            if let Some(err) = error {
+                self.ui_state.status_message = format!("输入无效: {}", err);
             }
         }
     }
 
+    /// 启动修改命令，`options` 依次作为子命令传给 action（如布尔运算类型）
+    fn start_action(&mut self, action_type: ActionType, options: &[&str]) {
+        self.ui_state.set_tool(DrawingTool::Select);
+        let mut action = create_action(action_type);
+        action.init();
+        self.ui_state.status_message = action.get_prompt().to_string();
+        self.active_action = Some(action);
+        for option in options {
+            self.drive_action(false, |action, ctx| action.on_command(ctx, option).unwrap_or(ActionResult::Continue));
+        }
+    }
+
+    /// 选择状态下点中唯一选中实体的夹点时开始夹点编辑，返回是否已开始
+    fn try_start_grip_edit(&mut self, world_pos: Point2, ctrl_pressed: bool) -> bool {
+        let [id] = self.ui_state.selected_entities[..] else {
+            return false;
+        };
+        let Some(entity) = self.document.get_entity(&id).filter(|e| !e.locked) else {
+            return false;
+        };
+        let tolerance = GRIP_PICK_TOLERANCE / self.camera.zoom;
+        let on_grip = get_grips_for_geometry(&entity.geometry)
+            .iter()
+            .any(|grip| (grip.position - world_pos).norm() < tolerance);
+        if !on_grip {
+            return false;
+        }
+
+        let mut action = GripEditAction::new();
+        action.set_entity(id, entity.geometry.clone());
+        self.active_action = Some(Box::new(action));
+        self.drive_action(ctrl_pressed, |action, ctx| action.on_coordinate(ctx, world_pos));
+        true
+    }
+
     /// 世界坐标转屏幕坐标
     fn world_to_screen(&self, point: Point2, rect: &egui::Rect) -> egui::Pos2 {
-        let center = rect.center();
-        let x = center.x + ((point.x - self.camera_center.x) * self.camera_zoom) as f32;
-        let y = center.y - ((point.y - self.camera_center.y) * self.camera_zoom) as f32; // Y轴翻转
-        egui::Pos2::new(x, y)
+        let screen = self.camera.world_to_screen(point);
+        egui::Pos2::new(rect.left() + screen.x as f32, rect.top() + screen.y as f32)
     }
 
     /// 屏幕坐标转世界坐标
     fn screen_to_world(&self, pos: egui::Pos2, rect: &egui::Rect) -> Point2 {
-        let center = rect.center();
-        let x = self.camera_center.x + ((pos.x - center.x) as f64 / self.camera_zoom);
-        let y = self.camera_center.y - ((pos.y - center.y) as f64 / self.camera_zoom); // Y轴翻转
-        Point2::new(x, y)
+        self.camera.screen_to_world(Point2::new(
+            (pos.x - rect.left()) as f64,
+            (pos.y - rect.top()) as f64,
+        ))
     }
 
     /// 绘制网格
@@ -265,13 +584,12 @@ impl ZcadApp {
             return;
         }
 
-        // 根据缩放级别调整网格间距
-        let mut spacing = 50.0;
-        while spacing * self.camera_zoom < 20.0 {
-            spacing *= 5.0;
-        }
-        while spacing * self.camera_zoom > 200.0 {
-            spacing /= 5.0;
+        // 根据缩放级别调整网格间距（只影响显示，网格捕捉使用独立的捕捉步长）
+        let spacing = self.ui_state.display_grid_spacing(self.camera.zoom);
+
+        if self.ui_state.grid_mode != GridMode::Rectangular || !self.ui_state.ucs.is_world() {
+            self.draw_grid_lattice(painter, rect, spacing);
+            return;
         }
 
         // 计算可见范围
@@ -283,18 +601,17 @@ impl ZcadApp {
         let start_y = (bottom_right.y / spacing).floor() * spacing;
         let end_y = (top_left.y / spacing).ceil() * spacing;
 
-        let grid_color = egui::Color32::from_rgb(50, 50, 60);
-        let axis_color = egui::Color32::from_rgb(80, 80, 100);
+        let theme = self.ui_state.active_theme();
 
         // 绘制垂直线
         let mut x = start_x;
         while x <= end_x {
             let screen_x = self.world_to_screen(Point2::new(x, 0.0), rect).x;
             if screen_x >= rect.left() && screen_x <= rect.right() {
-                let color = if x.abs() < 0.001 { axis_color } else { grid_color };
+                let color = theme.grid_line_color(x);
                 painter.line_segment(
                     [egui::Pos2::new(screen_x, rect.top()), egui::Pos2::new(screen_x, rect.bottom())],
-                    egui::Stroke::new(1.0, color),
+                    egui::Stroke::new(1.0, egui::Color32::from_rgb(color.r, color.g, color.b)),
                 );
             }
             x += spacing;
@@ -305,40 +622,152 @@ impl ZcadApp {
         while y <= end_y {
             let screen_y = self.world_to_screen(Point2::new(0.0, y), rect).y;
             if screen_y >= rect.top() && screen_y <= rect.bottom() {
-                let color = if y.abs() < 0.001 { axis_color } else { grid_color };
+                let color = theme.grid_line_color(y);
                 painter.line_segment(
                     [egui::Pos2::new(rect.left(), screen_y), egui::Pos2::new(rect.right(), screen_y)],
-                    egui::Stroke::new(1.0, color),
+                    egui::Stroke::new(1.0, egui::Color32::from_rgb(color.r, color.g, color.b)),
                 );
             }
             y += spacing;
         }
     }
 
+    /// 绘制等轴测/旋转网格或 UCS 网格：沿网格基向量的各组平行线，经过 UCS 原点
+    fn draw_grid_lattice(&self, painter: &egui::Painter, rect: &egui::Rect, spacing: f64) {
+        let theme = self.ui_state.active_theme();
+
+        let ucs = self.ui_state.ucs;
+        let (a, b) = self.ui_state.grid_mode.basis(spacing);
+        let (a, b) = (ucs.vector_to_world(a), ucs.vector_to_world(b));
+        let mut directions = vec![a, b];
+        if self.ui_state.grid_mode == GridMode::Isometric {
+            // 第三组线沿 150° 方向
+            directions.push(b - a);
+        }
+
+        let view_center = self.screen_to_world(rect.center(), rect);
+        let half_diagonal = (rect.width().hypot(rect.height()) as f64) / 2.0 / self.camera.zoom;
+
+        for dir in directions {
+            let d = dir.normalize();
+            let n = Vector2::new(-d.y, d.x);
+            // 相邻平行线的间距：网格节点在法向上投影的最小正间隔
+            let line_spacing = [a.dot(&n).abs(), b.dot(&n).abs()]
+                .into_iter()
+                .filter(|v| *v > 1e-9)
+                .fold(f64::MAX, f64::min);
+            if line_spacing == f64::MAX {
+                continue;
+            }
+
+            let center_offset = (view_center - ucs.origin).dot(&n);
+            let k_start = ((center_offset - half_diagonal) / line_spacing).floor() as i64;
+            let k_end = ((center_offset + half_diagonal) / line_spacing).ceil() as i64;
+            for k in k_start..=k_end {
+                let offset = k as f64 * line_spacing;
+                let base = view_center + n * (offset - center_offset);
+                let p1 = self.world_to_screen(base - d * half_diagonal, rect);
+                let p2 = self.world_to_screen(base + d * half_diagonal, rect);
+                let color = if k == 0 { theme.axis } else { theme.grid };
+                painter.line_segment([p1, p2], egui::Stroke::new(1.0, egui::Color32::from_rgb(color.r, color.g, color.b)));
+            }
+        }
+    }
+
     /// 绘制几何体
     fn draw_geometry(&self, painter: &egui::Painter, rect: &egui::Rect, geometry: &Geometry, color: Color) {
+        self.draw_geometry_with_width(painter, rect, geometry, color, 1.5);
+    }
+
+    /// 按文档点样式（PDMODE / PDSIZE）生成点标记
+    fn point_marker_shapes(&self, center: egui::Pos2, stroke: egui::Stroke, out: &mut Vec<egui::Shape>) {
+        let style = self.document.point_style;
+        let view_height = self.camera.visible_bounds().height();
+        let half = (style.display_size(view_height) * self.camera.zoom / 2.0) as f32;
+        for marker in style.markers() {
+            match marker {
+                PointMarker::Dot => {
+                    out.push(egui::Shape::circle_filled(center, 3.0, stroke.color));
+                }
+                PointMarker::Plus => {
+                    out.push(egui::Shape::line_segment([center - egui::vec2(half, 0.0), center + egui::vec2(half, 0.0)], stroke));
+                    out.push(egui::Shape::line_segment([center - egui::vec2(0.0, half), center + egui::vec2(0.0, half)], stroke));
+                }
+                PointMarker::Cross => {
+                    out.push(egui::Shape::line_segment([center - egui::vec2(half, half), center + egui::vec2(half, half)], stroke));
+                    out.push(egui::Shape::line_segment([center - egui::vec2(half, -half), center + egui::vec2(half, -half)], stroke));
+                }
+                PointMarker::Tick => {
+                    // 屏幕 y 轴向下，向上即减小 y
+                    out.push(egui::Shape::line_segment([center, center - egui::vec2(0.0, half)], stroke));
+                }
+                PointMarker::Circle => {
+                    out.push(egui::Shape::circle_stroke(center, half, stroke));
+                }
+                PointMarker::Square => {
+                    out.push(egui::Shape::rect_stroke(
+                        egui::Rect::from_center_size(center, egui::vec2(half * 2.0, half * 2.0)),
+                        egui::CornerRadius::ZERO,
+                        stroke,
+                        egui::StrokeKind::Middle,
+                    ));
+                }
+            }
+        }
+    }
+
+    /// 以指定线宽（像素）绘制几何体
+    fn draw_geometry_with_width(
+        &self,
+        painter: &egui::Painter,
+        rect: &egui::Rect,
+        geometry: &Geometry,
+        color: Color,
+        width: f32,
+    ) {
+        let mut shapes = Vec::new();
+        self.geometry_shapes(painter, rect, geometry, color, width, &mut shapes);
+        painter.extend(shapes);
+    }
+
+    /// 以指定线宽（像素）生成几何体的屏幕形状，`painter` 仅用于文字排版
+    fn geometry_shapes(
+        &self,
+        painter: &egui::Painter,
+        rect: &egui::Rect,
+        geometry: &Geometry,
+        color: Color,
+        width: f32,
+        out: &mut Vec<egui::Shape>,
+    ) {
         let stroke_color = egui::Color32::from_rgb(color.r, color.g, color.b);
-        let stroke = egui::Stroke::new(1.5, stroke_color);
+        let width = self.ui_state.line_width(width, painter.ctx().pixels_per_point());
+        let stroke = egui::Stroke::new(width, stroke_color);
 
         match geometry {
             Geometry::Point(p) => {
                 let screen = self.world_to_screen(p.position, rect);
-                painter.circle_filled(screen, 3.0, stroke_color);
+                self.point_marker_shapes(screen, stroke, out);
             }
             Geometry::Line(line) => {
                 let start = self.world_to_screen(line.start, rect);
                 let end = self.world_to_screen(line.end, rect);
-                painter.line_segment([start, end], stroke);
+                out.push(egui::Shape::line_segment([start, end], stroke));
             }
             Geometry::Circle(circle) => {
-                let center = self.world_to_screen(circle.center, rect);
-                let radius = (circle.radius * self.camera_zoom) as f32;
-                painter.circle_stroke(center, radius, stroke);
+                let segments = arc_segment_count(circle.radius * self.camera.zoom, std::f64::consts::TAU, self.ui_state.arc_chord_error);
+                let points: Vec<egui::Pos2> = (0..=segments)
+                    .map(|i| {
+                        let angle = std::f64::consts::TAU * i as f64 / segments as f64;
+                        self.world_to_screen(circle.point_at_angle(angle), rect)
+                    })
+                    .collect();
+                out.push(egui::Shape::line(points, stroke));
             }
             Geometry::Arc(arc) => {
-                // 简化：用线段近似弧线
-                let segments = 32;
+                // 用线段近似弧线，分段数随屏幕半径自适应
                 let sweep = arc.sweep_angle();
+                let segments = arc_segment_count(arc.radius * self.camera.zoom, sweep, self.ui_state.arc_chord_error);
                 let angle_step = sweep / segments as f64;
                 
                 for i in 0..segments {
@@ -356,7 +785,7 @@ impl ZcadApp {
                     
                     let s1 = self.world_to_screen(p1, rect);
                     let s2 = self.world_to_screen(p2, rect);
-                    painter.line_segment([s1, s2], stroke);
+                    out.push(egui::Shape::line_segment([s1, s2], stroke));
                 }
             }
             Geometry::Polyline(polyline) => {
@@ -364,51 +793,67 @@ impl ZcadApp {
                     return;
                 }
                 
+                // 有宽度的线段按轮廓逐段填充（每段四边形为凸多边形）
+                for outline in polyline.width_outlines() {
+                    let n = outline.len();
+                    for k in 0..n / 2 - 1 {
+                        let quad = [outline[k], outline[k + 1], outline[n - 2 - k], outline[n - 1 - k]]
+                            .map(|p| self.world_to_screen(p, rect));
+                        out.push(egui::Shape::convex_polygon(quad.to_vec(), stroke_color, egui::Stroke::NONE));
+                    }
+                }
+                
                 for i in 0..polyline.segment_count() {
                     let v1 = &polyline.vertices[i];
                     let v2 = &polyline.vertices[(i + 1) % polyline.vertices.len()];
+                    if v1.has_width() {
+                        continue;
+                    }
                     
                     let s1 = self.world_to_screen(v1.point, rect);
                     let s2 = self.world_to_screen(v2.point, rect);
-                    painter.line_segment([s1, s2], stroke);
+                    out.push(egui::Shape::line_segment([s1, s2], stroke));
                 }
             }
             Geometry::Text(text) => {
                 // 简化的文本绘制
                 let screen = self.world_to_screen(text.position, rect);
-                painter.text(
-                    screen,
-                    egui::Align2::LEFT_BOTTOM,
-                    &text.content,
-                    egui::FontId::proportional(12.0),
-                    stroke_color,
-                );
+                out.push(painter.fonts_mut(|f| {
+                    egui::Shape::text(
+                        f,
+                        screen,
+                        egui::Align2::LEFT_BOTTOM,
+                        &text.content,
+                        egui::FontId::proportional(12.0),
+                        stroke_color,
+                    )
+                }));
             }
             Geometry::Dimension(dim) => {
-                // 简化的标注绘制
-                let p1 = self.world_to_screen(dim.definition_point1, rect);
-                let p2 = self.world_to_screen(dim.definition_point2, rect);
-                let line_loc = self.world_to_screen(dim.line_location, rect);
-                
-                // 绘制标注线
-                painter.line_segment([p1, line_loc], stroke);
-                painter.line_segment([p2, line_loc], stroke);
+                // 标注图形（尺寸线、尺寸界线、箭头）
+                for segment in dim.render_segments() {
+                    self.geometry_shapes(painter, rect, &segment, color, width, out);
+                }
                 // 绘制文本
-                let distance = (dim.definition_point2 - dim.definition_point1).norm();
-                painter.text(
-                    line_loc,
-                    egui::Align2::CENTER_BOTTOM,
-                    format!("{:.2}", distance),
-                    egui::FontId::proportional(10.0),
-                    stroke_color,
-                );
+                let text_pos = self.world_to_screen(dim.get_text_position(), rect);
+                let text = painter.fonts_mut(|f| {
+                    egui::Shape::text(
+                        f,
+                        text_pos,
+                        egui::Align2::CENTER_CENTER,
+                        dim.display_text(),
+                        egui::FontId::proportional(10.0),
+                        stroke_color,
+                    )
+                });
+                self.push_masked_text(text, Some(dim.text_mask()), rect, out);
             }
             Geometry::Ellipse(ellipse) => {
-                // 用线段近似椭圆
-                let segments = 32;
-                let angle_step = std::f64::consts::TAU / segments as f64;
+                // 用线段近似椭圆（按长半轴估算分段数）
                 let major_len = ellipse.major_axis.norm();
                 let minor_len = major_len * ellipse.ratio;
+                let segments = arc_segment_count(major_len * self.camera.zoom, std::f64::consts::TAU, self.ui_state.arc_chord_error);
+                let angle_step = std::f64::consts::TAU / segments as f64;
                 
                 for i in 0..segments {
                     let a1 = i as f64 * angle_step;
@@ -425,31 +870,98 @@ impl ZcadApp {
                     
                     let s1 = self.world_to_screen(p1, rect);
                     let s2 = self.world_to_screen(p2, rect);
-                    painter.line_segment([s1, s2], stroke);
+                    out.push(egui::Shape::line_segment([s1, s2], stroke));
+                }
+            }
+            Geometry::Leader(leader) => {
+                // 引线路径（样条引线为采样后的曲线）
+                let points: Vec<egui::Pos2> =
+                    leader.path_points().iter().map(|p| self.world_to_screen(*p, rect)).collect();
+                for w in points.windows(2) {
+                    out.push(egui::Shape::line_segment([w[0], w[1]], stroke));
+                }
+                // 箭头：沿起点切线方向的两条短边
+                if let (Some(tip), Some(dir)) = (leader.arrow_point(), leader.arrow_direction()) {
+                    let perp = Vector2::new(-dir.y, dir.x);
+                    let back = tip - dir * leader.arrow_size;
+                    let tip = self.world_to_screen(tip, rect);
+                    for side in [back + perp * leader.arrow_size * 0.3, back - perp * leader.arrow_size * 0.3] {
+                        out.push(egui::Shape::line_segment([tip, self.world_to_screen(side, rect)], stroke));
+                    }
+                }
+                // 引线文本接在最后一个顶点
+                if let (Some(content), Some(position)) = (&leader.text, leader.text_position()) {
+                    let screen = self.world_to_screen(position, rect);
+                    let text = painter.fonts_mut(|f| {
+                        egui::Shape::text(
+                            f,
+                            screen,
+                            egui::Align2::LEFT_BOTTOM,
+                            content,
+                            egui::FontId::proportional(10.0),
+                            stroke_color,
+                        )
+                    });
+                    self.push_masked_text(text, leader.text_mask(), rect, out);
                 }
             }
             // 其他几何类型暂不渲染详细图形
-            Geometry::Spline(_) | Geometry::Hatch(_) | Geometry::Leader(_) => {
+            Geometry::Spline(_) | Geometry::Hatch(_) => {
                 // TODO: 实现详细渲染
             }
         }
     }
 
-    /// 绘制十字光标
+    /// 加入标注/引线文字；开启文字遮罩时先在遮罩范围（世界坐标，见 `text_mask`）内填充背景色
+    fn push_masked_text(
+        &self,
+        text: egui::Shape,
+        mask: Option<BoundingBox2>,
+        rect: &egui::Rect,
+        out: &mut Vec<egui::Shape>,
+    ) {
+        if let (true, Some(mask)) = (self.ui_state.dim_text_mask, mask) {
+            let background = self.ui_state.active_theme().background;
+            out.push(egui::Shape::rect_filled(
+                egui::Rect::from_two_pos(self.world_to_screen(mask.min, rect), self.world_to_screen(mask.max, rect)),
+                egui::CornerRadius::ZERO,
+                egui::Color32::from_rgb(background.r, background.g, background.b),
+            ));
+        }
+        out.push(text);
+    }
+
+    /// 影响实体显示、但不改变文档修订号的设置摘要（图层颜色与可见性、点样式、弧线精度、图纸空间、文字遮罩、线宽换算）
+    fn display_style_digest(&self, pixels_per_point: f32) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        for layer in self.document.layers.all_layers() {
+            layer.name.hash(&mut hasher);
+            (layer.color.r, layer.color.g, layer.color.b).hash(&mut hasher);
+            (layer.visible, layer.frozen).hash(&mut hasher);
+        }
+        format!("{:?}", self.document.point_style).hash(&mut hasher);
+        self.ui_state.arc_chord_error.to_bits().hash(&mut hasher);
+        self.ui_state.is_paper_space().hash(&mut hasher);
+        self.ui_state.dim_text_mask.hash(&mut hasher);
+        self.ui_state.antialiasing.hash(&mut hasher);
+        pixels_per_point.to_bits().hash(&mut hasher);
+        hasher.finish()
+    }
+
+    /// 绘制十字光标（沿当前 UCS 的坐标轴）
     fn draw_crosshair(&self, painter: &egui::Painter, rect: &egui::Rect, world_pos: Point2) {
         let screen = self.world_to_screen(world_pos, rect);
         let size = 15.0;
         let color = egui::Color32::WHITE;
         let stroke = egui::Stroke::new(1.0, color);
 
-        painter.line_segment(
-            [egui::Pos2::new(screen.x - size, screen.y), egui::Pos2::new(screen.x + size, screen.y)],
-            stroke,
-        );
-        painter.line_segment(
-            [egui::Pos2::new(screen.x, screen.y - size), egui::Pos2::new(screen.x, screen.y + size)],
-            stroke,
-        );
+        // 屏幕 Y 轴向下
+        let ucs = self.ui_state.ucs;
+        for axis in [ucs.x_axis(), ucs.y_axis()] {
+            let arm = egui::vec2(axis.x as f32, -axis.y as f32) * size;
+            painter.line_segment([screen - arm, screen + arm], stroke);
+        }
     }
 
     /// 绘制捕捉标记
@@ -543,6 +1055,36 @@ impl ZcadApp {
                     stroke,
                 );
             }
+            SnapType::ApparentIntersection => {
+                // 外观交点标记（方框内的X）
+                let half = size * 0.7;
+                painter.rect_stroke(
+                    egui::Rect::from_center_size(screen, egui::vec2(size * 2.0, size * 2.0)),
+                    egui::CornerRadius::ZERO,
+                    stroke,
+                    egui::StrokeKind::Outside,
+                );
+                painter.line_segment(
+                    [egui::Pos2::new(screen.x - half, screen.y - half), egui::Pos2::new(screen.x + half, screen.y + half)],
+                    stroke,
+                );
+                painter.line_segment(
+                    [egui::Pos2::new(screen.x - half, screen.y + half), egui::Pos2::new(screen.x + half, screen.y - half)],
+                    stroke,
+                );
+            }
+            SnapType::Insertion => {
+                // 插入点标记（两个错开的小方框）
+                let half = size * 0.5;
+                for offset in [egui::vec2(-half, -half), egui::vec2(half, half)] {
+                    painter.rect_stroke(
+                        egui::Rect::from_center_size(screen + offset, egui::vec2(size, size)),
+                        egui::CornerRadius::ZERO,
+                        stroke,
+                        egui::StrokeKind::Middle,
+                    );
+                }
+            }
             SnapType::Quadrant => {
                 // 象限点标记（菱形）
                 let points = [
@@ -577,28 +1119,31 @@ impl ZcadApp {
 
     /// 更新捕捉点
     fn update_snap(&mut self) {
-        // 获取当前视图内的实体
-        let entities: Vec<&Entity> = self.document.all_entities().collect();
-
-        // 获取参考点（绘图状态下的起始点）
-        let reference_point = match &self.ui_state.edit_state {
-            EditState::Drawing { points, .. } if !points.is_empty() => Some(points[0]),
-            _ => None,
-        };
-
-        // 查找捕捉点
-        let mut snap = self.ui_state.snap_state.engine_mut().find_snap_point(
+        // 获取当前视图内显示的实体（视口裁剪，屏幕外和隐藏的实体不参与捕捉）
+        let mut entities: Vec<&Entity> = self.document.query_view(&self.camera.visible_bounds());
+        entities.retain(|e| self.document.is_displayed(e));
+
+        // 参考点：绘图时最后确定的点，修改时为基点或中心点
+        let reference_point = self
+            .ui_state
+            .tracking_base()
+            .or_else(|| self.active_action.as_ref().and_then(|action| action.base_point()));
+
+        // 查找捕捉点（光标停在同一位置且文档和图层开关都未变化时复用上次结果）
+        let revision = self.document.display_revision();
+        let mut snap = self.ui_state.snap_state.engine_mut().find_snap_point_cached(
             self.ui_state.mouse_world_pos,
             &entities,
-            self.camera_zoom,
+            self.camera.zoom,
             reference_point,
+            revision,
         );
 
         // 特殊处理：绘制多段线时，检查是否接近起点（用于闭合）
         if let EditState::Drawing { tool: DrawingTool::Polyline, points, .. } = &self.ui_state.edit_state {
             if points.len() >= 2 {
                 let start_point = points[0];
-                let world_tolerance = self.ui_state.snap_state.config().tolerance / self.camera_zoom;
+                let world_tolerance = self.ui_state.snap_state.config().tolerance / self.camera.zoom;
                 let dist_to_start = (self.ui_state.mouse_world_pos - start_point).norm();
                 
                 if dist_to_start <= world_tolerance {
@@ -624,7 +1169,7 @@ impl ZcadApp {
         if let EditState::Drawing { tool: DrawingTool::Arc, points, .. } = &self.ui_state.edit_state {
             if !points.is_empty() {
                 let first_point = points[0];
-                let world_tolerance = self.ui_state.snap_state.config().tolerance / self.camera_zoom;
+                let world_tolerance = self.ui_state.snap_state.config().tolerance / self.camera.zoom;
                 let dist_to_first = (self.ui_state.mouse_world_pos - first_point).norm();
                 
                 if dist_to_first <= world_tolerance {
@@ -648,39 +1193,73 @@ impl ZcadApp {
         self.ui_state.snap_state.current_snap = snap;
     }
 
-    /// 应用正交约束
-    /// 
-    /// 将目标点约束到从参考点出发的水平或垂直方向
-    fn apply_ortho_constraint(&self, reference: Point2, target: Point2) -> Point2 {
-        if !self.ui_state.ortho_mode {
-            return target;
+    /// 获取有效的绘图点（应用捕捉和极轴/正交约束，极轴优先）
+    fn get_effective_draw_point(&self) -> Point2 {
+        let base_point = self.ui_state.effective_point();
+
+        // 如果正在绘图且有参考点，应用方向约束
+        if let EditState::Drawing { points, .. } = &self.ui_state.edit_state {
+            if let Some(&reference) = points.last() {
+                return self.ui_state.constrain_from(reference, base_point);
+            }
         }
 
-        let dx = (target.x - reference.x).abs();
-        let dy = (target.y - reference.y).abs();
+        base_point
+    }
 
-        if dx > dy {
-            // 水平方向更近，约束到水平线
-            Point2::new(target.x, reference.y)
+    /// 绘制框选矩形：窗口选择为蓝色实线，交叉选择为绿色虚线
+    fn draw_selection_box(&self, painter: &egui::Painter, rect: &egui::Rect, start: Point2, end: Point2) {
+        let (fill, stroke_color) = match BoxSelectMode::from_drag(start, end) {
+            BoxSelectMode::Window => (
+                egui::Color32::from_rgba_unmultiplied(0, 120, 255, 40),
+                egui::Color32::from_rgb(0, 120, 255),
+            ),
+            BoxSelectMode::Crossing => (
+                egui::Color32::from_rgba_unmultiplied(0, 200, 80, 40),
+                egui::Color32::from_rgb(0, 200, 80),
+            ),
+        };
+        let screen_rect = egui::Rect::from_two_pos(
+            self.world_to_screen(start, rect),
+            self.world_to_screen(end, rect),
+        );
+        painter.rect_filled(screen_rect, 0.0, fill);
+
+        let stroke = egui::Stroke::new(1.0, stroke_color);
+        let corners = [
+            screen_rect.left_top(),
+            screen_rect.right_top(),
+            screen_rect.right_bottom(),
+            screen_rect.left_bottom(),
+            screen_rect.left_top(),
+        ];
+        if BoxSelectMode::from_drag(start, end).is_crossing() {
+            painter.extend(egui::Shape::dashed_line(&corners, stroke, 6.0, 4.0));
         } else {
-            // 垂直方向更近，约束到垂直线
-            Point2::new(reference.x, target.y)
+            painter.line(corners.to_vec(), stroke);
         }
     }
 
-    /// 获取有效的绘图点（应用捕捉和正交约束）
-    fn get_effective_draw_point(&self) -> Point2 {
-        let base_point = self.ui_state.effective_point();
+    /// 完成框选：根据拖动方向选择窗口或交叉模式
+    fn finish_box_select(&mut self, start: Point2, end: Point2, add_to_selection: bool) {
+        let mode = BoxSelectMode::from_drag(start, end);
+        let bounds = BoundingBox2::from_points([start, end]);
+        let ids: Vec<EntityId> = self
+            .document
+            .query_box(&bounds, mode.is_crossing())
+            .iter()
+            .filter(|e| self.document.is_displayed(e))
+            .map(|e| e.id)
+            .collect();
 
-        // 如果正在绘图且有参考点，应用正交约束
-        if let EditState::Drawing { points, .. } = &self.ui_state.edit_state {
-            if !points.is_empty() && self.ui_state.ortho_mode {
-                let reference = *points.last().unwrap();
-                return self.apply_ortho_constraint(reference, base_point);
-            }
+        if !add_to_selection {
+            self.ui_state.clear_selection();
         }
-
-        base_point
+        for id in &ids {
+            self.ui_state.add_to_selection(*id);
+        }
+        let mode_name = if mode.is_crossing() { "交叉" } else { "窗口" };
+        self.ui_state.status_message = format!("{}选择: {} 个对象", mode_name, ids.len());
     }
 
     /// 绘制预览
@@ -707,16 +1286,7 @@ impl ZcadApp {
                     }
                 }
                 DrawingTool::Rectangle => {
-                    let p1 = points[0];
-                    let rect_geom = Polyline::from_points(
-                        [
-                            Point2::new(p1.x, p1.y),
-                            Point2::new(mouse_pos.x, p1.y),
-                            Point2::new(mouse_pos.x, mouse_pos.y),
-                            Point2::new(p1.x, mouse_pos.y),
-                        ],
-                        true,
-                    );
+                    let rect_geom = self.rectangle_polyline(points[0], mouse_pos);
                     self.draw_geometry(painter, rect, &Geometry::Polyline(rect_geom), preview_color);
                 }
                 DrawingTool::Arc => {
@@ -738,15 +1308,9 @@ impl ZcadApp {
                     }
                 }
                 DrawingTool::Polyline => {
-                    // 绘制已有的线段
-                    for i in 0..points.len().saturating_sub(1) {
-                        let line = Line::new(points[i], points[i + 1]);
-                        self.draw_geometry(painter, rect, &Geometry::Line(line), preview_color);
-                    }
-                    // 绘制到鼠标的预览线段
-                    if let Some(&last) = points.last() {
-                        let line = Line::new(last, mouse_pos);
-                        self.draw_geometry(painter, rect, &Geometry::Line(line), preview_color);
+                    // 已有的线段加上到鼠标的预览线段（圆弧模式下按相切凸度预览为圆弧）
+                    for segment in self.polyline.preview(mouse_pos).explode() {
+                        self.draw_geometry(painter, rect, &segment, preview_color);
                     }
                 }
                 _ => {}
@@ -754,11 +1318,87 @@ impl ZcadApp {
         }
     }
 
+    /// 绘制当前 action 的预览，参考线以灰色显示
+    fn draw_action_preview(&self, painter: &egui::Painter, rect: &egui::Rect) {
+        let Some(action) = &self.active_action else {
+            return;
+        };
+        let entities = self.entities_in_view();
+        let ctx = self.action_context(&entities, &self.ui_state.selected_entities, false, action.base_point());
+        for preview in action.get_preview(&ctx) {
+            let color = if preview.is_reference {
+                Color::from_hex(0x808080)
+            } else {
+                Color::from_hex(0xFF00FF)
+            };
+            self.draw_geometry(painter, rect, &preview.geometry, color);
+        }
+    }
+
+    /// 选择状态下为唯一选中的实体绘制夹点
+    fn draw_grips(&self, painter: &egui::Painter, rect: &egui::Rect) {
+        if self.active_action.is_some() || self.ui_state.current_tool != DrawingTool::Select {
+            return;
+        }
+        let [id] = self.ui_state.selected_entities[..] else {
+            return;
+        };
+        let Some(entity) = self.document.get_entity(&id) else {
+            return;
+        };
+        let fill = egui::Color32::from_rgb(0, 120, 255);
+        for grip in get_grips_for_geometry(&entity.geometry) {
+            let center = self.world_to_screen(grip.position, rect);
+            painter.rect_filled(egui::Rect::from_center_size(center, egui::vec2(7.0, 7.0)), 0.0, fill);
+        }
+    }
+
     /// 处理左键点击
-    fn handle_left_click(&mut self) {
+    fn handle_left_click(&mut self, ctrl_pressed: bool) {
+        if self.active_action.is_some() {
+            self.drive_action(ctrl_pressed, |action, ctx| action.on_mouse_click(ctx, MouseButton::Left));
+            return;
+        }
+
         // 使用捕捉点和正交约束
         let world_pos = self.get_effective_draw_point();
+        self.ui_state.dynamic_input.clear();
+
+        let selecting = self.ui_state.current_tool == DrawingTool::Select
+            && matches!(self.ui_state.edit_state, EditState::Idle);
+        if selecting && self.try_start_grip_edit(world_pos, ctrl_pressed) {
+            return;
+        }
+
+        // M2P：两次拾取合成一个中点，作为本次输入点
+        if self.ui_state.snap_state.is_mid_between_active() {
+            match self.ui_state.snap_state.pick_mid_between(world_pos) {
+                Some(midpoint) => self.handle_point_input(midpoint),
+                None => self.ui_state.status_message = "中点的第二点:".to_string(),
+            }
+            return;
+        }
+
+        self.handle_point_input(world_pos);
+    }
+
+    /// 选择模式下计算光标下的实体，用于悬停预高亮
+    fn update_hover(&mut self) {
+        let picking = self.ui_state.current_tool == DrawingTool::Select
+            && matches!(self.ui_state.edit_state, EditState::Idle)
+            && self.active_action.is_none()
+            && self.box_select_start.is_none();
+        self.ui_state.hovered_entity = if picking {
+            self.document
+                .pick_entity(&self.ui_state.mouse_world_pos, 5.0 / self.camera.zoom)
+                .map(|e| e.id)
+        } else {
+            None
+        };
+    }
 
+    /// 处理一个输入点（鼠标点击或动态输入提交）
+    fn handle_point_input(&mut self, world_pos: Point2) {
         match &self.ui_state.edit_state {
             EditState::Idle => match self.ui_state.current_tool {
                 DrawingTool::Line => {
@@ -799,19 +1439,21 @@ impl ZcadApp {
                         points: vec![world_pos],
                         expected_input: None,
                     };
-                    self.ui_state.status_message = "多段线: 指定下一点 (右键结束):".to_string();
+                    self.polyline.clear();
+                    self.polyline.push(world_pos);
+                    self.ui_state.status_message = self.polyline_prompt().to_string();
                 }
                 DrawingTool::Point => {
                     // 点直接创建，不需要绘图状态
                     let point = Point::from_point2(world_pos);
-                    let entity = Entity::new(Geometry::Point(point));
+                    let entity = self.document.new_entity(Geometry::Point(point));
                     self.add_entity_with_history(entity, "创建点");
                     self.ui_state.status_message = "点已创建".to_string();
                 }
                 DrawingTool::Select => {
-                    let hits = self.document.query_point(&world_pos, 5.0 / self.camera_zoom);
+                    let hit = self.document.pick_entity(&world_pos, 5.0 / self.camera.zoom);
                     self.ui_state.clear_selection();
-                    if let Some(entity) = hits.first() {
+                    if let Some(entity) = hit {
                         self.ui_state.add_to_selection(entity.id);
                         self.ui_state.status_message = format!("已选择: {}", entity.geometry.type_name());
                     } else {
@@ -833,7 +1475,7 @@ impl ZcadApp {
                     DrawingTool::Line => {
                         if new_points.len() >= 2 {
                             let line = Line::new(new_points[0], new_points[1]);
-                            let entity = Entity::new(Geometry::Line(line));
+                            let entity = self.document.new_entity(Geometry::Line(line));
                             self.add_entity_with_history(entity, "创建直线");
                             self.ui_state.edit_state = EditState::Drawing {
                                 tool: DrawingTool::Line,
@@ -847,7 +1489,7 @@ impl ZcadApp {
                         if new_points.len() >= 2 {
                             let radius = (new_points[1] - new_points[0]).norm();
                             let circle = Circle::new(new_points[0], radius);
-                            let entity = Entity::new(Geometry::Circle(circle));
+                            let entity = self.document.new_entity(Geometry::Circle(circle));
                             self.add_entity_with_history(entity, "创建圆");
                             self.ui_state.edit_state = EditState::Idle;
                             self.ui_state.status_message = "圆已创建".to_string();
@@ -855,18 +1497,8 @@ impl ZcadApp {
                     }
                     DrawingTool::Rectangle => {
                         if new_points.len() >= 2 {
-                            let p1 = new_points[0];
-                            let p2 = new_points[1];
-                            let rect = Polyline::from_points(
-                                [
-                                    Point2::new(p1.x, p1.y),
-                                    Point2::new(p2.x, p1.y),
-                                    Point2::new(p2.x, p2.y),
-                                    Point2::new(p1.x, p2.y),
-                                ],
-                                true,
-                            );
-                            let entity = Entity::new(Geometry::Polyline(rect));
+                            let rect = self.rectangle_polyline(new_points[0], new_points[1]);
+                            let entity = self.document.new_entity(Geometry::Polyline(rect));
                             self.add_entity_with_history(entity, "创建矩形");
                             self.ui_state.edit_state = EditState::Idle;
                             self.ui_state.status_message = "矩形已创建".to_string();
@@ -889,7 +1521,7 @@ impl ZcadApp {
                                 new_points[1],
                                 new_points[2],
                             ) {
-                                let entity = Entity::new(Geometry::Arc(arc));
+                                let entity = self.document.new_entity(Geometry::Arc(arc));
                                 self.add_entity_with_history(entity, "创建圆弧");
                                 self.ui_state.status_message = "圆弧已创建".to_string();
                             } else {
@@ -899,17 +1531,15 @@ impl ZcadApp {
                         }
                     }
                     DrawingTool::Polyline => {
+                        self.polyline.push(world_pos);
+
                         // 检查是否点击了起点（闭合多段线）
-                        if new_points.len() >= 3 {
-                            let start = new_points[0];
-                            let current = new_points[new_points.len() - 1];
-                            let tolerance = 0.001; // 很小的容差，因为捕捉已经对齐了
-                            
-                            if (current - start).norm() < tolerance {
-                                // 点击了起点，创建闭合多段线
-                                new_points.pop(); // 移除重复的终点
-                                let polyline = Polyline::from_points(new_points, true); // closed = true
-                                let entity = Entity::new(Geometry::Polyline(polyline));
+                        let start = new_points[0];
+                        let tolerance = 0.001; // 很小的容差，因为捕捉已经对齐了
+                        if (world_pos - start).norm() < tolerance {
+                            // 点击了起点，创建闭合多段线（闭合段的凸度记在最后一个顶点上）
+                            if let Some(polyline) = self.polyline.close_at_start() {
+                                let entity = self.document.new_entity(Geometry::Polyline(polyline));
                                 self.add_entity_with_history(entity, "创建闭合多段线");
                                 self.ui_state.edit_state = EditState::Idle;
                                 self.ui_state.status_message = "闭合多段线已创建".to_string();
@@ -923,7 +1553,7 @@ impl ZcadApp {
                             points: new_points,
                             expected_input: None,
                         };
-                        self.ui_state.status_message = "多段线: 指定下一点 (右键结束, 点击起点闭合):".to_string();
+                        self.ui_state.status_message = self.polyline_prompt().to_string();
                     }
                     _ => {}
                 }
@@ -932,12 +1562,124 @@ impl ZcadApp {
         }
     }
 
+    /// 处理动态输入按键
+    ///
+    /// 绘制直线/多段线时输入数字聚焦长度框，Tab 切换到角度框，
+    /// 回车按 `@长度<角度` 提交下一点，Esc 放弃输入。
+    fn handle_dynamic_input(&mut self, ui: &mut egui::Ui) {
+        let Some(reference) = self.ui_state.dynamic_input_reference() else {
+            self.ui_state.dynamic_input.clear();
+            return;
+        };
+        if ui.ctx().wants_keyboard_input() {
+            return;
+        }
+
+        let typed: Vec<char> = ui.input(|i| {
+            i.events
+                .iter()
+                .filter_map(|event| match event {
+                    egui::Event::Text(text) => Some(text.chars().collect::<Vec<_>>()),
+                    _ => None,
+                })
+                .flatten()
+                .collect()
+        });
+        for c in typed {
+            self.ui_state.dynamic_input.push_char(c);
+        }
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Tab)) {
+            self.ui_state.dynamic_input.switch_field();
+        }
+        if !self.ui_state.dynamic_input.is_active() {
+            return;
+        }
+
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Backspace)) {
+            self.ui_state.dynamic_input.backspace();
+        }
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Escape)) {
+            self.ui_state.dynamic_input.clear();
+        }
+        if ui.input_mut(|i| i.consume_key(egui::Modifiers::NONE, egui::Key::Enter)) {
+            let cursor = self.ui_state.effective_point();
+            match self.ui_state.commit_dynamic_input(reference, cursor) {
+                Ok(point) => self.handle_point_input(point),
+                Err(err) => self.ui_state.status_message = format!("输入无效: {}", err),
+            }
+        }
+    }
+
+    /// 在光标旁绘制动态输入框（长度、角度），聚焦的输入框高亮
+    fn draw_dynamic_input(&self, painter: &egui::Painter, cursor: egui::Pos2) {
+        let input = &self.ui_state.dynamic_input;
+        if !input.is_active() {
+            return;
+        }
+
+        let fields = [
+            ("长度", &input.length, input.focused == DynamicField::Length),
+            ("角度", &input.angle, input.focused == DynamicField::Angle),
+        ];
+        let mut pos = cursor + egui::vec2(16.0, -40.0);
+        for (label, text, focused) in fields {
+            let galley = painter.layout_no_wrap(
+                format!("{}: {}", label, text),
+                egui::FontId::monospace(12.0),
+                egui::Color32::WHITE,
+            );
+            let frame = egui::Rect::from_min_size(pos, galley.size()).expand(3.0);
+            painter.rect_filled(frame, 2.0, egui::Color32::from_rgba_unmultiplied(20, 20, 30, 220));
+            if focused {
+                painter.rect_stroke(
+                    frame,
+                    2.0,
+                    egui::Stroke::new(1.0, egui::Color32::from_rgb(0, 170, 255)),
+                    egui::StrokeKind::Outside,
+                );
+            }
+            let width = galley.size().x;
+            painter.galley(pos, galley, egui::Color32::WHITE);
+            pos.x += width + 12.0;
+        }
+    }
+
+    /// 由两个对角点构造矩形，设置了圆角半径时四角为圆弧
+    fn rectangle_polyline(&self, p1: Point2, p2: Point2) -> Polyline {
+        if self.rectangle_fillet_radius > 0.0 {
+            return Polyline::rounded_rectangle(p1, p2, self.rectangle_fillet_radius);
+        }
+        Polyline::from_points(
+            [
+                Point2::new(p1.x, p1.y),
+                Point2::new(p2.x, p1.y),
+                Point2::new(p2.x, p2.y),
+                Point2::new(p1.x, p2.y),
+            ],
+            true,
+        )
+    }
+
+    /// 多段线绘制提示
+    fn polyline_prompt(&self) -> &'static str {
+        if self.polyline.arc_mode {
+            "多段线: 指定圆弧端点 [直线(L)] (右键结束, 点击起点闭合):"
+        } else {
+            "多段线: 指定下一点 [圆弧(A)] (右键结束, 点击起点闭合):"
+        }
+    }
+
     /// 处理右键点击（结束多段线等）
     fn handle_right_click(&mut self) {
+        if self.active_action.is_some() {
+            self.drive_action(false, |action, ctx| action.on_mouse_click(ctx, MouseButton::Right));
+            return;
+        }
+
         // 先提取需要的信息，避免借用冲突
-        let (is_polyline, points_to_create) = if let EditState::Drawing { tool, points, .. } = &self.ui_state.edit_state {
-            if *tool == DrawingTool::Polyline && points.len() >= 2 {
-                (true, Some(points.clone()))
+        let (is_polyline, polyline_to_create) = if let EditState::Drawing { tool, .. } = &self.ui_state.edit_state {
+            if *tool == DrawingTool::Polyline && self.polyline.len() >= 2 {
+                (true, Some(self.polyline.build(false)))
             } else if *tool == DrawingTool::Polyline {
                 (true, None) // 点数不够
             } else {
@@ -948,11 +1690,11 @@ impl ZcadApp {
         };
 
         if is_polyline {
-            if let Some(pts) = points_to_create {
-                let polyline = Polyline::from_points(pts.clone(), false);
-                let entity = Entity::new(Geometry::Polyline(polyline));
+            if let Some(polyline) = polyline_to_create {
+                let count = polyline.vertices.len();
+                let entity = self.document.new_entity(Geometry::Polyline(polyline));
                 self.add_entity_with_history(entity, "创建多段线");
-                self.ui_state.status_message = format!("多段线已创建 ({} 个点)", pts.len());
+                self.ui_state.status_message = format!("多段线已创建 ({} 个点)", count);
             } else {
                 self.ui_state.status_message = "取消".to_string();
             }
@@ -965,18 +1707,16 @@ impl ZcadApp {
     /// 缩放到适合视图
     fn zoom_to_fit(&mut self) {
         if let Some(bounds) = self.document.bounds() {
-            self.camera_center = Point2::new(
-                (bounds.min.x + bounds.max.x) / 2.0,
-                (bounds.min.y + bounds.max.y) / 2.0,
-            );
+            self.camera.stop_inertia();
+            self.camera.center = bounds.center();
             
             let width = bounds.max.x - bounds.min.x;
             let height = bounds.max.y - bounds.min.y;
             
-            let zoom_x = (self.viewport_size.0 as f64 - 100.0) / width.max(1.0);
-            let zoom_y = (self.viewport_size.1 as f64 - 100.0) / height.max(1.0);
+            let zoom_x = (self.camera.viewport_width as f64 - 100.0) / width.max(1.0);
+            let zoom_y = (self.camera.viewport_height as f64 - 100.0) / height.max(1.0);
             
-            self.camera_zoom = zoom_x.min(zoom_y).clamp(0.01, 100.0);
+            self.camera.zoom = zoom_x.min(zoom_y).clamp(self.camera.min_zoom, self.camera.max_zoom);
         }
     }
 
@@ -1012,6 +1752,28 @@ impl ZcadApp {
         }
     }
 
+    /// 将当前视图状态写入文档，随文档一起保存
+    fn store_view_state(&mut self) {
+        self.document.view_state = Some(ViewState {
+            center_x: self.camera.center.x,
+            center_y: self.camera.center.y,
+            zoom: self.camera.zoom,
+            show_grid: self.ui_state.show_grid,
+            ortho_mode: self.ui_state.ortho_mode,
+            snap_enabled: self.ui_state.snap_state.enabled,
+        });
+    }
+
+    /// 恢复文档中保存的视图状态
+    fn restore_view_state(&mut self, view_state: &ViewState) {
+        self.camera.stop_inertia();
+        self.camera.center = Point2::new(view_state.center_x, view_state.center_y);
+        self.camera.zoom = view_state.zoom.clamp(self.camera.min_zoom, self.camera.max_zoom);
+        self.ui_state.show_grid = view_state.show_grid;
+        self.ui_state.ortho_mode = view_state.ortho_mode;
+        self.ui_state.snap_state.enabled = view_state.snap_enabled;
+    }
+
     /// 处理文件操作
     fn process_file_operations(&mut self) {
         if let Some(op) = self.pending_file_op.take() {
@@ -1020,12 +1782,22 @@ impl ZcadApp {
                     match Document::open(&path) {
                         Ok(doc) => {
                             self.document = doc;
+                            self.cached_stats = None;
+                            self.scene_cache.invalidate();
                             self.ui_state.clear_selection();
-                            self.zoom_to_fit();
+                            match self.document.view_state.clone() {
+                                Some(view_state) => self.restore_view_state(&view_state),
+                                None => self.zoom_to_fit(),
+                            }
                             self.ui_state.status_message = 
                                 format!("已打开: {}", path.display());
                             info!("Opened file: {}", path.display());
                         }
+                        Err(zcad_file::FileError::Unsupported(format)) => {
+                            self.ui_state.status_message =
+                                format!("打开失败: 暂不支持 {} 格式，请先转换为 DXF", format);
+                            tracing::warn!("Unsupported file format: {}", format);
+                        }
                         Err(e) => {
                             self.ui_state.status_message = 
                                 format!("打开失败: {}", e);
@@ -1034,6 +1806,7 @@ impl ZcadApp {
                     }
                 }
                 FileOperation::Save(path) => {
+                    self.store_view_state();
                     match self.document.save_as(&path) {
                         Ok(_) => {
                             self.ui_state.status_message = 
@@ -1054,6 +1827,7 @@ impl ZcadApp {
     /// 快速保存（已有路径）
     fn quick_save(&mut self) {
         if self.document.file_path().is_some() {
+            self.store_view_state();
             match self.document.save() {
                 Ok(_) => {
                     self.ui_state.status_message = "已保存".to_string();
@@ -1072,9 +1846,27 @@ impl ZcadApp {
 }
 
 impl eframe::App for ZcadApp {
+    fn on_exit(&mut self) {
+        // 保存捕捉、网格和界面设置，下次启动时恢复
+        if let Some(path) = Settings::default_path() {
+            if let Err(e) = Settings::from_state(&self.ui_state).save(&path) {
+                tracing::warn!("Failed to save settings to {}: {}", path.display(), e);
+            }
+        }
+    }
+
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         // 处理文件操作
         self.process_file_operations();
+
+        // 线条抗锯齿
+        let antialiasing = self.ui_state.antialiasing;
+        ctx.tessellation_options_mut(|options| options.feathering = antialiasing);
+
+        // 取出文档变更事件，有变更时使统计缓存失效
+        if !self.document.drain_changes().is_empty() {
+            self.cached_stats = None;
+        }
         
         // 更新窗口标题
         let title = if let Some(path) = self.document.file_path() {
@@ -1094,6 +1886,7 @@ impl eframe::App for ZcadApp {
         let ortho = self.ui_state.ortho_mode;
         let grid = self.ui_state.show_grid;
         let status = self.ui_state.status_message.clone();
+        let action_input = self.action_input.clone();
         let mouse_world = self.ui_state.mouse_world_pos;
         let entity_count = self.document.entity_count();
         let selected_count = self.ui_state.selected_entities.len();
@@ -1102,7 +1895,7 @@ impl eframe::App for ZcadApp {
         let selected_info: Option<(String, Vec<String>)> = if selected_count == 1 {
             self.document.get_entity(&self.ui_state.selected_entities[0]).map(|e| {
                 let name = e.geometry.type_name().to_string();
-                let props: Vec<String> = match &e.geometry {
+                let mut props: Vec<String> = match &e.geometry {
                     Geometry::Line(l) => vec![
                         format!("起点: ({:.2}, {:.2})", l.start.x, l.start.y),
                         format!("终点: ({:.2}, {:.2})", l.end.x, l.end.y),
@@ -1118,6 +1911,12 @@ impl eframe::App for ZcadApp {
                     ],
                     _ => vec![],
                 };
+                if let Some(area) = e.geometry.area() {
+                    props.push(format!("面积: {:.3}", area));
+                }
+                if e.locked {
+                    props.push("🔒 已锁定".to_string());
+                }
                 (name, props)
             })
         } else { None };
@@ -1134,6 +1933,8 @@ impl eframe::App for ZcadApp {
                 ui.menu_button("文件", |ui| {
                     if ui.button("📄 新建 (Ctrl+N)").clicked() {
                         self.document = Document::new();
+                        self.cached_stats = None;
+                        self.scene_cache.invalidate();
                         self.ui_state.clear_selection();
                         self.ui_state.status_message = "新文档".to_string();
                         ui.close();
@@ -1161,6 +1962,38 @@ impl eframe::App for ZcadApp {
                         self.delete_selected_entities();
                         ui.close();
                     }
+                    if ui.button("🔒 锁定/解锁选中").clicked() {
+                        self.toggle_lock_selected();
+                        ui.close();
+                    }
+                    if ui.button("闭合/打开多段线").clicked() {
+                        self.toggle_close_selected_polylines();
+                        ui.close();
+                    }
+                    ui.separator();
+                    if ui.button("由选择创建编组").clicked() {
+                        self.create_group_from_selection();
+                        ui.close();
+                    }
+                    ui.menu_button("编组", |ui| {
+                        let mut names: Vec<String> = self.document.groups().keys().cloned().collect();
+                        names.sort();
+                        if names.is_empty() {
+                            ui.label("（无编组）");
+                        }
+                        for name in names {
+                            ui.horizontal(|ui| {
+                                if ui.button(format!("选择 {}", name)).clicked() {
+                                    self.select_group(&name);
+                                    ui.close();
+                                }
+                                if ui.small_button("✖").on_hover_text("删除编组").clicked() {
+                                    self.document.remove_group(&name);
+                                    self.ui_state.status_message = format!("已删除 {}", name);
+                                }
+                            });
+                        }
+                    });
                     ui.separator();
                     if ui.button("↩ 撤销 (Ctrl+Z)").clicked() {
                         self.do_undo();
@@ -1180,10 +2013,77 @@ impl eframe::App for ZcadApp {
                         self.ui_state.show_grid = !self.ui_state.show_grid;
                         ui.close();
                     }
+                    ui.menu_button("网格模式", |ui| {
+                        let mode = self.ui_state.grid_mode;
+                        if ui.radio(mode == GridMode::Rectangular, "正交").clicked() {
+                            self.ui_state.set_grid_mode(GridMode::Rectangular);
+                        }
+                        if ui.radio(mode == GridMode::Isometric, "等轴测").clicked() {
+                            self.ui_state.set_grid_mode(GridMode::Isometric);
+                        }
+                        ui.horizontal(|ui| {
+                            let mut degrees = match mode {
+                                GridMode::Rotated(angle) => angle.to_degrees(),
+                                _ => 0.0,
+                            };
+                            let rotated = matches!(mode, GridMode::Rotated(_));
+                            if ui.radio(rotated, "旋转").clicked() {
+                                self.ui_state.set_grid_mode(GridMode::Rotated(degrees.to_radians()));
+                            }
+                            let changed = ui
+                                .add(egui::DragValue::new(&mut degrees).range(-90.0..=90.0).suffix("°"))
+                                .changed();
+                            if changed {
+                                self.ui_state.set_grid_mode(GridMode::Rotated(degrees.to_radians()));
+                            }
+                        });
+                        ui.horizontal(|ui| {
+                            ui.label("捕捉步长");
+                            let mut step = self.ui_state.snap_step();
+                            if ui.add(egui::DragValue::new(&mut step).range(0.001..=f64::MAX).speed(0.1)).changed() {
+                                self.ui_state.set_snap_step(step);
+                            }
+                        });
+                    });
+                    ui.menu_button("背景主题", |ui| {
+                        let preset = self.ui_state.theme.preset;
+                        if ui.radio(preset == ThemePreset::Dark, "深色").clicked() {
+                            self.ui_state.theme = Theme::from_preset(ThemePreset::Dark);
+                        }
+                        if ui.radio(preset == ThemePreset::Light, "浅色").clicked() {
+                            self.ui_state.theme = Theme::from_preset(ThemePreset::Light);
+                        }
+                        ui.checkbox(&mut self.ui_state.white_paper, "布局使用白色图纸");
+                    });
+                    ui.checkbox(&mut self.ui_state.dim_text_mask, "标注文字背景遮罩");
+                    ui.checkbox(&mut self.ui_state.antialiasing, "线条抗锯齿");
+                    ui.menu_button("对象捕捉设置", |ui| {
+                        for snap_type in SnapType::ALL {
+                            let mut enabled = self.ui_state.snap_state.is_snap_type_enabled(snap_type);
+                            let label = format!("{} ({})", snap_type.name(), snap_type.shortcut());
+                            if ui.checkbox(&mut enabled, label).changed() {
+                                self.ui_state.snap_state.toggle_snap_type(snap_type);
+                            }
+                        }
+                    });
                     if ui.button(format!("{} 正交 (F8)", if ortho { "☑" } else { "☐" })).clicked() {
                         self.ui_state.ortho_mode = !self.ui_state.ortho_mode;
                         ui.close();
                     }
+                    if ui.button(format!("{} 惯性平移", if self.camera.inertia_enabled { "☑" } else { "☐" })).clicked() {
+                        self.camera.inertia_enabled = !self.camera.inertia_enabled;
+                        self.camera.stop_inertia();
+                        ui.close();
+                    }
+                    ui.separator();
+                    if ui.button("📊 图纸信息").clicked() {
+                        self.show_statistics = true;
+                        ui.close();
+                    }
+                    if ui.button("🕘 历史记录").clicked() {
+                        self.show_history = true;
+                        ui.close();
+                    }
                 });
                 ui.menu_button("绘图", |ui| {
                     if ui.button("╱ 直线 (L)").clicked() {
@@ -1198,6 +2098,29 @@ impl eframe::App for ZcadApp {
                         self.ui_state.set_tool(DrawingTool::Rectangle);
                         ui.close();
                     }
+                    ui.separator();
+                    if ui.button("两点之间的中点 (M2P)").clicked() {
+                        self.ui_state.execute_command("M2P");
+                        ui.close();
+                    }
+                });
+                ui.menu_button("修改", |ui| {
+                    if ui.button("圆角 (F)").clicked() {
+                        self.start_action(ActionType::Fillet, &[]);
+                        ui.close();
+                    }
+                    if ui.button("倒角 (CHA)").clicked() {
+                        self.start_action(ActionType::Chamfer, &[]);
+                        ui.close();
+                    }
+                    ui.menu_button("区域布尔运算 (RB)", |ui| {
+                        for (label, option) in [("并集", "U"), ("差集", "S"), ("交集", "I")] {
+                            if ui.button(label).clicked() {
+                                self.start_action(ActionType::RegionBoolean, &[option]);
+                                ui.close();
+                            }
+                        }
+                    });
                 });
             });
         });
@@ -1224,6 +2147,11 @@ impl eframe::App for ZcadApp {
                 if ui.selectable_label(current_tool == DrawingTool::Polyline, "⌇ 多段线").clicked() {
                     self.ui_state.set_tool(DrawingTool::Polyline);
                 }
+                if current_tool == DrawingTool::Rectangle {
+                    ui.label("圆角");
+                    ui.add(egui::DragValue::new(&mut self.rectangle_fillet_radius).range(0.0..=f64::MAX).speed(0.1))
+                        .on_hover_text("矩形圆角半径，0 为直角");
+                }
                 ui.separator();
                 if ui.button("🗑").on_hover_text("删除选中").clicked() {
                     self.delete_selected_entities();
@@ -1241,6 +2169,9 @@ impl eframe::App for ZcadApp {
                 if ui.selectable_label(grid, "#").on_hover_text("网格 (G)").clicked() {
                     self.ui_state.show_grid = !self.ui_state.show_grid;
                 }
+                if ui.selectable_label(self.ui_state.pan_mode, "✋").on_hover_text("平移模式（或按住空格拖动）").clicked() {
+                    self.ui_state.pan_mode = !self.ui_state.pan_mode;
+                }
                 if ui.button("⊞").on_hover_text("缩放至全部 (Z)").clicked() {
                     self.zoom_to_fit();
                 }
@@ -1253,17 +2184,25 @@ impl eframe::App for ZcadApp {
         let snap_info = self.ui_state.snap_state.current_snap.as_ref().map(|s| {
             (s.snap_type.name().to_string(), s.point)
         });
+        let snap_override = self.ui_state.snap_state.override_type();
         let effective_pos = self.ui_state.effective_point();
 
         egui::TopBottomPanel::bottom("status").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 ui.label(&status);
+                if !action_input.is_empty() {
+                    ui.monospace(format!("» {}", action_input));
+                }
                 
                 // 捕捉状态显示
                 if let Some((snap_name, _)) = &snap_info {
                     ui.separator();
                     ui.colored_label(egui::Color32::YELLOW, format!("⊕ {}", snap_name));
                 }
+                if let Some(only) = snap_override {
+                    ui.separator();
+                    ui.colored_label(egui::Color32::LIGHT_BLUE, format!("仅{}", only.name()));
+                }
                 
                 ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                     ui.label(format!("X:{:>8.2} Y:{:>8.2}", effective_pos.x, effective_pos.y));
@@ -1315,12 +2254,81 @@ impl eframe::App for ZcadApp {
             ui.label(format!("Y: {:.4}", mouse_world.y));
         });
 
+        // ===== 图纸信息对话框 =====
+        if self.show_statistics {
+            let document = &self.document;
+            let stats = self.cached_stats.get_or_insert_with(|| document.statistics()).clone();
+            egui::Window::new("图纸信息")
+                .open(&mut self.show_statistics)
+                .resizable(false)
+                .collapsible(false)
+                .show(ctx, |ui| {
+                    egui::Grid::new("drawing_stats").num_columns(2).striped(true).show(ui, |ui| {
+                        ui.label("实体总数");
+                        ui.label(stats.entity_count.to_string());
+                        ui.end_row();
+                        for (type_name, count) in &stats.counts_by_type {
+                            ui.label(format!("  {}", type_name));
+                            ui.label(count.to_string());
+                            ui.end_row();
+                        }
+                        ui.label("图层数");
+                        ui.label(stats.layer_count.to_string());
+                        ui.end_row();
+                        ui.label("总长度");
+                        ui.label(format!("{:.4}", stats.total_length));
+                        ui.end_row();
+                        ui.label("范围");
+                        match &stats.bounds {
+                            Some(b) => ui.label(format!(
+                                "({:.2}, {:.2}) - ({:.2}, {:.2})",
+                                b.min.x, b.min.y, b.max.x, b.max.y
+                            )),
+                            None => ui.label("-"),
+                        };
+                        ui.end_row();
+                    });
+                });
+        }
+
+        // ===== 历史记录面板 =====
+        if self.show_history {
+            let entries = self.history.entries();
+            let at_start = entries.iter().all(|e| e.is_undone);
+            let mut target = None;
+            let mut open = self.show_history;
+            egui::Window::new("历史记录")
+                .open(&mut open)
+                .default_width(220.0)
+                .show(ctx, |ui| {
+                    egui::ScrollArea::vertical().max_height(360.0).show(ui, |ui| {
+                        if ui.selectable_label(at_start, "（初始状态）").clicked() {
+                            target = Some(None);
+                        }
+                        for entry in &entries {
+                            let mut text = egui::RichText::new(&entry.description);
+                            if entry.is_undone {
+                                text = text.weak().italics();
+                            }
+                            if ui.selectable_label(entry.is_current, text).clicked() {
+                                target = Some(Some(entry.id));
+                            }
+                        }
+                    });
+                });
+            self.show_history = open;
+            if let Some(target) = target {
+                self.goto_history(target);
+            }
+        }
+
         // ===== 中央绘图区域 =====
+        let background = self.ui_state.active_theme().background;
         egui::CentralPanel::default()
-            .frame(egui::Frame::NONE.fill(egui::Color32::from_rgb(30, 30, 46)))
+            .frame(egui::Frame::NONE.fill(egui::Color32::from_rgb(background.r, background.g, background.b)))
             .show(ctx, |ui| {
                 let available_rect = ui.available_rect_before_wrap();
-                self.viewport_size = (available_rect.width(), available_rect.height());
+                self.camera.set_viewport(available_rect.width() as u32, available_rect.height() as u32);
                 
                 let (response, painter) = ui.allocate_painter(available_rect.size(), egui::Sense::click_and_drag());
                 let rect = response.rect;
@@ -1328,8 +2336,17 @@ impl eframe::App for ZcadApp {
                 // 处理鼠标位置
                 if let Some(hover_pos) = response.hover_pos() {
                     self.ui_state.mouse_world_pos = self.screen_to_world(hover_pos, &rect);
+                    // 按住修饰键时临时覆盖捕捉类型
+                    let modifiers = ui.input(|i| i.modifiers);
+                    self.ui_state.snap_state.apply_modifier_override(modifiers.into());
                     // 更新捕捉点
                     self.update_snap();
+                    self.update_hover();
+                    if self.active_action.is_some() {
+                        self.drive_action(modifiers.command, |action, ctx| action.on_mouse_move(ctx));
+                    }
+                } else {
+                    self.ui_state.hovered_entity = None;
                 }
 
                 // 处理滚轮缩放
@@ -1339,25 +2356,51 @@ impl eframe::App for ZcadApp {
                     
                     // 缩放时保持鼠标位置不变
                     if let Some(hover_pos) = response.hover_pos() {
-                        let world_before = self.screen_to_world(hover_pos, &rect);
-                        self.camera_zoom *= zoom_factor;
-                        self.camera_zoom = self.camera_zoom.clamp(0.01, 100.0);
-                        let world_after = self.screen_to_world(hover_pos, &rect);
-                        self.camera_center.x += world_before.x - world_after.x;
-                        self.camera_center.y += world_before.y - world_after.y;
+                        let screen = hover_pos - rect.min;
+                        self.camera.zoom_at(Point2::new(screen.x as f64, screen.y as f64), zoom_factor);
                     }
                 }
 
-                // 处理中键平移
-                if response.dragged_by(egui::PointerButton::Middle) {
+                // 处理平移：中键拖动、平移模式下左键拖动、按住空格左键拖动
+                let dt = ui.input(|i| i.stable_dt).max(1.0 / 240.0) as f64;
+                let space_held = ui.input(|i| i.key_down(egui::Key::Space));
+                let left_pans = self.ui_state.pan_mode || space_held;
+                let panning = response.dragged_by(egui::PointerButton::Middle)
+                    || (left_pans && response.dragged_by(egui::PointerButton::Primary));
+                if panning {
                     let delta = response.drag_delta();
-                    self.camera_center.x -= (delta.x as f64) / self.camera_zoom;
-                    self.camera_center.y += (delta.y as f64) / self.camera_zoom;
+                    let delta = Vector2::new(delta.x as f64, delta.y as f64);
+                    self.camera.stop_inertia();
+                    self.camera.pan_screen(delta);
+                    self.pan_drag_velocity = delta / dt;
+                } else if response.drag_stopped() && self.pan_drag_velocity.norm() > 0.0 {
+                    self.camera.end_pan(self.pan_drag_velocity);
+                    self.pan_drag_velocity = Vector2::zeros();
+                } else {
+                    self.camera.update_inertia(dt);
+                }
+
+                // 处理框选：选择工具下左键拖动
+                let can_box_select = !left_pans
+                    && self.active_action.is_none()
+                    && self.ui_state.current_tool == DrawingTool::Select
+                    && matches!(self.ui_state.edit_state, EditState::Idle);
+                if can_box_select && response.drag_started_by(egui::PointerButton::Primary) {
+                    if let Some(press_pos) = ui.input(|i| i.pointer.press_origin()) {
+                        self.box_select_start = Some(self.screen_to_world(press_pos, &rect));
+                    }
+                }
+                if response.drag_stopped_by(egui::PointerButton::Primary) {
+                    if let Some(start) = self.box_select_start.take() {
+                        let shift = ui.input(|i| i.modifiers.shift);
+                        self.finish_box_select(start, self.ui_state.mouse_world_pos, shift);
+                    }
                 }
 
                 // 处理左键点击
-                if response.clicked_by(egui::PointerButton::Primary) {
-                    self.handle_left_click();
+                if response.clicked_by(egui::PointerButton::Primary) && !left_pans {
+                    let ctrl_pressed = ui.input(|i| i.modifiers.command);
+                    self.handle_left_click(ctrl_pressed);
                 }
 
                 // 处理右键（结束多段线或取消）
@@ -1365,104 +2408,61 @@ impl eframe::App for ZcadApp {
                     self.handle_right_click();
                 }
 
-                // 处理键盘快捷键
-                ui.input(|i| {
-                    // 文件操作
-                    if i.modifiers.command && i.key_pressed(egui::Key::N) {
-                        self.document = Document::new();
-                        self.ui_state.clear_selection();
-                        self.ui_state.status_message = "新文档".to_string();
-                    }
-                    if i.modifiers.command && i.key_pressed(egui::Key::O) {
-                        self.show_open_dialog();
-                    }
-                    if i.modifiers.command && i.key_pressed(egui::Key::S) {
-                        if i.modifiers.shift {
-                            self.show_save_dialog();
-                        } else {
-                            self.quick_save();
-                        }
-                    }
-                    
-                    // 编辑操作
-                    if i.key_pressed(egui::Key::Escape) {
-                        self.ui_state.cancel();
-                    }
-                    if i.key_pressed(egui::Key::Delete) || i.key_pressed(egui::Key::Backspace) {
-                        self.delete_selected_entities();
-                    }
-                    // 撤销 Ctrl+Z
-                    if i.modifiers.command && i.key_pressed(egui::Key::Z) && !i.modifiers.shift {
-                        self.do_undo();
-                    }
-                    // 重做 Ctrl+Y 或 Ctrl+Shift+Z
-                    if i.modifiers.command && (i.key_pressed(egui::Key::Y) || (i.key_pressed(egui::Key::Z) && i.modifiers.shift)) {
-                        self.do_redo();
-                    }
-                    
-                    // 绘图工具
-                    if i.key_pressed(egui::Key::L) {
-                        self.ui_state.set_tool(DrawingTool::Line);
-                    }
-                    if i.key_pressed(egui::Key::C) {
-                        self.ui_state.set_tool(DrawingTool::Circle);
-                    }
-                    if i.key_pressed(egui::Key::R) {
-                        self.ui_state.set_tool(DrawingTool::Rectangle);
-                    }
-                    if i.key_pressed(egui::Key::Space) {
-                        self.ui_state.set_tool(DrawingTool::Select);
-                    }
-                    
-                    // 视图操作
-                    if i.key_pressed(egui::Key::Z) {
-                        self.zoom_to_fit();
-                    }
-                    if i.key_pressed(egui::Key::G) {
-                        self.ui_state.show_grid = !self.ui_state.show_grid;
-                    }
-                    if i.key_pressed(egui::Key::F3) {
-                        self.ui_state.snap_state.enabled = !self.ui_state.snap_state.enabled;
-                        let status = if self.ui_state.snap_state.enabled { "捕捉已启用" } else { "捕捉已禁用" };
-                        self.ui_state.status_message = status.to_string();
-                    }
-                    if i.key_pressed(egui::Key::F8) {
-                        self.ui_state.ortho_mode = !self.ui_state.ortho_mode;
-                        let status = if self.ui_state.ortho_mode { "正交模式已启用" } else { "正交模式已禁用" };
-                        self.ui_state.status_message = status.to_string();
-                    }
-                    // 圆弧快捷键
-                    if i.key_pressed(egui::Key::A) {
-                        self.ui_state.set_tool(DrawingTool::Arc);
-                    }
-                    // 多段线快捷键
-                    if i.key_pressed(egui::Key::P) {
-                        self.ui_state.set_tool(DrawingTool::Polyline);
-                    }
-                });
+                // 处理动态输入和命令输入（需在快捷键之前，以便拦截 Tab/回车/退格/Esc）
+                self.handle_action_input(ui);
+                self.handle_dynamic_input(ui);
+
+                // 处理键盘快捷键（统一经由快捷键映射表分发）
+                let shortcuts = ui.input(|i| self.ui_state.keymap.pressed_actions(i));
+                for shortcut in shortcuts {
+                    self.handle_shortcut(ui.ctx(), shortcut);
+                }
 
                 // ===== 绘制 =====
                 // 绘制网格
                 self.draw_grid(&painter, &rect);
 
-                // 绘制所有实体
-                for entity in self.document.all_entities() {
-                    let color = if self.ui_state.selected_entities.contains(&entity.id) {
-                        Color::from_hex(0x00FF00)
-                    } else if entity.properties.color.is_by_layer() {
-                        self.document.layers.get_layer_by_id(entity.layer_id)
-                            .map(|l| l.color).unwrap_or(Color::WHITE)
-                    } else {
-                        entity.properties.color
+                // 绘制可见区域内的实体：所有实体按本色画入静态缓存，
+                // 选中和悬停的实体每帧在其上叠加高亮
+                let theme = self.ui_state.active_theme();
+                let key = SceneKey {
+                    revision: self.document.revision(),
+                    center: self.camera.center,
+                    zoom: self.camera.zoom,
+                    rect,
+                    theme,
+                    style: self.display_style_digest(ctx.pixels_per_point()),
+                };
+                let mut visible = self.document.query_view(&self.camera.visible_bounds());
+                visible.retain(|e| self.document.is_displayed(e));
+                let mut scene_cache = std::mem::take(&mut self.scene_cache);
+                let shapes = scene_cache.shapes(key, || {
+                    let mut shapes = Vec::new();
+                    for entity in &visible {
+                        let color = theme.display_color(self.document.effective_color(entity));
+                        self.geometry_shapes(&painter, &rect, &entity.geometry, color, 1.5, &mut shapes);
+                    }
+                    shapes
+                });
+                painter.extend(shapes.iter().cloned());
+                self.scene_cache = scene_cache;
+
+                for (entity, layer) in self.ui_state.draw_order(visible) {
+                    let (color, width) = match layer {
+                        DrawLayer::Normal => continue,
+                        DrawLayer::Selected => (Color::from_hex(0x00FF00), 1.5),
+                        DrawLayer::Hovered => (Color::from_hex(0x66CCFF), 3.0),
                     };
-                    self.draw_geometry(&painter, &rect, &entity.geometry, color);
+                    self.draw_geometry_with_width(&painter, &rect, &entity.geometry, color, width);
                 }
 
                 // 绘制预览
                 self.draw_preview(&painter, &rect);
+                self.draw_action_preview(&painter, &rect);
+                self.draw_grips(&painter, &rect);
 
-                // 绘制正交辅助线
-                if self.ui_state.ortho_mode {
+                // 绘制正交辅助线（极轴追踪开启时正交不生效）
+                if self.ui_state.ortho_active() {
                     if let EditState::Drawing { points, .. } = &self.ui_state.edit_state {
                         if let Some(&reference) = points.last() {
                             self.draw_ortho_guides(&painter, &rect, reference);
@@ -1470,6 +2470,11 @@ impl eframe::App for ZcadApp {
                     }
                 }
 
+                // 绘制框选矩形
+                if let Some(start) = self.box_select_start {
+                    self.draw_selection_box(&painter, &rect, start, self.ui_state.mouse_world_pos);
+                }
+
                 // 绘制捕捉标记
                 if let Some(ref snap) = self.ui_state.snap_state.current_snap {
                     if self.ui_state.snap_state.enabled {
@@ -1477,6 +2482,27 @@ impl eframe::App for ZcadApp {
                     }
                 }
 
+                // 光标旁的捕捉提示（捕捉类型、当前线段长度和角度）
+                if let (Some(text), Some(hover_pos)) = (self.ui_state.snap_tooltip(), response.hover_pos()) {
+                    let galley = painter.layout_no_wrap(
+                        text,
+                        egui::FontId::monospace(12.0),
+                        egui::Color32::from_rgb(230, 230, 230),
+                    );
+                    let pos = hover_pos + egui::vec2(16.0, 16.0);
+                    painter.rect_filled(
+                        egui::Rect::from_min_size(pos, galley.size()).expand(3.0),
+                        3.0,
+                        egui::Color32::from_rgba_unmultiplied(20, 20, 30, 220),
+                    );
+                    painter.galley(pos, galley, egui::Color32::WHITE);
+                }
+
+                // 光标旁的动态输入框
+                if let Some(hover_pos) = response.hover_pos() {
+                    self.draw_dynamic_input(&painter, hover_pos);
+                }
+
                 // 绘制十字光标（使用捕捉点如果有的话）
                 if response.hovered() {
                     let cursor_pos = self.ui_state.effective_point();
@@ -1553,7 +2579,12 @@ fn main() -> Result<()> {
         Box::new(|cc| {
             // 加载中文字体
             setup_chinese_fonts(&cc.egui_ctx);
-            Ok(Box::new(ZcadApp::default()))
+            // 恢复上次保存的捕捉、网格和界面设置
+            let mut app = ZcadApp::default();
+            if let Some(path) = Settings::default_path() {
+                Settings::load_or_default(&path).apply(&mut app.ui_state);
+            }
+            Ok(Box::new(app))
         }),
     ).map_err(|e| anyhow::anyhow!("eframe error: {}", e))?;
 
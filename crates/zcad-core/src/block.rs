@@ -5,6 +5,7 @@
 
 use crate::entity::Entity;
 use crate::math::{Point2, Vector2};
+use crate::transform::Transform2D;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -184,6 +185,28 @@ impl BlockReference {
         )
     }
 
+    /// 从块坐标到世界坐标的变换（插入点为 `insertion_point`）
+    pub fn transform_at(&self, insertion_point: Point2, base_point: Point2) -> Transform2D {
+        Transform2D::translation(insertion_point.x, insertion_point.y)
+            .then(&Transform2D::rotation(self.rotation))
+            .then(&Transform2D::scale(self.scale_x, self.scale_y))
+            .then(&Transform2D::translation(-base_point.x, -base_point.y))
+    }
+
+    /// 分解块参照：将块中的实体变换到世界坐标（考虑阵列），每个实体分配新ID
+    pub fn explode(&self, block: &Block) -> Vec<Entity> {
+        let mut result = Vec::new();
+        for insertion_point in self.all_insertion_points() {
+            let transform = self.transform_at(insertion_point, block.base_point);
+            for entity in &block.entities {
+                let mut exploded = entity.duplicate();
+                exploded.geometry = transform.transform_geometry(&entity.geometry);
+                result.push(exploded);
+            }
+        }
+        result
+    }
+
     /// 获取所有插入点（考虑阵列）
     pub fn all_insertion_points(&self) -> Vec<Point2> {
         let mut points = Vec::with_capacity((self.column_count * self.row_count) as usize);
@@ -345,4 +368,36 @@ mod tests {
         assert!(table.contains("Block1"));
         assert!(table.contains("Block2"));
     }
+
+    #[test]
+    fn test_block_reference_explode() {
+        let mut block = Block::new("Door", Point2::new(1.0, 0.0));
+        block.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(1.0, 0.0),
+            Point2::new(3.0, 0.0),
+        ))));
+
+        let reference = BlockReference::new("Door", Point2::new(10.0, 10.0))
+            .with_uniform_scale(2.0)
+            .with_rotation_degrees(90.0)
+            .with_array(2, 1, 5.0, 0.0);
+
+        let entities = reference.explode(&block);
+        assert_eq!(entities.len(), 2);
+        assert_ne!(entities[0].id, block.entities[0].id);
+
+        match &entities[0].geometry {
+            Geometry::Line(line) => {
+                // 基点落在插入点，长度 2 × 2 = 4，旋转到 +Y 方向
+                assert!((line.start - Point2::new(10.0, 10.0)).norm() < 1e-9);
+                assert!((line.end - Point2::new(10.0, 14.0)).norm() < 1e-9);
+            }
+            _ => panic!("应为线段"),
+        }
+        match &entities[1].geometry {
+            // 第二列沿旋转后的 X 方向偏移 5
+            Geometry::Line(line) => assert!((line.start - Point2::new(10.0, 15.0)).norm() < 1e-9),
+            _ => panic!("应为线段"),
+        }
+    }
 }
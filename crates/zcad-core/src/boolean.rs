@@ -0,0 +1,362 @@
+//! 区域布尔运算
+//!
+//! 对两个闭合区域执行并集、交集、差集和异或，结果为一个或多个闭合轮廓。
+//!
+//! 算法：先在两轮廓的所有交点处切分边，再按每段边中点相对另一轮廓的位置
+//! （内部 / 外部 / 同向重合 / 反向重合）挑选需要保留的边，最后把保留的边首尾相接成环。
+//! 圆、圆弧段、椭圆和样条边界先离散为折线。
+
+use crate::geometry::{Geometry, Polyline};
+use crate::math::{Point2, Vector2};
+use crate::parametric::BooleanOp;
+
+/// 整圆离散的段数
+const CURVE_SEGMENTS: usize = 64;
+
+/// 边中点相对另一轮廓的位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum EdgeClass {
+    Inside,
+    Outside,
+    /// 与另一轮廓的边重合且同向
+    SharedSame,
+    /// 与另一轮廓的边重合且反向
+    SharedOpposite,
+}
+
+/// 对两个简单多边形执行布尔运算
+///
+/// 输入轮廓方向任意（内部统一为逆时针），首尾不需要重复。
+/// 结果中外轮廓为逆时针，孔洞（如差集中被完全包含的减去区域）为顺时针。
+pub fn polygon_boolean(a: &[Point2], b: &[Point2], op: BooleanOp) -> Vec<Vec<Point2>> {
+    let (Some(a), Some(b)) = (normalize(a), normalize(b)) else {
+        return Vec::new();
+    };
+    let eps = tolerance(&a, &b);
+
+    let a_edges = split_edges(&a, &b, eps);
+    let b_edges = split_edges(&b, &a, eps);
+
+    let mut selected = Vec::new();
+    for &(start, end) in &a_edges {
+        let class = classify(start, end, &b, eps);
+        let keep = match op {
+            BooleanOp::Union => matches!(class, EdgeClass::Outside | EdgeClass::SharedSame),
+            BooleanOp::Intersection => matches!(class, EdgeClass::Inside | EdgeClass::SharedSame),
+            BooleanOp::Difference => matches!(class, EdgeClass::Outside | EdgeClass::SharedOpposite),
+            BooleanOp::Xor => matches!(class, EdgeClass::Outside | EdgeClass::Inside),
+        };
+        if keep {
+            // 异或中 A 落在 B 内的部分成为孔洞边界，需反向
+            if op == BooleanOp::Xor && class == EdgeClass::Inside {
+                selected.push((end, start));
+            } else {
+                selected.push((start, end));
+            }
+        }
+    }
+    // 重合边只从 A 中取，避免重复
+    for &(start, end) in &b_edges {
+        match (op, classify(start, end, &a, eps)) {
+            (BooleanOp::Union, EdgeClass::Outside)
+            | (BooleanOp::Intersection, EdgeClass::Inside)
+            | (BooleanOp::Xor, EdgeClass::Outside) => selected.push((start, end)),
+            (BooleanOp::Difference, EdgeClass::Inside) | (BooleanOp::Xor, EdgeClass::Inside) => {
+                selected.push((end, start))
+            }
+            _ => {}
+        }
+    }
+
+    chain_loops(selected, eps)
+        .into_iter()
+        .map(|ring| simplify(ring, eps))
+        .filter(|ring| ring.len() >= 3)
+        .collect()
+}
+
+/// 闭合几何的轮廓（曲线部分离散为折线）
+///
+/// 支持圆、整椭圆、闭合多段线（含凸度圆弧段）和闭合样条，其他几何返回 `None`。
+/// 采样得到的首尾重复点由 [`polygon_boolean`] 自行去除。
+pub fn region_outline(geometry: &Geometry) -> Option<Vec<Point2>> {
+    let points = match geometry {
+        Geometry::Circle(circle) => (0..CURVE_SEGMENTS)
+            .map(|i| circle.point_at_angle(std::f64::consts::TAU * i as f64 / CURVE_SEGMENTS as f64))
+            .collect(),
+        Geometry::Ellipse(ellipse) if ellipse.is_full() => ellipse.sample_points(CURVE_SEGMENTS),
+        Geometry::Polyline(polyline) if polyline.closed => polyline_outline(polyline),
+        Geometry::Spline(spline) if spline.closed => spline.sample_points(CURVE_SEGMENTS),
+        _ => return None,
+    };
+    (points.len() >= 3).then_some(points)
+}
+
+/// 对两个闭合几何执行布尔运算，结果为闭合多段线
+///
+/// 任一几何不是闭合区域时返回 `None`；两区域运算结果为空（如交集不相交）时返回空列表。
+pub fn region_boolean(a: &Geometry, b: &Geometry, op: BooleanOp) -> Option<Vec<Polyline>> {
+    let a = region_outline(a)?;
+    let b = region_outline(b)?;
+    Some(
+        polygon_boolean(&a, &b, op)
+            .into_iter()
+            .map(|ring| Polyline::from_points(ring, true))
+            .collect(),
+    )
+}
+
+/// 多边形有向面积（逆时针为正）
+pub fn signed_area(points: &[Point2]) -> f64 {
+    let n = points.len();
+    let mut sum = 0.0;
+    for i in 0..n {
+        let p = points[i];
+        let q = points[(i + 1) % n];
+        sum += p.x * q.y - q.x * p.y;
+    }
+    sum / 2.0
+}
+
+/// 多段线轮廓，凸度圆弧段按弧长比例离散
+fn polyline_outline(polyline: &Polyline) -> Vec<Point2> {
+    let n = polyline.vertices.len();
+    let mut points = Vec::new();
+    for i in 0..n {
+        let v1 = &polyline.vertices[i];
+        let p2 = polyline.vertices[(i + 1) % n].point;
+        points.push(v1.point);
+        if v1.bulge.abs() < 1e-12 {
+            continue;
+        }
+
+        // 圆心位于弦的左侧（逆时针弧）或右侧（顺时针弧）
+        let sweep = 4.0 * v1.bulge.atan();
+        let chord = p2 - v1.point;
+        let normal = Vector2::new(-chord.y, chord.x);
+        let center = v1.point + chord / 2.0 + normal / (2.0 * (sweep / 2.0).tan());
+        let steps = ((sweep.abs() / std::f64::consts::TAU * CURVE_SEGMENTS as f64).ceil() as usize).max(2);
+        let radial = v1.point - center;
+        for k in 1..steps {
+            let (sin, cos) = (sweep * k as f64 / steps as f64).sin_cos();
+            points.push(center + Vector2::new(radial.x * cos - radial.y * sin, radial.x * sin + radial.y * cos));
+        }
+    }
+    points
+}
+
+/// 去除重复点并统一为逆时针，退化轮廓返回 `None`
+fn normalize(points: &[Point2]) -> Option<Vec<Point2>> {
+    let mut ring: Vec<Point2> = Vec::with_capacity(points.len());
+    for &p in points {
+        if ring.last().is_none_or(|last| (p - last).norm() > 1e-12) {
+            ring.push(p);
+        }
+    }
+    while ring.len() > 1 && (ring[0] - ring[ring.len() - 1]).norm() <= 1e-12 {
+        ring.pop();
+    }
+    if ring.len() < 3 || signed_area(&ring).abs() < 1e-12 {
+        return None;
+    }
+    if signed_area(&ring) < 0.0 {
+        ring.reverse();
+    }
+    Some(ring)
+}
+
+/// 与图形尺寸成比例的几何容差
+fn tolerance(a: &[Point2], b: &[Point2]) -> f64 {
+    let extent = a
+        .iter()
+        .chain(b)
+        .fold(0.0_f64, |m, p| m.max(p.x.abs()).max(p.y.abs()));
+    extent.max(1.0) * 1e-9
+}
+
+fn edges(ring: &[Point2]) -> impl Iterator<Item = (Point2, Point2)> + '_ {
+    (0..ring.len()).map(move |i| (ring[i], ring[(i + 1) % ring.len()]))
+}
+
+/// 在与另一轮廓的交点处切分所有边
+fn split_edges(ring: &[Point2], other: &[Point2], eps: f64) -> Vec<(Point2, Point2)> {
+    let mut result = Vec::new();
+    for (p, q) in edges(ring) {
+        let d = q - p;
+        let len2 = d.norm_squared();
+        let mut params = Vec::new();
+        for (r, s) in edges(other) {
+            let e = s - r;
+            let denom = d.x * e.y - d.y * e.x;
+            if denom.abs() > eps * d.norm().max(e.norm()) {
+                let w = r - p;
+                let t = (w.x * e.y - w.y * e.x) / denom;
+                let u = (w.x * d.y - w.y * d.x) / denom;
+                let slack_t = eps / len2.sqrt();
+                let slack_u = eps / e.norm();
+                if (-slack_u..=1.0 + slack_u).contains(&u) && t > slack_t && t < 1.0 - slack_t {
+                    params.push(t);
+                }
+            } else {
+                // 平行：另一条边落在本边上的端点也是切分点
+                for x in [r, s] {
+                    let t = (x - p).dot(&d) / len2;
+                    let foot = p + d * t;
+                    if (x - foot).norm() <= eps && t > 0.0 && t < 1.0 {
+                        params.push(t);
+                    }
+                }
+            }
+        }
+        params.sort_by(|a, b| a.total_cmp(b));
+
+        let mut start = p;
+        for t in params {
+            let point = p + d * t;
+            if (point - start).norm() > eps {
+                result.push((start, point));
+                start = point;
+            }
+        }
+        if (q - start).norm() > eps {
+            result.push((start, q));
+        }
+    }
+    result
+}
+
+/// 判断边相对轮廓的位置（按边中点）
+fn classify(start: Point2, end: Point2, ring: &[Point2], eps: f64) -> EdgeClass {
+    let mid = Point2::new((start.x + end.x) / 2.0, (start.y + end.y) / 2.0);
+    let dir = end - start;
+    for (p, q) in edges(ring) {
+        let d = q - p;
+        let t = ((mid - p).dot(&d) / d.norm_squared()).clamp(0.0, 1.0);
+        if (mid - (p + d * t)).norm() <= eps * 10.0 {
+            return if dir.dot(&d) > 0.0 {
+                EdgeClass::SharedSame
+            } else {
+                EdgeClass::SharedOpposite
+            };
+        }
+    }
+    if point_in_ring(mid, ring) {
+        EdgeClass::Inside
+    } else {
+        EdgeClass::Outside
+    }
+}
+
+/// 射线法判断点是否在多边形内
+fn point_in_ring(point: Point2, ring: &[Point2]) -> bool {
+    let mut inside = false;
+    for (p, q) in edges(ring) {
+        if (p.y > point.y) != (q.y > point.y) {
+            let x = p.x + (point.y - p.y) / (q.y - p.y) * (q.x - p.x);
+            if point.x < x {
+                inside = !inside;
+            }
+        }
+    }
+    inside
+}
+
+/// 将有向边首尾相接成闭合环
+fn chain_loops(mut edges: Vec<(Point2, Point2)>, eps: f64) -> Vec<Vec<Point2>> {
+    let join = eps * 100.0;
+    let mut rings = Vec::new();
+    while let Some((first, mut current)) = edges.pop() {
+        let mut ring = vec![first];
+        loop {
+            if (current - first).norm() <= join {
+                rings.push(ring);
+                break;
+            }
+            let Some(index) = edges.iter().position(|(s, _)| (*s - current).norm() <= join) else {
+                // 无法闭合的残段丢弃
+                break;
+            };
+            let (start, end) = edges.swap_remove(index);
+            ring.push(start);
+            current = end;
+        }
+    }
+    rings
+}
+
+/// 去除共线的中间顶点
+fn simplify(ring: Vec<Point2>, eps: f64) -> Vec<Point2> {
+    let n = ring.len();
+    if n < 3 {
+        return ring;
+    }
+    (0..n)
+        .filter(|&i| {
+            let prev = ring[(i + n - 1) % n];
+            let next = ring[(i + 1) % n];
+            let a = ring[i] - prev;
+            let b = next - ring[i];
+            (a.x * b.y - a.y * b.x).abs() > eps * a.norm().max(b.norm()) || a.dot(&b) < 0.0
+        })
+        .map(|i| ring[i])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn square(x: f64, y: f64, size: f64) -> Vec<Point2> {
+        vec![
+            Point2::new(x, y),
+            Point2::new(x + size, y),
+            Point2::new(x + size, y + size),
+            Point2::new(x, y + size),
+        ]
+    }
+
+    fn total_area(rings: &[Vec<Point2>]) -> f64 {
+        rings.iter().map(|r| signed_area(r)).sum()
+    }
+
+    #[test]
+    fn test_overlapping_squares() {
+        let a = square(0.0, 0.0, 10.0);
+        let b = square(5.0, 5.0, 10.0);
+
+        let union = polygon_boolean(&a, &b, BooleanOp::Union);
+        assert_eq!(union.len(), 1);
+        assert!((total_area(&union) - 175.0).abs() < 1e-9);
+
+        let intersection = polygon_boolean(&a, &b, BooleanOp::Intersection);
+        assert_eq!(intersection.len(), 1);
+        assert_eq!(intersection[0].len(), 4);
+        assert!((total_area(&intersection) - 25.0).abs() < 1e-9);
+
+        let difference = polygon_boolean(&a, &b, BooleanOp::Difference);
+        assert_eq!(difference.len(), 1);
+        assert!((total_area(&difference) - 75.0).abs() < 1e-9);
+
+        let xor = polygon_boolean(&a, &b, BooleanOp::Xor);
+        assert!((total_area(&xor) - 150.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_contained_and_shared_edges() {
+        let outer = square(0.0, 0.0, 10.0);
+        let inner = square(2.0, 2.0, 4.0);
+
+        // 减去被完全包含的区域：外轮廓 + 顺时针孔洞
+        let difference = polygon_boolean(&outer, &inner, BooleanOp::Difference);
+        assert_eq!(difference.len(), 2);
+        assert!((total_area(&difference) - 84.0).abs() < 1e-9);
+
+        // 共边相邻的两个正方形合并为一个矩形
+        let right = square(10.0, 0.0, 10.0);
+        let union = polygon_boolean(&outer, &right, BooleanOp::Union);
+        assert_eq!(union.len(), 1);
+        assert_eq!(union[0].len(), 4);
+        assert!((total_area(&union) - 200.0).abs() < 1e-9);
+        assert!(polygon_boolean(&outer, &right, BooleanOp::Intersection).is_empty());
+    }
+}
@@ -5,8 +5,8 @@
 use serde::{Deserialize, Serialize};
 use std::sync::atomic::{AtomicU64, Ordering};
 
-/// 全局实体ID生成器
-static ENTITY_COUNTER: AtomicU64 = AtomicU64::new(1);
+/// 全局实体ID生成器（ID 1 保留给默认图层 0，见 `Layer::default_layer`）
+static ENTITY_COUNTER: AtomicU64 = AtomicU64::new(2);
 
 /// 实体唯一标识符
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -67,10 +67,66 @@ pub struct Entity {
     pub layer_id: EntityId,
 
     /// 是否可见
+    #[serde(default = "default_visible")]
     pub visible: bool,
 
     /// 是否锁定（不可编辑）
+    #[serde(default)]
     pub locked: bool,
+
+    /// 扩展实体数据（DXF XDATA），按注册应用分组，导入导出时原样保留
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub xdata: Vec<XData>,
+
+    /// 标高（Z 坐标），只在 DXF 导入导出时保留，显示和计算仍为二维
+    #[serde(default)]
+    pub elevation: f64,
+}
+
+/// 一个注册应用附加在实体上的扩展数据（DXF 组码 1001 开头的一段）
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct XData {
+    /// 注册应用名
+    pub application: String,
+    /// 数据项
+    pub items: Vec<XDataValue>,
+}
+
+/// 扩展数据项，与 DXF 的 1000~1071 组码一一对应
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum XDataValue {
+    /// 字符串（1000）
+    String(String),
+    /// 控制组 `{ ... }`（1002）
+    ControlGroup(Vec<XDataValue>),
+    /// 图层名（1003）
+    LayerName(String),
+    /// 二进制数据（1004）
+    Binary(Vec<u8>),
+    /// 数据库句柄（1005）
+    Handle(u64),
+    /// 三个实数（1010）
+    ThreeReals([f64; 3]),
+    /// 世界坐标位置（1011）
+    WorldPosition([f64; 3]),
+    /// 世界坐标位移（1012）
+    WorldDisplacement([f64; 3]),
+    /// 世界坐标方向（1013）
+    WorldDirection([f64; 3]),
+    /// 实数（1040）
+    Real(f64),
+    /// 距离（1041）
+    Distance(f64),
+    /// 比例因子（1042）
+    ScaleFactor(f64),
+    /// 16 位整数（1070）
+    Integer(i16),
+    /// 32 位整数（1071）
+    Long(i32),
+}
+
+fn default_visible() -> bool {
+    true
 }
 
 impl Entity {
@@ -83,6 +139,8 @@ impl Entity {
             layer_id: EntityId::NULL,
             visible: true,
             locked: false,
+            xdata: Vec::new(),
+            elevation: 0.0,
         }
     }
 
@@ -102,5 +160,54 @@ impl Entity {
         self.properties = properties;
         self
     }
+
+    /// 使用指定的标高
+    pub fn with_elevation(mut self, elevation: f64) -> Self {
+        self.elevation = elevation;
+        self
+    }
+
+    /// 指定注册应用的扩展数据
+    pub fn xdata_for(&self, application: &str) -> Option<&XData> {
+        self.xdata.iter().find(|x| x.application.eq_ignore_ascii_case(application))
+    }
+
+    /// 复制实体：几何、属性、图层等全部克隆，但分配新的ID
+    pub fn duplicate(&self) -> Self {
+        Self {
+            id: EntityId::new(),
+            ..self.clone()
+        }
+    }
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{Geometry, Line};
+    use crate::math::Point2;
+    use crate::properties::{Color, Properties};
+
+    #[test]
+    fn test_duplicate_assigns_new_id() {
+        let layer = EntityId::new();
+        let original = Entity::new(Geometry::Line(Line::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 5.0),
+        )))
+        .with_layer(layer)
+        .with_properties(Properties::with_color(Color::RED));
+
+        let copy = original.duplicate();
+        assert_ne!(copy.id, original.id);
+        assert_eq!(copy.layer_id, layer);
+        assert_eq!(copy.properties, original.properties);
+        match (&copy.geometry, &original.geometry) {
+            (Geometry::Line(a), Geometry::Line(b)) => {
+                assert_eq!(a.start, b.start);
+                assert_eq!(a.end, b.end);
+            }
+            _ => panic!("几何类型应保持一致"),
+        }
+    }
+}
@@ -12,7 +12,8 @@
 //! - 填充 (Hatch)
 //! - 引线 (Leader)
 
-use crate::math::{BoundingBox2, Point2, Vector2, EPSILON};
+use crate::entity::EntityId;
+use crate::math::{angle_between, BoundingBox2, Point2, Vector2, EPSILON};
 use serde::{Deserialize, Serialize};
 
 /// 几何类型枚举
@@ -66,6 +67,59 @@ impl Geometry {
         }
     }
 
+    /// 计算面积（圆、整椭圆、闭合多段线、填充）
+    ///
+    /// 开放曲线及文字、标注等返回 None
+    pub fn area(&self) -> Option<f64> {
+        match self {
+            Geometry::Circle(c) => Some(c.area()),
+            Geometry::Ellipse(e) if e.is_full() => Some(e.area()),
+            Geometry::Polyline(pl) if pl.closed => Some(pl.area()),
+            Geometry::Hatch(h) => Some(h.area()),
+            _ => None,
+        }
+    }
+
+    /// 计算周长（闭合对象）或长度（开放曲线）
+    ///
+    /// 点、文字、标注返回 0
+    pub fn perimeter_or_length(&self) -> f64 {
+        match self {
+            Geometry::Line(l) => l.length(),
+            Geometry::Circle(c) => c.circumference(),
+            Geometry::Arc(a) => a.length(),
+            Geometry::Polyline(pl) => pl.length(),
+            Geometry::Ellipse(e) => {
+                if e.is_full() {
+                    e.circumference()
+                } else {
+                    sampled_length(&e.sample_points(64))
+                }
+            }
+            Geometry::Spline(s) => sampled_length(&s.sample_points(64)),
+            Geometry::Hatch(h) => h.boundaries.iter().map(|b| b.length()).sum(),
+            Geometry::Leader(l) => l.length(),
+            Geometry::Point(_) | Geometry::Text(_) | Geometry::Dimension(_) => 0.0,
+        }
+    }
+
+    /// 点到几何的距离（曲线取到曲线的距离；文字、标注和填充取到包围盒的距离）
+    pub fn distance_to_point(&self, point: &Point2) -> f64 {
+        match self {
+            Geometry::Point(p) => (p.position - point).norm(),
+            Geometry::Line(l) => l.distance_to_point(point),
+            Geometry::Circle(c) => c.distance_to_point(point).abs(),
+            Geometry::Arc(a) => a.distance_to_point(point),
+            Geometry::Polyline(pl) => pl.distance_to_point(point),
+            Geometry::Ellipse(e) => e.distance_to_point(point),
+            Geometry::Spline(s) => s.distance_to_point(point),
+            Geometry::Leader(l) => l.distance_to_point(point),
+            Geometry::Text(_) | Geometry::Dimension(_) | Geometry::Hatch(_) => {
+                self.bounding_box().distance_to_point(point)
+            }
+        }
+    }
+
     /// 检查点是否在几何上（考虑容差）
     pub fn contains_point(&self, point: &Point2, tolerance: f64) -> bool {
         match self {
@@ -82,6 +136,357 @@ impl Geometry {
             Geometry::Leader(l) => l.distance_to_point(point) <= tolerance,
         }
     }
+
+    /// 打断：删除两点之间的部分，两点相同时在该点一分为二
+    ///
+    /// 支持直线、圆弧、圆和多段线。圆不能在单点打断，两点打断时删除从第一点
+    /// 逆时针到第二点的部分，得到一段圆弧。不支持的类型或打断后无剩余时返回 `None`。
+    pub fn break_between(&self, first: Point2, second: Point2) -> Option<Vec<Geometry>> {
+        let pieces = match self {
+            Geometry::Line(line) => {
+                let direction = line.end - line.start;
+                let length_sq = direction.norm_squared();
+                if length_sq < EPSILON {
+                    return None;
+                }
+                let param = |p: Point2| ((p - line.start).dot(&direction) / length_sq).clamp(0.0, 1.0);
+                let (t1, t2) = (param(first), param(second));
+                let (t1, t2) = (t1.min(t2), t1.max(t2));
+                let at = |t: f64| line.start + direction * t;
+                [Line::new(line.start, at(t1)), Line::new(at(t2), line.end)]
+                    .into_iter()
+                    .filter(|piece| piece.length() > EPSILON)
+                    .map(Geometry::Line)
+                    .collect()
+            }
+            Geometry::Arc(arc) => {
+                let sweep = arc.sweep_angle();
+                // 点相对起始角的扫掠位置，弧外的点归到较近的端点
+                let offset = |p: Point2| {
+                    let s = ((p.y - arc.center.y).atan2(p.x - arc.center.x) - arc.start_angle)
+                        .rem_euclid(std::f64::consts::TAU);
+                    if s <= sweep {
+                        s
+                    } else if s - sweep < std::f64::consts::TAU - s {
+                        sweep
+                    } else {
+                        0.0
+                    }
+                };
+                let (s1, s2) = (offset(first), offset(second));
+                let (s1, s2) = (s1.min(s2), s1.max(s2));
+                let mut pieces = Vec::new();
+                if s1 > EPSILON {
+                    pieces.push(Geometry::Arc(Arc::new(arc.center, arc.radius, arc.start_angle, arc.start_angle + s1)));
+                }
+                if sweep - s2 > EPSILON {
+                    pieces.push(Geometry::Arc(Arc::new(arc.center, arc.radius, arc.start_angle + s2, arc.end_angle)));
+                }
+                pieces
+            }
+            Geometry::Circle(circle) => {
+                let angle = |p: Point2| (p.y - circle.center.y).atan2(p.x - circle.center.x);
+                let (a1, a2) = (angle(first), angle(second));
+                if (a2 - a1).rem_euclid(std::f64::consts::TAU) < EPSILON {
+                    return None;
+                }
+                // 保留从第二点逆时针回到第一点的部分
+                vec![Geometry::Arc(Arc::new(circle.center, circle.radius, a2, a1))]
+            }
+            Geometry::Polyline(polyline) => polyline
+                .break_between(first, second)
+                .into_iter()
+                .map(Geometry::Polyline)
+                .collect(),
+            _ => return None,
+        };
+        (!pieces.is_empty()).then_some(pieces)
+    }
+
+    /// 在容差内按结构比较两个几何
+    ///
+    /// 坐标和长度按 `tol` 比较，角度换算为对应的弧长后比较；直线允许起终点互换，
+    /// 其余类型要求顶点顺序一致，文本等非数值属性须完全相同。类型不同时不相等。
+    pub fn approx_eq(&self, other: &Geometry, tol: f64) -> bool {
+        let close = |a: f64, b: f64| (a - b).abs() <= tol;
+        let near = |a: Point2, b: Point2| (a - b).norm() <= tol;
+        let all_near = |a: &[Point2], b: &[Point2]| a.len() == b.len() && a.iter().zip(b).all(|(p, q)| near(*p, *q));
+        let all_close = |a: &[f64], b: &[f64]| a.len() == b.len() && a.iter().zip(b).all(|(x, y)| close(*x, *y));
+        // 角度差（取最短方向）乘以参考长度
+        let angle_close = |a: f64, b: f64, length: f64| {
+            let diff = (a - b).rem_euclid(std::f64::consts::TAU);
+            diff.min(std::f64::consts::TAU - diff) * length <= tol
+        };
+
+        match (self, other) {
+            (Geometry::Point(a), Geometry::Point(b)) => near(a.position, b.position),
+            (Geometry::Line(a), Geometry::Line(b)) => {
+                (near(a.start, b.start) && near(a.end, b.end)) || (near(a.start, b.end) && near(a.end, b.start))
+            }
+            (Geometry::Circle(a), Geometry::Circle(b)) => near(a.center, b.center) && close(a.radius, b.radius),
+            (Geometry::Arc(a), Geometry::Arc(b)) => {
+                near(a.center, b.center)
+                    && close(a.radius, b.radius)
+                    && angle_close(a.start_angle, b.start_angle, a.radius)
+                    && close(a.sweep_angle() * a.radius, b.sweep_angle() * b.radius)
+            }
+            (Geometry::Polyline(a), Geometry::Polyline(b)) => {
+                a.closed == b.closed
+                    && a.vertices.len() == b.vertices.len()
+                    && a.vertices.iter().zip(&b.vertices).all(|(u, v)| {
+                        near(u.point, v.point)
+                            && close(u.bulge, v.bulge)
+                            && close(u.start_width, v.start_width)
+                            && close(u.end_width, v.end_width)
+                    })
+            }
+            (Geometry::Text(a), Geometry::Text(b)) => {
+                a.content == b.content
+                    && a.alignment == b.alignment
+                    && a.style == b.style
+                    && near(a.position, b.position)
+                    && close(a.height, b.height)
+                    && angle_close(a.rotation, b.rotation, a.height)
+            }
+            (Geometry::Dimension(a), Geometry::Dimension(b)) => {
+                a.dim_type == b.dim_type
+                    && a.text_override == b.text_override
+                    && near(a.definition_point1, b.definition_point1)
+                    && near(a.definition_point2, b.definition_point2)
+                    && near(a.line_location, b.line_location)
+                    && close(a.text_height, b.text_height)
+                    && match (a.text_position, b.text_position) {
+                        (Some(p), Some(q)) => near(p, q),
+                        (None, None) => true,
+                        _ => false,
+                    }
+            }
+            (Geometry::Ellipse(a), Geometry::Ellipse(b)) => {
+                let major = a.major_axis.norm();
+                near(a.center, b.center)
+                    && (a.major_axis - b.major_axis).norm() <= tol
+                    && close(a.ratio * major, b.ratio * b.major_axis.norm())
+                    && angle_close(a.start_param, b.start_param, major)
+                    && angle_close(a.end_param, b.end_param, major)
+            }
+            (Geometry::Spline(a), Geometry::Spline(b)) => {
+                a.spline_type == b.spline_type
+                    && a.degree == b.degree
+                    && a.closed == b.closed
+                    && all_near(&a.control_points, &b.control_points)
+                    && all_near(&a.fit_points, &b.fit_points)
+                    && all_close(&a.knots, &b.knots)
+                    && all_close(&a.weights, &b.weights)
+            }
+            (Geometry::Hatch(a), Geometry::Hatch(b)) => {
+                let same_pattern = match (&a.pattern_type, &b.pattern_type) {
+                    (HatchPatternType::Solid, HatchPatternType::Solid) => true,
+                    (HatchPatternType::Predefined(p), HatchPatternType::Predefined(q)) => p == q,
+                    (HatchPatternType::Custom { lines: p }, HatchPatternType::Custom { lines: q }) => {
+                        p.len() == q.len()
+                            && p.iter().zip(q).all(|(u, v)| {
+                                close(u.angle, v.angle)
+                                    && near(u.base_point, v.base_point)
+                                    && (u.offset - v.offset).norm() <= tol
+                                    && all_close(&u.dash_pattern, &v.dash_pattern)
+                            })
+                    }
+                    _ => false,
+                };
+                same_pattern
+                    && close(a.angle, b.angle)
+                    && close(a.scale, b.scale)
+                    && a.boundary_entity_ids == b.boundary_entity_ids
+                    && a.boundaries.len() == b.boundaries.len()
+                    && a.boundaries.iter().zip(&b.boundaries).all(|(u, v)| {
+                        u.is_outer == v.is_outer
+                            && u.elements.len() == v.elements.len()
+                            && u.elements.iter().zip(&v.elements).all(|(e, f)| {
+                                boundary_element_geometry(e).approx_eq(&boundary_element_geometry(f), tol)
+                            })
+                    })
+            }
+            (Geometry::Leader(a), Geometry::Leader(b)) => {
+                a.arrow_type == b.arrow_type
+                    && a.spline == b.spline
+                    && a.text == b.text
+                    && all_near(&a.vertices, &b.vertices)
+                    && close(a.arrow_size, b.arrow_size)
+                    && close(a.text_height, b.text_height)
+            }
+            _ => false,
+        }
+    }
+
+    /// 所有坐标和数值参数是否都是有限值（不含 NaN 和无穷大）
+    pub fn is_finite(&self) -> bool {
+        fn points<'a>(points: impl IntoIterator<Item = &'a Point2>) -> bool {
+            points.into_iter().all(|p| p.x.is_finite() && p.y.is_finite())
+        }
+        fn values(values: &[f64]) -> bool {
+            values.iter().all(|v| v.is_finite())
+        }
+        fn arc(a: &Arc) -> bool {
+            points([&a.center]) && values(&[a.radius, a.start_angle, a.end_angle])
+        }
+        fn ellipse(e: &Ellipse) -> bool {
+            points([&e.center]) && values(&[e.major_axis.x, e.major_axis.y, e.ratio, e.start_param, e.end_param])
+        }
+        fn spline(s: &Spline) -> bool {
+            points(&s.control_points) && points(&s.fit_points) && values(&s.knots) && values(&s.weights)
+        }
+
+        match self {
+            Geometry::Point(p) => points([&p.position]),
+            Geometry::Line(l) => points([&l.start, &l.end]),
+            Geometry::Circle(c) => points([&c.center]) && values(&[c.radius]),
+            Geometry::Arc(a) => arc(a),
+            Geometry::Polyline(p) => p
+                .vertices
+                .iter()
+                .all(|v| points([&v.point]) && values(&[v.bulge, v.start_width, v.end_width])),
+            Geometry::Text(t) => points([&t.position]) && values(&[t.height, t.rotation]),
+            Geometry::Dimension(d) => {
+                points([&d.definition_point1, &d.definition_point2, &d.line_location])
+                    && points(&d.text_position)
+                    && values(&[d.text_height])
+            }
+            Geometry::Ellipse(e) => ellipse(e),
+            Geometry::Spline(s) => spline(s),
+            Geometry::Hatch(h) => {
+                values(&[h.angle, h.scale])
+                    && h.boundaries.iter().flat_map(|b| &b.elements).all(|element| match element {
+                        HatchBoundaryElement::Line(l) => points([&l.start, &l.end]),
+                        HatchBoundaryElement::Arc(a) => arc(a),
+                        HatchBoundaryElement::Ellipse(e) => ellipse(e),
+                        HatchBoundaryElement::Spline(s) => spline(s),
+                    })
+            }
+            Geometry::Leader(l) => points(&l.vertices) && values(&[l.arrow_size, l.text_height]),
+        }
+    }
+
+    /// 几何上距离 `p` 最近的点及其归一化参数
+    ///
+    /// 参数在 `[0, 1]` 内：线段、圆弧、椭圆（弧）和样条从起点到终点；圆从 0° 起逆时针一周；
+    /// 多段线和引线按线段均分，第 i 段对应 `[i/n, (i+1)/n]`。
+    /// 点、文字的最近点是其位置，标注和填充取图形或边界上的最近点，参数均为 0。
+    pub fn closest_point(&self, p: &Point2) -> (Point2, f64) {
+        match self {
+            Geometry::Point(point) => (point.position, 0.0),
+            Geometry::Line(line) => {
+                let v = line.end - line.start;
+                let len_sq = v.norm_squared();
+                if len_sq < EPSILON * EPSILON {
+                    return (line.start, 0.0);
+                }
+                let t = ((p - line.start).dot(&v) / len_sq).clamp(0.0, 1.0);
+                (line.start + v * t, t)
+            }
+            Geometry::Circle(circle) => {
+                let nearest = circle.nearest_point(p);
+                let angle = (nearest.y - circle.center.y).atan2(nearest.x - circle.center.x);
+                (nearest, angle.rem_euclid(std::f64::consts::TAU) / std::f64::consts::TAU)
+            }
+            Geometry::Arc(arc) => {
+                let nearest = arc.nearest_point(p);
+                let sweep = arc.sweep_angle();
+                if sweep < EPSILON {
+                    return (nearest, 0.0);
+                }
+                let angle = (nearest.y - arc.center.y).atan2(nearest.x - arc.center.x);
+                let offset = (angle - arc.start_angle).rem_euclid(std::f64::consts::TAU);
+                // 起点处的角度可能因舍入落在 2π 附近
+                let t = if offset <= sweep {
+                    offset / sweep
+                } else if offset - sweep < std::f64::consts::TAU - offset {
+                    1.0
+                } else {
+                    0.0
+                };
+                (nearest, t)
+            }
+            Geometry::Polyline(polyline) => {
+                if polyline.vertices.len() == 1 {
+                    return (polyline.vertices[0].point, 0.0);
+                }
+                let segments = polyline.explode();
+                let n = segments.len() as f64;
+                segments
+                    .iter()
+                    .enumerate()
+                    .map(|(i, segment)| {
+                        let (point, mut t) = segment.closest_point(p);
+                        // 负凸度的弧线段按逆时针存储，方向与多段线相反
+                        if matches!(segment, Geometry::Arc(_)) && polyline.vertices[i].bulge < 0.0 {
+                            t = 1.0 - t;
+                        }
+                        (point, (i as f64 + t) / n)
+                    })
+                    .min_by(|a, b| (a.0 - p).norm().total_cmp(&(b.0 - p).norm()))
+                    .unwrap_or((*p, 0.0))
+            }
+            Geometry::Text(text) => (text.position, 0.0),
+            Geometry::Dimension(dim) => closest_among(dim.render_segments().iter(), p),
+            Geometry::Ellipse(ellipse) => {
+                let (t, span) = ellipse.closest_param(p);
+                let offset = (t - ellipse.start_param).rem_euclid(std::f64::consts::TAU);
+                let normalized = if span < EPSILON { 0.0 } else { (offset / span).min(1.0) };
+                (ellipse.point_at_param(t), normalized)
+            }
+            Geometry::Spline(spline) => {
+                if spline.control_points.len() < 2 {
+                    return (spline.control_points.first().copied().unwrap_or(*p), 0.0);
+                }
+                let (start, end) = spline.param_range();
+                let t = nearest_param(|t| spline.point_at_param(t), p, start, end, false);
+                let normalized = if end - start < EPSILON { 0.0 } else { (t - start) / (end - start) };
+                (spline.point_at_param(t), normalized)
+            }
+            Geometry::Hatch(hatch) => {
+                let elements: Vec<Geometry> = hatch
+                    .boundaries
+                    .iter()
+                    .flat_map(|b| b.elements.iter().map(boundary_element_geometry))
+                    .collect();
+                closest_among(elements.iter(), p)
+            }
+            Geometry::Leader(leader) => {
+                let path = leader.path_points();
+                if path.len() < 2 {
+                    return (path.first().copied().unwrap_or(*p), 0.0);
+                }
+                let n = (path.len() - 1) as f64;
+                path.windows(2)
+                    .enumerate()
+                    .map(|(i, w)| {
+                        let (point, t) = Geometry::Line(Line::new(w[0], w[1])).closest_point(p);
+                        (point, (i as f64 + t) / n)
+                    })
+                    .min_by(|a, b| (a.0 - p).norm().total_cmp(&(b.0 - p).norm()))
+                    .unwrap_or((*p, 0.0))
+            }
+        }
+    }
+}
+
+/// 若干几何中距离 `p` 最近的点（参数固定为 0，用于标注、填充等组合图形）
+fn closest_among<'a>(geometries: impl Iterator<Item = &'a Geometry>, p: &Point2) -> (Point2, f64) {
+    geometries
+        .map(|g| g.closest_point(p).0)
+        .min_by(|a, b| (a - p).norm().total_cmp(&(b - p).norm()))
+        .map(|point| (point, 0.0))
+        .unwrap_or((*p, 0.0))
+}
+
+/// 填充边界元素对应的几何
+fn boundary_element_geometry(element: &HatchBoundaryElement) -> Geometry {
+    match element {
+        HatchBoundaryElement::Line(l) => Geometry::Line(l.clone()),
+        HatchBoundaryElement::Arc(a) => Geometry::Arc(a.clone()),
+        HatchBoundaryElement::Ellipse(e) => Geometry::Ellipse(e.clone()),
+        HatchBoundaryElement::Spline(s) => Geometry::Spline(s.clone()),
+    }
 }
 
 /// 点
@@ -156,6 +561,17 @@ impl Line {
         (point - pb).norm()
     }
 
+    /// 线段上距离给定点最近的点（垂足，超出线段时取端点）
+    pub fn nearest_point(&self, point: &Point2) -> Point2 {
+        let v = self.end - self.start;
+        let len_sq = v.norm_squared();
+        if len_sq < EPSILON * EPSILON {
+            return self.start;
+        }
+        let t = ((point - self.start).dot(&v) / len_sq).clamp(0.0, 1.0);
+        self.start + v * t
+    }
+
     pub fn bounding_box(&self) -> BoundingBox2 {
         BoundingBox2::from_points([self.start, self.end])
     }
@@ -173,6 +589,51 @@ impl Circle {
         Self { center, radius }
     }
 
+    /// 两点定圆：两点为直径的两端，两点重合时返回 `None`
+    pub fn from_two_points(p1: Point2, p2: Point2) -> Option<Self> {
+        let diameter = (p2 - p1).norm();
+        if diameter < EPSILON {
+            return None;
+        }
+        Some(Self::new(Point2::from((p1.coords + p2.coords) / 2.0), diameter / 2.0))
+    }
+
+    /// 三点定圆（外接圆），三点共线时返回 `None`
+    pub fn from_three_points(p1: Point2, p2: Point2, p3: Point2) -> Option<Self> {
+        Arc::from_three_points(p1, p2, p3).map(|arc| Self::new(arc.center, arc.radius))
+    }
+
+    /// 相切、相切、半径（TTR）：与两个对象相切的指定半径的圆
+    ///
+    /// 支持直线（按无限长直线处理）、圆和圆弧（按整圆处理）。满足条件的圆通常有多个，
+    /// 取切点分别离两个拾取点最近的一个；无解时返回 `None`。
+    pub fn tangent_tangent_radius(
+        first: &Geometry,
+        first_pick: Point2,
+        second: &Geometry,
+        second_pick: Point2,
+        radius: f64,
+    ) -> Option<Self> {
+        if radius < EPSILON {
+            return None;
+        }
+        let mut best: Option<(f64, Point2)> = None;
+        for a in center_loci(first, radius) {
+            for b in center_loci(second, radius) {
+                for center in a.intersections(&b) {
+                    let (Some(t1), Some(t2)) = (tangent_point(first, center), tangent_point(second, center)) else {
+                        continue;
+                    };
+                    let score = (t1 - first_pick).norm() + (t2 - second_pick).norm();
+                    if best.is_none_or(|(s, _)| score < s) {
+                        best = Some((score, center));
+                    }
+                }
+            }
+        }
+        best.map(|(_, center)| Self::new(center, radius))
+    }
+
     /// 计算周长
     pub fn circumference(&self) -> f64 {
         2.0 * std::f64::consts::PI * self.radius
@@ -188,6 +649,18 @@ impl Circle {
         (point - self.center).norm() - self.radius
     }
 
+    /// 圆上距离给定点最近的点（沿圆心到该点的射线方向）
+    ///
+    /// 给定点与圆心重合时所有点等距，返回角度 0 处的点。
+    pub fn nearest_point(&self, point: &Point2) -> Point2 {
+        let dir = point - self.center;
+        let dist = dir.norm();
+        if dist < EPSILON {
+            return self.point_at_angle(0.0);
+        }
+        self.center + dir * (self.radius / dist)
+    }
+
     /// 获取圆上指定角度的点
     pub fn point_at_angle(&self, angle: f64) -> Point2 {
         Point2::new(
@@ -196,6 +669,13 @@ impl Circle {
         )
     }
 
+    /// 转换为内接正多边形的闭合多段线（至少 3 段，从角度 0 开始逆时针）
+    pub fn to_polyline(&self, segments: usize) -> Polyline {
+        let segments = segments.max(3);
+        let step = std::f64::consts::TAU / segments as f64;
+        Polyline::from_points((0..segments).map(|i| self.point_at_angle(step * i as f64)), true)
+    }
+
     pub fn bounding_box(&self) -> BoundingBox2 {
         BoundingBox2::new(
             Point2::new(self.center.x - self.radius, self.center.y - self.radius),
@@ -204,6 +684,183 @@ impl Circle {
     }
 }
 
+/// 与对象相切的定半径圆的圆心轨迹
+enum CenterLocus {
+    /// 直线：经过的点和方向
+    Line(Point2, Vector2),
+    /// 圆：圆心和半径
+    Circle(Point2, f64),
+}
+
+impl CenterLocus {
+    /// 两条轨迹的交点
+    fn intersections(&self, other: &CenterLocus) -> Vec<Point2> {
+        match (self, other) {
+            (CenterLocus::Line(p1, d1), CenterLocus::Line(p2, d2)) => {
+                let cross = d1.x * d2.y - d1.y * d2.x;
+                if cross.abs() < EPSILON {
+                    return Vec::new();
+                }
+                let d = p2 - p1;
+                vec![p1 + d1 * ((d.x * d2.y - d.y * d2.x) / cross)]
+            }
+            (CenterLocus::Line(p, d), CenterLocus::Circle(c, r))
+            | (CenterLocus::Circle(c, r), CenterLocus::Line(p, d)) => {
+                let dir = d.normalize();
+                let foot = p + dir * (c - p).dot(&dir);
+                let h2 = r * r - (c - foot).norm_squared();
+                if h2 < -EPSILON {
+                    return Vec::new();
+                }
+                let h = h2.max(0.0).sqrt();
+                if h < EPSILON {
+                    vec![foot]
+                } else {
+                    vec![foot + dir * h, foot - dir * h]
+                }
+            }
+            (CenterLocus::Circle(c1, r1), CenterLocus::Circle(c2, r2)) => {
+                let d = (c2 - c1).norm();
+                if d < EPSILON || d > r1 + r2 + EPSILON || d < (r1 - r2).abs() - EPSILON {
+                    return Vec::new();
+                }
+                let dir = (c2 - c1) / d;
+                let a = (r1 * r1 - r2 * r2 + d * d) / (2.0 * d);
+                let h = (r1 * r1 - a * a).max(0.0).sqrt();
+                let foot = c1 + dir * a;
+                let perp = Vector2::new(-dir.y, dir.x);
+                if h < EPSILON {
+                    vec![foot]
+                } else {
+                    vec![foot + perp * h, foot - perp * h]
+                }
+            }
+        }
+    }
+}
+
+/// 与对象相切、半径为 `radius` 的圆的圆心轨迹（直线两侧的平行线；圆的内外同心圆）
+fn center_loci(geometry: &Geometry, radius: f64) -> Vec<CenterLocus> {
+    let (center, r) = match geometry {
+        Geometry::Line(line) => {
+            let d = line.end - line.start;
+            if d.norm() < EPSILON {
+                return Vec::new();
+            }
+            let n = Vector2::new(-d.y, d.x).normalize() * radius;
+            return vec![CenterLocus::Line(line.start + n, d), CenterLocus::Line(line.start - n, d)];
+        }
+        Geometry::Circle(circle) => (circle.center, circle.radius),
+        Geometry::Arc(arc) => (arc.center, arc.radius),
+        _ => return Vec::new(),
+    };
+    let mut loci = vec![CenterLocus::Circle(center, r + radius)];
+    if (r - radius).abs() > EPSILON {
+        loci.push(CenterLocus::Circle(center, (r - radius).abs()));
+    }
+    loci
+}
+
+/// 以 `center` 为圆心的相切圆在对象上的切点
+fn tangent_point(geometry: &Geometry, center: Point2) -> Option<Point2> {
+    match geometry {
+        Geometry::Line(line) => {
+            let d = line.end - line.start;
+            let len2 = d.norm_squared();
+            (len2 > EPSILON).then(|| line.start + d * ((center - line.start).dot(&d) / len2))
+        }
+        Geometry::Circle(Circle { center: c, radius })
+        | Geometry::Arc(Arc { center: c, radius, .. }) => {
+            let dir = center - c;
+            (dir.norm() > EPSILON).then(|| c + dir * (radius / dir.norm()))
+        }
+        _ => None,
+    }
+}
+
+/// 两个对象之间的公切线（或点到圆的切线）
+///
+/// 支持点-圆、圆-圆（先外公切线后内公切线）；圆弧按所在的整圆计算，只保留切点落在弧上的切线。
+/// 返回的线段起点在 `first` 上、终点在 `second` 上，无解或不支持的组合返回空。
+pub fn tangents(first: &Geometry, second: &Geometry) -> Vec<Line> {
+    let circle_of = |geometry: &Geometry| match geometry {
+        Geometry::Circle(circle) => Some(circle.clone()),
+        Geometry::Arc(arc) => Some(Circle::new(arc.center, arc.radius)),
+        _ => None,
+    };
+    let lines = match (first, second) {
+        (Geometry::Point(point), other) => match circle_of(other) {
+            Some(circle) => point_circle_tangents(point.position, &circle),
+            None => return Vec::new(),
+        },
+        (other, Geometry::Point(point)) => match circle_of(other) {
+            Some(circle) => point_circle_tangents(point.position, &circle)
+                .into_iter()
+                .map(|line| Line::new(line.end, line.start))
+                .collect(),
+            None => return Vec::new(),
+        },
+        _ => match (circle_of(first), circle_of(second)) {
+            (Some(a), Some(b)) => circle_circle_tangents(&a, &b),
+            _ => return Vec::new(),
+        },
+    };
+
+    // 圆弧上的切点必须落在弧内
+    let on_object = |geometry: &Geometry, p: Point2| match geometry {
+        Geometry::Arc(arc) => arc.contains_angle((p.y - arc.center.y).atan2(p.x - arc.center.x)),
+        _ => true,
+    };
+    lines
+        .into_iter()
+        .filter(|line| on_object(first, line.start) && on_object(second, line.end))
+        .collect()
+}
+
+/// 圆外一点到圆的两条切线（点在圆上或圆内时无解）
+fn point_circle_tangents(point: Point2, circle: &Circle) -> Vec<Line> {
+    let offset = point - circle.center;
+    let d = offset.norm();
+    if d <= circle.radius + EPSILON {
+        return Vec::new();
+    }
+    // 圆心处切点方向与圆心-点连线的夹角
+    let alpha = (circle.radius / d).acos();
+    let base = offset.y.atan2(offset.x);
+    [base + alpha, base - alpha]
+        .into_iter()
+        .map(|angle| Line::new(point, circle.point_at_angle(angle)))
+        .collect()
+}
+
+/// 两圆的外公切线和内公切线（相切时对应的一对切线合并为一条）
+fn circle_circle_tangents(a: &Circle, b: &Circle) -> Vec<Line> {
+    let offset = b.center - a.center;
+    let d = offset.norm();
+    if d < EPSILON {
+        return Vec::new();
+    }
+    let v = offset / d;
+    let mut lines = Vec::new();
+    // sign = 1 为外公切线，-1 为内公切线
+    for sign in [1.0, -1.0] {
+        let c = (a.radius - sign * b.radius) / d;
+        if c.abs() > 1.0 + EPSILON {
+            continue;
+        }
+        let h = (1.0 - c * c).max(0.0).sqrt();
+        for side in [1.0, -1.0] {
+            // 切线的单位法向
+            let n = Vector2::new(v.x * c - side * h * v.y, v.y * c + side * h * v.x);
+            lines.push(Line::new(a.center + n * a.radius, b.center + n * (sign * b.radius)));
+            if h < EPSILON {
+                break;
+            }
+        }
+    }
+    lines
+}
+
 /// 圆弧
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Arc {
@@ -286,6 +943,35 @@ impl Arc {
         )
     }
 
+    /// 弧上中点（从起点逆时针扫过一半包含角，起止角跨越 ±π 时同样正确）
+    pub fn midpoint(&self) -> Point2 {
+        let angle = self.start_angle + self.sweep_angle() / 2.0;
+        Point2::new(
+            self.center.x + self.radius * angle.cos(),
+            self.center.y + self.radius * angle.sin(),
+        )
+    }
+
+    /// 转换为等分的多段线（从起点逆时针到终点，至少 1 段）
+    ///
+    /// `preserve_bulge` 为 true 时每段带凸度，精确表示圆弧；否则为折线近似。
+    pub fn to_polyline(&self, segments: usize, preserve_bulge: bool) -> Polyline {
+        let segments = segments.max(1);
+        let step = self.sweep_angle() / segments as f64;
+        let bulge = if preserve_bulge { (step / 4.0).tan() } else { 0.0 };
+        let vertices = (0..=segments)
+            .map(|i| {
+                let angle = self.start_angle + step * i as f64;
+                let point = Point2::new(
+                    self.center.x + self.radius * angle.cos(),
+                    self.center.y + self.radius * angle.sin(),
+                );
+                PolylineVertex::with_bulge(point, if i < segments { bulge } else { 0.0 })
+            })
+            .collect();
+        Polyline::new(vertices, false)
+    }
+
     /// 计算点到圆弧的距离
     pub fn distance_to_point(&self, point: &Point2) -> f64 {
         let angle = (point.y - self.center.y).atan2(point.x - self.center.x);
@@ -301,8 +987,28 @@ impl Arc {
         }
     }
 
+    /// 圆弧上距离给定点最近的点
+    ///
+    /// 给定点的方向角在扫掠范围内时取径向投影，否则取较近的端点。
+    pub fn nearest_point(&self, point: &Point2) -> Point2 {
+        let dir = point - self.center;
+        if dir.norm() >= EPSILON {
+            let angle = dir.y.atan2(dir.x);
+            if self.contains_angle(angle) {
+                return self.center + dir * (self.radius / dir.norm());
+            }
+        }
+        let start = self.start_point();
+        let end = self.end_point();
+        if (point - start).norm() <= (point - end).norm() {
+            start
+        } else {
+            end
+        }
+    }
+
     /// 检查角度是否在弧的范围内
-    fn contains_angle(&self, angle: f64) -> bool {
+    pub fn contains_angle(&self, angle: f64) -> bool {
         let mut a = angle;
         let mut start = self.start_angle;
         let mut end = self.end_angle;
@@ -349,15 +1055,38 @@ pub struct PolylineVertex {
     pub point: Point2,
     /// 凸度（bulge）- 用于弧线段，0表示直线
     pub bulge: f64,
+    /// 从该顶点出发的线段起点宽度
+    #[serde(default)]
+    pub start_width: f64,
+    /// 从该顶点出发的线段终点宽度
+    #[serde(default)]
+    pub end_width: f64,
 }
 
 impl PolylineVertex {
     pub fn new(point: Point2) -> Self {
-        Self { point, bulge: 0.0 }
+        Self::with_bulge(point, 0.0)
     }
 
     pub fn with_bulge(point: Point2, bulge: f64) -> Self {
-        Self { point, bulge }
+        Self {
+            point,
+            bulge,
+            start_width: 0.0,
+            end_width: 0.0,
+        }
+    }
+
+    /// 设置线段起止宽度
+    pub fn with_widths(mut self, start_width: f64, end_width: f64) -> Self {
+        self.start_width = start_width;
+        self.end_width = end_width;
+        self
+    }
+
+    /// 从该顶点出发的线段是否有宽度
+    pub fn has_width(&self) -> bool {
+        self.start_width > EPSILON || self.end_width > EPSILON
     }
 }
 
@@ -385,6 +1114,47 @@ impl Polyline {
         }
     }
 
+    /// 创建圆角矩形：以两个对角点确定范围，四角为半径 `radius` 的 90° 圆弧（凸度）
+    ///
+    /// 顶点逆时针排列；半径不超过短边的一半，为 0 时得到普通矩形。
+    pub fn rounded_rectangle(corner1: Point2, corner2: Point2, radius: f64) -> Self {
+        let (min_x, max_x) = (corner1.x.min(corner2.x), corner1.x.max(corner2.x));
+        let (min_y, max_y) = (corner1.y.min(corner2.y), corner1.y.max(corner2.y));
+        let r = radius.clamp(0.0, (max_x - min_x).min(max_y - min_y) / 2.0);
+        if r < EPSILON {
+            return Self::from_points(
+                [
+                    Point2::new(min_x, min_y),
+                    Point2::new(max_x, min_y),
+                    Point2::new(max_x, max_y),
+                    Point2::new(min_x, max_y),
+                ],
+                true,
+            );
+        }
+
+        // 每条边一段直线，接一段逆时针 90° 圆角
+        let bulge = (std::f64::consts::FRAC_PI_8).tan();
+        let candidates = [
+            PolylineVertex::new(Point2::new(min_x + r, min_y)),
+            PolylineVertex::with_bulge(Point2::new(max_x - r, min_y), bulge),
+            PolylineVertex::new(Point2::new(max_x, min_y + r)),
+            PolylineVertex::with_bulge(Point2::new(max_x, max_y - r), bulge),
+            PolylineVertex::new(Point2::new(max_x - r, max_y)),
+            PolylineVertex::with_bulge(Point2::new(min_x + r, max_y), bulge),
+            PolylineVertex::new(Point2::new(min_x, max_y - r)),
+            PolylineVertex::with_bulge(Point2::new(min_x, min_y + r), bulge),
+        ];
+        // 半径等于半边长时直线段长度为 0，去掉其起点
+        let vertices = candidates
+            .iter()
+            .enumerate()
+            .filter(|(i, v)| v.bulge != 0.0 || (candidates[(i + 1) % 8].point - v.point).norm() > EPSILON)
+            .map(|(_, v)| v.clone())
+            .collect();
+        Self::new(vertices, true)
+    }
+
     /// 顶点数量
     pub fn vertex_count(&self) -> usize {
         self.vertices.len()
@@ -402,6 +1172,45 @@ impl Polyline {
         }
     }
 
+    /// 闭合或打开多段线
+    ///
+    /// 闭合时末点与起点重合的重复顶点被去掉，新增的闭合段为直线，等宽多段线沿用原宽度。
+    /// 不足三个顶点时闭合会得到退化的回路，不做修改并返回 `false`。
+    pub fn set_closed(&mut self, closed: bool) -> bool {
+        if closed == self.closed {
+            return true;
+        }
+        if closed {
+            let duplicate_end = self.vertices.len() > 1
+                && (self.vertices[self.vertices.len() - 1].point - self.vertices[0].point).norm() < EPSILON;
+            let distinct = self.vertices.len() - usize::from(duplicate_end);
+            if distinct < 3 {
+                return false;
+            }
+            let width = self.constant_width();
+            if duplicate_end {
+                self.vertices.pop();
+            }
+            if let Some(last) = self.vertices.last_mut() {
+                *last = PolylineVertex::new(last.point);
+                if let Some(width) = width {
+                    last.start_width = width;
+                    last.end_width = width;
+                }
+            }
+        } else if let Some(last) = self.vertices.last_mut() {
+            // 末顶点不再有出发的线段
+            *last = PolylineVertex::new(last.point);
+        }
+        self.closed = closed;
+        true
+    }
+
+    /// 切换闭合 / 打开，返回是否成功
+    pub fn toggle_closed(&mut self) -> bool {
+        self.set_closed(!self.closed)
+    }
+
     /// 计算总长度
     pub fn length(&self) -> f64 {
         if self.vertices.len() < 2 {
@@ -424,6 +1233,59 @@ impl Polyline {
         total
     }
 
+    /// 计算面积形心（按顶点多边形的鞋带公式，忽略凸度）
+    ///
+    /// 面积退化（共线或顶点不足）时返回包围盒中心
+    pub fn centroid(&self) -> Point2 {
+        let n = self.vertices.len();
+        let mut area2 = 0.0;
+        let mut cx = 0.0;
+        let mut cy = 0.0;
+
+        if n >= 3 {
+            for i in 0..n {
+                let p = self.vertices[i].point;
+                let q = self.vertices[(i + 1) % n].point;
+                let cross = p.x * q.y - q.x * p.y;
+                area2 += cross;
+                cx += (p.x + q.x) * cross;
+                cy += (p.y + q.y) * cross;
+            }
+        }
+
+        if area2.abs() < EPSILON {
+            return self.bounding_box().center();
+        }
+
+        Point2::new(cx / (3.0 * area2), cy / (3.0 * area2))
+    }
+
+    /// 计算面积（按闭合处理，包含弧线段的弓形面积）
+    pub fn area(&self) -> f64 {
+        let n = self.vertices.len();
+        if n < 2 {
+            return 0.0;
+        }
+
+        let mut signed = 0.0;
+        for i in 0..n {
+            let v1 = &self.vertices[i];
+            let v2 = &self.vertices[(i + 1) % n];
+            signed += (v1.point.x * v2.point.y - v2.point.x * v1.point.y) / 2.0;
+
+            // 闭合多段线才有末段的凸度
+            if v1.bulge.abs() >= EPSILON && (self.closed || i + 1 < n) {
+                let chord = (v2.point - v1.point).norm();
+                let theta = 4.0 * v1.bulge.abs().atan();
+                let radius = chord / (2.0 * (theta / 2.0).sin());
+                let segment_area = radius * radius * (theta - theta.sin()) / 2.0;
+                // 正凸度（逆时针弧）位于弦的右侧，逆时针多边形中向外凸出
+                signed += segment_area * v1.bulge.signum();
+            }
+        }
+        signed.abs()
+    }
+
     /// 计算弧线段长度
     fn arc_segment_length(&self, v1: &PolylineVertex, v2: &PolylineVertex) -> f64 {
         let chord = (v2.point - v1.point).norm();
@@ -467,7 +1329,47 @@ impl Polyline {
         if self.vertices.is_empty() {
             return BoundingBox2::empty();
         }
-        BoundingBox2::from_points(self.vertices.iter().map(|v| v.point))
+        let bbox = BoundingBox2::from_points(self.vertices.iter().map(|v| v.point));
+
+        // 有宽度时按最大半宽外扩
+        let half_width = self.max_half_width();
+        if half_width > EPSILON {
+            let margin = Vector2::new(half_width, half_width);
+            BoundingBox2::new(bbox.min - margin, bbox.max + margin)
+        } else {
+            bbox
+        }
+    }
+
+    /// 各段起止宽度中最大值的一半
+    fn max_half_width(&self) -> f64 {
+        self.vertices[..self.segment_count()]
+            .iter()
+            .map(|v| v.start_width.max(v.end_width))
+            .fold(0.0, f64::max)
+            / 2.0
+    }
+
+    /// 单个顶点移动后增量更新包围盒
+    ///
+    /// `self` 为移动后的多段线，`previous` 为移动前的包围盒，`old_point` 为该顶点原位置。
+    /// 只有相邻两段随之变化，其余顶点不动：旧顶点（含线宽外扩）严格位于旧包围盒内部时，
+    /// 去掉它不会使包围盒收缩，结果为旧包围盒与新顶点的并集，与 [`Polyline::bounding_box`]
+    /// 一致；旧顶点在边界上时回退到完整重算。
+    pub fn bounding_box_after_vertex_move(&self, previous: &BoundingBox2, index: usize, old_point: Point2) -> BoundingBox2 {
+        let Some(vertex) = self.vertices.get(index) else {
+            return self.bounding_box();
+        };
+        let half_width = self.max_half_width();
+        let margin = if half_width > EPSILON {
+            Vector2::new(half_width, half_width)
+        } else {
+            Vector2::zeros()
+        };
+        if !previous.strictly_contains(&(old_point - margin)) || !previous.strictly_contains(&(old_point + margin)) {
+            return self.bounding_box();
+        }
+        previous.union(&BoundingBox2::new(vertex.point - margin, vertex.point + margin))
     }
 }
 
@@ -496,6 +1398,9 @@ pub struct Text {
     pub rotation: f64,
     /// 对齐方式
     pub alignment: TextAlignment,
+    /// 文字样式名称（`None` 表示使用当前样式）
+    #[serde(default)]
+    pub style: Option<String>,
 }
 
 impl Text {
@@ -507,9 +1412,16 @@ impl Text {
             height,
             rotation: 0.0,
             alignment: TextAlignment::Left,
+            style: None,
         }
     }
 
+    /// 设置文字样式
+    pub fn with_style(mut self, style: impl Into<String>) -> Self {
+        self.style = Some(style.into());
+        self
+    }
+
     /// 设置旋转角度
     pub fn with_rotation(mut self, rotation: f64) -> Self {
         self.rotation = rotation;
@@ -589,6 +1501,9 @@ impl Text {
     }
 }
 
+/// 文字背景遮罩相对文字外框的留白（文字高度的倍数，对应 DIMGAP / DIMTXT 的默认比例 0.625 / 2.5）
+pub const TEXT_MASK_PADDING: f64 = 0.25;
+
 /// 标注类型
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum DimensionType {
@@ -671,7 +1586,18 @@ impl Dimension {
                 // 默认位置就是 line_location (用户点击的位置)
                 self.line_location
             }
-            DimensionType::Angular | DimensionType::ArcLength => {
+            DimensionType::ArcLength => {
+                // 弧长标注：文本位于标注弧中点外侧
+                match self.arc_length_arc() {
+                    Some(arc) => {
+                        let mid = arc.start_angle + arc.sweep_angle() / 2.0;
+                        arc.center
+                            + Vector2::new(mid.cos(), mid.sin()) * (arc.radius + self.text_height * 0.8)
+                    }
+                    None => self.line_location,
+                }
+            }
+            DimensionType::Angular => {
                 // 角度标注：文本位于角平分线上
                 let v1 = (self.definition_point2 - self.definition_point1).normalize();
                 let v2 = (self.line_location - self.definition_point1).normalize();
@@ -680,8 +1606,17 @@ impl Dimension {
                 self.definition_point1 + bisector * radius
             }
             DimensionType::Ordinate => {
-                // 坐标标注：文本位于 line_location
-                self.line_location
+                // 坐标标注：文本接在引线末端之外
+                let v = self.line_location - self.definition_point1;
+                if self.ordinate_is_x_datum() {
+                    let sign = if v.y < 0.0 { -1.0 } else { 1.0 };
+                    self.line_location + Vector2::new(0.0, sign * self.text_height)
+                } else {
+                    let sign = if v.x < 0.0 { -1.0 } else { 1.0 };
+                    let width = Text::new(self.line_location, self.display_text(), self.text_height)
+                        .estimated_width();
+                    self.line_location + Vector2::new(sign * (width / 2.0 + self.text_height * 0.5), 0.0)
+                }
             }
         }
     }
@@ -695,6 +1630,11 @@ impl Dimension {
         text.bounding_box()
     }
 
+    /// 文字背景遮罩：文本包围盒四周外扩 [`TEXT_MASK_PADDING`] 倍文字高度
+    pub fn text_mask(&self) -> BoundingBox2 {
+        self.text_bounding_box().expanded(self.text_height * TEXT_MASK_PADDING)
+    }
+
     /// 获取测量值
     pub fn measurement(&self) -> f64 {
         match self.dim_type {
@@ -718,24 +1658,20 @@ impl Dimension {
                 // 角度标注：p1 是顶点，p2 是第一条边上的点，line_location 是第二条边上的点
                 let v1 = self.definition_point2 - self.definition_point1;
                 let v2 = self.line_location - self.definition_point1;
-                let dot = v1.x * v2.x + v1.y * v2.y;
-                let cross = v1.x * v2.y - v1.y * v2.x;
-                cross.atan2(dot).abs().to_degrees()
+                let angle = angle_between(&v1, &v2);
+                angle.min(360.0 - angle)
             }
             DimensionType::ArcLength => {
                 // 弧长标注：p1 是圆心，p2 是起点，line_location 是终点
                 let radius = (self.definition_point2 - self.definition_point1).norm();
                 let v1 = self.definition_point2 - self.definition_point1;
                 let v2 = self.line_location - self.definition_point1;
-                let angle = (v1.x * v2.y - v1.y * v2.x).atan2(v1.x * v2.x + v1.y * v2.y).abs();
-                radius * angle
+                let angle = angle_between(&v1, &v2);
+                radius * angle.min(360.0 - angle).to_radians()
             }
             DimensionType::Ordinate => {
-                // 坐标标注：显示 x 或 y 坐标
-                // 根据 line_location 相对于 definition_point1 的位置决定
-                let dx = (self.line_location.x - self.definition_point1.x).abs();
-                let dy = (self.line_location.y - self.definition_point1.y).abs();
-                if dx > dy {
+                // 坐标标注：引线竖直时显示 X 坐标，水平时显示 Y 坐标
+                if self.ordinate_is_x_datum() {
                     self.definition_point1.x
                 } else {
                     self.definition_point1.y
@@ -745,42 +1681,274 @@ impl Dimension {
     }
 
     /// 获取显示的文本
+    ///
+    /// 覆盖文本中的 `<>` 替换为测量值（AutoCAD 约定），其余文字原样保留。
     pub fn display_text(&self) -> String {
-        if let Some(text) = &self.text_override {
-            text.clone()
-        } else {
-            let val = self.measurement();
-            match self.dim_type {
-                DimensionType::Radius => format!("R{:.2}", val),
-                DimensionType::Diameter => format!("%%C{:.2}", val), // %%C 是 CAD 中直径符号的转义
-                DimensionType::Angular => format!("{:.1}°", val),
-                DimensionType::ArcLength => format!("⌒{:.2}", val),
-                DimensionType::Ordinate => format!("{:.2}", val),
-                _ => format!("{:.2}", val),
-            }
+        match &self.text_override {
+            Some(text) => text.replace("<>", &self.measurement_text()),
+            None => self.measurement_text(),
         }
     }
 
-    /// 计算包围盒 (简化估算)
-    pub fn bounding_box(&self) -> BoundingBox2 {
-        BoundingBox2::from_points([
-            self.definition_point1,
-            self.definition_point2,
-            self.line_location,
-        ])
+    /// 格式化后的测量值
+    fn measurement_text(&self) -> String {
+        let val = self.measurement();
+        match self.dim_type {
+            DimensionType::Radius => format!("R{:.2}", val),
+            DimensionType::Diameter => format!("%%C{:.2}", val), // %%C 是 CAD 中直径符号的转义
+            DimensionType::Angular => format!("{:.1}°", val),
+            DimensionType::ArcLength => format!("⌒{:.2}", val),
+            DimensionType::Ordinate => format!("{:.2}", val),
+            _ => format!("{:.2}", val),
+        }
     }
 
-    /// 检查点是否在标注上 (简化：检查是否在包围盒内)
-    pub fn contains_point(&self, point: &Point2, tolerance: f64) -> bool {
-        let bbox = self.bounding_box();
-        let expanded = BoundingBox2::new(
-            Point2::new(bbox.min.x - tolerance, bbox.min.y - tolerance),
+    /// 箭头大小（与文本高度一致，对应标注样式中 DIMASZ = DIMTXT 的默认比例）
+    fn arrow_size(&self) -> f64 {
+        self.text_height
+    }
+
+    /// 生成标注的图形元素（尺寸线、尺寸界线、箭头），不含文本
+    ///
+    /// 渲染器与导出器共用此方法，文本由 `display_text` / `get_text_position` 单独绘制。
+    pub fn render_segments(&self) -> Vec<Geometry> {
+        let p1 = self.definition_point1;
+        let p2 = self.definition_point2;
+        let size = self.arrow_size();
+        let mut segments = Vec::new();
+
+        match self.dim_type {
+            DimensionType::Aligned | DimensionType::Linear => {
+                let (d1, d2) = if self.dim_type == DimensionType::Aligned {
+                    let dir = p2 - p1;
+                    if dir.norm() < EPSILON {
+                        return segments;
+                    }
+                    let dir = dir.normalize();
+                    let perp = Vector2::new(-dir.y, dir.x);
+                    let offset = perp * (self.line_location - p1).dot(&perp);
+                    (p1 + offset, p2 + offset)
+                } else if (p2.x - p1.x).abs() >= (p2.y - p1.y).abs() {
+                    (Point2::new(p1.x, self.line_location.y), Point2::new(p2.x, self.line_location.y))
+                } else {
+                    (Point2::new(self.line_location.x, p1.y), Point2::new(self.line_location.x, p2.y))
+                };
+
+                segments.push(Geometry::Line(Line::new(p1, d1)));
+                segments.push(Geometry::Line(Line::new(p2, d2)));
+                segments.push(Geometry::Line(Line::new(d1, d2)));
+                if (d2 - d1).norm() > EPSILON {
+                    let dir = (d2 - d1).normalize();
+                    segments.push(dimension_arrow(d1, -dir, size));
+                    segments.push(dimension_arrow(d2, dir, size));
+                }
+            }
+            DimensionType::Radius => {
+                segments.push(Geometry::Line(Line::new(p1, p2)));
+                if (p2 - p1).norm() > EPSILON {
+                    segments.push(dimension_arrow(p2, (p2 - p1).normalize(), size));
+                }
+                if (self.line_location - p2).norm() > EPSILON {
+                    segments.push(Geometry::Line(Line::new(p2, self.line_location)));
+                }
+            }
+            DimensionType::Diameter => {
+                let opposite = p1 - (p2 - p1);
+                segments.push(Geometry::Line(Line::new(opposite, p2)));
+                if (p2 - p1).norm() > EPSILON {
+                    let dir = (p2 - p1).normalize();
+                    segments.push(dimension_arrow(p2, dir, size));
+                    segments.push(dimension_arrow(opposite, -dir, size));
+                }
+            }
+            DimensionType::Angular => {
+                if let Some(arc) = self.angular_arc() {
+                    // 尺寸界线：从边上的定义点沿边延伸到标注弧
+                    for edge_point in [p2, self.line_location] {
+                        let dir = edge_point - p1;
+                        if dir.norm() < EPSILON {
+                            continue;
+                        }
+                        let on_arc = p1 + dir.normalize() * arc.radius;
+                        if (on_arc - edge_point).norm() > EPSILON {
+                            segments.push(Geometry::Line(Line::new(edge_point, on_arc)));
+                        }
+                    }
+                    // 箭头与弧相切，分别指向两条边
+                    let (s, e) = (arc.start_angle, arc.end_angle);
+                    segments.push(dimension_arrow(arc.start_point(), Vector2::new(s.sin(), -s.cos()), size));
+                    segments.push(dimension_arrow(arc.end_point(), Vector2::new(-e.sin(), e.cos()), size));
+                    segments.push(Geometry::Arc(arc));
+                }
+            }
+            DimensionType::ArcLength => {
+                if let Some(arc) = self.arc_length_arc() {
+                    // 尺寸界线：从被测圆弧的两个端点沿径向延伸到标注弧
+                    let radius = (p2 - p1).norm();
+                    for angle in [arc.start_angle, arc.end_angle] {
+                        let dir = Vector2::new(angle.cos(), angle.sin());
+                        segments.push(Geometry::Line(Line::new(p1 + dir * radius, p1 + dir * arc.radius)));
+                    }
+                    // 箭头与标注弧相切，分别指向两条尺寸界线
+                    let (s, e) = (arc.start_angle, arc.end_angle);
+                    segments.push(dimension_arrow(arc.start_point(), Vector2::new(s.sin(), -s.cos()), size));
+                    segments.push(dimension_arrow(arc.end_point(), Vector2::new(-e.sin(), e.cos()), size));
+                    segments.push(Geometry::Arc(arc));
+                }
+            }
+            DimensionType::Ordinate => {
+                let points = self.ordinate_leader_points();
+                if points.len() >= 2 {
+                    segments.push(Geometry::Polyline(Polyline::from_points(points, false)));
+                }
+            }
+        }
+
+        segments
+    }
+
+    /// 坐标标注是否为 X 基准（显示 X 坐标）
+    ///
+    /// 与 AutoCAD 一致：引线在 Y 方向上的跨度较大（竖直引线）时标注 X 坐标，
+    /// 否则标注 Y 坐标。
+    pub fn ordinate_is_x_datum(&self) -> bool {
+        let v = self.line_location - self.definition_point1;
+        v.y.abs() >= v.x.abs()
+    }
+
+    /// 坐标标注引线的顶点（从特征点到引线端点）
+    ///
+    /// 引线先沿垂直于基准轴的方向引出，若端点与特征点不在同一直线上，
+    /// 则在靠近端点处折弯（dogleg）后再到达端点。
+    pub fn ordinate_leader_points(&self) -> Vec<Point2> {
+        let start = self.definition_point1;
+        let end = self.line_location;
+        let v = end - start;
+        if v.norm() < EPSILON {
+            return vec![];
+        }
+
+        let x_datum = self.ordinate_is_x_datum();
+        // 主方向分量（沿引线方向）和横向偏移
+        let (along, lateral) = if x_datum { (v.y, v.x) } else { (v.x, v.y) };
+        if lateral.abs() < EPSILON {
+            return vec![start, end];
+        }
+
+        // 折弯段长度取文本高度，引线过短时按三等分折弯
+        let jog = self.text_height;
+        let sign = along.signum();
+        let (t1, t2) = if along.abs() > 2.0 * jog {
+            (along - sign * 2.0 * jog, along - sign * jog)
+        } else {
+            (along / 3.0, along * 2.0 / 3.0)
+        };
+
+        let (k1, k2) = if x_datum {
+            (Point2::new(start.x, start.y + t1), Point2::new(end.x, start.y + t2))
+        } else {
+            (Point2::new(start.x + t1, start.y), Point2::new(start.x + t2, end.y))
+        };
+        vec![start, k1, k2, end]
+    }
+
+    /// 角度标注的标注弧
+    ///
+    /// 圆心为顶点（definition_point1），半径为顶点到文本位置的距离，
+    /// 逆时针从一条边扫到另一条边，覆盖两边之间较小的夹角。
+    pub fn angular_arc(&self) -> Option<Arc> {
+        let vertex = self.definition_point1;
+        let v1 = self.definition_point2 - vertex;
+        let v2 = self.line_location - vertex;
+        if v1.norm() < EPSILON || v2.norm() < EPSILON {
+            return None;
+        }
+
+        let mut radius = (self.get_text_position() - vertex).norm();
+        if radius < EPSILON {
+            radius = v1.norm() * 0.6;
+        }
+
+        let a1 = v1.y.atan2(v1.x);
+        let a2 = v2.y.atan2(v2.x);
+        let ccw = angle_between(&v1, &v2) <= 180.0;
+        let (start, end) = if ccw { (a1, a2) } else { (a2, a1) };
+        Some(Arc::new(vertex, radius, start, end))
+    }
+
+    /// 弧长标注的标注弧
+    ///
+    /// 与被测圆弧同心（圆心为 definition_point1），逆时针覆盖从起点
+    /// （definition_point2）到终点（line_location）的较小夹角。
+    /// 设置了文本位置时以圆心到文本的距离减去半个文字高度为半径，
+    /// 否则位于被测圆弧外侧两个箭头大小处。
+    pub fn arc_length_arc(&self) -> Option<Arc> {
+        let center = self.definition_point1;
+        let v1 = self.definition_point2 - center;
+        let v2 = self.line_location - center;
+        let radius = v1.norm();
+        if radius < EPSILON || v2.norm() < EPSILON {
+            return None;
+        }
+
+        let dim_radius = match self.text_position {
+            Some(pos) => ((pos - center).norm() - self.text_height * 0.5).max(EPSILON),
+            None => radius + 2.0 * self.arrow_size(),
+        };
+
+        let a1 = v1.y.atan2(v1.x);
+        let a2 = v2.y.atan2(v2.x);
+        let ccw = angle_between(&v1, &v2) <= 180.0;
+        let (start, end) = if ccw { (a1, a2) } else { (a2, a1) };
+        Some(Arc::new(center, dim_radius, start, end))
+    }
+
+    /// 计算包围盒 (简化估算)
+    pub fn bounding_box(&self) -> BoundingBox2 {
+        BoundingBox2::from_points([
+            self.definition_point1,
+            self.definition_point2,
+            self.line_location,
+        ])
+    }
+
+    /// 检查点是否在标注上 (简化：检查是否在包围盒内)
+    pub fn contains_point(&self, point: &Point2, tolerance: f64) -> bool {
+        let bbox = self.bounding_box();
+        let expanded = BoundingBox2::new(
+            Point2::new(bbox.min.x - tolerance, bbox.min.y - tolerance),
             Point2::new(bbox.max.x + tolerance, bbox.max.y + tolerance),
         );
         expanded.contains(point)
     }
 }
 
+/// 折线（采样点序列）的长度
+fn sampled_length(points: &[Point2]) -> f64 {
+    points.windows(2).map(|w| (w[1] - w[0]).norm()).sum()
+}
+
+/// 采样点序列对有向面积的贡献（格林公式，结果为面积的两倍）
+fn sampled_signed_area2(points: &[Point2]) -> f64 {
+    points.windows(2).map(|w| w[0].x * w[1].y - w[1].x * w[0].y).sum()
+}
+
+/// 标注箭头：以 `tip` 为尖端、沿 `direction` 指向的闭合三角形
+fn dimension_arrow(tip: Point2, direction: Vector2, size: f64) -> Geometry {
+    let dir = if direction.norm() < EPSILON {
+        Vector2::new(1.0, 0.0)
+    } else {
+        direction.normalize()
+    };
+    let perp = Vector2::new(-dir.y, dir.x);
+    let base = tip - dir * size;
+    let half_width = size / 6.0;
+    Geometry::Polyline(Polyline::from_points(
+        [tip, base + perp * half_width, base - perp * half_width],
+        true,
+    ))
+}
+
 impl Polyline {
     /// 爆炸为独立的线段/圆弧
     ///
@@ -801,7 +1969,11 @@ impl Polyline {
                 result.push(Geometry::Line(Line::new(v1.point, v2.point)));
             } else {
                 // 弧线段
-                if let Some(arc) = self.vertex_pair_to_arc(v1, v2) {
+                if let Some(mut arc) = self.vertex_pair_to_arc(v1, v2) {
+                    // 圆弧按逆时针存储，负凸度（顺时针）时交换起止角
+                    if v1.bulge < 0.0 {
+                        std::mem::swap(&mut arc.start_angle, &mut arc.end_angle);
+                    }
                     result.push(Geometry::Arc(arc));
                 } else {
                     // 回退到直线
@@ -813,6 +1985,468 @@ impl Polyline {
         result
     }
 
+    /// 合并距离不超过 `tol` 的相邻重复顶点，返回删除的顶点数
+    ///
+    /// 重复顶点之间的零长度段被去掉，保留后一个顶点出发的线段的凸度和宽度；
+    /// 闭合多段线的最后一个顶点与起点重合时也会删除。
+    pub fn remove_duplicate_vertices(&mut self, tol: f64) -> usize {
+        let original = self.vertices.len();
+        let mut kept: Vec<PolylineVertex> = Vec::with_capacity(original);
+        for vertex in self.vertices.drain(..) {
+            match kept.last_mut() {
+                Some(last) if (last.point - vertex.point).norm() <= tol => {
+                    last.bulge = vertex.bulge;
+                    last.start_width = vertex.start_width;
+                    last.end_width = vertex.end_width;
+                }
+                _ => kept.push(vertex),
+            }
+        }
+        if self.closed && kept.len() > 1 && (kept[kept.len() - 1].point - kept[0].point).norm() <= tol {
+            kept.pop();
+        }
+        self.vertices = kept;
+        original - self.vertices.len()
+    }
+
+    /// 将位于同一圆上的连续直线段识别为圆弧段（凸度）
+    ///
+    /// 至少 3 段、转向一致、顶点到拟合圆的距离和各段弦高都不超过 `tolerance`
+    /// 的直线段合并为一段圆弧；每段圆弧不超过半圆，整圆会识别为两段半圆。
+    /// 已有的圆弧段保持不变。
+    pub fn recognize_arcs(&self, tolerance: f64) -> Polyline {
+        /// 识别为圆弧所需的最少直线段数
+        const MIN_ARC_SEGMENTS: usize = 3;
+
+        let n = self.vertices.len();
+        let segments = self.segment_count();
+        let mut vertices = Vec::with_capacity(n);
+        let mut i = 0;
+        while i < segments {
+            // 尽量向后延伸，直到不再能拟合为圆弧
+            let mut best = None;
+            let mut j = i + MIN_ARC_SEGMENTS;
+            while j <= segments {
+                match self.fit_arc_run(i, j, tolerance) {
+                    Some(sweep) => best = Some((j, sweep)),
+                    None => break,
+                }
+                j += 1;
+            }
+
+            let mut vertex = self.vertices[i].clone();
+            match best {
+                Some((end, sweep)) => {
+                    vertex.bulge = (sweep / 4.0).tan();
+                    vertex.end_width = self.vertices[end - 1].end_width;
+                    i = end;
+                }
+                None => i += 1,
+            }
+            vertices.push(vertex);
+        }
+        if !self.closed && n > 0 {
+            vertices.push(self.vertices[n - 1].clone());
+        }
+        Polyline::new(vertices, self.closed)
+    }
+
+    /// 第 `first` 段到第 `last - 1` 段能否拟合为一段圆弧，返回带符号的扫掠角（逆时针为正）
+    fn fit_arc_run(&self, first: usize, last: usize, tolerance: f64) -> Option<f64> {
+        let n = self.vertices.len();
+        if (first..last).any(|k| self.vertices[k].bulge.abs() > EPSILON) {
+            return None;
+        }
+        let points: Vec<Point2> = (first..=last).map(|k| self.vertices[k % n].point).collect();
+        let arc = Arc::from_three_points(points[0], points[points.len() / 2], points[points.len() - 1])?;
+        let (center, radius) = (arc.center, arc.radius);
+        if points.iter().any(|p| ((p - center).norm() - radius).abs() > tolerance) {
+            return None;
+        }
+
+        let mut sweep = 0.0;
+        let mut direction = 0.0;
+        for pair in points.windows(2) {
+            let (a, b) = (pair[0] - center, pair[1] - center);
+            let step = (a.x * b.y - a.y * b.x).atan2(a.dot(&b));
+            // 转向必须一致，弦高不超过容差
+            if step.abs() < EPSILON || step * direction < 0.0 {
+                return None;
+            }
+            if radius * (1.0 - (step / 2.0).cos()) > tolerance {
+                return None;
+            }
+            direction = step.signum();
+            sweep += step;
+        }
+        (sweep.abs() <= std::f64::consts::PI + EPSILON).then_some(sweep)
+    }
+
+    /// 设置全局宽度（所有线段起止宽度相同）
+    pub fn set_constant_width(&mut self, width: f64) {
+        for vertex in &mut self.vertices {
+            vertex.start_width = width;
+            vertex.end_width = width;
+        }
+    }
+
+    /// 全局宽度：所有线段起止宽度都相同时返回该宽度
+    pub fn constant_width(&self) -> Option<f64> {
+        let segments = &self.vertices[..self.segment_count()];
+        let width = segments.first()?.start_width;
+        segments
+            .iter()
+            .all(|v| (v.start_width - width).abs() < EPSILON && (v.end_width - width).abs() < EPSILON)
+            .then_some(width)
+    }
+
+    /// 是否有线段设置了宽度
+    pub fn has_width(&self) -> bool {
+        self.vertices[..self.segment_count()].iter().any(PolylineVertex::has_width)
+    }
+
+    /// 有宽度线段的填充轮廓
+    ///
+    /// 每个有宽度的线段返回一个多边形：先沿左侧从起点到终点，再沿右侧返回，
+    /// 左右两侧点数相同，宽度沿线段线性渐变；弧线段按约 11.25° 一步采样。
+    pub fn width_outlines(&self) -> Vec<Vec<Point2>> {
+        let mut outlines = Vec::new();
+        for (i, segment) in self.explode().iter().enumerate() {
+            let vertex = &self.vertices[i];
+            if !vertex.has_width() {
+                continue;
+            }
+
+            let centerline = match segment {
+                Geometry::Arc(arc) => {
+                    let sweep = arc.sweep_angle();
+                    let steps = ((sweep / (std::f64::consts::PI / 16.0)).ceil() as usize).max(2);
+                    let mut points: Vec<Point2> = (0..=steps)
+                        .map(|k| {
+                            let angle = arc.start_angle + sweep * k as f64 / steps as f64;
+                            arc.center + Vector2::new(angle.cos(), angle.sin()) * arc.radius
+                        })
+                        .collect();
+                    // 弧按逆时针存储，顺时针线段需要反转为从起点顶点出发
+                    if (points[0] - vertex.point).norm() > (points[steps] - vertex.point).norm() {
+                        points.reverse();
+                    }
+                    points
+                }
+                Geometry::Line(line) => vec![line.start, line.end],
+                _ => continue,
+            };
+
+            let count = centerline.len();
+            let mut left = Vec::with_capacity(count * 2);
+            let mut right = Vec::with_capacity(count);
+            for k in 0..count {
+                let direction = centerline[(k + 1).min(count - 1)] - centerline[k.saturating_sub(1)];
+                let length = direction.norm();
+                if length < EPSILON {
+                    continue;
+                }
+                let normal = Vector2::new(-direction.y, direction.x) / length;
+                let t = k as f64 / (count - 1) as f64;
+                let half = (vertex.start_width + (vertex.end_width - vertex.start_width) * t) / 2.0;
+                left.push(centerline[k] + normal * half);
+                right.push(centerline[k] - normal * half);
+            }
+            if left.len() < 2 {
+                continue;
+            }
+            left.extend(right.into_iter().rev());
+            outlines.push(left);
+        }
+        outlines
+    }
+
+    /// 线段中点（弧线段返回弧上的中点而非弦中点）
+    pub fn segment_midpoint(&self, segment_index: usize) -> Option<Point2> {
+        if segment_index >= self.segment_count() {
+            return None;
+        }
+
+        let v1 = &self.vertices[segment_index];
+        let v2 = &self.vertices[(segment_index + 1) % self.vertices.len()];
+        let chord = v2.point - v1.point;
+        let mid = v1.point + chord * 0.5;
+
+        let chord_len = chord.norm();
+        if v1.bulge.abs() < EPSILON || chord_len < EPSILON {
+            return Some(mid);
+        }
+
+        // 弧高 = 半弦长 × 凸度，正凸度（逆时针）时弧位于弦的右侧
+        let right = Vector2::new(chord.y, -chord.x) / chord_len;
+        Some(mid + right * (chord_len / 2.0 * v1.bulge))
+    }
+
+    /// 多段线上距离给定点最近的点（直线段和弧线段分别精确求解）
+    pub fn nearest_point(&self, point: &Point2) -> Option<Point2> {
+        if self.vertices.len() == 1 {
+            return Some(self.vertices[0].point);
+        }
+        self.explode()
+            .iter()
+            .filter_map(|segment| match segment {
+                Geometry::Line(line) => Some(line.nearest_point(point)),
+                Geometry::Arc(arc) => Some(arc.nearest_point(point)),
+                _ => None,
+            })
+            .min_by(|a, b| (a - point).norm().total_cmp(&(b - point).norm()))
+    }
+
+    /// 线段在终点处的切线方向（单位向量）
+    ///
+    /// 弧线段的终点切线等于弦方向转过半个包含角，即 `2·atan(bulge)`。
+    pub fn segment_end_tangent(&self, segment_index: usize) -> Option<Vector2> {
+        if segment_index >= self.segment_count() {
+            return None;
+        }
+
+        let v1 = &self.vertices[segment_index];
+        let v2 = &self.vertices[(segment_index + 1) % self.vertices.len()];
+        let chord = v2.point - v1.point;
+        let chord_len = chord.norm();
+        if chord_len < EPSILON {
+            return None;
+        }
+
+        let (sin, cos) = (2.0 * v1.bulge.atan()).sin_cos();
+        let dir = chord / chord_len;
+        Some(Vector2::new(dir.x * cos - dir.y * sin, dir.x * sin + dir.y * cos))
+    }
+
+    /// 由起点切线方向计算到终点的圆弧凸度
+    ///
+    /// 切线与弦的夹角等于包含角的一半，因此凸度为 `tan(夹角 / 2)`；
+    /// 正值为逆时针弧。切线与弦同向或退化时返回 0（直线段）。
+    pub fn bulge_from_tangent(start: Point2, tangent: Vector2, end: Point2) -> f64 {
+        let chord = end - start;
+        if chord.norm() < EPSILON || tangent.norm() < EPSILON {
+            return 0.0;
+        }
+        let cross = tangent.x * chord.y - tangent.y * chord.x;
+        let half_angle = cross.atan2(tangent.dot(&chord));
+        (half_angle / 2.0).tan()
+    }
+
+    /// 在指定索引处插入顶点
+    ///
+    /// `index` 可以等于顶点数（追加到末尾），超出范围时返回 false。
+    pub fn insert_vertex(&mut self, index: usize, vertex: PolylineVertex) -> bool {
+        if index > self.vertices.len() {
+            return false;
+        }
+        self.vertices.insert(index, vertex);
+        true
+    }
+
+    /// 删除指定索引处的顶点
+    ///
+    /// 多段线至少保留两个顶点；被合并的线段退化为直线段。
+    pub fn remove_vertex(&mut self, index: usize) -> Option<PolylineVertex> {
+        if index >= self.vertices.len() || self.vertices.len() <= 2 {
+            return None;
+        }
+
+        let removed = self.vertices.remove(index);
+
+        // 前一个顶点原本连接到被删除顶点，现在直接连接到下一个顶点
+        let prev = if index > 0 {
+            Some(index - 1)
+        } else if self.closed {
+            Some(self.vertices.len() - 1)
+        } else {
+            None
+        };
+        if let Some(prev) = prev {
+            self.vertices[prev].bulge = 0.0;
+        }
+
+        Some(removed)
+    }
+
+    /// 在线段上插入新顶点，将该线段一分为二
+    ///
+    /// `segment_index` 为线段起点的顶点索引。对于弧线段，会根据新顶点在弧上的
+    /// 位置重新分配两段的凸度，保持原有弧形不变；线段宽度按位置线性插值。
+    pub fn split_segment_at(&mut self, segment_index: usize, point: Point2) -> bool {
+        if segment_index >= self.segment_count() {
+            return false;
+        }
+
+        let v1 = self.vertices[segment_index].clone();
+        let v2 = &self.vertices[(segment_index + 1) % self.vertices.len()];
+
+        let (bulge1, bulge2, t) = match self.vertex_pair_to_arc(&v1, v2) {
+            Some(arc) if v1.bulge.abs() >= EPSILON => {
+                let total = 4.0 * v1.bulge.abs().atan();
+                let point_angle = (point.y - arc.center.y).atan2(point.x - arc.center.x);
+                let sweep = if v1.bulge > 0.0 {
+                    point_angle - arc.start_angle
+                } else {
+                    arc.start_angle - point_angle
+                };
+                let sweep = sweep.rem_euclid(std::f64::consts::TAU).min(total);
+                let sign = v1.bulge.signum();
+                (sign * (sweep / 4.0).tan(), sign * ((total - sweep) / 4.0).tan(), sweep / total)
+            }
+            _ => {
+                let chord = v2.point - v1.point;
+                let length_sq = chord.norm_squared();
+                let t = if length_sq > EPSILON {
+                    ((point - v1.point).dot(&chord) / length_sq).clamp(0.0, 1.0)
+                } else {
+                    0.0
+                };
+                (0.0, 0.0, t)
+            }
+        };
+
+        let split_width = v1.start_width + (v1.end_width - v1.start_width) * t;
+        self.vertices[segment_index].bulge = bulge1;
+        self.vertices[segment_index].end_width = split_width;
+        self.vertices.insert(
+            segment_index + 1,
+            PolylineVertex::with_bulge(point, bulge2).with_widths(split_width, v1.end_width),
+        );
+        true
+    }
+
+    /// 多段线上距离给定点最近的位置：(线段索引, 线段上的最近点)
+    fn locate(&self, point: &Point2) -> Option<(usize, Point2)> {
+        self.explode()
+            .iter()
+            .enumerate()
+            .filter_map(|(i, segment)| match segment {
+                Geometry::Line(line) => Some((i, line.nearest_point(point))),
+                Geometry::Arc(arc) => Some((i, arc.nearest_point(point))),
+                _ => None,
+            })
+            .min_by(|a, b| (a.1 - point).norm().total_cmp(&(b.1 - point).norm()))
+    }
+
+    /// 打断：删除两点之间的部分（两点相同时在该点一分为二）
+    ///
+    /// 开放多段线返回一到两段；闭合多段线返回一段从第二点绕回第一点的开放多段线。
+    /// 两点按多段线走向排序，与拾取顺序无关。
+    pub fn break_between(&self, first: Point2, second: Point2) -> Vec<Polyline> {
+        let (Some(a), Some(b)) = (self.locate(&first), self.locate(&second)) else {
+            return Vec::new();
+        };
+        let along = |(i, q): (usize, Point2)| (i, (q - self.vertices[i].point).norm());
+        let (a, b) = if along(a) <= along(b) { (a, b) } else { (b, a) };
+
+        // 先插入靠后的点，前面的索引不受影响
+        let mut split = self.clone();
+        split.split_segment_at(b.0, b.1);
+        split.split_segment_at(a.0, a.1);
+        let (ka, kb) = (a.0 + 1, b.0 + 2);
+
+        let runs: Vec<Vec<PolylineVertex>> = if self.closed {
+            vec![split.vertices[kb..].iter().chain(&split.vertices[..=ka]).cloned().collect()]
+        } else {
+            vec![split.vertices[..=ka].to_vec(), split.vertices[kb..].to_vec()]
+        };
+        runs.into_iter().filter_map(Self::open_run).collect()
+    }
+
+    /// 由连续顶点构造开放多段线，合并重合的相邻顶点；不足两个顶点时返回 `None`
+    fn open_run(vertices: Vec<PolylineVertex>) -> Option<Polyline> {
+        let mut merged: Vec<PolylineVertex> = Vec::with_capacity(vertices.len());
+        for vertex in vertices {
+            match merged.last_mut() {
+                // 重合顶点之间是零长度线段，保留后一个顶点出发的线段数据
+                Some(last) if (last.point - vertex.point).norm() < EPSILON => *last = vertex,
+                _ => merged.push(vertex),
+            }
+        }
+        if merged.len() < 2 {
+            return None;
+        }
+        if let Some(last) = merged.last_mut() {
+            *last = PolylineVertex::new(last.point);
+        }
+        Some(Polyline::new(merged, false))
+    }
+
+    /// 以同一半径对所有内部拐角倒圆角
+    ///
+    /// 每个两侧都是直线段的拐角被替换为相切圆弧（以凸度编码）。拐角过尖、
+    /// 共线，或相邻线段剩余长度不足以容纳切点时跳过该拐角。
+    pub fn fillet_all(&self, radius: f64) -> Polyline {
+        let n = self.vertices.len();
+        let segments = self.segment_count();
+        if radius <= EPSILON || n < 3 {
+            return self.clone();
+        }
+
+        // 每条线段起点侧/终点侧已被圆角占用的长度
+        let mut used_start = vec![0.0; segments];
+        let mut used_end = vec![0.0; segments];
+        // 每个顶点的圆角：(切点距离, 凸度)
+        let mut fillets: Vec<Option<(f64, f64)>> = vec![None; n];
+
+        for i in 0..n {
+            let (seg_in, seg_out) = match (i, self.closed) {
+                (0, true) => (n - 1, 0),
+                (0, false) => continue,
+                (i, false) if i == n - 1 => continue,
+                (i, _) => (i - 1, i),
+            };
+            if self.vertices[seg_in].bulge.abs() > EPSILON || self.vertices[seg_out].bulge.abs() > EPSILON {
+                continue;
+            }
+
+            let corner = self.vertices[i].point;
+            let prev = self.vertices[seg_in].point;
+            let next = self.vertices[(i + 1) % n].point;
+            let (len_in, len_out) = ((corner - prev).norm(), (next - corner).norm());
+            if len_in < EPSILON || len_out < EPSILON {
+                continue;
+            }
+
+            let d1 = (prev - corner) / len_in;
+            let d2 = (next - corner) / len_out;
+            let theta = d1.dot(&d2).clamp(-1.0, 1.0).acos();
+            if theta < EPSILON || std::f64::consts::PI - theta < EPSILON {
+                continue;
+            }
+
+            let tangent = radius / (theta / 2.0).tan();
+            if tangent > len_in - used_start[seg_in] + EPSILON || tangent > len_out - used_end[seg_out] + EPSILON {
+                continue;
+            }
+            used_end[seg_in] = tangent;
+            used_start[seg_out] = tangent;
+
+            // 左转为逆时针圆弧（正凸度）
+            let turn = (corner - prev).perp(&(next - corner));
+            let sweep = std::f64::consts::PI - theta;
+            fillets[i] = Some((tangent, turn.signum() * (sweep / 4.0).tan()));
+        }
+
+        let mut vertices = Vec::with_capacity(n * 2);
+        for (i, vertex) in self.vertices.iter().enumerate() {
+            match fillets[i] {
+                Some((tangent, bulge)) => {
+                    let prev = self.vertices[(i + n - 1) % n].point;
+                    let next = self.vertices[(i + 1) % n].point;
+                    let corner = vertex.point;
+                    let t1 = corner + (prev - corner).normalize() * tangent;
+                    let t2 = corner + (next - corner).normalize() * tangent;
+                    vertices.push(PolylineVertex::with_bulge(t1, bulge));
+                    vertices.push(PolylineVertex::with_bulge(t2, vertex.bulge));
+                }
+                None => vertices.push(vertex.clone()),
+            }
+        }
+
+        Polyline::new(vertices, self.closed)
+    }
+
     /// 将顶点对转换为圆弧
     fn vertex_pair_to_arc(&self, v1: &PolylineVertex, v2: &PolylineVertex) -> Option<Arc> {
         let chord = v2.point - v1.point;
@@ -932,6 +2566,17 @@ impl Ellipse {
         (self.end_param - self.start_param - 2.0 * std::f64::consts::PI).abs() < EPSILON
     }
 
+    /// 检查参数是否在椭圆弧的范围内（逆时针从起始参数到终止参数）
+    pub fn contains_param(&self, t: f64) -> bool {
+        if self.is_full() {
+            return true;
+        }
+        let two_pi = 2.0 * std::f64::consts::PI;
+        let span = (self.end_param - self.start_param).rem_euclid(two_pi);
+        let offset = (t - self.start_param).rem_euclid(two_pi);
+        offset <= span + EPSILON || offset >= two_pi - EPSILON
+    }
+
     /// 获取椭圆上指定参数的点
     /// 
     /// 参数 t 是椭圆的参数化角度，不是真正的几何角度
@@ -947,6 +2592,22 @@ impl Ellipse {
         )
     }
 
+    /// 从中心出发、世界坐标系中角度为 `theta` 的射线所对应的参数
+    ///
+    /// 先扣除长轴旋转得到局部角度 φ，再由 tan t = (a/b)·tan φ 求参数。
+    pub fn param_at_geometric_angle(&self, theta: f64) -> f64 {
+        let phi = theta - self.rotation();
+        let t = (self.major_radius() * phi.sin()).atan2(self.minor_radius() * phi.cos());
+        t.rem_euclid(2.0 * std::f64::consts::PI)
+    }
+
+    /// 获取从中心出发、世界坐标系中角度为 `theta` 的射线与椭圆的交点
+    ///
+    /// 与 [`Ellipse::point_at_param`] 不同，这里的角度是真正的几何角度
+    pub fn point_at_geometric_angle(&self, theta: f64) -> Point2 {
+        self.point_at_param(self.param_at_geometric_angle(theta))
+    }
+
     /// 获取起点
     pub fn start_point(&self) -> Point2 {
         self.point_at_param(self.start_param)
@@ -970,6 +2631,31 @@ impl Ellipse {
         std::f64::consts::PI * self.major_radius() * self.minor_radius()
     }
 
+    /// 椭圆（弧）上距离给定点最近的点
+    ///
+    /// 先按参数均匀采样确定最近点所在区间，再在区间内做黄金分割搜索，
+    /// 结果为参数范围内的精确垂足（或端点）。
+    pub fn nearest_point(&self, point: &Point2) -> Point2 {
+        self.point_at_param(self.closest_param(point).0)
+    }
+
+    /// 最近点的参数及椭圆（弧）的参数跨度（完整椭圆的参数可能越过首尾衔接处）
+    fn closest_param(&self, point: &Point2) -> (f64, f64) {
+        let span = if self.is_full() {
+            2.0 * std::f64::consts::PI
+        } else {
+            (self.end_param - self.start_param).rem_euclid(2.0 * std::f64::consts::PI)
+        };
+        let t = nearest_param(
+            |t| self.point_at_param(t),
+            point,
+            self.start_param,
+            self.start_param + span,
+            self.is_full(),
+        );
+        (t, span)
+    }
+
     /// 计算点到椭圆的距离（近似值）
     pub fn distance_to_point(&self, point: &Point2) -> f64 {
         // 将点转换到椭圆的局部坐标系
@@ -1112,17 +2798,79 @@ pub enum SplineType {
     Bezier,
 }
 
-/// 样条曲线
-/// 
-/// 支持 B-样条和 NURBS 曲线，使用 De Boor 算法求值
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Spline {
-    /// 样条类型
-    pub spline_type: SplineType,
-    /// 曲线阶数（通常为 3 或 4）
-    pub degree: u8,
-    /// 控制点
-    pub control_points: Vec<Point2>,
+/// 齐次坐标线性插值 (1 - t)·a + t·b
+fn lerp3(a: [f64; 3], b: [f64; 3], t: f64) -> [f64; 3] {
+    [
+        (1.0 - t) * a[0] + t * b[0],
+        (1.0 - t) * a[1] + t * b[1],
+        (1.0 - t) * a[2] + t * b[2],
+    ]
+}
+
+/// 参数曲线 `curve` 在 `[start, end]` 内距离 `point` 最近的参数
+///
+/// 均匀采样找到最近的采样点，再在其相邻区间内做黄金分割搜索。
+/// 曲线在该区间内到点的距离是单峰的，因此结果收敛到真正的垂足或端点。
+/// `periodic` 为 true 时（完整椭圆等闭合曲线）搜索区间允许跨过首尾衔接处。
+fn nearest_param(curve: impl Fn(f64) -> Point2, point: &Point2, start: f64, end: f64, periodic: bool) -> f64 {
+    const SAMPLES: usize = 64;
+    let dist = |t: f64| (curve(t) - point).norm_squared();
+    let step = (end - start) / SAMPLES as f64;
+    if step.abs() < EPSILON {
+        return start;
+    }
+
+    let best = (0..=SAMPLES)
+        .min_by(|&a, &b| dist(start + step * a as f64).total_cmp(&dist(start + step * b as f64)))
+        .unwrap_or(0);
+    let (mut lo, mut hi) = (start + step * (best as f64 - 1.0), start + step * (best as f64 + 1.0));
+    if !periodic {
+        lo = lo.max(start);
+        hi = hi.min(end);
+    }
+
+    let ratio = (5.0_f64.sqrt() - 1.0) / 2.0;
+    let mut x1 = hi - ratio * (hi - lo);
+    let mut x2 = lo + ratio * (hi - lo);
+    let (mut f1, mut f2) = (dist(x1), dist(x2));
+    for _ in 0..80 {
+        if f1 < f2 {
+            hi = x2;
+            x2 = x1;
+            f2 = f1;
+            x1 = hi - ratio * (hi - lo);
+            f1 = dist(x1);
+        } else {
+            lo = x1;
+            x1 = x2;
+            f1 = f2;
+            x2 = lo + ratio * (hi - lo);
+            f2 = dist(x2);
+        }
+    }
+    let t = (lo + hi) / 2.0;
+    if periodic {
+        return t;
+    }
+
+    // 端点可能比区间内的极小值更近
+    [start, t, end]
+        .into_iter()
+        .min_by(|&a, &b| dist(a).total_cmp(&dist(b)))
+        .unwrap_or(t)
+}
+
+/// 样条曲线
+/// 
+/// 支持 B-样条和 NURBS 曲线，使用 De Boor 算法求值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Spline {
+    /// 样条类型
+    pub spline_type: SplineType,
+    /// 曲线阶数（通常为 3 或 4）
+    pub degree: u8,
+    /// 控制点
+    pub control_points: Vec<Point2>,
     /// 节点向量（knot vector）
     pub knots: Vec<f64>,
     /// 权重（用于 NURBS，如果为空则默认全为 1）
@@ -1133,6 +2881,9 @@ pub struct Spline {
     pub fit_points: Vec<Point2>,
 }
 
+/// 样条包围盒的采样段数
+const SPLINE_BBOX_SAMPLES: usize = 32;
+
 impl Spline {
     /// 创建一个空的 B-样条
     pub fn new(degree: u8) -> Self {
@@ -1187,15 +2938,10 @@ impl Spline {
             return self.control_points[0];
         }
         
-        let n = self.control_points.len();
         let k = self.degree as usize;
         
         // 找到 t 所在的区间
-        let mut span = k;
-        while span < n && self.knots.get(span + 1).map_or(false, |&k| k <= t) {
-            span += 1;
-        }
-        span = span.min(n - 1);
+        let span = self.find_span(t);
         
         // De Boor 算法
         let mut d: Vec<Point2> = (0..=k)
@@ -1236,6 +2982,158 @@ impl Spline {
         d.get(k).copied().unwrap_or(Point2::origin())
     }
 
+    /// 参数 t 所在的节点区间索引
+    fn find_span(&self, t: f64) -> usize {
+        let n = self.control_points.len();
+        let mut span = self.degree as usize;
+        while span < n && self.knots.get(span + 1).is_some_and(|&k| k <= t) {
+            span += 1;
+        }
+        span.min(n - 1)
+    }
+
+    /// 节点向量和控制点数量是否构成有效的样条
+    fn is_well_formed(&self) -> bool {
+        let p = self.degree as usize;
+        p >= 1
+            && self.control_points.len() > p
+            && self.knots.len() == self.control_points.len() + p + 1
+            && (self.weights.is_empty() || self.weights.len() == self.control_points.len())
+    }
+
+    /// 齐次坐标形式的控制点 (w·x, w·y, w)
+    fn homogeneous_points(&self) -> Vec<[f64; 3]> {
+        self.control_points
+            .iter()
+            .enumerate()
+            .map(|(i, p)| {
+                let w = self.weights.get(i).copied().unwrap_or(1.0);
+                [p.x * w, p.y * w, w]
+            })
+            .collect()
+    }
+
+    /// 从齐次坐标写回控制点和权重（原本没有权重时保持为空）
+    fn set_homogeneous_points(&mut self, points: Vec<[f64; 3]>) {
+        let rational = !self.weights.is_empty();
+        self.control_points = points.iter().map(|h| Point2::new(h[0] / h[2], h[1] / h[2])).collect();
+        self.weights = if rational { points.iter().map(|h| h[2]).collect() } else { Vec::new() };
+    }
+
+    /// 插入节点（Boehm 算法）
+    ///
+    /// 增加一个控制点而不改变曲线形状，用于在局部获得更细的控制。
+    /// `u` 必须在参数范围内部，否则返回 `false` 且不做修改。
+    pub fn insert_knot(&mut self, u: f64) -> bool {
+        let (start, end) = self.param_range();
+        if !self.is_well_formed() || u <= start || u >= end {
+            return false;
+        }
+
+        let p = self.degree as usize;
+        let span = self.find_span(u);
+        let old = self.homogeneous_points();
+
+        let mut points = Vec::with_capacity(old.len() + 1);
+        for i in 0..=old.len() {
+            let q = if i + p <= span {
+                old[i]
+            } else if i > span {
+                old[i - 1]
+            } else {
+                let alpha = (u - self.knots[i]) / (self.knots[i + p] - self.knots[i]);
+                lerp3(old[i - 1], old[i], alpha)
+            };
+            points.push(q);
+        }
+
+        self.knots.insert(span + 1, u);
+        self.set_homogeneous_points(points);
+        true
+    }
+
+    /// 升阶：阶数加一而不改变曲线形状
+    ///
+    /// 先按节点区间分解为贝塞尔段（由开花值求控制点），逐段升阶后重新拼接为
+    /// 端点夹紧、内部节点重数等于新阶数的样条。
+    pub fn elevate_degree(&mut self) -> bool {
+        if !self.is_well_formed() || self.degree == u8::MAX {
+            return false;
+        }
+
+        let p = self.degree as usize;
+        let q = p + 1;
+        let (start, end) = self.param_range();
+        let mut breaks: Vec<f64> = self
+            .knots
+            .iter()
+            .copied()
+            .filter(|&k| k > start + EPSILON && k < end - EPSILON)
+            .collect();
+        breaks.dedup_by(|a, b| (*a - *b).abs() < EPSILON);
+        breaks.insert(0, start);
+        breaks.push(end);
+
+        let homogeneous = self.homogeneous_points();
+        let mut points: Vec<[f64; 3]> = Vec::new();
+        for window in breaks.windows(2) {
+            let (a, b) = (window[0], window[1]);
+            let span = self.find_span(a);
+
+            // 该区间上的贝塞尔控制点 B_j = f(a^(p-j), b^j)
+            let bezier: Vec<[f64; 3]> = (0..=p)
+                .map(|j| {
+                    let args: Vec<f64> = (0..p).map(|r| if r < p - j { a } else { b }).collect();
+                    self.blossom(&homogeneous, span, &args)
+                })
+                .collect();
+
+            // 贝塞尔升阶：C_i = i/(p+1)·B_(i-1) + (1 - i/(p+1))·B_i
+            let first = if points.is_empty() { 0 } else { 1 };
+            for i in first..=q {
+                let c = if i == 0 {
+                    bezier[0]
+                } else if i == q {
+                    bezier[p]
+                } else {
+                    lerp3(bezier[i], bezier[i - 1], i as f64 / q as f64)
+                };
+                points.push(c);
+            }
+        }
+
+        let mut knots = vec![start; q + 1];
+        for &k in &breaks[1..breaks.len() - 1] {
+            knots.extend(std::iter::repeat_n(k, q));
+        }
+        knots.extend(std::iter::repeat_n(end, q + 1));
+
+        self.degree = q as u8;
+        self.knots = knots;
+        self.set_homogeneous_points(points);
+        true
+    }
+
+    /// 开花值：逐层使用不同参数的 De Boor 算法
+    fn blossom(&self, points: &[[f64; 3]], span: usize, args: &[f64]) -> [f64; 3] {
+        let p = self.degree as usize;
+        let mut d: Vec<[f64; 3]> = points[span - p..=span].to_vec();
+        for r in 1..=p {
+            let t = args[r - 1];
+            for j in (r..=p).rev() {
+                let i = span - p + j;
+                let left = self.knots[i];
+                let right = self.knots[i + p + 1 - r];
+                let denom = right - left;
+                if denom.abs() < EPSILON {
+                    continue;
+                }
+                d[j] = lerp3(d[j - 1], d[j], (t - left) / denom);
+            }
+        }
+        d[p]
+    }
+
     /// 获取参数范围
     pub fn param_range(&self) -> (f64, f64) {
         let k = self.degree as usize;
@@ -1244,6 +3142,26 @@ impl Spline {
         (start, end)
     }
 
+    /// 曲线起点（参数范围起点处的点，非夹持节点向量时不一定与首个控制点重合）
+    pub fn start_point(&self) -> Point2 {
+        self.point_at_param(self.param_range().0)
+    }
+
+    /// 曲线终点
+    pub fn end_point(&self) -> Point2 {
+        self.point_at_param(self.param_range().1)
+    }
+
+    /// 样条曲线上距离给定点最近的点（采样定位后黄金分割精确求解）
+    pub fn nearest_point(&self, point: &Point2) -> Point2 {
+        if self.control_points.len() < 2 {
+            return self.control_points.first().copied().unwrap_or(*point);
+        }
+        let (start, end) = self.param_range();
+        let t = nearest_param(|t| self.point_at_param(t), point, start, end, false);
+        self.point_at_param(t)
+    }
+
     /// 计算点到样条曲线的距离（近似值）
     pub fn distance_to_point(&self, point: &Point2) -> f64 {
         let samples = self.sample_points(64);
@@ -1268,13 +3186,50 @@ impl Spline {
         let mut bbox = BoundingBox2::from_points(self.control_points.iter().copied());
         
         // 添加采样点以获得更精确的包围盒
-        for pt in self.sample_points(32) {
+        for pt in self.sample_points(SPLINE_BBOX_SAMPLES) {
             bbox.expand_to_include(&pt);
         }
         
         bbox
     }
 
+    /// 单个控制点移动后增量更新包围盒
+    ///
+    /// `self` 为移动后的样条，`previous` 为移动前的包围盒，`old_point` 为该控制点原位置。
+    /// 控制点 i 只影响节点区间 i..=i+p 上的曲线，只重新求值落在这些区间的采样点（与
+    /// [`Spline::bounding_box`] 的采样参数相同）。被替换的旧控制点和旧采样点都严格位于
+    /// 旧包围盒内部时，结果为旧包围盒与新点的并集，与完整重算一致；否则回退到完整重算。
+    pub fn bounding_box_after_control_point_move(
+        &self,
+        previous: &BoundingBox2,
+        index: usize,
+        old_point: Point2,
+    ) -> BoundingBox2 {
+        let p = self.degree as usize;
+        if !self.is_well_formed() || index >= self.control_points.len() || !previous.strictly_contains(&old_point) {
+            return self.bounding_box();
+        }
+
+        let mut old = self.clone();
+        old.control_points[index] = old_point;
+
+        let (start, end) = self.param_range();
+        let mut bbox = *previous;
+        bbox.expand_to_include(&self.control_points[index]);
+        for i in 0..=SPLINE_BBOX_SAMPLES {
+            let t = start + (end - start) * (i as f64) / (SPLINE_BBOX_SAMPLES as f64);
+            let span = self.find_span(t);
+            if span < index || span > index + p {
+                continue;
+            }
+            if !previous.strictly_contains(&old.point_at_param(t)) {
+                return self.bounding_box();
+            }
+            bbox.expand_to_include(&self.point_at_param(t));
+        }
+        bbox
+    }
+
     /// 获取采样点（用于渲染）
     pub fn sample_points(&self, segments: usize) -> Vec<Point2> {
         let mut points = Vec::with_capacity(segments + 1);
@@ -1318,6 +3273,74 @@ impl HatchBoundary {
         Self { elements, is_outer }
     }
 
+    /// 由闭合几何生成边界（闭合多段线、圆、整椭圆、闭合样条），其他几何返回 None
+    pub fn from_geometry(geometry: &Geometry, is_outer: bool) -> Option<Self> {
+        let elements = match geometry {
+            Geometry::Polyline(pl) if pl.closed => pl
+                .explode()
+                .into_iter()
+                .filter_map(|g| match g {
+                    Geometry::Line(l) => Some(HatchBoundaryElement::Line(l)),
+                    Geometry::Arc(a) => Some(HatchBoundaryElement::Arc(a)),
+                    _ => None,
+                })
+                .collect(),
+            Geometry::Circle(c) => vec![HatchBoundaryElement::Arc(Arc::new(
+                c.center,
+                c.radius,
+                0.0,
+                std::f64::consts::TAU,
+            ))],
+            Geometry::Ellipse(e) if e.is_full() => vec![HatchBoundaryElement::Ellipse(e.clone())],
+            Geometry::Spline(s) if s.closed => vec![HatchBoundaryElement::Spline(s.clone())],
+            _ => return None,
+        };
+        Some(Self::new(elements, is_outer))
+    }
+
+    /// 边界元素的采样点（按元素自身方向）
+    fn element_points(elem: &HatchBoundaryElement) -> Vec<Point2> {
+        match elem {
+            HatchBoundaryElement::Line(l) => vec![l.start, l.end],
+            HatchBoundaryElement::Arc(a) => {
+                let sweep = a.sweep_angle();
+                (0..=64)
+                    .map(|i| {
+                        let angle = a.start_angle + sweep * (i as f64) / 64.0;
+                        Point2::new(
+                            a.center.x + a.radius * angle.cos(),
+                            a.center.y + a.radius * angle.sin(),
+                        )
+                    })
+                    .collect()
+            }
+            HatchBoundaryElement::Ellipse(e) => e.sample_points(64),
+            HatchBoundaryElement::Spline(s) => s.sample_points(64),
+        }
+    }
+
+    /// 边界围成的面积（曲线元素采样近似）
+    pub fn area(&self) -> f64 {
+        let signed: f64 = self
+            .elements
+            .iter()
+            .map(|elem| sampled_signed_area2(&Self::element_points(elem)))
+            .sum();
+        signed.abs() / 2.0
+    }
+
+    /// 边界总长度
+    pub fn length(&self) -> f64 {
+        self.elements
+            .iter()
+            .map(|elem| match elem {
+                HatchBoundaryElement::Line(l) => l.length(),
+                HatchBoundaryElement::Arc(a) => a.length(),
+                _ => sampled_length(&Self::element_points(elem)),
+            })
+            .sum()
+    }
+
     /// 获取边界的包围盒
     pub fn bounding_box(&self) -> BoundingBox2 {
         let mut bbox = BoundingBox2::empty();
@@ -1372,6 +3395,11 @@ pub struct Hatch {
     pub angle: f64,
     /// 图案比例
     pub scale: f64,
+    /// 关联的边界实体（为空表示非关联填充，使用显式边界）
+    ///
+    /// 第一个实体为外边界，其余为孔洞；边界实体修改后由文档重新生成 `boundaries`。
+    #[serde(default)]
+    pub boundary_entity_ids: Vec<EntityId>,
 }
 
 impl Hatch {
@@ -1382,6 +3410,7 @@ impl Hatch {
             pattern_type: HatchPatternType::Solid,
             angle: 0.0,
             scale: 1.0,
+            boundary_entity_ids: Vec::new(),
         }
     }
 
@@ -1392,9 +3421,21 @@ impl Hatch {
             pattern_type: HatchPatternType::Predefined(pattern_name.to_string()),
             angle,
             scale,
+            boundary_entity_ids: Vec::new(),
         }
     }
 
+    /// 关联到边界实体
+    pub fn with_boundary_entities(mut self, ids: Vec<EntityId>) -> Self {
+        self.boundary_entity_ids = ids;
+        self
+    }
+
+    /// 是否为关联填充
+    pub fn is_associative(&self) -> bool {
+        !self.boundary_entity_ids.is_empty()
+    }
+
     /// 获取包围盒
     pub fn bounding_box(&self) -> BoundingBox2 {
         let mut bbox = BoundingBox2::empty();
@@ -1404,6 +3445,16 @@ impl Hatch {
         bbox
     }
 
+    /// 填充面积（外边界面积减去孔洞面积）
+    pub fn area(&self) -> f64 {
+        let area: f64 = self
+            .boundaries
+            .iter()
+            .map(|b| if b.is_outer { b.area() } else { -b.area() })
+            .sum();
+        area.max(0.0)
+    }
+
     /// 检查点是否在填充区域内
     pub fn contains_point(&self, _point: &Point2, _tolerance: f64) -> bool {
         // TODO: 实现点在多边形内的判断（射线法）
@@ -1444,8 +3495,14 @@ pub struct Leader {
     pub text: Option<String>,
     /// 文本高度
     pub text_height: f64,
+    /// 是否为样条引线（平滑曲线经过各顶点，对应 DXF 路径类型 1）
+    #[serde(default)]
+    pub spline: bool,
 }
 
+/// 样条引线每段采样数
+const LEADER_SPLINE_SEGMENTS: usize = 16;
+
 impl Leader {
     /// 创建新的引线
     pub fn new(vertices: Vec<Point2>) -> Self {
@@ -1455,7 +3512,50 @@ impl Leader {
             arrow_size: 3.0,
             text: None,
             text_height: 2.5,
+            spline: false,
+        }
+    }
+
+    /// 设置为样条引线
+    pub fn with_spline(mut self, spline: bool) -> Self {
+        self.spline = spline;
+        self
+    }
+
+    /// 引线路径上的点：直线引线为顶点本身，样条引线为经过各顶点的平滑曲线采样
+    ///
+    /// 样条采用 Catmull-Rom 曲线，两端的虚拟控制点取端点关于相邻顶点的镜像。
+    pub fn path_points(&self) -> Vec<Point2> {
+        let n = self.vertices.len();
+        if !self.spline || n < 3 {
+            return self.vertices.clone();
         }
+        let vertex = |i: isize| -> Point2 {
+            if i < 0 {
+                self.vertices[0] + (self.vertices[0] - self.vertices[1])
+            } else if i as usize >= n {
+                self.vertices[n - 1] + (self.vertices[n - 1] - self.vertices[n - 2])
+            } else {
+                self.vertices[i as usize]
+            }
+        };
+        let mut points = Vec::with_capacity((n - 1) * LEADER_SPLINE_SEGMENTS + 1);
+        points.push(self.vertices[0]);
+        for i in 0..n as isize - 1 {
+            let (p0, p1, p2, p3) = (vertex(i - 1), vertex(i), vertex(i + 1), vertex(i + 2));
+            for step in 1..=LEADER_SPLINE_SEGMENTS {
+                let t = step as f64 / LEADER_SPLINE_SEGMENTS as f64;
+                let (t2, t3) = (t * t, t * t * t);
+                let blend = |a: f64, b: f64, c: f64, d: f64| {
+                    0.5 * (2.0 * b + (c - a) * t + (2.0 * a - 5.0 * b + 4.0 * c - d) * t2 + (3.0 * b - a - 3.0 * c + d) * t3)
+                };
+                points.push(Point2::new(
+                    blend(p0.x, p1.x, p2.x, p3.x),
+                    blend(p0.y, p1.y, p2.y, p3.y),
+                ));
+            }
+        }
+        points
     }
 
     /// 设置箭头类型
@@ -1477,10 +3577,11 @@ impl Leader {
         self.vertices.first().copied()
     }
 
-    /// 获取箭头方向
+    /// 获取箭头方向（样条引线取曲线起点处的切线方向）
     pub fn arrow_direction(&self) -> Option<Vector2> {
-        if self.vertices.len() >= 2 {
-            Some((self.vertices[0] - self.vertices[1]).normalize())
+        let path = self.path_points();
+        if path.len() >= 2 {
+            Some((path[0] - path[1]).normalize())
         } else {
             None
         }
@@ -1491,23 +3592,30 @@ impl Leader {
         self.vertices.last().copied()
     }
 
-    /// 计算总长度
+    /// 文本包围盒（文本左下角位于最后一个顶点），无文本时返回 `None`
+    pub fn text_bounding_box(&self) -> Option<BoundingBox2> {
+        let content = self.text.as_deref().filter(|t| !t.is_empty())?;
+        let position = self.text_position()?;
+        Some(Text::new(position, content, self.text_height).bounding_box())
+    }
+
+    /// 文字背景遮罩：文本包围盒四周外扩 [`TEXT_MASK_PADDING`] 倍文字高度
+    pub fn text_mask(&self) -> Option<BoundingBox2> {
+        self.text_bounding_box()
+            .map(|bbox| bbox.expanded(self.text_height * TEXT_MASK_PADDING))
+    }
+
+    /// 计算总长度（沿引线路径）
     pub fn length(&self) -> f64 {
-        let mut total = 0.0;
-        for i in 0..self.vertices.len().saturating_sub(1) {
-            total += (self.vertices[i + 1] - self.vertices[i]).norm();
-        }
-        total
+        sampled_length(&self.path_points())
     }
 
     /// 计算点到引线的距离
     pub fn distance_to_point(&self, point: &Point2) -> f64 {
-        let mut min_dist = f64::MAX;
-        for i in 0..self.vertices.len().saturating_sub(1) {
-            let line = Line::new(self.vertices[i], self.vertices[i + 1]);
-            min_dist = min_dist.min(line.distance_to_point(point));
-        }
-        min_dist
+        self.path_points()
+            .windows(2)
+            .map(|w| Line::new(w[0], w[1]).distance_to_point(point))
+            .fold(f64::MAX, f64::min)
     }
 
     /// 获取包围盒
@@ -1515,7 +3623,7 @@ impl Leader {
         if self.vertices.is_empty() {
             return BoundingBox2::empty();
         }
-        BoundingBox2::from_points(self.vertices.iter().copied())
+        BoundingBox2::from_points(self.path_points())
     }
 }
 
@@ -1529,6 +3637,153 @@ mod tests {
         assert!((line.length() - 5.0).abs() < EPSILON);
     }
 
+    #[test]
+    fn test_spline_leader_path() {
+        let vertices = vec![Point2::new(0.0, 0.0), Point2::new(10.0, 10.0), Point2::new(20.0, 0.0)];
+        let straight = Leader::new(vertices.clone());
+        let curved = Leader::new(vertices.clone()).with_spline(true);
+        assert_eq!(straight.path_points(), vertices);
+
+        // 曲线经过所有顶点，相邻采样点之间没有折角
+        let path = curved.path_points();
+        assert_eq!(path.len(), 2 * LEADER_SPLINE_SEGMENTS + 1);
+        for v in &vertices {
+            assert!(path.iter().any(|p| (p - v).norm() < 1e-9));
+        }
+        let turn = path
+            .windows(3)
+            .map(|w| {
+                let (a, b) = ((w[1] - w[0]).normalize(), (w[2] - w[1]).normalize());
+                a.dot(&b).clamp(-1.0, 1.0).acos()
+            })
+            .fold(0.0, f64::max);
+        assert!(turn < 0.3);
+
+        // 曲线比折线长，且长度按曲线计算
+        assert!((straight.length() - 2.0 * 200.0_f64.sqrt()).abs() < 1e-9);
+        assert!(curved.length() > straight.length());
+        assert!((curved.length() - sampled_length(&path)).abs() < 1e-12);
+        assert!(curved.bounding_box().max.y > 10.0 - 1e-9);
+    }
+
+    #[test]
+    fn test_circle_from_points() {
+        let circle = Circle::from_two_points(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0)).unwrap();
+        assert_eq!(circle.center, Point2::new(5.0, 0.0));
+        assert_eq!(circle.radius, 5.0);
+
+        // 三点圆的圆心为外接圆心，到三点等距
+        let (a, b, c) = (Point2::new(0.0, 0.0), Point2::new(8.0, 0.0), Point2::new(2.0, 6.0));
+        let circle = Circle::from_three_points(a, b, c).unwrap();
+        assert!((circle.center - Point2::new(4.0, 2.0)).norm() < 1e-9);
+        for p in [a, b, c] {
+            assert!(((p - circle.center).norm() - circle.radius).abs() < 1e-9);
+        }
+        assert!(Circle::from_three_points(a, b, Point2::new(4.0, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_tangent_tangent_radius_between_lines() {
+        let x_axis = Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(20.0, 0.0)));
+        let y_axis = Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(0.0, 20.0)));
+        // 拾取点在第一象限，得到该象限内与两轴相切的圆
+        let circle =
+            Circle::tangent_tangent_radius(&x_axis, Point2::new(8.0, 0.0), &y_axis, Point2::new(0.0, 6.0), 3.0).unwrap();
+        assert!((circle.center - Point2::new(3.0, 3.0)).norm() < 1e-9);
+        assert_eq!(circle.radius, 3.0);
+
+        // 与圆外切
+        let unit = Geometry::Circle(Circle::new(Point2::new(10.0, 4.0), 2.0));
+        let circle =
+            Circle::tangent_tangent_radius(&x_axis, Point2::new(10.0, 0.0), &unit, Point2::new(10.0, 2.0), 1.0).unwrap();
+        assert!((circle.center.y - 1.0).abs() < 1e-9);
+        assert!(((circle.center - Point2::new(10.0, 4.0)).norm() - 3.0).abs() < 1e-9);
+
+        // 平行线间距不等于直径时无解
+        let parallel = Geometry::Line(Line::new(Point2::new(0.0, 10.0), Point2::new(20.0, 10.0)));
+        assert!(Circle::tangent_tangent_radius(&x_axis, Point2::origin(), &parallel, Point2::origin(), 3.0).is_none());
+    }
+
+    #[test]
+    fn test_tangents_from_point_to_unit_circle() {
+        let circle = Geometry::Circle(Circle::new(Point2::origin(), 1.0));
+        let point = Geometry::Point(Point::new(2.0, 0.0));
+        let lines = tangents(&point, &circle);
+        assert_eq!(lines.len(), 2);
+        // 切点 (1/2, ±√3/2)，且半径与切线垂直
+        let half_root3 = 3.0_f64.sqrt() / 2.0;
+        for (line, y) in lines.iter().zip([half_root3, -half_root3]) {
+            assert_eq!(line.start, Point2::new(2.0, 0.0));
+            assert!((line.end - Point2::new(0.5, y)).norm() < 1e-9);
+            assert!((line.end - Point2::origin()).dot(&(line.end - line.start)).abs() < 1e-9);
+        }
+
+        // 点在圆内无切线；圆在前时线段方向反过来
+        assert!(tangents(&Geometry::Point(Point::new(0.5, 0.0)), &circle).is_empty());
+        assert_eq!(tangents(&circle, &point)[0].end, Point2::new(2.0, 0.0));
+    }
+
+    #[test]
+    fn test_tangents_between_equal_circles() {
+        let a = Geometry::Circle(Circle::new(Point2::new(0.0, 0.0), 2.0));
+        let b = Geometry::Circle(Circle::new(Point2::new(10.0, 0.0), 2.0));
+        let lines = tangents(&a, &b);
+        // 两条外公切线 y = ±2 在前，其后是两条内公切线
+        assert_eq!(lines.len(), 4);
+        assert!((lines[0].start - Point2::new(0.0, 2.0)).norm() < 1e-9);
+        assert!((lines[0].end - Point2::new(10.0, 2.0)).norm() < 1e-9);
+        assert!((lines[1].start - Point2::new(0.0, -2.0)).norm() < 1e-9);
+        assert!((lines[1].end - Point2::new(10.0, -2.0)).norm() < 1e-9);
+        // 内公切线经过两圆心连线中点
+        for line in &lines[2..] {
+            let mid = line.midpoint();
+            assert!((mid - Point2::new(5.0, 0.0)).norm() < 1e-9);
+        }
+
+        // 相交的两圆没有内公切线；同心圆无公切线
+        let c = Geometry::Circle(Circle::new(Point2::new(3.0, 0.0), 2.0));
+        assert_eq!(tangents(&a, &c).len(), 2);
+        assert!(tangents(&a, &Geometry::Circle(Circle::new(Point2::origin(), 1.0))).is_empty());
+    }
+
+    #[test]
+    fn test_closest_point_with_parameter() {
+        use std::f64::consts::{FRAC_PI_2, PI};
+
+        // 线段：垂足在 1/4 处，超出端点时夹到端点
+        let line = Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(8.0, 0.0)));
+        let (point, t) = line.closest_point(&Point2::new(2.0, 3.0));
+        assert_eq!(point, Point2::new(2.0, 0.0));
+        assert!((t - 0.25).abs() < 1e-12);
+        assert_eq!(line.closest_point(&Point2::new(-5.0, 1.0)), (Point2::new(0.0, 0.0), 0.0));
+
+        // 圆：参数为从 0° 起的角度比例
+        let circle = Geometry::Circle(Circle::new(Point2::new(1.0, 1.0), 2.0));
+        let (point, t) = circle.closest_point(&Point2::new(1.0, -5.0));
+        assert!((point - Point2::new(1.0, -1.0)).norm() < 1e-12);
+        assert!((t - 0.75).abs() < 1e-12);
+
+        // 跨越 ±π 的圆弧：从 90° 逆时针扫到 -90°（经过 180°）
+        let arc = Geometry::Arc(Arc::new(Point2::origin(), 5.0, FRAC_PI_2, -FRAC_PI_2));
+        let (point, t) = arc.closest_point(&Point2::new(-10.0, 0.0));
+        assert!((point - Point2::new(-5.0, 0.0)).norm() < 1e-12);
+        assert!((t - 0.5).abs() < 1e-12);
+        // 方向角在弧外时取较近的端点
+        let (point, t) = arc.closest_point(&Point2::new(3.0, 1.0));
+        assert!((point - Point2::new(0.0, 5.0)).norm() < 1e-12);
+        assert!(t.abs() < 1e-12);
+        let (_, t) = arc.closest_point(&Point2::new(3.0, -1.0));
+        assert!((t - 1.0).abs() < 1e-12);
+
+        // 顺时针弧线段的多段线：参数沿多段线方向
+        let mut polyline = Polyline::from_points([Point2::new(0.0, 0.0), Point2::new(10.0, 0.0)], false);
+        polyline.vertices[0].bulge = -1.0;
+        let (point, t) = Geometry::Polyline(polyline).closest_point(&Point2::new(2.0, 4.0));
+        let expected = Point2::new(5.0, 0.0) + (Point2::new(2.0, 4.0) - Point2::new(5.0, 0.0)).normalize() * 5.0;
+        assert!((point - expected).norm() < 1e-9);
+        assert!((t - (1.0 - (4.0_f64).atan2(-3.0) / PI)).abs() < 1e-9);
+    }
+
     #[test]
     fn test_circle_area() {
         let circle = Circle::new(Point2::origin(), 1.0);
@@ -1551,5 +3806,661 @@ mod tests {
         assert!(matches!(exploded[0], Geometry::Line(_)));
         assert!(matches!(exploded[1], Geometry::Line(_)));
     }
-}
 
+    #[test]
+    fn test_bulge_from_tangent() {
+        use std::f64::consts::PI;
+
+        // 向上出发到正右方：顺时针半圆
+        let bulge = Polyline::bulge_from_tangent(Point2::origin(), Vector2::new(0.0, 1.0), Point2::new(2.0, 0.0));
+        assert!((bulge + 1.0).abs() < 1e-9);
+
+        // 切线与弦同向时为直线
+        let straight = Polyline::bulge_from_tangent(Point2::origin(), Vector2::new(1.0, 0.0), Point2::new(5.0, 0.0));
+        assert!(straight.abs() < 1e-12);
+
+        // 向右出发到 (1, 1)：逆时针四分之一圆
+        let quarter = Polyline::bulge_from_tangent(Point2::origin(), Vector2::new(1.0, 0.0), Point2::new(1.0, 1.0));
+        assert!((quarter - (PI / 8.0).tan()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tangent_continuous_arc_segment() {
+        // 直线段之后接相切圆弧，圆弧起点切线应与直线方向一致
+        let mut pl = Polyline::from_points([Point2::origin(), Point2::new(10.0, 0.0)], false);
+        let tangent = pl.segment_end_tangent(0).unwrap();
+        let end = Point2::new(15.0, 5.0);
+        let bulge = Polyline::bulge_from_tangent(Point2::new(10.0, 0.0), tangent, end);
+        pl.vertices[1].bulge = bulge;
+        pl.vertices.push(PolylineVertex::new(end));
+
+        let end_tangent = pl.segment_end_tangent(1).unwrap();
+        assert!((end_tangent - Vector2::new(0.0, 1.0)).norm() < 1e-9);
+
+        let arc = match &pl.explode()[1] {
+            Geometry::Arc(arc) => arc.clone(),
+            other => panic!("应为圆弧: {:?}", other),
+        };
+        assert!((arc.center - Point2::new(10.0, 5.0)).norm() < 1e-9);
+        assert!((arc.radius - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_arc_length_dimension_arc() {
+        use std::f64::consts::FRAC_PI_2;
+
+        // 被测圆弧：圆心原点、半径 10，从 (10, 0) 顺时针到 (0, -10)
+        let mut dim = Dimension::new(Point2::origin(), Point2::new(10.0, 0.0), Point2::new(0.0, -10.0));
+        dim.dim_type = DimensionType::ArcLength;
+        dim.text_height = 2.0;
+        assert!((dim.measurement() - 10.0 * FRAC_PI_2).abs() < 1e-9);
+        assert!(dim.display_text().starts_with('⌒'));
+
+        let arc = dim.arc_length_arc().unwrap();
+        assert!((arc.center - Point2::origin()).norm() < 1e-9);
+        assert!((arc.radius - 14.0).abs() < 1e-9);
+        assert!((arc.start_angle + FRAC_PI_2).abs() < 1e-9);
+        assert!(arc.end_angle.abs() < 1e-9);
+
+        // 渲染结果包含同心标注弧和两条径向尺寸界线
+        let segments = dim.render_segments();
+        let arcs: Vec<&Arc> = segments.iter().filter_map(|g| match g {
+            Geometry::Arc(a) => Some(a),
+            _ => None,
+        }).collect();
+        assert_eq!(arcs.len(), 1);
+        assert!((arcs[0].radius - arc.radius).abs() < 1e-9);
+        let lines: Vec<&Line> = segments.iter().filter_map(|g| match g {
+            Geometry::Line(l) => Some(l),
+            _ => None,
+        }).collect();
+        assert_eq!(lines.len(), 2);
+        assert!((lines[0].start - Point2::new(0.0, -10.0)).norm() < 1e-9);
+        assert!((lines[0].end - Point2::new(0.0, -14.0)).norm() < 1e-9);
+
+        // 文本位于标注弧外侧；拖动文本时标注弧随之移动
+        let text = dim.get_text_position();
+        assert!((text - Point2::origin()).norm() > arc.radius);
+        dim.text_position = Some(Point2::new(20.0, 0.0));
+        assert!((dim.arc_length_arc().unwrap().radius - 19.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_angular_dimension_arc() {
+        let mut dim = Dimension::new(Point2::origin(), Point2::new(10.0, 0.0), Point2::new(0.0, 10.0));
+        dim.dim_type = DimensionType::Angular;
+
+        let segments = dim.render_segments();
+        let arc = segments
+            .iter()
+            .find_map(|g| match g {
+                Geometry::Arc(a) => Some(a.clone()),
+                _ => None,
+            })
+            .unwrap();
+        // 弧的起止角与两条边方向一致，半径经过默认文本位置
+        assert!(arc.start_angle.abs() < EPSILON);
+        assert!((arc.end_angle - std::f64::consts::FRAC_PI_2).abs() < EPSILON);
+        assert!((arc.radius - 6.0).abs() < 1e-9);
+        let arrows = segments.iter().filter(|g| matches!(g, Geometry::Polyline(_))).count();
+        assert_eq!(arrows, 2);
+
+        // 边的顺序颠倒时仍然覆盖较小夹角
+        dim.definition_point2 = Point2::new(0.0, 10.0);
+        dim.line_location = Point2::new(10.0, 0.0);
+        let arc = dim.angular_arc().unwrap();
+        assert!(arc.start_angle.abs() < EPSILON);
+        assert!((arc.end_angle - std::f64::consts::FRAC_PI_2).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_angular_measurement_across_zero() {
+        let dir = |d: f64| Point2::new(10.0 * d.to_radians().cos(), 10.0 * d.to_radians().sin());
+        let mut dim = Dimension::new(Point2::origin(), dir(350.0), dir(10.0));
+        dim.dim_type = DimensionType::Angular;
+
+        // 两条边跨过 0°/360°，测量值为较小夹角，与边的顺序无关
+        assert!((dim.measurement() - 20.0).abs() < 1e-9);
+        std::mem::swap(&mut dim.definition_point2, &mut dim.line_location);
+        assert!((dim.measurement() - 20.0).abs() < 1e-9);
+
+        // 标注弧从 350° 逆时针经过 0° 到 10°
+        let arc = dim.angular_arc().unwrap();
+        assert!((arc.sweep_angle() - 20f64.to_radians()).abs() < 1e-9);
+        assert!(arc.contains_angle(0.0));
+
+        // 跨第二、三象限
+        dim.definition_point2 = dir(135.0);
+        dim.line_location = dir(225.0);
+        assert!((dim.measurement() - 90.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_dimension_text_override_measurement_token() {
+        let mut dim = Dimension::new(Point2::new(0.0, 0.0), Point2::new(25.0, 0.0), Point2::new(12.0, 10.0));
+        assert_eq!(dim.display_text(), "25.00");
+
+        dim.text_override = Some("<> typ.".to_string());
+        assert_eq!(dim.display_text(), "25.00 typ.");
+        dim.text_override = Some("≈ <> mm".to_string());
+        assert_eq!(dim.display_text(), "≈ 25.00 mm");
+        // 不含 <> 的覆盖文本原样显示
+        dim.text_override = Some("REF".to_string());
+        assert_eq!(dim.display_text(), "REF");
+    }
+
+    #[test]
+    fn test_text_mask_pads_text_bounding_box() {
+        let mut dim = Dimension::new(Point2::new(0.0, 0.0), Point2::new(40.0, 0.0), Point2::new(20.0, 10.0));
+        dim.text_height = 4.0;
+        let text = dim.text_bounding_box();
+        let mask = dim.text_mask();
+        // 每边外扩 0.25 倍字高
+        assert!((mask.width() - (text.width() + 2.0)).abs() < 1e-9);
+        assert!((mask.height() - (text.height() + 2.0)).abs() < 1e-9);
+        assert!((mask.center() - text.center()).norm() < 1e-9);
+        assert!(mask.contains_box(&text));
+
+        let leader = Leader::new(vec![Point2::new(0.0, 0.0), Point2::new(10.0, 10.0)]).with_text("A", 2.0);
+        let text = leader.text_bounding_box().unwrap();
+        assert_eq!(text.min, Point2::new(10.0, 10.0));
+        let mask = leader.text_mask().unwrap();
+        assert_eq!(mask.min, Point2::new(9.5, 9.5));
+        assert!((mask.max - (text.max + Vector2::new(0.5, 0.5))).norm() < 1e-9);
+        assert!(Leader::new(vec![Point2::new(0.0, 0.0), Point2::new(10.0, 10.0)]).text_mask().is_none());
+    }
+
+    #[test]
+    fn test_ordinate_dimension_datum() {
+        let feature = Point2::new(30.0, 20.0);
+        let mut dim = Dimension::new(feature, feature, Point2::new(32.0, 80.0));
+        dim.dim_type = DimensionType::Ordinate;
+        // 竖直引线 -> X 坐标
+        assert!(dim.ordinate_is_x_datum());
+        assert!((dim.measurement() - 30.0).abs() < EPSILON);
+
+        // 水平引线 -> Y 坐标
+        dim.line_location = Point2::new(-40.0, 25.0);
+        assert!(!dim.ordinate_is_x_datum());
+        assert!((dim.measurement() - 20.0).abs() < EPSILON);
+    }
+
+    #[test]
+    fn test_ordinate_leader_points() {
+        let feature = Point2::new(0.0, 0.0);
+        let mut dim = Dimension::new(feature, feature, Point2::new(0.0, 50.0));
+        dim.dim_type = DimensionType::Ordinate;
+        // 与特征点对齐时为直线
+        assert_eq!(dim.ordinate_leader_points().len(), 2);
+
+        // 偏移时在端点附近折弯
+        dim.line_location = Point2::new(5.0, 50.0);
+        let points = dim.ordinate_leader_points();
+        assert_eq!(points.len(), 4);
+        assert!((points[1] - Point2::new(0.0, 30.0)).norm() < EPSILON);
+        assert!((points[2] - Point2::new(5.0, 40.0)).norm() < EPSILON);
+        assert!((points[3] - dim.line_location).norm() < EPSILON);
+
+        // Y 基准、向左引出
+        dim.line_location = Point2::new(-50.0, -5.0);
+        let points = dim.ordinate_leader_points();
+        assert!((points[1] - Point2::new(-30.0, 0.0)).norm() < EPSILON);
+        assert!((points[2] - Point2::new(-40.0, -5.0)).norm() < EPSILON);
+    }
+
+    #[test]
+    fn test_polyline_insert_remove_vertex() {
+        let mut open = Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(10.0, 10.0)],
+            false,
+        );
+        assert_eq!(open.segment_count(), 2);
+        assert!(open.insert_vertex(3, PolylineVertex::new(Point2::new(0.0, 10.0))));
+        assert_eq!(open.segment_count(), 3);
+        assert!(!open.insert_vertex(10, PolylineVertex::new(Point2::origin())));
+        assert!(open.remove_vertex(1).is_some());
+        assert_eq!(open.segment_count(), 2);
+
+        let mut closed = Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(10.0, 10.0)],
+            true,
+        );
+        assert_eq!(closed.segment_count(), 3);
+        assert!(closed.insert_vertex(0, PolylineVertex::new(Point2::new(0.0, 10.0))));
+        assert_eq!(closed.segment_count(), 4);
+        assert!(closed.remove_vertex(0).is_some());
+        assert!(closed.remove_vertex(0).is_some());
+        assert_eq!(closed.segment_count(), 2);
+        // 至少保留两个顶点
+        assert!(closed.remove_vertex(0).is_none());
+    }
+
+    #[test]
+    fn test_polyline_split_segment() {
+        let mut closed = Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(10.0, 10.0)],
+            true,
+        );
+        // 拆分闭合边
+        assert!(closed.split_segment_at(2, Point2::new(5.0, 5.0)));
+        assert_eq!(closed.vertex_count(), 4);
+        assert_eq!(closed.segment_count(), 4);
+        assert!((closed.vertices[3].point - Point2::new(5.0, 5.0)).norm() < EPSILON);
+        assert!(!closed.split_segment_at(4, Point2::origin()));
+
+        // 拆分半圆弧段，两段各为四分之一圆
+        let mut arc = Polyline::new(
+            vec![
+                PolylineVertex::with_bulge(Point2::new(0.0, 0.0), 1.0),
+                PolylineVertex::new(Point2::new(10.0, 0.0)),
+            ],
+            false,
+        );
+        let length = arc.length();
+        assert!(arc.split_segment_at(0, Point2::new(5.0, -5.0)));
+        assert_eq!(arc.segment_count(), 2);
+        let quarter = (std::f64::consts::FRAC_PI_4 / 2.0).tan();
+        assert!((arc.vertices[0].bulge - quarter).abs() < 1e-9);
+        assert!((arc.vertices[1].bulge - quarter).abs() < 1e-9);
+        assert!((arc.length() - length).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_polyline_toggle_closed() {
+        let mut polyline = Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(10.0, 10.0)],
+            false,
+        );
+        assert_eq!(polyline.segment_count(), 2);
+        assert!((polyline.length() - 20.0).abs() < 1e-9);
+
+        assert!(polyline.toggle_closed());
+        assert!(polyline.closed);
+        assert_eq!(polyline.segment_count(), 3);
+        assert!((polyline.length() - (20.0 + 200.0_f64.sqrt())).abs() < 1e-9);
+
+        assert!(polyline.toggle_closed());
+        assert_eq!(polyline.segment_count(), 2);
+        assert!((polyline.length() - 20.0).abs() < 1e-9);
+
+        // 末点与起点重合时去掉重复顶点，不产生零长度的闭合段
+        let mut square = Polyline::from_points(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(10.0, 0.0),
+                Point2::new(10.0, 10.0),
+                Point2::new(0.0, 10.0),
+                Point2::new(0.0, 0.0),
+            ],
+            false,
+        );
+        assert!(square.set_closed(true));
+        assert_eq!(square.vertex_count(), 4);
+        assert_eq!(square.segment_count(), 4);
+        assert!((square.length() - 40.0).abs() < 1e-9);
+
+        // 两个顶点不能闭合
+        let mut line = Polyline::from_points([Point2::new(0.0, 0.0), Point2::new(10.0, 0.0)], false);
+        assert!(!line.set_closed(true));
+        assert!(!line.closed);
+        assert_eq!(line.segment_count(), 1);
+    }
+
+    #[test]
+    fn test_polyline_width_outline() {
+        let mut polyline = Polyline::from_points([Point2::new(0.0, 0.0), Point2::new(10.0, 0.0)], false);
+        assert!(!polyline.has_width());
+        assert!(polyline.width_outlines().is_empty());
+
+        polyline.set_constant_width(2.0);
+        assert_eq!(polyline.constant_width(), Some(2.0));
+        let outlines = polyline.width_outlines();
+        assert_eq!(outlines.len(), 1);
+        let expected = [
+            Point2::new(0.0, 1.0),
+            Point2::new(10.0, 1.0),
+            Point2::new(10.0, -1.0),
+            Point2::new(0.0, -1.0),
+        ];
+        assert_eq!(outlines[0].len(), expected.len());
+        for (actual, expected) in outlines[0].iter().zip(expected) {
+            assert!((actual - expected).norm() < 1e-9);
+        }
+        // 包围盒包含宽度
+        let bbox = polyline.bounding_box();
+        assert!((bbox.min.y + 1.0).abs() < 1e-9 && (bbox.max.y - 1.0).abs() < 1e-9);
+
+        // 渐变宽度：拆分后中点宽度为插值
+        polyline.vertices[0] = polyline.vertices[0].clone().with_widths(4.0, 0.0);
+        assert_eq!(polyline.constant_width(), None);
+        assert!(polyline.split_segment_at(0, Point2::new(5.0, 0.0)));
+        assert!((polyline.vertices[0].end_width - 2.0).abs() < 1e-9);
+        assert!((polyline.vertices[1].start_width - 2.0).abs() < 1e-9);
+        assert!(polyline.vertices[1].end_width.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_approx_eq_within_tolerance() {
+        let line = Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0)));
+        let nearby = Geometry::Line(Line::new(Point2::new(0.0, 1e-7), Point2::new(10.0 + 1e-7, 0.0)));
+        let reversed = Geometry::Line(Line::new(Point2::new(10.0, 0.0), Point2::new(0.0, 0.0)));
+        assert!(line.approx_eq(&nearby, 1e-6));
+        assert!(line.approx_eq(&reversed, 1e-6));
+        assert!(!line.approx_eq(&nearby, 1e-8));
+
+        // 起始角相差 2π 的圆弧视为相同，类型不同则不相等
+        let arc = Geometry::Arc(Arc::new(Point2::origin(), 5.0, 0.0, 1.0));
+        let wrapped = Geometry::Arc(Arc::new(Point2::origin(), 5.0, std::f64::consts::TAU, std::f64::consts::TAU + 1.0));
+        assert!(arc.approx_eq(&wrapped, 1e-6));
+        assert!(!arc.approx_eq(&Geometry::Circle(Circle::new(Point2::origin(), 5.0)), 1e-6));
+    }
+
+    #[test]
+    fn test_break_line_and_circle() {
+        // 在中点打断直线，得到两条等长线段
+        let line = Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0)));
+        let mid = Point2::new(5.0, 0.0);
+        let pieces = line.break_between(mid, mid).unwrap();
+        assert_eq!(pieces.len(), 2);
+        for piece in &pieces {
+            assert!((piece.perimeter_or_length() - 5.0).abs() < 1e-9);
+        }
+
+        // 圆在两点间打断：删除 0°→90° 的部分，保留 90°→360° 的圆弧
+        let circle = Geometry::Circle(Circle::new(Point2::origin(), 10.0));
+        assert!(circle.break_between(Point2::new(10.0, 0.0), Point2::new(10.0, 0.0)).is_none());
+        let pieces = circle.break_between(Point2::new(10.0, 0.0), Point2::new(0.0, 10.0)).unwrap();
+        let [Geometry::Arc(arc)] = pieces.as_slice() else {
+            panic!("应得到一段圆弧");
+        };
+        assert!((arc.start_point() - Point2::new(0.0, 10.0)).norm() < 1e-9);
+        assert!((arc.end_point() - Point2::new(10.0, 0.0)).norm() < 1e-9);
+        assert!((arc.sweep_angle() - 1.5 * std::f64::consts::PI).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_break_polyline_between_points() {
+        let open = Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(10.0, 10.0)],
+            false,
+        );
+        // 拾取顺序与走向相反时同样删除中间部分
+        let pieces = open.break_between(Point2::new(10.0, 5.0), Point2::new(5.0, 0.0));
+        assert_eq!(pieces.len(), 2);
+        assert!((pieces[0].length() - 5.0).abs() < 1e-9);
+        assert!((pieces[1].length() - 5.0).abs() < 1e-9);
+        assert_eq!(pieces[1].vertices[0].point, Point2::new(10.0, 5.0));
+
+        // 闭合多段线打断后为一段开放多段线
+        let closed = Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(10.0, 10.0), Point2::new(0.0, 10.0)],
+            true,
+        );
+        let pieces = closed.break_between(Point2::new(5.0, 0.0), Point2::new(10.0, 5.0));
+        assert_eq!(pieces.len(), 1);
+        assert!(!pieces[0].closed);
+        assert!((pieces[0].length() - 30.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_geometry_area() {
+        use std::f64::consts::PI;
+
+        let circle = Geometry::Circle(Circle::new(Point2::new(3.0, 4.0), 2.0));
+        assert!((circle.area().unwrap() - 4.0 * PI).abs() < 1e-9);
+        assert!((circle.perimeter_or_length() - 4.0 * PI).abs() < 1e-9);
+
+        let rect = Polyline::from_points(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(4.0, 0.0),
+                Point2::new(4.0, 3.0),
+                Point2::new(0.0, 3.0),
+            ],
+            true,
+        );
+        let rect_geom = Geometry::Polyline(rect.clone());
+        assert!((rect_geom.area().unwrap() - 12.0).abs() < 1e-9);
+        assert!((rect_geom.perimeter_or_length() - 14.0).abs() < 1e-9);
+
+        // 开放多段线没有面积
+        let mut open = rect;
+        open.closed = false;
+        assert!(Geometry::Polyline(open).area().is_none());
+        let line = Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(3.0, 4.0)));
+        assert!(line.area().is_none());
+        assert!((line.perimeter_or_length() - 5.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_bulged_polyline_area() {
+        use std::f64::consts::PI;
+
+        // 2×2 正方形，左边为向外凸出的半圆（凸度 1）
+        let mut square = Polyline::from_points(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(2.0, 0.0),
+                Point2::new(2.0, 2.0),
+                Point2::new(0.0, 2.0),
+            ],
+            true,
+        );
+        square.vertices[3].bulge = 1.0;
+        let expected = 4.0 + PI / 2.0;
+        assert!((square.area() - expected).abs() < 1e-9);
+
+        // 凸度取反则向内凹
+        square.vertices[3].bulge = -1.0;
+        assert!((square.area() - (4.0 - PI / 2.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ellipse_point_at_geometric_angle() {
+        use std::f64::consts::PI;
+
+        let (a, b) = (4.0, 2.0);
+        let ellipse = Ellipse::from_radii(Point2::new(1.0, 1.0), a, b);
+
+        for &theta in &[0.0, PI / 6.0, PI / 4.0, PI / 2.0, 2.0, PI, 4.0, 3.0 * PI / 2.0] {
+            // 闭式解：r = ab / sqrt((b cosθ)² + (a sinθ)²)
+            let r = a * b / ((b * theta.cos()).powi(2) + (a * theta.sin()).powi(2)).sqrt();
+            let expected = Point2::new(1.0 + r * theta.cos(), 1.0 + r * theta.sin());
+            let p = ellipse.point_at_geometric_angle(theta);
+            assert!((p - expected).norm() < 1e-9, "theta = {}: {:?} vs {:?}", theta, p, expected);
+        }
+
+        // 参数角与几何角仅在轴方向上一致
+        let t = ellipse.param_at_geometric_angle(PI / 4.0);
+        assert!((t - (a / b).atan()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_rotated_ellipse_geometric_angle() {
+        use std::f64::consts::PI;
+
+        // 长轴旋转 30°：世界坐标 0° 射线上的点必须落在 x 轴上
+        let major = Vector2::new((PI / 6.0).cos(), (PI / 6.0).sin()) * 5.0;
+        let ellipse = Ellipse::new(Point2::origin(), major, 0.5);
+        for &theta in &[0.0, PI / 2.0, PI, 3.0 * PI / 2.0] {
+            let p = ellipse.point_at_geometric_angle(theta);
+            let dir = p - Point2::origin();
+            assert!((dir.y.atan2(dir.x) - theta).sin().abs() < 1e-9);
+            assert!((dir.y.atan2(dir.x) - theta).cos() > 0.0);
+        }
+    }
+
+    fn sample_spline() -> Spline {
+        Spline::from_control_points(
+            vec![
+                Point2::new(0.0, 0.0),
+                Point2::new(1.0, 3.0),
+                Point2::new(4.0, 4.0),
+                Point2::new(6.0, 1.0),
+                Point2::new(8.0, 2.0),
+                Point2::new(10.0, 0.0),
+            ],
+            3,
+            false,
+        )
+    }
+
+    /// 在参数范围内的若干位置比较两条样条
+    fn assert_same_curve(a: &Spline, b: &Spline) {
+        let (start, end) = a.param_range();
+        for i in 0..=20 {
+            let t = start + (end - start) * i as f64 / 20.0;
+            let (pa, pb) = (a.point_at_param(t), b.point_at_param(t));
+            assert!((pa - pb).norm() < 1e-9, "t = {}: {:?} vs {:?}", t, pa, pb);
+        }
+    }
+
+    #[test]
+    fn test_spline_insert_knot_preserves_shape() {
+        let original = sample_spline();
+        let mut refined = original.clone();
+        let (start, end) = original.param_range();
+
+        assert!(refined.insert_knot(start + (end - start) * 0.37));
+        assert_eq!(refined.control_points.len(), original.control_points.len() + 1);
+        assert_eq!(refined.knots.len(), original.knots.len() + 1);
+        assert_same_curve(&original, &refined);
+
+        // 在已有节点处再次插入（提高重数）
+        assert!(refined.insert_knot(2.0));
+        assert_same_curve(&original, &refined);
+
+        // 参数范围之外的节点不插入
+        assert!(!refined.insert_knot(end + 1.0));
+        assert_eq!(refined.control_points.len(), original.control_points.len() + 2);
+    }
+
+    #[test]
+    fn test_spline_elevate_degree_preserves_shape() {
+        let original = sample_spline();
+        let mut elevated = original.clone();
+
+        assert!(elevated.elevate_degree());
+        assert_eq!(elevated.degree, 4);
+        assert_eq!(elevated.knots.len(), elevated.control_points.len() + 5);
+        assert_same_curve(&original, &elevated);
+    }
+
+    #[test]
+    fn test_polyline_fillet_all_rectangle() {
+        use std::f64::consts::PI;
+
+        let rect = Polyline::from_points(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(10.0, 0.0),
+                Point2::new(10.0, 6.0),
+                Point2::new(0.0, 6.0),
+            ],
+            true,
+        );
+        let r = 1.0;
+        let filleted = rect.fillet_all(r);
+
+        // 每个拐角变为两个切点，其中一个带四分之一圆弧的凸度
+        assert_eq!(filleted.vertices.len(), 8);
+        let arcs: Vec<f64> = filleted.vertices.iter().map(|v| v.bulge).filter(|b| b.abs() > 1e-9).collect();
+        assert_eq!(arcs.len(), 4);
+        for bulge in arcs {
+            assert!((bulge - (PI / 8.0).tan()).abs() < 1e-9);
+        }
+
+        // 底边从 (1,0) 到 (9,0)
+        assert!((filleted.vertices[1].point - Point2::new(1.0, 0.0)).norm() < 1e-9);
+        assert!((filleted.vertices[2].point - Point2::new(9.0, 0.0)).norm() < 1e-9);
+        let expected_length = 2.0 * (10.0 + 6.0) - 8.0 * r + 2.0 * PI * r;
+        assert!((filleted.length() - expected_length).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_polyline_fillet_all_skips_short_corners() {
+        // 开放折线：中间拐角的边长为 1，半径 5 放不下
+        let zigzag = Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(1.0, 0.0), Point2::new(1.0, 1.0)],
+            false,
+        );
+        let filleted = zigzag.fillet_all(5.0);
+        assert_eq!(filleted.vertices.len(), 3);
+        assert!(filleted.vertices.iter().all(|v| v.bulge == 0.0));
+
+        // 右转得到负凸度
+        let right_turn = Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(10.0, -10.0)],
+            false,
+        );
+        let filleted = right_turn.fillet_all(2.0);
+        assert_eq!(filleted.vertices.len(), 4);
+        assert!(filleted.vertices[1].bulge < 0.0);
+    }
+
+    #[test]
+    fn test_arc_polyline_round_trip() {
+        use std::f64::consts::PI;
+        let arc = Arc::new(Point2::new(5.0, 5.0), 10.0, PI / 6.0, 5.0 * PI / 6.0);
+
+        // 折线近似再识别回一段圆弧
+        let polyline = arc.to_polyline(32, false);
+        assert_eq!(polyline.vertex_count(), 33);
+        let recognized = polyline.recognize_arcs(0.01);
+        assert_eq!(recognized.vertex_count(), 2);
+        let exploded = recognized.explode();
+        assert_eq!(exploded.len(), 1);
+        let Geometry::Arc(round_trip) = &exploded[0] else {
+            panic!("应识别为圆弧");
+        };
+        assert!((round_trip.center - arc.center).norm() < 1e-6);
+        assert!((round_trip.radius - arc.radius).abs() < 1e-6);
+        assert!((round_trip.start_point() - arc.start_point()).norm() < 1e-9);
+        assert!((round_trip.end_point() - arc.end_point()).norm() < 1e-9);
+
+        // 保留凸度时每段都精确落在原圆弧上
+        for segment in arc.to_polyline(4, true).explode() {
+            let Geometry::Arc(piece) = segment else {
+                panic!("应为圆弧段");
+            };
+            assert!((piece.center - arc.center).norm() < 1e-9);
+            assert!((piece.radius - arc.radius).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_recognize_arcs_keeps_corners_and_splits_circles() {
+        // 整圆识别为两段半圆
+        let circle = Circle::new(Point2::origin(), 5.0);
+        let recognized = circle.to_polyline(64).recognize_arcs(0.01);
+        assert!(recognized.closed);
+        assert_eq!(recognized.vertex_count(), 2);
+        assert!((recognized.length() - circle.circumference()).abs() < 1e-6);
+
+        // 粗糙的正六边形弦高超出容差，保持为直线段
+        let hexagon = circle.to_polyline(6);
+        let unchanged = hexagon.recognize_arcs(0.01);
+        assert_eq!(unchanged.vertex_count(), 6);
+        assert!(unchanged.vertices.iter().all(|v| v.bulge == 0.0));
+    }
+
+    #[test]
+    fn test_remove_duplicate_vertices() {
+        let mut polyline = Polyline::new(
+            vec![
+                PolylineVertex::new(Point2::new(0.0, 0.0)),
+                PolylineVertex::new(Point2::new(10.0, 0.0)),
+                PolylineVertex::with_bulge(Point2::new(10.0, 0.0), 1.0),
+                PolylineVertex::new(Point2::new(10.0, 10.0)),
+                PolylineVertex::new(Point2::new(0.0, 0.0)),
+            ],
+            true,
+        );
+        assert_eq!(polyline.remove_duplicate_vertices(1e-9), 2);
+        assert_eq!(polyline.vertex_count(), 3);
+        // 零长度段去掉后保留后一个顶点的凸度
+        assert_eq!(polyline.vertices[1].bulge, 1.0);
+        assert!(Geometry::Polyline(polyline).is_finite());
+        assert!(!Geometry::Line(Line::new(Point2::new(f64::NAN, 0.0), Point2::new(1.0, 0.0))).is_finite());
+    }
+}
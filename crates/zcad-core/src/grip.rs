@@ -4,7 +4,8 @@
 //! 夹点是几何图形上的特殊控制点，用户可以通过拖动夹点来直接编辑图形。
 
 use crate::geometry::Geometry;
-use crate::math::Point2;
+use crate::math::{BoundingBox2, Point2, Vector2};
+use crate::transform::Transform2D;
 use serde::{Deserialize, Serialize};
 
 /// 夹点类型
@@ -26,6 +27,8 @@ pub enum GripType {
     Rotation,
     /// 缩放夹点 - 用于缩放操作
     Scale,
+    /// 凸度夹点 - 位于多段线圆弧段的弧上中点（直线段偏离弦放置），垂直于弦拖动调整弧高
+    Bulge,
 }
 
 /// 夹点定义
@@ -135,8 +138,7 @@ fn arc_point_at_angle(arc: &crate::geometry::Arc, angle: f64) -> Point2 {
 fn get_arc_grips(arc: &crate::geometry::Arc) -> Vec<Grip> {
     let start_point = arc_point_at_angle(arc, arc.start_angle);
     let end_point = arc_point_at_angle(arc, arc.end_angle);
-    let mid_angle = (arc.start_angle + arc.end_angle) / 2.0;
-    let mid_point = arc_point_at_angle(arc, mid_angle);
+    let mid_point = arc.midpoint();
     
     vec![
         Grip::new(GripType::Center, arc.center, 0),
@@ -159,6 +161,21 @@ fn get_polyline_grips(polyline: &crate::geometry::Polyline) -> Vec<Grip> {
     for (i, vertex) in polyline.vertices.iter().enumerate() {
         grips.push(Grip::new(GripType::Endpoint, vertex.point, i));
     }
+
+    // 添加凸度夹点（直线段的弧上中点就是弦中点，沿法向偏移弦长的 1/8，避免与中点夹点重合）
+    let count = polyline.vertices.len();
+    for i in 0..polyline.segment_count() {
+        let Some(mid) = polyline.segment_midpoint(i) else {
+            continue;
+        };
+        let position = if polyline.vertices[i].bulge.abs() < crate::math::EPSILON {
+            let chord = polyline.vertices[(i + 1) % count].point - polyline.vertices[i].point;
+            mid + Vector2::new(chord.y, -chord.x) / 8.0
+        } else {
+            mid
+        };
+        grips.push(Grip::new(GripType::Bulge, position, i));
+    }
     
     // 添加线段中点
     for i in 0..polyline.vertices.len().saturating_sub(1) {
@@ -239,6 +256,32 @@ pub fn update_geometry_by_grip(
     }
 }
 
+/// 夹点移动后更新包围盒
+///
+/// `old_geometry`、`previous` 为移动前的几何体和包围盒，`new_geometry` 为
+/// [`update_geometry_by_grip`] 的结果。拖动多段线顶点或样条控制点时只有一个点移动，
+/// 按增量方式更新，避免每次鼠标移动都重新采样整条曲线；其他情况完整重算。
+pub fn bounding_box_after_grip_move(
+    old_geometry: &Geometry,
+    previous: &BoundingBox2,
+    new_geometry: &Geometry,
+    grip: &Grip,
+) -> BoundingBox2 {
+    match (old_geometry, new_geometry, grip.grip_type) {
+        (Geometry::Polyline(old), Geometry::Polyline(new), GripType::Endpoint)
+            if old.vertices.len() == new.vertices.len() && grip.index < old.vertices.len() =>
+        {
+            new.bounding_box_after_vertex_move(previous, grip.index, old.vertices[grip.index].point)
+        }
+        (Geometry::Spline(old), Geometry::Spline(new), GripType::ControlPoint)
+            if old.control_points.len() == new.control_points.len() && grip.index < old.control_points.len() =>
+        {
+            new.bounding_box_after_control_point_move(previous, grip.index, old.control_points[grip.index])
+        }
+        _ => new_geometry.bounding_box(),
+    }
+}
+
 fn update_line_by_grip(line: &crate::geometry::Line, grip: &Grip, new_pos: Point2) -> Option<Geometry> {
     let mut new_line = line.clone();
     match grip.grip_type {
@@ -317,6 +360,24 @@ fn update_polyline_by_grip(polyline: &crate::geometry::Polyline, grip: &Grip, ne
                 new_polyline.vertices[grip.index].point = new_pos;
             }
         }
+        GripType::Bulge => {
+            // 拖动量在弦法向上的分量叠加到当前弧高，凸度 = 弧高 / 半弦长
+            let count = new_polyline.vertices.len();
+            if grip.index >= new_polyline.segment_count() {
+                return None;
+            }
+            let start = new_polyline.vertices[grip.index].point;
+            let end = new_polyline.vertices[(grip.index + 1) % count].point;
+            let chord = end - start;
+            let chord_len = chord.norm();
+            if chord_len < crate::math::EPSILON {
+                return None;
+            }
+            let right = Vector2::new(chord.y, -chord.x) / chord_len;
+            let half_chord = chord_len / 2.0;
+            let sagitta = new_polyline.vertices[grip.index].bulge * half_chord + (new_pos - grip.position).dot(&right);
+            new_polyline.vertices[grip.index].bulge = sagitta / (chord_len / 2.0);
+        }
         GripType::Midpoint => {
             // 移动线段中点 = 移动相邻两个顶点
             let offset = new_pos - grip.position;
@@ -383,3 +444,147 @@ fn update_leader_by_grip(leader: &crate::geometry::Leader, grip: &Grip, new_pos:
         None
     }
 }
+
+/// 在中点夹点处插入新顶点（Ctrl+点击多段线中点或凸度夹点）
+///
+/// 新顶点位于该线段的中点（弧线段为弧上中点），返回更新后的几何体副本。
+/// 非多段线或非中点、凸度夹点返回 None。
+pub fn insert_vertex_at_grip(geometry: &Geometry, grip: &Grip) -> Option<Geometry> {
+    match geometry {
+        Geometry::Polyline(polyline) if matches!(grip.grip_type, GripType::Midpoint | GripType::Bulge) => {
+            let point = polyline.segment_midpoint(grip.index)?;
+            let mut new_polyline = polyline.clone();
+            new_polyline
+                .split_segment_at(grip.index, point)
+                .then_some(Geometry::Polyline(new_polyline))
+        }
+        _ => None,
+    }
+}
+
+/// 拉伸几何体（STRETCH）：只移动落在窗口内的夹点
+///
+/// 几何体完全在窗口内时整体平移；否则移动窗口内的端点/顶点/控制点，
+/// 圆、椭圆和点按中心是否在窗口内决定是否移动。没有夹点落在窗口内时返回 None。
+pub fn stretch_geometry(
+    geometry: &Geometry,
+    window: &BoundingBox2,
+    offset: Vector2,
+) -> Option<Geometry> {
+    if window.contains_box(&geometry.bounding_box()) {
+        return Some(Transform2D::translation(offset.x, offset.y).transform_geometry(geometry));
+    }
+
+    let grips: Vec<Grip> = get_grips_for_geometry(geometry)
+        .into_iter()
+        .filter(|grip| is_stretch_grip(geometry, grip) && window.contains(&grip.position))
+        .collect();
+    if grips.is_empty() {
+        return None;
+    }
+
+    // 夹点按索引定位，逐个应用不会相互影响
+    let mut result = geometry.clone();
+    for grip in &grips {
+        result = update_geometry_by_grip(&result, grip, grip.position + offset)?;
+    }
+    Some(result)
+}
+
+/// 拉伸时参与判断的夹点（定义形状的点，而不是中点、象限点等派生夹点）
+fn is_stretch_grip(geometry: &Geometry, grip: &Grip) -> bool {
+    match geometry {
+        Geometry::Circle(_) | Geometry::Ellipse(_) | Geometry::Point(_) => grip.grip_type == GripType::Center,
+        Geometry::Spline(_) => grip.grip_type == GripType::ControlPoint,
+        _ => grip.grip_type == GripType::Endpoint,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::geometry::{Polyline, Spline};
+
+    fn l_shape() -> Polyline {
+        Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(10.0, 10.0)],
+            false,
+        )
+    }
+
+    #[test]
+    fn test_grip_move_bounding_box_matches_full_recompute() {
+        // 被拖动的顶点/控制点位于包围盒内部（走增量路径）和边界上（回退完整重算）
+        let zigzag = Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(5.0, 5.0), Point2::new(10.0, 0.0), Point2::new(10.0, 10.0)],
+            false,
+        );
+        let ys = [0.0, 20.0, 0.0, 20.0, 10.0, 20.0, 0.0, 20.0, 0.0, 20.0];
+        let spline = Spline::from_control_points(
+            ys.iter().enumerate().map(|(i, &y)| Point2::new(i as f64 * 10.0, y)).collect(),
+            3,
+            false,
+        );
+        let cases = [
+            (Geometry::Polyline(zigzag.clone()), GripType::Endpoint, 1, Point2::new(6.0, 4.0)),
+            (Geometry::Polyline(zigzag.clone()), GripType::Endpoint, 1, Point2::new(-3.0, 14.0)),
+            (Geometry::Polyline(zigzag), GripType::Endpoint, 3, Point2::new(8.0, 6.0)),
+            (Geometry::Spline(spline.clone()), GripType::ControlPoint, 4, Point2::new(42.0, 12.0)),
+            (Geometry::Spline(spline.clone()), GripType::ControlPoint, 4, Point2::new(40.0, 50.0)),
+            (Geometry::Spline(spline), GripType::ControlPoint, 1, Point2::new(10.0, 5.0)),
+        ];
+        for (geometry, grip_type, index, target) in cases {
+            let grip = get_grips_for_geometry(&geometry)
+                .into_iter()
+                .find(|g| g.grip_type == grip_type && g.index == index)
+                .unwrap();
+            let moved = update_geometry_by_grip(&geometry, &grip, target).unwrap();
+            let bbox = bounding_box_after_grip_move(&geometry, &geometry.bounding_box(), &moved, &grip);
+            let full = moved.bounding_box();
+            assert_eq!((bbox.min, bbox.max), (full.min, full.max));
+        }
+    }
+
+    #[test]
+    fn test_bulge_grip_turns_straight_segment_into_arc() {
+        let geometry = Geometry::Polyline(l_shape());
+        let grips = get_grips_for_geometry(&geometry);
+        let bulge_grips: Vec<&Grip> = grips.iter().filter(|g| g.grip_type == GripType::Bulge).collect();
+        assert_eq!(bulge_grips.len(), 2);
+        // 直线段的凸度夹点偏离弦中点，不与中点夹点重合
+        assert_eq!(bulge_grips[0].position, Point2::new(5.0, -1.25));
+        let at_mid: Vec<GripType> = grips
+            .iter()
+            .filter(|g| g.position == Point2::new(5.0, 0.0))
+            .map(|g| g.grip_type)
+            .collect();
+        assert_eq!(at_mid, vec![GripType::Midpoint]);
+
+        // 垂直于弦拖动 2：弧高 2，半弦长 5，凸度 0.4；沿弦方向的分量不影响结果
+        let Some(Geometry::Polyline(arced)) = update_geometry_by_grip(&geometry, bulge_grips[0], Point2::new(6.0, -3.25)) else {
+            panic!("应为多段线");
+        };
+        assert!((arced.vertices[0].bulge - 0.4).abs() < 1e-9);
+        assert_eq!(arced.vertices[0].point, Point2::new(0.0, 0.0));
+        assert_eq!(arced.vertices[1].point, Point2::new(10.0, 0.0));
+        assert!((arced.segment_midpoint(0).unwrap() - Point2::new(5.0, -2.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_bulge_grip_sits_on_arc_and_flattens_on_chord() {
+        let mut polyline = l_shape();
+        polyline.vertices[0].bulge = 1.0;
+        let geometry = Geometry::Polyline(polyline);
+        let grip = get_grips_for_geometry(&geometry)
+            .into_iter()
+            .find(|g| g.grip_type == GripType::Bulge && g.index == 0)
+            .unwrap();
+        // 半圆的弧上中点
+        assert!((grip.position - Point2::new(5.0, -5.0)).norm() < 1e-9);
+
+        let Some(Geometry::Polyline(flat)) = update_geometry_by_grip(&geometry, &grip, Point2::new(5.0, 0.0)) else {
+            panic!("应为多段线");
+        };
+        assert!(flat.vertices[0].bulge.abs() < 1e-9);
+    }
+}
@@ -235,6 +235,26 @@ pub struct HistoryTree {
     max_depth: usize,
 }
 
+/// 历史面板中的一项
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    /// 操作ID
+    pub id: OperationId,
+    /// 操作描述
+    pub description: String,
+    /// 是否为当前操作（最后执行的操作）
+    pub is_current: bool,
+    /// 是否已撤销（可重做）
+    pub is_undone: bool,
+}
+
+/// 沿撤销/重做线移动的一步
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HistoryStep {
+    Undo,
+    Redo,
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct HistoryStats {
     /// 总操作数
@@ -334,11 +354,24 @@ impl HistoryTree {
     }
 
     /// 跳转到指定操作
+    ///
+    /// 目标在当前撤销/重做线上时逐步撤销或重做，保留可重做的操作；
+    /// 否则（其他分支）重建撤销栈。
     pub fn goto_operation(&mut self, operation_id: OperationId) -> Result<(), String> {
         if !self.nodes.contains_key(&operation_id) {
             return Err(format!("Operation {:?} not found", operation_id));
         }
 
+        if let Some(walk) = self.walk_to(Some(operation_id)) {
+            for step in walk {
+                match step {
+                    HistoryStep::Undo => self.undo(),
+                    HistoryStep::Redo => self.redo(),
+                };
+            }
+            return Ok(());
+        }
+
         // 重新构建撤销/重做栈
         self.rebuild_stacks(operation_id);
         self.set_current_node(Some(operation_id));
@@ -346,6 +379,52 @@ impl HistoryTree {
         Ok(())
     }
 
+    /// 跳转到指定操作所需的步数：负数为撤销次数，正数为重做次数
+    ///
+    /// 目标不在当前撤销/重做线上时返回 None。
+    pub fn steps_to(&self, operation_id: OperationId) -> Option<isize> {
+        if let Some(pos) = self.undo_stack.iter().position(|id| *id == operation_id) {
+            return Some(-((self.undo_stack.len() - 1 - pos) as isize));
+        }
+        self.redo_stack
+            .iter()
+            .rev()
+            .position(|id| *id == operation_id)
+            .map(|pos| pos as isize + 1)
+    }
+
+    /// 沿撤销/重做线跳转到 `target` 的逐步撤销/重做序列，`None` 表示撤销全部操作
+    ///
+    /// 目标不在当前撤销/重做线上时返回 None。
+    pub fn walk_to(&self, target: Option<OperationId>) -> Option<Vec<HistoryStep>> {
+        let steps = match target {
+            Some(id) => self.steps_to(id)?,
+            None => -(self.undo_stack.len() as isize),
+        };
+        let step = if steps < 0 { HistoryStep::Undo } else { HistoryStep::Redo };
+        Some(vec![step; steps.unsigned_abs()])
+    }
+
+    /// 历史面板列表：当前撤销/重做线上的操作，按执行顺序排列
+    ///
+    /// 已执行的操作在前，已撤销可重做的操作在后。
+    pub fn entries(&self) -> Vec<HistoryEntry> {
+        let applied = self.undo_stack.iter().map(|id| (*id, false));
+        let undone = self.redo_stack.iter().rev().map(|id| (*id, true));
+        applied
+            .chain(undone)
+            .filter_map(|(id, is_undone)| {
+                let node = self.nodes.get(&id)?;
+                Some(HistoryEntry {
+                    id,
+                    description: node.operation.description.clone(),
+                    is_current: self.current_node == Some(id),
+                    is_undone,
+                })
+            })
+            .collect()
+    }
+
     /// 创建分支
     pub fn create_branch(&mut self, branch_name: String, from_operation: OperationId) -> Result<(), String> {
         if !self.nodes.contains_key(&from_operation) {
@@ -703,4 +782,45 @@ mod tests {
         assert_eq!(stats.total_operations, 2);
         assert_eq!(stats.current_depth, 1);
     }
+
+    #[test]
+    fn test_entries_and_goto_operation() {
+        let mut history = HistoryTree::new(100);
+        let ids: Vec<OperationId> = (0..3)
+            .map(|i| {
+                let op = operations::create_entity(
+                    Entity::new(Geometry::Point(crate::geometry::Point::new(i as f64, 0.0))),
+                    format!("Create point {}", i),
+                );
+                let id = op.id;
+                history.add_operation(op).unwrap();
+                id
+            })
+            .collect();
+
+        let entries = history.entries();
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[1].description, "Create point 1");
+        assert!(entries[2].is_current && !entries[2].is_undone);
+        assert!(entries[..2].iter().all(|e| !e.is_current));
+
+        // 跳回第一个操作：后两个操作标记为已撤销，仍留在列表中
+        assert_eq!(history.steps_to(ids[0]), Some(-2));
+        assert_eq!(history.walk_to(Some(ids[0])), Some(vec![HistoryStep::Undo; 2]));
+        history.goto_operation(ids[0]).unwrap();
+        let entries = history.entries();
+        assert_eq!(entries.iter().map(|e| e.id).collect::<Vec<_>>(), ids);
+        assert!(entries[0].is_current);
+        assert!(entries[1].is_undone && entries[2].is_undone);
+
+        // 再跳到最后一个操作：重做两步
+        assert_eq!(history.steps_to(ids[2]), Some(2));
+        history.goto_operation(ids[2]).unwrap();
+        assert!(history.entries().iter().all(|e| !e.is_undone));
+        assert!(history.entries()[2].is_current);
+        assert_eq!(history.current_operations().len(), 3);
+
+        // 跳到最前：撤销全部操作
+        assert_eq!(history.walk_to(None), Some(vec![HistoryStep::Undo; 3]));
+    }
 }
@@ -2,7 +2,7 @@
 //!
 //! 图层是CAD中组织实体的重要方式。
 
-use crate::entity::EntityId;
+use crate::entity::{Entity, EntityId};
 use crate::properties::{Color, LineType, LineWeight};
 use serde::{Deserialize, Serialize};
 
@@ -194,6 +194,93 @@ impl LayerManager {
         }
     }
 
+    /// 删除图层，并把其上的实体迁移到目标图层（默认为图层0）
+    ///
+    /// 图层管理器不持有实体，需要调用方传入全部实体。返回迁移的实体数量。
+    /// 不能删除图层0、当前图层或最后一个图层。
+    pub fn delete_layer_and_reassign<'a>(
+        &mut self,
+        id: EntityId,
+        reassign_to: Option<EntityId>,
+        entities: impl IntoIterator<Item = &'a mut Entity>,
+    ) -> Result<usize, LayerError> {
+        let idx = self
+            .layers
+            .iter()
+            .position(|l| l.id == id)
+            .ok_or_else(|| LayerError::LayerNotFound(format!("#{}", id.id)))?;
+
+        if self.layers[idx].name == "0" {
+            return Err(LayerError::CannotDeleteLayerZero);
+        }
+        if self.layers.len() == 1 {
+            return Err(LayerError::CannotDeleteLastLayer);
+        }
+        if idx == self.current_layer_index {
+            return Err(LayerError::CannotDeleteCurrentLayer);
+        }
+
+        let target = match reassign_to {
+            Some(target) => target,
+            None => self
+                .get_layer("0")
+                .map(|l| l.id)
+                .ok_or_else(|| LayerError::LayerNotFound("0".to_string()))?,
+        };
+        if target == id {
+            return Err(LayerError::InvalidReassignTarget);
+        }
+        if self.get_layer_by_id(target).is_none() {
+            return Err(LayerError::LayerNotFound(format!("#{}", target.id)));
+        }
+
+        let mut moved = 0;
+        for entity in entities {
+            if entity.layer_id == id {
+                entity.layer_id = target;
+                moved += 1;
+            }
+        }
+
+        self.layers.remove(idx);
+        if idx < self.current_layer_index {
+            self.current_layer_index -= 1;
+        }
+
+        Ok(moved)
+    }
+
+    /// 合并图层：`src` 上的实体全部迁移到 `dst`，然后删除 `src`
+    ///
+    /// 与 [`delete_layer_and_reassign`](Self::delete_layer_and_reassign) 一样需要调用方传入全部实体，
+    /// 返回迁移的实体数量。`src` 为当前图层时当前图层改为 `dst`；图层0不能被合并。
+    pub fn merge_layers<'a>(
+        &mut self,
+        src: &str,
+        dst: &str,
+        entities: impl IntoIterator<Item = &'a mut Entity>,
+    ) -> Result<usize, LayerError> {
+        let src_id = self
+            .get_layer(src)
+            .map(|l| l.id)
+            .ok_or_else(|| LayerError::LayerNotFound(src.to_string()))?;
+        let dst_id = self
+            .get_layer(dst)
+            .map(|l| l.id)
+            .ok_or_else(|| LayerError::LayerNotFound(dst.to_string()))?;
+        if src == "0" {
+            return Err(LayerError::CannotDeleteLayerZero);
+        }
+        if src_id == dst_id {
+            return Err(LayerError::InvalidReassignTarget);
+        }
+
+        if self.current_layer().id == src_id {
+            self.set_current_layer(dst);
+        }
+        self.delete_layer_and_reassign(src_id, Some(dst_id), entities)
+    }
+
     /// 重命名图层
     pub fn rename_layer(&mut self, old_name: &str, new_name: &str) -> Result<(), LayerError> {
         if old_name == "0" {
@@ -233,6 +320,12 @@ pub enum LayerError {
     #[error("Cannot delete current layer")]
     CannotDeleteCurrentLayer,
 
+    #[error("Cannot delete the last layer")]
+    CannotDeleteLastLayer,
+
+    #[error("Cannot reassign entities to the layer being deleted")]
+    InvalidReassignTarget,
+
     #[error("Cannot rename layer 0")]
     CannotRenameLayerZero,
 
@@ -264,5 +357,96 @@ mod tests {
         assert!(manager.delete_layer("Layer2").is_ok());
         assert_eq!(manager.count(), 2);
     }
-}
 
+    #[test]
+    fn test_delete_layer_reassigns_entities() {
+        use crate::geometry::{Geometry, Point};
+
+        let mut manager = LayerManager::new();
+        let walls = manager.create_layer("Walls");
+        let doors = manager.create_layer("Doors");
+        let zero = manager.get_layer("0").unwrap().id;
+
+        let point = || Geometry::Point(Point::new(0.0, 0.0));
+        let mut entities = vec![
+            Entity::new(point()).with_layer(walls),
+            Entity::new(point()).with_layer(walls),
+            Entity::new(point()).with_layer(doors),
+        ];
+
+        // 默认迁移到图层0
+        assert_eq!(manager.delete_layer_and_reassign(walls, None, entities.iter_mut()).unwrap(), 2);
+        assert!(manager.get_layer_by_id(walls).is_none());
+        assert_eq!(entities[0].layer_id, zero);
+        assert_eq!(entities[1].layer_id, zero);
+        assert_eq!(entities[2].layer_id, doors);
+
+        // 指定目标图层
+        let trim = manager.create_layer("Trim");
+        assert_eq!(manager.delete_layer_and_reassign(doors, Some(trim), entities.iter_mut()).unwrap(), 1);
+        assert_eq!(entities[2].layer_id, trim);
+        assert_eq!(manager.count(), 2);
+    }
+
+    #[test]
+    fn test_merge_layers_moves_entities_and_current_layer() {
+        use crate::geometry::{Geometry, Point};
+
+        let mut manager = LayerManager::new();
+        let walls = manager.create_layer("Walls");
+        let old_walls = manager.create_layer("OldWalls");
+        manager.set_current_layer("OldWalls");
+
+        let point = || Geometry::Point(Point::new(0.0, 0.0));
+        let mut entities = vec![
+            Entity::new(point()).with_layer(old_walls),
+            Entity::new(point()).with_layer(walls),
+            Entity::new(point()).with_layer(old_walls),
+        ];
+
+        assert_eq!(manager.merge_layers("OldWalls", "Walls", entities.iter_mut()).unwrap(), 2);
+        assert!(manager.get_layer("OldWalls").is_none());
+        assert!(entities.iter().all(|e| e.layer_id == walls));
+        assert_eq!(manager.current_layer().name, "Walls");
+
+        // 图层0、自身合并和不存在的图层都被拒绝
+        assert!(matches!(
+            manager.merge_layers("0", "Walls", entities.iter_mut()),
+            Err(LayerError::CannotDeleteLayerZero)
+        ));
+        assert!(matches!(
+            manager.merge_layers("Walls", "Walls", entities.iter_mut()),
+            Err(LayerError::InvalidReassignTarget)
+        ));
+        assert!(matches!(
+            manager.merge_layers("Missing", "Walls", entities.iter_mut()),
+            Err(LayerError::LayerNotFound(_))
+        ));
+        assert_eq!(manager.count(), 2);
+    }
+
+    #[test]
+    fn test_delete_layer_refuses_protected_layers() {
+        let mut manager = LayerManager::new();
+        let zero = manager.get_layer("0").unwrap().id;
+        let current = manager.create_layer("Current");
+        manager.set_current_layer("Current");
+        let mut entities: Vec<Entity> = Vec::new();
+
+        assert!(matches!(
+            manager.delete_layer_and_reassign(zero, None, entities.iter_mut()),
+            Err(LayerError::CannotDeleteLayerZero)
+        ));
+        assert!(matches!(
+            manager.delete_layer_and_reassign(current, None, entities.iter_mut()),
+            Err(LayerError::CannotDeleteCurrentLayer)
+        ));
+
+        let other = manager.create_layer("Other");
+        assert!(matches!(
+            manager.delete_layer_and_reassign(other, Some(other), entities.iter_mut()),
+            Err(LayerError::InvalidReassignTarget)
+        ));
+        assert_eq!(manager.count(), 3);
+    }
+}
@@ -22,6 +22,7 @@
 //! ```
 
 use crate::entity::{Entity, EntityId};
+use crate::geometry::Geometry;
 use crate::math::{Point2, Vector2};
 use serde::{Deserialize, Serialize};
 
@@ -156,6 +157,9 @@ pub struct Viewport {
     pub scale: f64,
     /// 视图旋转角度（弧度）
     pub rotation: f64,
+    /// 注释比例（`None` 表示与视图比例同步）
+    #[serde(default)]
+    pub annotation_scale: Option<f64>,
     
     // ===== 视口状态 =====
     /// 视口状态
@@ -182,6 +186,7 @@ impl Viewport {
             view_center: Point2::origin(),
             scale: 1.0,
             rotation: 0.0,
+            annotation_scale: None,
             status: ViewportStatus::Inactive,
             show_border: true,
             border_color: (0, 0, 0),
@@ -194,25 +199,45 @@ impl Viewport {
         self.scale = scale;
     }
 
-    /// 设置常用比例
+    /// 设置常用比例（名称见 `STANDARD_SCALES`，未知名称时为 1:1）
     pub fn set_standard_scale(&mut self, scale_str: &str) {
-        self.scale = match scale_str {
-            "1:1" => 1.0,
-            "1:2" => 2.0,
-            "1:5" => 5.0,
-            "1:10" => 10.0,
-            "1:20" => 20.0,
-            "1:25" => 25.0,
-            "1:50" => 50.0,
-            "1:100" => 100.0,
-            "1:200" => 200.0,
-            "1:500" => 500.0,
-            "1:1000" => 1000.0,
-            "2:1" => 0.5,
-            "5:1" => 0.2,
-            "10:1" => 0.1,
-            _ => 1.0,
-        };
+        self.scale = STANDARD_SCALES
+            .iter()
+            .find(|(name, _)| *name == scale_str)
+            .map_or(1.0, |&(_, scale)| scale);
+    }
+
+    /// 可选的标准比例（用于下拉列表）
+    pub fn standard_scales() -> &'static [(&'static str, f64)] {
+        STANDARD_SCALES
+    }
+
+    /// 当前比例对应的标准比例名称（非标准比例返回 `None`）
+    pub fn standard_scale_name(&self) -> Option<&'static str> {
+        STANDARD_SCALES
+            .iter()
+            .find(|(_, scale)| (scale - self.scale).abs() < 1e-9)
+            .map(|&(name, _)| name)
+    }
+
+    /// 实际使用的注释比例
+    pub fn effective_annotation_scale(&self) -> f64 {
+        self.annotation_scale.unwrap_or(self.scale)
+    }
+
+    /// 按注释比例调整文字和标注的尺寸
+    ///
+    /// 注释的高度按图纸尺寸（mm）定义，在 1:100 的视口中模型空间高度放大 100 倍，
+    /// 经视口缩小后在图纸上仍保持原高度。其他几何原样返回。
+    pub fn annotation_geometry(&self, geometry: &Geometry) -> Geometry {
+        let factor = self.effective_annotation_scale();
+        let mut scaled = geometry.clone();
+        match &mut scaled {
+            Geometry::Text(text) => text.height *= factor,
+            Geometry::Dimension(dim) => dim.text_height *= factor,
+            _ => {}
+        }
+        scaled
     }
 
     /// 获取视口在图纸空间的边界框
@@ -694,6 +719,7 @@ mod tests {
             view_center: Point2::new(5000.0, 3000.0),
             scale: 100.0,  // 1:100
             rotation: 0.0,
+            annotation_scale: None,
             status: ViewportStatus::Inactive,
             show_border: true,
             border_color: (0, 0, 0),
@@ -712,6 +738,33 @@ mod tests {
         assert!((model_point.y - viewport.view_center.y).abs() < 0.001);
     }
 
+    #[test]
+    fn test_annotation_text_scaled_in_viewport() {
+        let mut viewport = Viewport::new(ViewportId::new(1), Point2::origin(), 200.0, 150.0);
+        viewport.set_standard_scale("1:100");
+        assert_eq!(viewport.scale, 100.0);
+        assert_eq!(viewport.standard_scale_name(), Some("1:100"));
+        assert!(Viewport::standard_scales().iter().any(|(name, _)| *name == "1:50"));
+
+        // 图纸上 2.5mm 高的注释文字在 1:100 视口中为 250 个模型单位
+        let text = Geometry::Text(crate::geometry::Text::new(Point2::origin(), "A", 2.5));
+        let Geometry::Text(scaled) = viewport.annotation_geometry(&text) else {
+            panic!("应为文字");
+        };
+        assert!((scaled.height - 250.0).abs() < 1e-9);
+        // 映射到图纸空间后恢复为 2.5mm
+        let top = viewport.model_to_paper(Point2::new(0.0, scaled.height));
+        let base = viewport.model_to_paper(Point2::origin());
+        assert!(((top - base).norm() - 2.5).abs() < 1e-9);
+
+        // 固定注释比例时不随视图比例变化
+        viewport.annotation_scale = Some(50.0);
+        let Geometry::Text(scaled) = viewport.annotation_geometry(&text) else {
+            panic!("应为文字");
+        };
+        assert!((scaled.height - 125.0).abs() < 1e-9);
+    }
+
     #[test]
     fn test_layout_manager() {
         let mut manager = LayoutManager::new();
@@ -23,6 +23,7 @@
 
 pub mod async_core;
 pub mod block;
+pub mod boolean;
 pub mod buffer;
 pub mod dimstyle;
 pub mod entity;
@@ -35,11 +36,14 @@ pub mod layer;
 pub mod math;
 pub mod parametric;
 pub mod performance;
+pub mod pointstyle;
 pub mod properties;
 pub mod snap;
 pub mod solver;
 pub mod spatial;
+pub mod textstyle;
 pub mod transform;
+pub mod ucs;
 pub mod units;
 pub mod version_control;
 
@@ -50,19 +54,22 @@ pub mod prelude {
     pub use crate::buffer::{DoubleBufferedEntities, EntityBuffer};
     pub use crate::entity::{Entity, EntityId};
     pub use crate::geometry::{Arc, Circle, Ellipse, Geometry, Hatch, Leader, Line, Point, Polyline, Spline, Text, TextAlignment};
-    pub use crate::history::{HistoryTree, Operation, OperationId};
+    pub use crate::history::{HistoryEntry, HistoryTree, Operation, OperationId};
     pub use crate::layer::Layer;
     pub use crate::input_parser::{InputParser, InputValue, ParseError};
     pub use crate::math::{Point2, Point3, Vector2, Vector3};
     pub use crate::parametric::{Constraint, ConstraintSystem, Variable};
     pub use crate::properties::{Color, LineType, Properties};
-    pub use crate::snap::{SnapConfig, SnapEngine, SnapMask, SnapPoint, SnapType};
+    pub use crate::snap::{GridMode, SnapConfig, SnapEngine, SnapMask, SnapPoint, SnapType};
     pub use crate::solver::NewtonSolver;
     pub use crate::transform::Transform2D;
+    pub use crate::ucs::Ucs;
     pub use crate::version_control::{VersionControl, Commit, Branch};
-    pub use crate::grip::{Grip, GripType, GripData, get_grips_for_geometry, update_geometry_by_grip};
+    pub use crate::grip::{Grip, GripType, GripData, bounding_box_after_grip_move, get_grips_for_geometry, insert_vertex_at_grip, update_geometry_by_grip};
     pub use crate::units::{Unit, LinearFormat, AngleUnit, AngleFormat, convert, format_linear, format_angle};
     pub use crate::dimstyle::{DimStyle, DimStyleManager, ArrowType, DimTextAlignment, DimTextVertical};
+    pub use crate::textstyle::{TextStyle, TextStyleManager};
+    pub use crate::pointstyle::{PointMarker, PointStyle};
     pub use crate::layout::{Layout, LayoutId, LayoutManager, Viewport, ViewportId, SpaceType, PaperSize, PaperOrientation, ViewportStatus, STANDARD_SCALES};
 }
 
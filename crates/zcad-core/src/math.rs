@@ -38,6 +38,28 @@ pub fn points_approx_eq(a: &Point2, b: &Point2) -> bool {
     approx_eq(a.x, b.x) && approx_eq(a.y, b.y)
 }
 
+/// 角度（度）归一化到 [0°, 360°)
+#[inline]
+pub fn normalize_degrees(degrees: f64) -> f64 {
+    let normalized = degrees.rem_euclid(360.0);
+    // 极小的负角度经 rem_euclid 会舍入为 360.0
+    if normalized >= 360.0 {
+        0.0
+    } else {
+        normalized
+    }
+}
+
+/// 从 `v1` 逆时针转到 `v2` 的角度（度），归一化到 [0°, 360°)
+///
+/// 任一向量为零时返回 0。两向量夹角（不分方向的较小角）为
+/// `a.min(360.0 - a)`。
+pub fn angle_between(v1: &Vector2, v2: &Vector2) -> f64 {
+    let dot = v1.x * v2.x + v1.y * v2.y;
+    let cross = v1.x * v2.y - v1.y * v2.x;
+    normalize_degrees(cross.atan2(dot).to_degrees())
+}
+
 /// 2D包围盒
 #[derive(Debug, Clone, Copy, Serialize, Deserialize)]
 pub struct BoundingBox2 {
@@ -76,6 +98,14 @@ impl BoundingBox2 {
         self.max.y = self.max.y.max(point.y);
     }
 
+    /// 四周各向外扩展 `margin` 后的包围盒
+    pub fn expanded(&self, margin: f64) -> Self {
+        Self {
+            min: Point2::new(self.min.x - margin, self.min.y - margin),
+            max: Point2::new(self.max.x + margin, self.max.y + margin),
+        }
+    }
+
     /// 合并两个包围盒
     pub fn union(&self, other: &Self) -> Self {
         Self {
@@ -100,6 +130,52 @@ impl BoundingBox2 {
             && point.y <= self.max.y
     }
 
+    /// 检查点是否严格位于包围盒内部（不在边界上）
+    pub fn strictly_contains(&self, point: &Point2) -> bool {
+        point.x > self.min.x && point.x < self.max.x && point.y > self.min.y && point.y < self.max.y
+    }
+
+    /// 检查是否完全包含另一个包围盒
+    pub fn contains_box(&self, other: &Self) -> bool {
+        self.contains(&other.min) && self.contains(&other.max)
+    }
+
+    /// 将线段裁剪到包围盒内（Liang-Barsky 算法）
+    ///
+    /// 线段完全在包围盒外时返回 None
+    pub fn clip_segment(&self, a: Point2, b: Point2) -> Option<(Point2, Point2)> {
+        let d = b - a;
+        let mut t0: f64 = 0.0;
+        let mut t1: f64 = 1.0;
+
+        let checks = [
+            (-d.x, a.x - self.min.x),
+            (d.x, self.max.x - a.x),
+            (-d.y, a.y - self.min.y),
+            (d.y, self.max.y - a.y),
+        ];
+        for (p, q) in checks {
+            if p.abs() < EPSILON {
+                // 平行于该边界：在外侧则整段不可见
+                if q < 0.0 {
+                    return None;
+                }
+            } else {
+                let t = q / p;
+                if p < 0.0 {
+                    t0 = t0.max(t);
+                } else {
+                    t1 = t1.min(t);
+                }
+                if t0 > t1 {
+                    return None;
+                }
+            }
+        }
+
+        Some((a + d * t0, a + d * t1))
+    }
+
     /// 获取中心点
     pub fn center(&self) -> Point2 {
         Point2::new(
@@ -117,12 +193,72 @@ impl BoundingBox2 {
     pub fn height(&self) -> f64 {
         self.max.y - self.min.y
     }
+
+    /// 点到包围盒的距离（在盒内为 0）
+    pub fn distance_to_point(&self, point: &Point2) -> f64 {
+        let dx = (self.min.x - point.x).max(point.x - self.max.x).max(0.0);
+        let dy = (self.min.y - point.y).max(point.y - self.max.y).max(0.0);
+        dx.hypot(dy)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_clip_segment() {
+        let bbox = BoundingBox2::new(Point2::new(0.0, 0.0), Point2::new(10.0, 10.0));
+
+        let (a, b) = bbox
+            .clip_segment(Point2::new(-5.0, 5.0), Point2::new(15.0, 5.0))
+            .unwrap();
+        assert!(points_approx_eq(&a, &Point2::new(0.0, 5.0)));
+        assert!(points_approx_eq(&b, &Point2::new(10.0, 5.0)));
+
+        // 完全在内部：保持不变
+        let (a, b) = bbox
+            .clip_segment(Point2::new(1.0, 1.0), Point2::new(2.0, 3.0))
+            .unwrap();
+        assert!(points_approx_eq(&a, &Point2::new(1.0, 1.0)));
+        assert!(points_approx_eq(&b, &Point2::new(2.0, 3.0)));
+
+        // 完全在外部
+        assert!(bbox.clip_segment(Point2::new(-5.0, -1.0), Point2::new(15.0, -1.0)).is_none());
+        assert!(bbox.clip_segment(Point2::new(12.0, 0.0), Point2::new(20.0, 10.0)).is_none());
+    }
+
+    #[test]
+    fn test_angle_between_across_quadrants() {
+        let deg = |d: f64| Vector2::new(d.to_radians().cos(), d.to_radians().sin());
+
+        // 逆时针跨越象限
+        assert!((angle_between(&deg(80.0), &deg(100.0)) - 20.0).abs() < 1e-9);
+        assert!((angle_between(&deg(170.0), &deg(190.0)) - 20.0).abs() < 1e-9);
+        assert!((angle_between(&deg(45.0), &deg(225.0)) - 180.0).abs() < 1e-9);
+        // 顺时针方向得到补角
+        assert!((angle_between(&deg(100.0), &deg(80.0)) - 340.0).abs() < 1e-9);
+        // 与长度无关
+        assert!((angle_between(&(deg(30.0) * 5.0), &(deg(120.0) * 0.1)) - 90.0).abs() < 1e-9);
+        assert_eq!(angle_between(&Vector2::zeros(), &deg(30.0)), 0.0);
+    }
+
+    #[test]
+    fn test_angle_wraps_at_360() {
+        let deg = |d: f64| Vector2::new(d.to_radians().cos(), d.to_radians().sin());
+
+        // 跨过 0°/360°
+        assert!((angle_between(&deg(350.0), &deg(10.0)) - 20.0).abs() < 1e-9);
+        assert!((angle_between(&deg(10.0), &deg(350.0)) - 340.0).abs() < 1e-9);
+        // 同向为 0 而不是 360
+        assert_eq!(angle_between(&deg(0.0), &deg(0.0)), 0.0);
+        assert_eq!(angle_between(&Vector2::x(), &Vector2::new(1.0, -1e-18)), 0.0);
+
+        assert_eq!(normalize_degrees(-90.0), 270.0);
+        assert_eq!(normalize_degrees(720.0), 0.0);
+        assert_eq!(normalize_degrees(-1e-15), 0.0);
+    }
+
     #[test]
     fn test_bounding_box() {
         let bbox = BoundingBox2::from_points([
@@ -0,0 +1,103 @@
+//! 点样式（Point Style）
+//!
+//! 对应 DXF 头变量 `$PDMODE` / `$PDSIZE`，决定图中所有点对象的显示形状和大小。
+//! PDMODE 的低位选择基本形状（0 圆点、1 不显示、2 十字、3 叉号、4 短竖线），
+//! 加 32 外套圆、加 64 外套方框，可组合。
+
+use serde::{Deserialize, Serialize};
+
+/// 点标记的组成图形
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PointMarker {
+    /// 圆点
+    Dot,
+    /// 十字（+）
+    Plus,
+    /// 叉号（×）
+    Cross,
+    /// 从点向上的短竖线
+    Tick,
+    /// 外圆
+    Circle,
+    /// 外方框
+    Square,
+}
+
+/// 文档的点样式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct PointStyle {
+    /// 显示模式（PDMODE）
+    pub mode: i32,
+    /// 显示大小（PDSIZE）：0 为视图高度的 5%，正值为绝对大小，负值为视图高度的百分比
+    pub size: f64,
+}
+
+impl Default for PointStyle {
+    fn default() -> Self {
+        Self { mode: 0, size: 0.0 }
+    }
+}
+
+impl PointStyle {
+    pub fn new(mode: i32, size: f64) -> Self {
+        Self { mode, size }
+    }
+
+    /// 按 PDMODE 组成点标记的图形
+    pub fn markers(&self) -> Vec<PointMarker> {
+        let mut markers = Vec::new();
+        match self.mode & 0x1F {
+            0 => markers.push(PointMarker::Dot),
+            2 => markers.push(PointMarker::Plus),
+            3 => markers.push(PointMarker::Cross),
+            4 => markers.push(PointMarker::Tick),
+            // 1 及未定义的值不画基本形状
+            _ => {}
+        }
+        if self.mode & 32 != 0 {
+            markers.push(PointMarker::Circle);
+        }
+        if self.mode & 64 != 0 {
+            markers.push(PointMarker::Square);
+        }
+        markers
+    }
+
+    /// 点标记在世界坐标中的大小，`view_height` 为当前视图的世界高度
+    pub fn display_size(&self, view_height: f64) -> f64 {
+        if self.size > 0.0 {
+            self.size
+        } else if self.size < 0.0 {
+            view_height * -self.size / 100.0
+        } else {
+            view_height * 0.05
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pdmode_markers() {
+        use PointMarker::*;
+        let markers = |mode| PointStyle::new(mode, 0.0).markers();
+        assert_eq!(markers(0), vec![Dot]);
+        assert!(markers(1).is_empty());
+        assert_eq!(markers(2), vec![Plus]);
+        assert_eq!(markers(3), vec![Cross]);
+        assert_eq!(markers(4), vec![Tick]);
+        assert_eq!(markers(33), vec![Circle]);
+        assert_eq!(markers(34), vec![Plus, Circle]);
+        assert_eq!(markers(67), vec![Cross, Square]);
+        assert_eq!(markers(99), vec![Cross, Circle, Square]);
+    }
+
+    #[test]
+    fn test_pdsize_display_size() {
+        assert_eq!(PointStyle::new(0, 2.5).display_size(200.0), 2.5);
+        assert_eq!(PointStyle::new(0, 0.0).display_size(200.0), 10.0);
+        assert_eq!(PointStyle::new(0, -10.0).display_size(200.0), 20.0);
+    }
+}
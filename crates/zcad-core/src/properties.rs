@@ -10,9 +10,15 @@ pub struct Color {
     pub r: u8,
     pub g: u8,
     pub b: u8,
+    /// 旧版本文件中没有 alpha 通道，缺省为不透明
+    #[serde(default = "opaque_alpha")]
     pub a: u8,
 }
 
+fn opaque_alpha() -> u8 {
+    255
+}
+
 impl Color {
     pub const fn new(r: u8, g: u8, b: u8) -> Self {
         Self { r, g, b, a: 255 }
@@ -163,7 +169,7 @@ impl Default for LineWeight {
 }
 
 /// 实体的视觉属性
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Properties {
     /// 颜色
     pub color: Color,
@@ -172,6 +178,7 @@ pub struct Properties {
     /// 线宽
     pub line_weight: LineWeight,
     /// 透明度 (0-100, 0=不透明)
+    #[serde(default)]
     pub transparency: u8,
 }
 
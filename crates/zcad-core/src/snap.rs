@@ -11,10 +11,12 @@
 //! - 切点 (Tangent)
 //! - 最近点 (Nearest)
 //! - 网格点 (Grid)
+//! - 插入点 (Insertion)
 
 use crate::entity::{Entity, EntityId};
-use crate::geometry::{Arc, Circle, Ellipse, Geometry, Leader, Line, Polyline, Spline};
+use crate::geometry::{tangents, Arc, Circle, Ellipse, Geometry, Leader, Line, Polyline, Spline};
 use crate::math::{Point2, Vector2, EPSILON};
+use crate::ucs::Ucs;
 use serde::{Deserialize, Serialize};
 
 /// 捕捉类型
@@ -38,9 +40,28 @@ pub enum SnapType {
     Grid,
     /// 象限点（圆/弧的0°, 90°, 180°, 270°位置）
     Quadrant,
+    /// 外观交点（两条线段延长后的交点）
+    ApparentIntersection,
+    /// 插入点（文字位置、标注文字位置、引线文字位置）
+    Insertion,
 }
 
 impl SnapType {
+    /// 所有捕捉类型（用于设置面板）
+    pub const ALL: [SnapType; 11] = [
+        SnapType::Endpoint,
+        SnapType::Midpoint,
+        SnapType::Center,
+        SnapType::Intersection,
+        SnapType::Perpendicular,
+        SnapType::Tangent,
+        SnapType::Nearest,
+        SnapType::Grid,
+        SnapType::Quadrant,
+        SnapType::ApparentIntersection,
+        SnapType::Insertion,
+    ];
+
     /// 获取捕捉类型的名称
     pub fn name(&self) -> &'static str {
         match self {
@@ -53,6 +74,8 @@ impl SnapType {
             SnapType::Nearest => "最近点",
             SnapType::Grid => "网格点",
             SnapType::Quadrant => "象限点",
+            SnapType::ApparentIntersection => "外观交点",
+            SnapType::Insertion => "插入点",
         }
     }
 
@@ -68,6 +91,8 @@ impl SnapType {
             SnapType::Nearest => "NEA",
             SnapType::Grid => "GRI",
             SnapType::Quadrant => "QUA",
+            SnapType::ApparentIntersection => "APP",
+            SnapType::Insertion => "INS",
         }
     }
 }
@@ -96,15 +121,98 @@ impl SnapPoint {
     }
 }
 
+/// 网格模式
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize, Default)]
+pub enum GridMode {
+    /// 正交网格
+    #[default]
+    Rectangular,
+    /// 等轴测网格（30°/150°/竖直三组线）
+    Isometric,
+    /// 旋转网格（角度为弧度）
+    Rotated(f64),
+}
+
+impl GridMode {
+    /// 显示名称
+    pub fn name(&self) -> &'static str {
+        match self {
+            GridMode::Rectangular => "正交",
+            GridMode::Isometric => "等轴测",
+            GridMode::Rotated(_) => "旋转",
+        }
+    }
+
+    /// 网格的两个基向量（网格节点 = i·a + j·b）
+    ///
+    /// 等轴测网格：a 沿 30° 方向，b 竖直，相邻节点距离均为 `spacing`
+    pub fn basis(&self, spacing: f64) -> (Vector2, Vector2) {
+        match *self {
+            GridMode::Rectangular => (Vector2::new(spacing, 0.0), Vector2::new(0.0, spacing)),
+            GridMode::Isometric => {
+                let angle = std::f64::consts::PI / 6.0;
+                (
+                    Vector2::new(angle.cos(), angle.sin()) * spacing,
+                    Vector2::new(0.0, spacing),
+                )
+            }
+            GridMode::Rotated(angle) => {
+                let (sin, cos) = angle.sin_cos();
+                (
+                    Vector2::new(cos, sin) * spacing,
+                    Vector2::new(-sin, cos) * spacing,
+                )
+            }
+        }
+    }
+
+    /// 离指定点最近的网格节点
+    pub fn nearest_node(&self, point: Point2, spacing: f64) -> Point2 {
+        let (a, b) = self.basis(spacing);
+        let det = a.x * b.y - a.y * b.x;
+        if det.abs() < EPSILON {
+            return point;
+        }
+
+        // 求点在基向量下的坐标，再在所在单元及相邻单元的节点中取最近者
+        let u = (point.x * b.y - point.y * b.x) / det;
+        let v = (a.x * point.y - a.y * point.x) / det;
+        let (u0, v0) = (u.floor(), v.floor());
+
+        let mut best = point;
+        let mut best_dist = f64::MAX;
+        for du in -1..=2 {
+            for dv in -1..=2 {
+                let node = Point2::origin() + a * (u0 + du as f64) + b * (v0 + dv as f64);
+                let dist = (node - point).norm_squared();
+                if dist < best_dist {
+                    best_dist = dist;
+                    best = node;
+                }
+            }
+        }
+        best
+    }
+}
+
 /// 捕捉配置
+///
+/// 反序列化时缺少的字段取默认值，旧版本保存的设置文件仍可读取。
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SnapConfig {
     /// 捕捉容差（屏幕像素）
     pub tolerance: f64,
     /// 启用的捕捉类型
     pub enabled_types: SnapMask,
-    /// 网格间距
+    /// 网格捕捉步长（世界单位），与显示网格随缩放调整的间距无关
     pub grid_spacing: f64,
+    /// 网格模式
+    #[serde(default)]
+    pub grid_mode: GridMode,
+    /// 用户坐标系：网格节点、正交和极轴角度按其原点和轴向计算（由界面同步，不保存）
+    #[serde(skip)]
+    pub ucs: Ucs,
     /// 是否显示捕捉标记
     pub show_markers: bool,
     /// 是否显示捕捉提示
@@ -137,6 +245,8 @@ impl Default for SnapConfig {
             tolerance: 10.0, // 10像素
             enabled_types: SnapMask::default(),
             grid_spacing: 10.0,
+            grid_mode: GridMode::Rectangular,
+            ucs: Ucs::world(),
             show_markers: true,
             show_tooltips: true,
             // 极轴追踪默认配置
@@ -177,6 +287,8 @@ impl SnapMask {
     pub const NEAREST: u16 = 1 << 6;
     pub const GRID: u16 = 1 << 7;
     pub const QUADRANT: u16 = 1 << 8;
+    pub const APPARENT_INTERSECTION: u16 = 1 << 9;
+    pub const INSERTION: u16 = 1 << 10;
 
     pub const NONE: SnapMask = SnapMask { bits: 0 };
     pub const ALL: SnapMask = SnapMask { bits: 0xFFFF };
@@ -196,6 +308,8 @@ impl SnapMask {
             SnapType::Nearest => Self::NEAREST,
             SnapType::Grid => Self::GRID,
             SnapType::Quadrant => Self::QUADRANT,
+            SnapType::ApparentIntersection => Self::APPARENT_INTERSECTION,
+            SnapType::Insertion => Self::INSERTION,
         };
         self.bits & bit != 0
     }
@@ -211,6 +325,8 @@ impl SnapMask {
             SnapType::Nearest => Self::NEAREST,
             SnapType::Grid => Self::GRID,
             SnapType::Quadrant => Self::QUADRANT,
+            SnapType::ApparentIntersection => Self::APPARENT_INTERSECTION,
+            SnapType::Insertion => Self::INSERTION,
         };
         if enabled {
             self.bits |= bit;
@@ -229,7 +345,42 @@ impl Default for SnapMask {
     fn default() -> Self {
         // 默认启用常用的捕捉类型
         Self {
-            bits: Self::ENDPOINT | Self::MIDPOINT | Self::CENTER | Self::INTERSECTION,
+            bits: Self::ENDPOINT
+                | Self::MIDPOINT
+                | Self::CENTER
+                | Self::INTERSECTION
+                | Self::APPARENT_INTERSECTION
+                | Self::INSERTION,
+        }
+    }
+}
+
+/// 两点之间的中点（M2P）临时捕捉
+///
+/// 依次拾取两个点，第二个点拾取后返回两点的中点并复位，可再次使用。
+#[derive(Debug, Clone, Default)]
+pub struct MidBetweenPoints {
+    first: Option<Point2>,
+}
+
+impl MidBetweenPoints {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 已拾取的第一点
+    pub fn first_point(&self) -> Option<Point2> {
+        self.first
+    }
+
+    /// 拾取一个点：第一个点返回 `None`，第二个点返回两点中点
+    pub fn pick(&mut self, point: Point2) -> Option<Point2> {
+        match self.first.take() {
+            Some(first) => Some(Point2::from((first.coords + point.coords) / 2.0)),
+            None => {
+                self.first = Some(point);
+                None
+            }
         }
     }
 }
@@ -242,6 +393,24 @@ pub struct SnapEngine {
     config: SnapConfig,
     /// 缓存的候选捕捉点
     candidates: Vec<SnapPoint>,
+    /// 临时捕捉覆盖（按住修饰键时只捕捉这一种类型，不修改配置）
+    override_type: Option<SnapType>,
+    /// 上一次带缓存查询的键和结果
+    cache: Option<(SnapCacheKey, Option<SnapPoint>)>,
+}
+
+/// 捕捉结果缓存的像素格大小：光标在同一格内移动时复用上次结果
+const SNAP_CACHE_CELL: f64 = 2.0;
+
+/// 捕捉结果缓存的键
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SnapCacheKey {
+    /// 光标所在的屏幕像素格
+    cell: (i64, i64),
+    zoom: f64,
+    reference_point: Option<Point2>,
+    /// 文档版本号
+    revision: u64,
 }
 
 impl SnapEngine {
@@ -249,7 +418,60 @@ impl SnapEngine {
         Self {
             config,
             candidates: Vec::with_capacity(64),
+            override_type: None,
+            cache: None,
+        }
+    }
+
+    /// 设置临时捕捉覆盖，`None` 恢复使用配置中的捕捉类型
+    pub fn set_override(&mut self, snap_type: Option<SnapType>) {
+        if self.override_type != snap_type {
+            self.override_type = snap_type;
+            self.invalidate_cache();
+        }
+    }
+
+    /// 清空捕捉结果缓存
+    pub fn invalidate_cache(&mut self) {
+        self.cache = None;
+    }
+
+    /// 带缓存的 [`find_snap_point`](Self::find_snap_point)
+    ///
+    /// 光标仍在同一屏幕像素格内，且缩放、参考点和文档版本号 `revision` 都未变化时
+    /// 直接返回上次结果，不再遍历实体。文档的任何修改都会改变版本号，使缓存失效；
+    /// 候选实体还取决于图层开关时，应传入同时反映图层状态的版本号。
+    /// 修改捕捉配置或临时覆盖也会清空缓存。
+    pub fn find_snap_point_cached(
+        &mut self,
+        mouse_world: Point2,
+        entities: &[&Entity],
+        zoom: f64,
+        reference_point: Option<Point2>,
+        revision: u64,
+    ) -> Option<SnapPoint> {
+        let key = SnapCacheKey {
+            cell: (
+                (mouse_world.x * zoom / SNAP_CACHE_CELL).floor() as i64,
+                (mouse_world.y * zoom / SNAP_CACHE_CELL).floor() as i64,
+            ),
+            zoom,
+            reference_point,
+            revision,
+        };
+        if let Some((cached_key, result)) = &self.cache {
+            if *cached_key == key {
+                return result.clone();
+            }
         }
+        let result = self.find_snap_point(mouse_world, entities, zoom, reference_point);
+        self.cache = Some((key, result.clone()));
+        result
+    }
+
+    /// 当前的临时捕捉覆盖
+    pub fn override_type(&self) -> Option<SnapType> {
+        self.override_type
     }
 
     /// 获取配置
@@ -257,8 +479,9 @@ impl SnapEngine {
         &self.config
     }
 
-    /// 获取配置（可变）
+    /// 获取配置（可变），配置可能被修改，捕捉结果缓存随之清空
     pub fn config_mut(&mut self) -> &mut SnapConfig {
+        self.invalidate_cache();
         &mut self.config
     }
 
@@ -269,12 +492,37 @@ impl SnapEngine {
     /// - `entities`: 要搜索的实体列表
     /// - `zoom`: 当前缩放级别（用于计算屏幕距离）
     /// - `reference_point`: 参考点（用于垂足、切点等计算）
+    ///
+    /// 设置了临时覆盖时只搜索覆盖的捕捉类型。
     pub fn find_snap_point(
         &mut self,
         mouse_world: Point2,
         entities: &[&Entity],
         zoom: f64,
         reference_point: Option<Point2>,
+    ) -> Option<SnapPoint> {
+        let Some(only) = self.override_type else {
+            return self.find_snap_point_with_config(mouse_world, entities, zoom, reference_point);
+        };
+
+        // 临时替换启用的捕捉类型，搜索完成后恢复
+        let saved = self.config.enabled_types;
+        let mut mask = SnapMask::NONE;
+        mask.set(only, true);
+        self.config.enabled_types = mask;
+        let result = self.find_snap_point_with_config(mouse_world, entities, zoom, reference_point);
+        self.config.enabled_types = saved;
+
+        result.filter(|p| p.snap_type == only)
+    }
+
+    /// 按配置中启用的捕捉类型寻找最佳捕捉点
+    fn find_snap_point_with_config(
+        &mut self,
+        mouse_world: Point2,
+        entities: &[&Entity],
+        zoom: f64,
+        reference_point: Option<Point2>,
     ) -> Option<SnapPoint> {
         self.candidates.clear();
 
@@ -302,11 +550,22 @@ impl SnapEngine {
         if self.config.enabled_types.is_enabled(SnapType::Intersection) {
             self.collect_intersection_points(entities, mouse_world, world_tolerance);
         }
+        if self.config.enabled_types.is_enabled(SnapType::ApparentIntersection) {
+            self.collect_apparent_intersection_points(entities, mouse_world, world_tolerance);
+        }
 
-        // 4. 找到最近的捕捉点
+        // 真实交点在容差内时优先于外观交点
+        let has_real_intersection = self
+            .candidates
+            .iter()
+            .any(|p| p.snap_type == SnapType::Intersection && p.distance <= world_tolerance);
+
+        // 4. 找到最近的捕捉点（只考虑已启用的类型）
+        let enabled = self.config.enabled_types;
         self.candidates
             .iter()
-            .filter(|p| p.distance <= world_tolerance)
+            .filter(|p| p.distance <= world_tolerance && enabled.is_enabled(p.snap_type))
+            .filter(|p| !(has_real_intersection && p.snap_type == SnapType::ApparentIntersection))
             .min_by(|a, b| a.distance.partial_cmp(&b.distance).unwrap_or(std::cmp::Ordering::Equal))
             .cloned()
     }
@@ -347,20 +606,11 @@ impl SnapEngine {
             }
             Geometry::Text(text) => {
                 // 文本只捕捉插入点
-                if self.config.enabled_types.is_enabled(SnapType::Endpoint) {
-                    let dist = (text.position - mouse).norm();
-                    if dist <= tolerance {
-                        self.candidates.push(SnapPoint::new(
-                            text.position,
-                            SnapType::Endpoint,
-                            Some(entity.id),
-                            dist,
-                        ));
-                    }
-                }
+                self.collect_insertion_point(text.position, entity.id, mouse, tolerance);
             }
             Geometry::Dimension(dim) => {
-                // 标注捕捉定义点
+                // 标注文字位置为插入点，另捕捉定义点
+                self.collect_insertion_point(dim.get_text_position(), entity.id, mouse, tolerance);
                 if self.config.enabled_types.is_enabled(SnapType::Endpoint) {
                     for &pt in &[dim.definition_point1, dim.definition_point2] {
                         let dist = (pt - mouse).norm();
@@ -386,10 +636,26 @@ impl SnapEngine {
             }
             Geometry::Leader(leader) => {
                 self.collect_leader_snap_points(leader, entity.id, mouse, tolerance);
+                if leader.text.is_some() {
+                    if let Some(position) = leader.text_position() {
+                        self.collect_insertion_point(position, entity.id, mouse, tolerance);
+                    }
+                }
             }
         }
     }
 
+    /// 文字、标注、引线的插入点
+    fn collect_insertion_point(&mut self, point: Point2, entity_id: EntityId, mouse: Point2, tolerance: f64) {
+        if !self.config.enabled_types.is_enabled(SnapType::Insertion) {
+            return;
+        }
+        let dist = (point - mouse).norm();
+        if dist <= tolerance {
+            self.candidates.push(SnapPoint::new(point, SnapType::Insertion, Some(entity_id), dist));
+        }
+    }
+
     /// 线段的捕捉点
     fn collect_line_snap_points(
         &mut self,
@@ -470,6 +736,30 @@ impl SnapEngine {
         }
     }
 
+    /// 加入圆心捕捉候选
+    ///
+    /// 与 AutoCAD 一致，光标靠近圆心或悬停在圆周上（`rim` 为圆周上离光标最近的点）
+    /// 都会提示圆心。经由圆周触发时以容差作为排序距离，
+    /// 使端点、象限点等就在光标处的特征点优先。
+    fn push_center_candidate(
+        &mut self,
+        center: Point2,
+        rim: Point2,
+        entity_id: EntityId,
+        mouse: Point2,
+        tolerance: f64,
+    ) {
+        let dist = (center - mouse).norm();
+        let rank = if dist <= tolerance {
+            dist
+        } else if (rim - mouse).norm() <= tolerance {
+            tolerance
+        } else {
+            return;
+        };
+        self.candidates.push(SnapPoint::new(center, SnapType::Center, Some(entity_id), rank));
+    }
+
     /// 圆的捕捉点
     fn collect_circle_snap_points(
         &mut self,
@@ -479,19 +769,12 @@ impl SnapEngine {
         tolerance: f64,
         reference_point: Option<Point2>,
     ) {
-        let enabled = &self.config.enabled_types;
+        let enabled = self.config.enabled_types;
 
-        // 圆心
+        // 圆心（靠近圆心或圆周时）
         if enabled.is_enabled(SnapType::Center) {
-            let dist = (circle.center - mouse).norm();
-            if dist <= tolerance {
-                self.candidates.push(SnapPoint::new(
-                    circle.center,
-                    SnapType::Center,
-                    Some(entity_id),
-                    dist,
-                ));
-            }
+            let rim = circle.nearest_point(&mouse);
+            self.push_center_candidate(circle.center, rim, entity_id, mouse, tolerance);
         }
 
         // 象限点
@@ -530,8 +813,7 @@ impl SnapEngine {
 
         // 最近点（圆上）
         if enabled.is_enabled(SnapType::Nearest) {
-            let dir = (mouse - circle.center).normalize();
-            let nearest = circle.center + dir * circle.radius;
+            let nearest = circle.nearest_point(&mouse);
             let dist = (nearest - mouse).norm();
             if dist <= tolerance {
                 self.candidates.push(SnapPoint::new(
@@ -553,7 +835,7 @@ impl SnapEngine {
         tolerance: f64,
         _reference_point: Option<Point2>,
     ) {
-        let enabled = &self.config.enabled_types;
+        let enabled = self.config.enabled_types;
 
         // 端点
         if enabled.is_enabled(SnapType::Endpoint) {
@@ -580,16 +862,29 @@ impl SnapEngine {
             }
         }
 
-        // 圆心
+        // 圆心（靠近圆心或圆弧时）
         if enabled.is_enabled(SnapType::Center) {
-            let dist = (arc.center - mouse).norm();
-            if dist <= tolerance {
-                self.candidates.push(SnapPoint::new(
-                    arc.center,
-                    SnapType::Center,
-                    Some(entity_id),
-                    dist,
-                ));
+            let rim = arc.nearest_point(&mouse);
+            self.push_center_candidate(arc.center, rim, entity_id, mouse, tolerance);
+        }
+
+        // 象限点（只取扫掠范围内的）
+        if enabled.is_enabled(SnapType::Quadrant) {
+            let quadrant_angles = [0.0, std::f64::consts::FRAC_PI_2, std::f64::consts::PI, 3.0 * std::f64::consts::FRAC_PI_2];
+            for angle in quadrant_angles {
+                if !arc.contains_angle(angle) {
+                    continue;
+                }
+                let point = arc.center + Vector2::new(angle.cos(), angle.sin()) * arc.radius;
+                let dist = (point - mouse).norm();
+                if dist <= tolerance {
+                    self.candidates.push(SnapPoint::new(
+                        point,
+                        SnapType::Quadrant,
+                        Some(entity_id),
+                        dist,
+                    ));
+                }
             }
         }
 
@@ -610,6 +905,20 @@ impl SnapEngine {
                 ));
             }
         }
+
+        // 最近点（弧上）
+        if enabled.is_enabled(SnapType::Nearest) {
+            let nearest = arc.nearest_point(&mouse);
+            let dist = (nearest - mouse).norm();
+            if dist <= tolerance {
+                self.candidates.push(SnapPoint::new(
+                    nearest,
+                    SnapType::Nearest,
+                    Some(entity_id),
+                    dist,
+                ));
+            }
+        }
     }
 
     /// 多段线的捕捉点
@@ -638,6 +947,20 @@ impl SnapEngine {
             }
         }
 
+        // 闭合多段线的形心
+        if enabled.is_enabled(SnapType::Center) && polyline.closed {
+            let centroid = polyline.centroid();
+            let dist = (centroid - mouse).norm();
+            if dist <= tolerance {
+                self.candidates.push(SnapPoint::new(
+                    centroid,
+                    SnapType::Center,
+                    Some(entity_id),
+                    dist,
+                ));
+            }
+        }
+
         // 线段中点
         if enabled.is_enabled(SnapType::Midpoint) {
             for i in 0..polyline.segment_count() {
@@ -663,43 +986,46 @@ impl SnapEngine {
             }
         }
 
-        // 最近点和垂足需要遍历所有线段
-        if enabled.is_enabled(SnapType::Nearest) || enabled.is_enabled(SnapType::Perpendicular) {
+        // 最近点（直线段取垂足，弧线段取径向投影）
+        if enabled.is_enabled(SnapType::Nearest) {
+            for segment in polyline.explode() {
+                let nearest = match &segment {
+                    Geometry::Line(line) => line.nearest_point(&mouse),
+                    Geometry::Arc(arc) => arc.nearest_point(&mouse),
+                    _ => continue,
+                };
+                let dist = (nearest - mouse).norm();
+                if dist <= tolerance {
+                    self.candidates.push(SnapPoint::new(
+                        nearest,
+                        SnapType::Nearest,
+                        Some(entity_id),
+                        dist,
+                    ));
+                }
+            }
+        }
+
+        // 垂足需要遍历所有直线段
+        if let (true, Some(ref_point)) = (enabled.is_enabled(SnapType::Perpendicular), reference_point) {
             for i in 0..polyline.segment_count() {
                 let v1 = &polyline.vertices[i];
                 let v2 = &polyline.vertices[(i + 1) % polyline.vertices.len()];
 
                 // 只处理直线段
-                if v1.bulge.abs() < EPSILON {
-                    let line = Line::new(v1.point, v2.point);
-
-                    if enabled.is_enabled(SnapType::Nearest) {
-                        let nearest = self.nearest_point_on_line(&line, mouse);
-                        let dist = (nearest - mouse).norm();
-                        if dist <= tolerance {
-                            self.candidates.push(SnapPoint::new(
-                                nearest,
-                                SnapType::Nearest,
-                                Some(entity_id),
-                                dist,
-                            ));
-                        }
-                    }
-
-                    if enabled.is_enabled(SnapType::Perpendicular) {
-                        if let Some(ref_point) = reference_point {
-                            if let Some(perp) = self.perpendicular_to_line(&line, ref_point) {
-                                let dist = (perp - mouse).norm();
-                                if dist <= tolerance {
-                                    self.candidates.push(SnapPoint::new(
-                                        perp,
-                                        SnapType::Perpendicular,
-                                        Some(entity_id),
-                                        dist,
-                                    ));
-                                }
-                            }
-                        }
+                if v1.bulge.abs() >= EPSILON {
+                    continue;
+                }
+                let line = Line::new(v1.point, v2.point);
+                if let Some(perp) = self.perpendicular_to_line(&line, ref_point) {
+                    let dist = (perp - mouse).norm();
+                    if dist <= tolerance {
+                        self.candidates.push(SnapPoint::new(
+                            perp,
+                            SnapType::Perpendicular,
+                            Some(entity_id),
+                            dist,
+                        ));
                     }
                 }
             }
@@ -754,14 +1080,14 @@ impl SnapEngine {
             }
         }
 
-        // 象限点（长轴和短轴的端点）
+        // 象限点（椭圆自身坐标系中长轴和短轴的端点，椭圆弧只取范围内的）
         if enabled.is_enabled(SnapType::Quadrant) {
-            let major_end1 = ellipse.center + ellipse.major_axis;
-            let major_end2 = ellipse.center - ellipse.major_axis;
-            let minor_end1 = ellipse.center + ellipse.minor_axis();
-            let minor_end2 = ellipse.center - ellipse.minor_axis();
-
-            for point in [major_end1, major_end2, minor_end1, minor_end2] {
+            let quadrant_params = [0.0, std::f64::consts::FRAC_PI_2, std::f64::consts::PI, 3.0 * std::f64::consts::FRAC_PI_2];
+            for t in quadrant_params {
+                if !ellipse.contains_param(t) {
+                    continue;
+                }
+                let point = ellipse.point_at_param(t);
                 let dist = (point - mouse).norm();
                 if dist <= tolerance {
                     self.candidates.push(SnapPoint::new(
@@ -776,25 +1102,14 @@ impl SnapEngine {
 
         // 最近点
         if enabled.is_enabled(SnapType::Nearest) {
-            // 使用采样点找最近点
-            let samples = ellipse.sample_points(64);
-            let mut min_dist = f64::MAX;
-            let mut nearest = mouse;
-            
-            for pt in samples {
-                let dist = (pt - mouse).norm();
-                if dist < min_dist {
-                    min_dist = dist;
-                    nearest = pt;
-                }
-            }
-            
-            if min_dist <= tolerance {
+            let nearest = ellipse.nearest_point(&mouse);
+            let dist = (nearest - mouse).norm();
+            if dist <= tolerance {
                 self.candidates.push(SnapPoint::new(
                     nearest,
                     SnapType::Nearest,
                     Some(entity_id),
-                    min_dist,
+                    dist,
                 ));
             }
         }
@@ -810,6 +1125,21 @@ impl SnapEngine {
     ) {
         let enabled = &self.config.enabled_types;
 
+        // 曲线自身的起点和终点
+        if enabled.is_enabled(SnapType::Endpoint) && !spline.closed && !spline.control_points.is_empty() {
+            for pt in [spline.start_point(), spline.end_point()] {
+                let dist = (pt - mouse).norm();
+                if dist <= tolerance {
+                    self.candidates.push(SnapPoint::new(
+                        pt,
+                        SnapType::Endpoint,
+                        Some(entity_id),
+                        dist,
+                    ));
+                }
+            }
+        }
+
         // 控制点（作为端点）
         if enabled.is_enabled(SnapType::Endpoint) {
             for &pt in &spline.control_points {
@@ -842,24 +1172,14 @@ impl SnapEngine {
 
         // 最近点
         if enabled.is_enabled(SnapType::Nearest) {
-            let samples = spline.sample_points(64);
-            let mut min_dist = f64::MAX;
-            let mut nearest = mouse;
-            
-            for pt in samples {
-                let dist = (pt - mouse).norm();
-                if dist < min_dist {
-                    min_dist = dist;
-                    nearest = pt;
-                }
-            }
-            
-            if min_dist <= tolerance {
+            let nearest = spline.nearest_point(&mouse);
+            let dist = (nearest - mouse).norm();
+            if dist <= tolerance {
                 self.candidates.push(SnapPoint::new(
                     nearest,
                     SnapType::Nearest,
                     Some(entity_id),
-                    min_dist,
+                    dist,
                 ));
             }
         }
@@ -937,14 +1257,62 @@ impl SnapEngine {
         }
     }
 
+    /// 收集外观交点：两条线段本身不相交，但延长后的交点靠近光标
+    fn collect_apparent_intersection_points(
+        &mut self,
+        entities: &[&Entity],
+        mouse: Point2,
+        tolerance: f64,
+    ) {
+        for i in 0..entities.len() {
+            for j in (i + 1)..entities.len() {
+                let (Geometry::Line(l1), Geometry::Line(l2)) = (&entities[i].geometry, &entities[j].geometry) else {
+                    continue;
+                };
+                // 真实交点由交点捕捉处理
+                if self.line_line_intersection(l1, l2).is_some() {
+                    continue;
+                }
+                if let Some(point) = Self::extended_line_intersection(l1, l2) {
+                    let dist = (point - mouse).norm();
+                    if dist <= tolerance {
+                        self.candidates.push(SnapPoint::new(
+                            point,
+                            SnapType::ApparentIntersection,
+                            None,
+                            dist,
+                        ));
+                    }
+                }
+            }
+        }
+    }
+
+    /// 两条线段所在直线的交点（不限制在线段范围内），平行时返回 None
+    fn extended_line_intersection(l1: &Line, l2: &Line) -> Option<Point2> {
+        let d1 = l1.end - l1.start;
+        let d2 = l2.end - l2.start;
+
+        let cross = d1.x * d2.y - d1.y * d2.x;
+        if cross.abs() < EPSILON {
+            return None;
+        }
+
+        let d = l2.start - l1.start;
+        let t1 = (d.x * d2.y - d.y * d2.x) / cross;
+        Some(l1.start + d1 * t1)
+    }
+
     /// 网格捕捉
     fn snap_to_grid(&self, mouse: Point2, tolerance: f64) -> Option<SnapPoint> {
-        let spacing = self.config.grid_spacing;
-        
-        let grid_x = (mouse.x / spacing).round() * spacing;
-        let grid_y = (mouse.y / spacing).round() * spacing;
-        let grid_point = Point2::new(grid_x, grid_y);
-        
+        // 网格节点在 UCS 中计算，再映射回世界坐标
+        let ucs = &self.config.ucs;
+        let node = self
+            .config
+            .grid_mode
+            .nearest_node(ucs.to_ucs(mouse), self.config.grid_spacing);
+        let grid_point = ucs.to_world(node);
+
         let dist = (grid_point - mouse).norm();
         if dist <= tolerance {
             Some(SnapPoint::new(grid_point, SnapType::Grid, None, dist))
@@ -957,21 +1325,7 @@ impl SnapEngine {
 
     /// 计算点到线段的最近点
     fn nearest_point_on_line(&self, line: &Line, point: Point2) -> Point2 {
-        let v = line.end - line.start;
-        let w = point - line.start;
-
-        let c1 = w.dot(&v);
-        if c1 <= 0.0 {
-            return line.start;
-        }
-
-        let c2 = v.dot(&v);
-        if c2 <= c1 {
-            return line.end;
-        }
-
-        let b = c1 / c2;
-        line.start + v * b
+        line.nearest_point(&point)
     }
 
     /// 计算从参考点到线段的垂足
@@ -998,35 +1352,11 @@ impl SnapEngine {
 
     /// 计算从点到圆的切点
     fn tangent_points_to_circle(&self, circle: &Circle, point: Point2) -> Vec<Point2> {
-        let d = (point - circle.center).norm();
-        
-        // 点在圆内，没有切点
-        if d <= circle.radius {
-            return vec![];
-        }
-
-        // 切线长度
-        let _tangent_len = (d * d - circle.radius * circle.radius).sqrt();
-        
-        // 从圆心到点的方向
-        let dir = (point - circle.center) / d;
-        
-        // 切点角度
-        let angle = (circle.radius / d).asin();
-        
-        // 计算两个切点
-        let base_angle = dir.y.atan2(dir.x);
-        
-        vec![
-            Point2::new(
-                circle.center.x + circle.radius * (base_angle + angle).cos(),
-                circle.center.y + circle.radius * (base_angle + angle).sin(),
-            ),
-            Point2::new(
-                circle.center.x + circle.radius * (base_angle - angle).cos(),
-                circle.center.y + circle.radius * (base_angle - angle).sin(),
-            ),
-        ]
+        let from = Geometry::Point(crate::geometry::Point::new(point.x, point.y));
+        tangents(&from, &Geometry::Circle(circle.clone()))
+            .into_iter()
+            .map(|line| line.end)
+            .collect()
     }
 
     /// 计算两个几何体的交点
@@ -1208,7 +1538,9 @@ impl SnapEngine {
             return None;
         }
 
-        let current_angle = delta.y.atan2(delta.x);
+        // 极轴角度从 UCS 的 X 轴量起
+        let ucs_angle = self.config.ucs.angle;
+        let current_angle = delta.y.atan2(delta.x) - ucs_angle;
         
         // 检查每个极轴角度（考虑4个象限）
         for &polar_angle in &self.config.polar_angles {
@@ -1222,9 +1554,10 @@ impl SnapEngine {
                 
                 if diff <= self.config.polar_tolerance {
                     // 捕捉到这个角度
+                    let world_angle = normalized_check + ucs_angle;
                     let snapped_point = Point2::new(
-                        base.x + dist * normalized_check.cos(),
-                        base.y + dist * normalized_check.sin(),
+                        base.x + dist * world_angle.cos(),
+                        base.y + dist * world_angle.sin(),
                     );
                     let snap_dist = (snapped_point - coord).norm();
                     
@@ -1241,26 +1574,44 @@ impl SnapEngine {
         None
     }
 
-    /// 正交限制 - 限制为水平或垂直方向
+    /// 正交限制 - 限制为 UCS 的水平或垂直方向
     pub fn restrict_orthogonal(&self, coord: Point2, base: Point2) -> Point2 {
-        let dx = (coord.x - base.x).abs();
-        let dy = (coord.y - base.y).abs();
+        let (x_axis, y_axis) = (self.config.ucs.x_axis(), self.config.ucs.y_axis());
+        let delta = coord - base;
+        let dx = delta.dot(&x_axis);
+        let dy = delta.dot(&y_axis);
         
-        if dx > dy {
-            Point2::new(coord.x, base.y)
+        if dx.abs() > dy.abs() {
+            base + x_axis * dx
+        } else {
+            base + y_axis * dy
+        }
+    }
+
+    /// 按极轴或正交约束相对参考点的方向
+    ///
+    /// 与 AutoCAD 一致，极轴追踪与正交互斥：极轴开启时忽略正交，
+    /// 只在接近某个极轴角度时吸附，否则保持原点不变；极轴关闭时才应用正交。
+    pub fn constrain_direction(&self, coord: Point2, base: Point2, ortho: bool) -> Point2 {
+        if self.config.polar_tracking {
+            self.snap_to_polar(coord, base).map_or(coord, |snap| snap.point)
+        } else if ortho {
+            self.restrict_orthogonal(coord, base)
         } else {
-            Point2::new(base.x, coord.y)
+            coord
         }
     }
 
-    /// 水平限制
+    /// 水平限制（沿 UCS 的 X 轴）
     pub fn restrict_horizontal(&self, coord: Point2, base: Point2) -> Point2 {
-        Point2::new(coord.x, base.y)
+        let x_axis = self.config.ucs.x_axis();
+        base + x_axis * (coord - base).dot(&x_axis)
     }
 
-    /// 垂直限制
+    /// 垂直限制（沿 UCS 的 Y 轴）
     pub fn restrict_vertical(&self, coord: Point2, base: Point2) -> Point2 {
-        Point2::new(base.x, coord.y)
+        let y_axis = self.config.ucs.y_axis();
+        base + y_axis * (coord - base).dot(&y_axis)
     }
 
     /// 角度限制 - 限制到指定角度
@@ -1526,6 +1877,299 @@ mod tests {
         assert!((p.y - 5.0).abs() < EPSILON);
     }
 
+    #[test]
+    fn test_apparent_intersection_snap() {
+        // 两条线段不相接，延长线交于 (10, 0)
+        let horizontal = Entity::new(Geometry::Line(Line::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(9.0, 0.0),
+        )));
+        let vertical = Entity::new(Geometry::Line(Line::new(
+            Point2::new(10.0, 1.5),
+            Point2::new(10.0, 20.0),
+        )));
+        let entities = vec![&horizontal, &vertical];
+
+        let mut engine = SnapEngine::default();
+        let snap = engine
+            .find_snap_point(Point2::new(10.3, 0.2), &entities, 1.0, None)
+            .unwrap();
+        assert_eq!(snap.snap_type, SnapType::ApparentIntersection);
+        assert!((snap.point - Point2::new(10.0, 0.0)).norm() < 1e-9);
+
+        // 光标远离延长线交点时不捕捉
+        assert!(engine
+            .find_snap_point(Point2::new(40.0, 40.0), &entities, 1.0, None)
+            .is_none());
+    }
+
+    #[test]
+    fn test_real_intersection_beats_apparent() {
+        // 真实交点 (0, 0) 与外观交点 (2, 0) 都在容差内
+        let h = Entity::new(Geometry::Line(Line::new(Point2::new(-10.0, 0.0), Point2::new(1.0, 0.0))));
+        let v1 = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, -10.0), Point2::new(0.0, 10.0))));
+        let v2 = Entity::new(Geometry::Line(Line::new(Point2::new(2.0, 1.0), Point2::new(2.0, 10.0))));
+        let entities = vec![&h, &v1, &v2];
+
+        let mut engine = SnapEngine::default();
+        engine.config_mut().enabled_types = SnapMask::new(SnapMask::INTERSECTION | SnapMask::APPARENT_INTERSECTION);
+        // 光标更接近外观交点，但仍应选择真实交点
+        let snap = engine
+            .find_snap_point(Point2::new(1.6, 0.0), &entities, 1.0, None)
+            .unwrap();
+        assert_eq!(snap.snap_type, SnapType::Intersection);
+        assert!((snap.point - Point2::new(0.0, 0.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_snap_override_restricts_type() {
+        // 线段 (0,0)-(4,0)：光标靠近端点 (0,0)，中点 (2,0) 稍远但仍在容差内
+        let line = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(4.0, 0.0))));
+        let entities = vec![&line];
+        let mut engine = SnapEngine::default();
+        let mouse = Point2::new(0.5, 0.0);
+
+        let snap = engine.find_snap_point(mouse, &entities, 1.0, None).unwrap();
+        assert_eq!(snap.snap_type, SnapType::Endpoint);
+
+        engine.set_override(Some(SnapType::Midpoint));
+        let snap = engine.find_snap_point(mouse, &entities, 1.0, None).unwrap();
+        assert_eq!(snap.snap_type, SnapType::Midpoint);
+        assert!((snap.point - Point2::new(2.0, 0.0)).norm() < 1e-9);
+        // 覆盖不修改配置
+        assert!(engine.config().enabled_types.is_enabled(SnapType::Endpoint));
+
+        engine.set_override(None);
+        let snap = engine.find_snap_point(mouse, &entities, 1.0, None).unwrap();
+        assert_eq!(snap.snap_type, SnapType::Endpoint);
+    }
+
+    #[test]
+    fn test_tangent_snap_from_external_point() {
+        let center = Point2::new(0.0, 0.0);
+        let circle = Entity::new(Geometry::Circle(Circle::new(center, 5.0)));
+        let entities = vec![&circle];
+        let reference = Point2::new(10.0, 0.0);
+        let mut engine = SnapEngine::default();
+        engine.set_override(Some(SnapType::Tangent));
+
+        for mouse in [Point2::new(2.6, 4.2), Point2::new(2.6, -4.2)] {
+            let snap = engine.find_snap_point(mouse, &entities, 1.0, Some(reference)).unwrap();
+            assert_eq!(snap.snap_type, SnapType::Tangent);
+            // 切点处半径与切线垂直，且切点在圆上
+            let p = snap.point;
+            assert!((p - center).dot(&(p - reference)).abs() < 1e-9);
+            assert!(((p - center).norm() - 5.0).abs() < 1e-9);
+            assert!(p.y * mouse.y > 0.0);
+        }
+    }
+
+    #[test]
+    fn test_disabled_endpoint_falls_back_to_midpoint() {
+        // 与覆盖测试相同的布局：默认端点胜出
+        let line = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(4.0, 0.0))));
+        let entities = vec![&line];
+        let mut engine = SnapEngine::default();
+        let mouse = Point2::new(0.5, 0.0);
+        assert_eq!(engine.find_snap_point(mouse, &entities, 1.0, None).unwrap().snap_type, SnapType::Endpoint);
+
+        engine.config_mut().enabled_types.set(SnapType::Endpoint, false);
+        let snap = engine.find_snap_point(mouse, &entities, 1.0, None).unwrap();
+        assert_eq!(snap.snap_type, SnapType::Midpoint);
+        assert!((snap.point - Point2::new(2.0, 0.0)).norm() < 1e-9);
+
+        // 全部关闭时不返回任何捕捉点
+        engine.config_mut().enabled_types = SnapMask::NONE;
+        assert!(engine.find_snap_point(mouse, &entities, 1.0, None).is_none());
+    }
+
+    #[test]
+    fn test_snap_cache_reuses_and_invalidates() {
+        let line = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(4.0, 0.0))));
+        let circle = Entity::new(Geometry::Circle(Circle::new(Point2::new(0.1, 0.15), 1.0)));
+        let mut engine = SnapEngine::default();
+        let mouse = Point2::new(0.1, 0.1);
+
+        let first = engine.find_snap_point_cached(mouse, &[&line], 10.0, None, 1).unwrap();
+        let uncached = engine.find_snap_point(mouse, &[&line], 10.0, None).unwrap();
+        assert_eq!(first.snap_type, uncached.snap_type);
+        assert_eq!(first.point, uncached.point);
+
+        // 同一像素格、同一版本：复用缓存，即使实体列表已变化
+        let again = engine.find_snap_point_cached(Point2::new(0.11, 0.1), &[&line, &circle], 10.0, None, 1).unwrap();
+        assert_eq!(again.point, first.point);
+        assert_eq!(again.entity_id, Some(line.id));
+
+        // 添加实体后版本号变化，重新计算得到新实体的圆心
+        let updated = engine.find_snap_point_cached(Point2::new(0.11, 0.1), &[&line, &circle], 10.0, None, 2).unwrap();
+        assert_eq!(updated.snap_type, SnapType::Center);
+        assert_eq!(updated.entity_id, Some(circle.id));
+
+        // 修改配置也会清空缓存
+        engine.config_mut().enabled_types = SnapMask::NONE;
+        assert!(engine.find_snap_point_cached(Point2::new(0.11, 0.1), &[&line, &circle], 10.0, None, 2).is_none());
+    }
+
+    #[test]
+    fn test_mid_between_points() {
+        let mut m2p = MidBetweenPoints::new();
+        assert_eq!(m2p.pick(Point2::new(0.0, 0.0)), None);
+        assert_eq!(m2p.first_point(), Some(Point2::new(0.0, 0.0)));
+        assert_eq!(m2p.pick(Point2::new(10.0, 4.0)), Some(Point2::new(5.0, 2.0)));
+
+        // 产生中点后复位，重新从第一点开始
+        assert_eq!(m2p.first_point(), None);
+        assert_eq!(m2p.pick(Point2::new(-2.0, 6.0)), None);
+        assert_eq!(m2p.pick(Point2::new(2.0, 6.0)), Some(Point2::new(0.0, 6.0)));
+    }
+
+    /// 只启用 `mask` 中的捕捉类型时找到的捕捉点；世界容差 = 默认 10 像素 / `zoom`
+    fn snap_only(mask: u16, zoom: f64, entity: &Entity, mouse: Point2) -> Option<SnapPoint> {
+        let mut engine = SnapEngine::default();
+        engine.config_mut().enabled_types = SnapMask::new(mask);
+        engine.find_snap_point(mouse, &[entity], zoom, None)
+    }
+
+    #[test]
+    fn test_nearest_snap_exact_on_line_and_circle() {
+        // 线段：垂足 (3.7, 0)
+        let line = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0))));
+        let snap = snap_only(SnapMask::NEAREST, 1.0, &line, Point2::new(3.7, 2.3)).unwrap();
+        assert_eq!(snap.snap_type, SnapType::Nearest);
+        assert!((snap.point - Point2::new(3.7, 0.0)).norm() < 1e-12);
+
+        // 圆：沿圆心到光标方向投影到圆周
+        let circle = Entity::new(Geometry::Circle(Circle::new(Point2::new(1.0, 1.0), 5.0)));
+        let mouse = Point2::new(5.0, 5.0);
+        let expected = Point2::new(1.0, 1.0) + (mouse - Point2::new(1.0, 1.0)).normalize() * 5.0;
+        let snap = snap_only(SnapMask::NEAREST, 1.0, &circle, mouse).unwrap();
+        assert!((snap.point - expected).norm() < 1e-12);
+        assert!((snap.distance - ((mouse - Point2::new(1.0, 1.0)).norm() - 5.0)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_nearest_snap_ellipse_is_foot_point() {
+        let ellipse = Ellipse::from_radii(Point2::origin(), 10.0, 5.0);
+        let entity = Entity::new(Geometry::Ellipse(ellipse.clone()));
+        let mouse = Point2::new(7.0, 4.5);
+        let snap = snap_only(SnapMask::NEAREST, 1.0, &entity, mouse).unwrap();
+
+        // 最近点在椭圆上，且光标到该点的连线垂直于切线
+        let p = snap.point;
+        assert!(((p.x / 10.0).powi(2) + (p.y / 5.0).powi(2) - 1.0).abs() < 1e-9);
+        let t = (p.y / 5.0).atan2(p.x / 10.0);
+        let tangent = Vector2::new(-10.0 * t.sin(), 5.0 * t.cos());
+        assert!((mouse - p).dot(&tangent).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_endpoint_snap_spline_curve_end() {
+        let control_points = vec![
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 20.0),
+            Point2::new(20.0, -20.0),
+            Point2::new(30.0, 20.0),
+            Point2::new(40.0, 0.0),
+        ];
+        let spline = Spline::from_control_points(control_points, 3, false);
+        let end = spline.end_point();
+        let entity = Entity::new(Geometry::Spline(spline));
+
+        let snap = snap_only(SnapMask::ENDPOINT, 20.0, &entity, end + Vector2::new(0.2, 0.1)).unwrap();
+        assert_eq!(snap.snap_type, SnapType::Endpoint);
+        assert!((snap.point - end).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_endpoint_snap_covers_arcs_and_leaders() {
+        use std::f64::consts::FRAC_PI_2;
+
+        let arc = Arc::new(Point2::origin(), 10.0, 0.0, FRAC_PI_2);
+        let entity = Entity::new(Geometry::Arc(arc));
+        let snap = snap_only(SnapMask::ENDPOINT, 20.0, &entity, Point2::new(0.2, 10.1)).unwrap();
+        assert!((snap.point - Point2::new(0.0, 10.0)).norm() < 1e-9);
+
+        let ellipse = Ellipse::arc(Point2::origin(), Vector2::new(10.0, 0.0), 0.5, 0.0, FRAC_PI_2);
+        let end = ellipse.end_point();
+        let entity = Entity::new(Geometry::Ellipse(ellipse));
+        let snap = snap_only(SnapMask::ENDPOINT, 20.0, &entity, end + Vector2::new(0.1, 0.1)).unwrap();
+        assert!((snap.point - end).norm() < 1e-9);
+
+        let leader = Leader::new(vec![Point2::new(0.0, 0.0), Point2::new(5.0, 5.0), Point2::new(15.0, 5.0)]);
+        let entity = Entity::new(Geometry::Leader(leader));
+        let snap = snap_only(SnapMask::ENDPOINT, 20.0, &entity, Point2::new(14.8, 5.1)).unwrap();
+        assert!((snap.point - Point2::new(15.0, 5.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_insertion_snap_for_text_leader_and_dimension() {
+        use crate::geometry::{Dimension, Text};
+
+        let text = Entity::new(Geometry::Text(Text::new(Point2::new(10.0, 5.0), "Hello", 2.5)));
+        let mut engine = SnapEngine::default();
+        let snap = engine.find_snap_point(Point2::new(10.2, 5.1), &[&text], 20.0, None).unwrap();
+        assert_eq!(snap.snap_type, SnapType::Insertion);
+        assert_eq!(snap.point, Point2::new(10.0, 5.0));
+        assert_eq!(snap.entity_id, Some(text.id));
+
+        engine.config_mut().enabled_types.set(SnapType::Insertion, false);
+        assert!(engine.find_snap_point(Point2::new(10.2, 5.1), &[&text], 20.0, None).is_none());
+
+        // 只启用插入点：带文字的引线捕捉文字位置，标注捕捉文字位置
+        engine.config_mut().enabled_types = SnapMask::new(SnapMask::INSERTION);
+        let leader = Leader::new(vec![Point2::new(0.0, 0.0), Point2::new(5.0, 5.0)]).with_text("A", 2.5);
+        let leader = Entity::new(Geometry::Leader(leader));
+        let snap = engine.find_snap_point(Point2::new(5.1, 5.0), &[&leader], 20.0, None).unwrap();
+        assert_eq!(snap.snap_type, SnapType::Insertion);
+        assert_eq!(snap.point, Point2::new(5.0, 5.0));
+
+        let dimension = Dimension::new(Point2::new(0.0, 0.0), Point2::new(20.0, 0.0), Point2::new(10.0, 8.0));
+        let text_position = dimension.get_text_position();
+        let dimension = Entity::new(Geometry::Dimension(dimension));
+        let snap = engine.find_snap_point(text_position + Vector2::new(0.1, 0.1), &[&dimension], 20.0, None).unwrap();
+        assert_eq!(snap.snap_type, SnapType::Insertion);
+        assert!((snap.point - text_position).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_rotated_ellipse_quadrants() {
+        use std::f64::consts::FRAC_PI_4;
+
+        // 长轴旋转 45°，长半轴 10，短半轴 5
+        let major = Vector2::new(FRAC_PI_4.cos(), FRAC_PI_4.sin()) * 10.0;
+        let ellipse = Entity::new(Geometry::Ellipse(Ellipse::new(Point2::origin(), major, 0.5)));
+        let minor = Vector2::new(-FRAC_PI_4.sin(), FRAC_PI_4.cos()) * 5.0;
+
+        for expected in [Point2::origin() + major, Point2::origin() - major, Point2::origin() + minor, Point2::origin() - minor] {
+            let snap = snap_only(SnapMask::QUADRANT, 10.0, &ellipse, expected + Vector2::new(0.3, 0.0)).unwrap();
+            assert_eq!(snap.snap_type, SnapType::Quadrant);
+            assert!((snap.point - expected).norm() < 1e-9);
+        }
+
+        // 世界坐标轴方向上不是象限点
+        assert!(snap_only(SnapMask::QUADRANT, 10.0, &ellipse, Point2::new(7.5, 0.0)).is_none());
+    }
+
+    #[test]
+    fn test_arc_quadrants_within_sweep() {
+        use std::f64::consts::PI;
+
+        // 从 10° 到 170° 的上半圆弧：只有 90° 象限点在范围内
+        let arc = Entity::new(Geometry::Arc(Arc::new(Point2::origin(), 5.0, PI / 18.0, 17.0 * PI / 18.0)));
+
+        let snap = snap_only(SnapMask::QUADRANT, 10.0, &arc, Point2::new(0.2, 5.1)).unwrap();
+        assert!((snap.point - Point2::new(0.0, 5.0)).norm() < 1e-9);
+
+        assert!(snap_only(SnapMask::QUADRANT, 10.0, &arc, Point2::new(5.0, 0.0)).is_none());
+        assert!(snap_only(SnapMask::QUADRANT, 10.0, &arc, Point2::new(-5.0, 0.0)).is_none());
+        assert!(snap_only(SnapMask::QUADRANT, 10.0, &arc, Point2::new(0.0, -5.0)).is_none());
+
+        // 椭圆弧同样只暴露范围内的象限点
+        let half = Entity::new(Geometry::Ellipse(Ellipse::arc(Point2::origin(), Vector2::new(8.0, 0.0), 0.5, 0.0, PI)));
+        assert!(snap_only(SnapMask::QUADRANT, 10.0, &half, Point2::new(0.0, 4.0)).is_some());
+        assert!(snap_only(SnapMask::QUADRANT, 10.0, &half, Point2::new(0.0, -4.0)).is_none());
+    }
+
     #[test]
     fn test_nearest_point_on_line() {
         let engine = SnapEngine::default();
@@ -1540,5 +2184,156 @@ mod tests {
         let nearest = engine.nearest_point_on_line(&line, Point2::new(-5.0, 0.0));
         assert!((nearest.x).abs() < EPSILON); // 应该返回起点
     }
-}
 
+    #[test]
+    fn test_center_snap_from_rim_hover() {
+        let mut engine = SnapEngine::new(SnapConfig::default());
+        let circle = Entity::new(Geometry::Circle(Circle::new(Point2::new(100.0, 100.0), 50.0)));
+
+        // 悬停在圆周上（远离圆心和象限点）时捕捉到圆心
+        let rim = Point2::new(100.0, 100.0) + Vector2::new(0.6, 0.8) * 52.0;
+        let snap = engine.find_snap_point(rim, &[&circle], 1.0, None).unwrap();
+        assert_eq!(snap.snap_type, SnapType::Center);
+        assert_eq!(snap.point, Point2::new(100.0, 100.0));
+        assert_eq!(snap.entity_id, Some(circle.id));
+
+        // 远离圆周和圆心时没有捕捉
+        let inside = Point2::new(100.0, 100.0) + Vector2::new(0.6, 0.8) * 25.0;
+        assert!(engine.find_snap_point(inside, &[&circle], 1.0, None).is_none());
+
+        // 圆弧上同样适用，但端点就在光标处时端点优先
+        let arc = Entity::new(Geometry::Arc(Arc::new(Point2::origin(), 100.0, 0.0, std::f64::consts::FRAC_PI_2)));
+        let on_arc = Point2::new(100.0 * 20f64.to_radians().cos(), 100.0 * 20f64.to_radians().sin());
+        let snap = engine.find_snap_point(on_arc, &[&arc], 1.0, None).unwrap();
+        assert_eq!(snap.snap_type, SnapType::Center);
+        assert_eq!(snap.point, Point2::origin());
+        let snap = engine.find_snap_point(Point2::new(101.0, 0.5), &[&arc], 1.0, None).unwrap();
+        assert_eq!(snap.snap_type, SnapType::Endpoint);
+    }
+
+    #[test]
+    fn test_closed_polyline_centroid_snap() {
+        // L 形：形心与包围盒中心 (5, 5) 不同
+        let polyline = Polyline::from_points(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(10.0, 0.0),
+                Point2::new(10.0, 2.0),
+                Point2::new(2.0, 2.0),
+                Point2::new(2.0, 10.0),
+                Point2::new(0.0, 10.0),
+            ],
+            true,
+        );
+        let expected = 116.0 / 36.0;
+        let centroid = polyline.centroid();
+        assert!((centroid.x - expected).abs() < 1e-9);
+        assert!((centroid.y - expected).abs() < 1e-9);
+
+        let bbox_center = polyline.bounding_box().center();
+        assert!((centroid - bbox_center).norm() > 1.0);
+
+        let mut config = SnapConfig::default();
+        config.enabled_types.set(SnapType::Center, true);
+        let mut engine = SnapEngine::new(config);
+        let entity = Entity::new(Geometry::Polyline(polyline));
+        let snap = engine
+            .find_snap_point(Point2::new(3.3, 3.3), &[&entity], 1.0, None)
+            .unwrap();
+        assert_eq!(snap.snap_type, SnapType::Center);
+        assert!((snap.point.x - expected).abs() < 1e-9);
+
+        // 退化（共线）时回退到包围盒中心
+        let degenerate = Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(4.0, 0.0), Point2::new(8.0, 0.0)],
+            true,
+        );
+        assert!((degenerate.centroid() - Point2::new(4.0, 0.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_iso_grid_snap() {
+        let iso = GridMode::Isometric;
+        let (a, b) = iso.basis(10.0);
+        let node = Point2::origin() + a * 2.0 + b;
+
+        // 偏离节点少许的光标回到该节点
+        let snapped = iso.nearest_node(node + Vector2::new(1.2, -0.8), 10.0);
+        assert!((snapped - node).norm() < 1e-9);
+
+        // 正交网格上 (17.32, 20) 会落到 (20, 20)，等轴测网格上落到 (17.32, 20)
+        let p = Point2::new(17.32, 20.0);
+        let rect = GridMode::Rectangular.nearest_node(p, 10.0);
+        assert!((rect - Point2::new(20.0, 20.0)).norm() < 1e-9);
+        let snapped = iso.nearest_node(p, 10.0);
+        assert!((snapped - node).norm() < 1e-9);
+
+        // 引擎使用配置的网格模式
+        let mut config = SnapConfig::default();
+        config.enabled_types = SnapMask::new(0);
+        config.enabled_types.set(SnapType::Grid, true);
+        config.grid_mode = GridMode::Isometric;
+        let mut engine = SnapEngine::new(config);
+        let snap = engine.find_snap_point(p, &[], 1.0, None).unwrap();
+        assert_eq!(snap.snap_type, SnapType::Grid);
+        assert!((snap.point - node).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_rotated_grid_snap() {
+        let rotated = GridMode::Rotated(std::f64::consts::FRAC_PI_4);
+        let snapped = rotated.nearest_node(Point2::new(0.5, 6.8), 5.0);
+        // 旋转 45° 后 (1, 1) 节点位于 (0, 5√2)
+        assert!((snapped - Point2::new(0.0, 5.0 * 2f64.sqrt())).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_polar_takes_precedence_over_ortho() {
+        let mut engine = SnapEngine::default();
+        let base = Point2::origin();
+        // 约 29° 方向，正交会压到水平线，极轴会吸附到 30°
+        let near_30 = Point2::new(10.0, 5.5);
+
+        assert_eq!(engine.constrain_direction(near_30, base, true), Point2::new(10.0, 0.0));
+        assert_eq!(engine.constrain_direction(near_30, base, false), near_30);
+
+        // 两者同时开启：只按极轴约束
+        engine.config_mut().polar_tracking = true;
+        let snapped = engine.constrain_direction(near_30, base, true);
+        let angle = snapped.y.atan2(snapped.x);
+        assert!((angle - std::f64::consts::FRAC_PI_6).abs() < 1e-9);
+        assert!((snapped.coords.norm() - near_30.coords.norm()).abs() < 1e-9);
+
+        // 不在任何极轴角度附近时保持原点，正交同样被忽略
+        let off_axis = Point2::new(10.0 * 8f64.to_radians().cos(), 10.0 * 8f64.to_radians().sin());
+        assert_eq!(engine.constrain_direction(off_axis, base, true), off_axis);
+    }
+
+    #[test]
+    fn test_grid_and_ortho_follow_rotated_ucs() {
+        let ucs = Ucs::new(Point2::new(3.0, 1.0), std::f64::consts::FRAC_PI_6);
+        let mut config = SnapConfig::default();
+        config.enabled_types = SnapMask::NONE;
+        config.enabled_types.set(SnapType::Grid, true);
+        config.ucs = ucs;
+        let mut engine = SnapEngine::new(config);
+
+        // 网格节点 (2, 1) 位于 UCS 中，光标稍有偏离
+        let node = ucs.to_world(Point2::new(20.0, 10.0));
+        let snap = engine.find_snap_point(node + Vector2::new(0.3, -0.2), &[], 1.0, None).unwrap();
+        assert!((snap.point - node).norm() < 1e-9);
+
+        // 正交沿 UCS 的 X 轴
+        let base = ucs.to_world(Point2::new(5.0, 5.0));
+        let target = ucs.to_world(Point2::new(15.0, 6.0));
+        let constrained = engine.constrain_direction(target, base, true);
+        assert!((constrained - ucs.to_world(Point2::new(15.0, 5.0))).norm() < 1e-9);
+
+        // 极轴 0° 也从 UCS 的 X 轴量起
+        engine.config_mut().polar_tracking = true;
+        let near_axis = ucs.to_world(Point2::new(15.0, 5.5));
+        let constrained = engine.constrain_direction(near_axis, base, false);
+        let local = ucs.to_ucs(constrained) - Point2::new(5.0, 5.0);
+        assert!(local.y.abs() < 1e-9 && local.x > 0.0);
+    }
+}
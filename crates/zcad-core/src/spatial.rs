@@ -68,6 +68,13 @@ impl SpatialIndex {
         cells
     }
 
+    /// 包围盒覆盖的网格单元数量（用浮点数避免溢出）
+    fn cell_count(&self, bbox: &BoundingBox2) -> f64 {
+        let (min_gx, min_gy) = self.to_grid_coord(bbox.min.x, bbox.min.y);
+        let (max_gx, max_gy) = self.to_grid_coord(bbox.max.x, bbox.max.y);
+        (max_gx as f64 - min_gx as f64 + 1.0) * (max_gy as f64 - min_gy as f64 + 1.0)
+    }
+
     /// 插入实体
     pub fn insert(&mut self, id: EntityId, bbox: BoundingBox2) {
         // 先移除旧的（如果存在）
@@ -101,8 +108,24 @@ impl SpatialIndex {
         self.insert(id, new_bbox);
     }
 
+    /// 实体的缓存包围盒
+    pub fn bbox(&self, id: &EntityId) -> Option<BoundingBox2> {
+        self.bboxes.get(id).copied()
+    }
+
     /// 范围查询：查找与指定矩形相交的所有实体
     pub fn query_rect(&self, rect: &BoundingBox2) -> Vec<EntityId> {
+        // 查询矩形覆盖的网格单元多于已占用的单元时（如视图缩得很小），
+        // 直接遍历包围盒缓存，避免逐个枚举大量空单元
+        if self.cell_count(rect) > self.grid.len() as f64 {
+            return self
+                .bboxes
+                .iter()
+                .filter(|(_, bbox)| bbox.intersects(rect))
+                .map(|(id, _)| *id)
+                .collect();
+        }
+
         let mut result = Vec::new();
         let mut seen = std::collections::HashSet::new();
 
@@ -239,6 +262,12 @@ mod tests {
         assert!(result.contains(&id1));
         assert!(result.contains(&id2));
         assert!(!result.contains(&id3));
+
+        // 远大于网格的查询矩形（缩得很小的视图）走包围盒遍历，结果一致
+        let huge = BoundingBox2::new(Point2::new(-1.0e9, -1.0e9), Point2::new(1.0e9, 50.0));
+        let result = index.query_rect(&huge);
+        assert_eq!(result.len(), 2);
+        assert!(!result.contains(&id3));
     }
 }
 
@@ -0,0 +1,206 @@
+//! 文字样式（Text Style）
+//!
+//! 对应 DXF 的 STYLE 表，定义文字的字体、默认高度、宽度因子和倾斜角。
+//! 文本通过 `Text::style` 按名称引用样式，未显式指定的属性从样式中解析。
+
+use crate::geometry::Text;
+use serde::{Deserialize, Serialize};
+use std::sync::OnceLock;
+
+/// 样式和文本都未指定高度时使用的默认文字高度
+pub const DEFAULT_TEXT_HEIGHT: f64 = 2.5;
+
+/// 文字样式
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TextStyle {
+    /// 样式名称
+    pub name: String,
+    /// 字体文件或字体名称
+    pub font: String,
+    /// 默认文字高度（0 表示不固定，由文本自身或默认值决定）
+    pub height: f64,
+    /// 宽度因子
+    pub width_factor: f64,
+    /// 倾斜角度（弧度）
+    pub oblique_angle: f64,
+}
+
+impl Default for TextStyle {
+    fn default() -> Self {
+        Self {
+            name: "Standard".to_string(),
+            font: "txt".to_string(),
+            height: 0.0,
+            width_factor: 1.0,
+            oblique_angle: 0.0,
+        }
+    }
+}
+
+impl TextStyle {
+    /// 创建新的文字样式
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// 设置字体
+    pub fn with_font(mut self, font: impl Into<String>) -> Self {
+        self.font = font.into();
+        self
+    }
+
+    /// 设置默认高度
+    pub fn with_height(mut self, height: f64) -> Self {
+        self.height = height;
+        self
+    }
+}
+
+/// 文字样式管理器
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TextStyleManager {
+    /// 所有文字样式
+    styles: Vec<TextStyle>,
+    /// 当前活动样式索引
+    current_style_index: usize,
+}
+
+impl Default for TextStyleManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextStyleManager {
+    /// 创建新的样式管理器（带 Standard 样式）
+    pub fn new() -> Self {
+        Self {
+            styles: vec![TextStyle::default()],
+            current_style_index: 0,
+        }
+    }
+
+    /// 获取当前样式
+    ///
+    /// 索引失效（如反序列化得到的数据不一致）时回退到 Standard 样式。
+    pub fn current_style(&self) -> &TextStyle {
+        static STANDARD: OnceLock<TextStyle> = OnceLock::new();
+        self.styles
+            .get(self.current_style_index)
+            .or_else(|| self.get_style("Standard"))
+            .unwrap_or_else(|| STANDARD.get_or_init(TextStyle::default))
+    }
+
+    /// 设置当前样式
+    pub fn set_current_style(&mut self, name: &str) -> bool {
+        if let Some(index) = self.index_of(name) {
+            self.current_style_index = index;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// 添加样式，同名样式（不区分大小写）被替换
+    pub fn add_style(&mut self, style: TextStyle) {
+        match self.index_of(&style.name) {
+            Some(index) => self.styles[index] = style,
+            None => self.styles.push(style),
+        }
+    }
+
+    /// 获取所有样式名称
+    pub fn style_names(&self) -> Vec<&str> {
+        self.styles.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    /// 按名称获取样式（不区分大小写，DXF 中样式名不区分大小写）
+    pub fn get_style(&self, name: &str) -> Option<&TextStyle> {
+        self.index_of(name).map(|i| &self.styles[i])
+    }
+
+    /// 按名称获取样式（可变）
+    pub fn get_style_mut(&mut self, name: &str) -> Option<&mut TextStyle> {
+        self.index_of(name).map(move |i| &mut self.styles[i])
+    }
+
+    /// 文本引用的样式，未引用或样式不存在时回退到当前样式
+    pub fn style_for(&self, text: &Text) -> &TextStyle {
+        text.style
+            .as_deref()
+            .and_then(|name| self.get_style(name))
+            .unwrap_or_else(|| self.current_style())
+    }
+
+    /// 文本的实际高度
+    ///
+    /// 文本自身高度大于 0 时视为显式覆盖；否则取样式高度，
+    /// 样式也未固定高度时使用 [`DEFAULT_TEXT_HEIGHT`]。
+    pub fn effective_height(&self, text: &Text) -> f64 {
+        if text.height > 0.0 {
+            return text.height;
+        }
+        let style = self.style_for(text);
+        if style.height > 0.0 {
+            style.height
+        } else {
+            DEFAULT_TEXT_HEIGHT
+        }
+    }
+
+    /// 文本使用的字体
+    pub fn effective_font(&self, text: &Text) -> &str {
+        &self.style_for(text).font
+    }
+
+    fn index_of(&self, name: &str) -> Option<usize> {
+        self.styles.iter().position(|s| s.name.eq_ignore_ascii_case(name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math::Point2;
+
+    #[test]
+    fn test_current_style_falls_back_to_standard() {
+        // 例如从不一致的文件数据反序列化得到的越界索引
+        let mut manager = TextStyleManager {
+            styles: vec![TextStyle::new("Title").with_height(5.0)],
+            current_style_index: 3,
+        };
+        assert_eq!(manager.current_style().name, "Standard");
+
+        manager.add_style(TextStyle::default().with_height(2.0));
+        assert_eq!(manager.current_style().height, 2.0);
+    }
+
+    #[test]
+    fn test_effective_height_from_style() {
+        let mut manager = TextStyleManager::new();
+        manager.add_style(TextStyle::new("Title").with_font("simhei.ttf").with_height(5.0));
+
+        // 未覆盖高度时取样式高度
+        let text = Text::new(Point2::origin(), "标题", 0.0).with_style("TITLE");
+        assert_eq!(manager.effective_height(&text), 5.0);
+        assert_eq!(manager.effective_font(&text), "simhei.ttf");
+
+        // 文本自身高度优先
+        let text = Text::new(Point2::origin(), "标题", 3.5).with_style("Title");
+        assert_eq!(manager.effective_height(&text), 3.5);
+    }
+
+    #[test]
+    fn test_effective_height_falls_back_to_default() {
+        let manager = TextStyleManager::new();
+
+        // 样式不存在时回退到当前样式，Standard 未固定高度
+        let text = Text::new(Point2::origin(), "A", 0.0).with_style("Missing");
+        assert_eq!(manager.style_for(&text).name, "Standard");
+        assert_eq!(manager.effective_height(&text), DEFAULT_TEXT_HEIGHT);
+    }
+}
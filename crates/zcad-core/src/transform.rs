@@ -2,7 +2,8 @@
 //!
 //! 支持平移、旋转、缩放、镜像等变换。
 
-use crate::math::{Matrix3, Point2, Vector2};
+use crate::geometry::{Arc, Circle, Ellipse, Geometry, HatchBoundaryElement};
+use crate::math::{Matrix3, Point2, Vector2, EPSILON};
 use serde::{Deserialize, Serialize};
 
 /// 2D仿射变换
@@ -149,6 +150,192 @@ impl Transform2D {
     }
 }
 
+impl Transform2D {
+    /// 线性部分的行列式（小于 0 表示包含镜像）
+    pub fn determinant(&self) -> f64 {
+        self.matrix[(0, 0)] * self.matrix[(1, 1)] - self.matrix[(0, 1)] * self.matrix[(1, 0)]
+    }
+
+    /// 是否包含镜像（改变方向）
+    pub fn is_mirroring(&self) -> bool {
+        self.determinant() < 0.0
+    }
+
+    /// 变换几何体
+    ///
+    /// 圆/圆弧在非均匀缩放下变为椭圆/椭圆弧；镜像时圆弧方向保持逆时针，
+    /// 多段线凸度取反。多段线弧段在非均匀缩放下仍按圆弧近似。
+    /// 文字高度、标注文字高度和引线箭头大小按 y 方向缩放比例缩放，
+    /// 均匀缩放后注释与图形保持原有比例。
+    pub fn transform_geometry(&self, geometry: &Geometry) -> Geometry {
+        let (sx, sy) = self.scale_component();
+        match geometry {
+            Geometry::Point(p) => {
+                let mut p = p.clone();
+                p.position = self.transform_point(&p.position);
+                Geometry::Point(p)
+            }
+            Geometry::Line(l) => {
+                let mut l = l.clone();
+                l.start = self.transform_point(&l.start);
+                l.end = self.transform_point(&l.end);
+                Geometry::Line(l)
+            }
+            Geometry::Circle(c) => self.transform_circle(c),
+            Geometry::Arc(a) => self.transform_arc(a),
+            Geometry::Polyline(pl) => {
+                let mut pl = pl.clone();
+                let mirrored = self.is_mirroring();
+                for v in &mut pl.vertices {
+                    v.point = self.transform_point(&v.point);
+                    if mirrored {
+                        v.bulge = -v.bulge;
+                    }
+                }
+                Geometry::Polyline(pl)
+            }
+            Geometry::Text(t) => {
+                let mut t = t.clone();
+                t.position = self.transform_point(&t.position);
+                t.height *= sy;
+                t.rotation += self.rotation_angle();
+                Geometry::Text(t)
+            }
+            Geometry::Dimension(d) => {
+                let mut d = d.clone();
+                d.definition_point1 = self.transform_point(&d.definition_point1);
+                d.definition_point2 = self.transform_point(&d.definition_point2);
+                d.line_location = self.transform_point(&d.line_location);
+                d.text_position = d.text_position.map(|p| self.transform_point(&p));
+                d.text_height *= sy;
+                Geometry::Dimension(d)
+            }
+            Geometry::Ellipse(e) => Geometry::Ellipse(self.transform_ellipse(e)),
+            Geometry::Spline(s) => {
+                let mut s = s.clone();
+                for p in s.control_points.iter_mut().chain(s.fit_points.iter_mut()) {
+                    *p = self.transform_point(p);
+                }
+                Geometry::Spline(s)
+            }
+            Geometry::Hatch(h) => {
+                let mut h = h.clone();
+                for boundary in &mut h.boundaries {
+                    for elem in &mut boundary.elements {
+                        *elem = match elem {
+                            HatchBoundaryElement::Line(l) => match self.transform_geometry(&Geometry::Line(l.clone())) {
+                                Geometry::Line(l) => HatchBoundaryElement::Line(l),
+                                _ => unreachable!(),
+                            },
+                            HatchBoundaryElement::Arc(a) => match self.transform_arc(a) {
+                                Geometry::Arc(a) => HatchBoundaryElement::Arc(a),
+                                Geometry::Ellipse(e) => HatchBoundaryElement::Ellipse(e),
+                                _ => unreachable!(),
+                            },
+                            HatchBoundaryElement::Ellipse(e) => HatchBoundaryElement::Ellipse(self.transform_ellipse(e)),
+                            HatchBoundaryElement::Spline(s) => match self.transform_geometry(&Geometry::Spline(s.clone())) {
+                                Geometry::Spline(s) => HatchBoundaryElement::Spline(s),
+                                _ => unreachable!(),
+                            },
+                        };
+                    }
+                }
+                h.angle += self.rotation_angle();
+                h.scale *= (sx * sy).sqrt();
+                Geometry::Hatch(h)
+            }
+            Geometry::Leader(l) => {
+                let mut l = l.clone();
+                for p in &mut l.vertices {
+                    *p = self.transform_point(p);
+                }
+                // 箭头和文字大小与文字、标注一致，随缩放变化
+                l.arrow_size *= sy;
+                l.text_height *= sy;
+                Geometry::Leader(l)
+            }
+        }
+    }
+
+    /// 由一对共轭半径求变换后的椭圆主轴（长轴向量、短长轴比）
+    fn principal_axes(&self, u: Vector2, v: Vector2) -> (Vector2, f64) {
+        let u = self.transform_vector(&u);
+        let v = self.transform_vector(&v);
+        let m = nalgebra::Matrix2::new(u.x, v.x, u.y, v.y);
+        let svd = m.svd(true, false);
+        let (major, minor) = if svd.singular_values[0] >= svd.singular_values[1] { (0, 1) } else { (1, 0) };
+        let u_mat = svd.u.unwrap_or_else(nalgebra::Matrix2::identity);
+        let sigma_major = svd.singular_values[major];
+        let axis = Vector2::new(u_mat[(0, major)], u_mat[(1, major)]) * sigma_major;
+        let ratio = if sigma_major > EPSILON { svd.singular_values[minor] / sigma_major } else { 1.0 };
+        (axis, ratio)
+    }
+
+    /// 点在椭圆上的参数
+    fn ellipse_param(center: Point2, major_axis: Vector2, ratio: f64, point: Point2) -> f64 {
+        let a = major_axis.norm();
+        let major_dir = major_axis / a;
+        let minor_dir = Vector2::new(-major_dir.y, major_dir.x);
+        let d = point - center;
+        (d.dot(&minor_dir) / (a * ratio)).atan2(d.dot(&major_dir) / a)
+    }
+
+    fn transform_circle(&self, circle: &Circle) -> Geometry {
+        let center = self.transform_point(&circle.center);
+        let r = circle.radius;
+        let (major_axis, ratio) = self.principal_axes(Vector2::new(r, 0.0), Vector2::new(0.0, r));
+        if (1.0 - ratio).abs() < 1e-9 {
+            Geometry::Circle(Circle::new(center, major_axis.norm()))
+        } else {
+            Geometry::Ellipse(Ellipse::new(center, major_axis, ratio))
+        }
+    }
+
+    fn transform_arc(&self, arc: &Arc) -> Geometry {
+        let center = self.transform_point(&arc.center);
+        let mut start = self.transform_point(&arc.start_point());
+        let mut end = self.transform_point(&arc.end_point());
+        if self.is_mirroring() {
+            std::mem::swap(&mut start, &mut end);
+        }
+
+        let r = arc.radius;
+        let (major_axis, ratio) = self.principal_axes(Vector2::new(r, 0.0), Vector2::new(0.0, r));
+        if (1.0 - ratio).abs() < 1e-9 {
+            let start_angle = (start.y - center.y).atan2(start.x - center.x);
+            let end_angle = (end.y - center.y).atan2(end.x - center.x);
+            Geometry::Arc(Arc::new(center, major_axis.norm(), start_angle, end_angle))
+        } else {
+            let start_param = Self::ellipse_param(center, major_axis, ratio, start);
+            let mut end_param = Self::ellipse_param(center, major_axis, ratio, end);
+            if end_param <= start_param {
+                end_param += 2.0 * std::f64::consts::PI;
+            }
+            Geometry::Ellipse(Ellipse::arc(center, major_axis, ratio, start_param, end_param))
+        }
+    }
+
+    fn transform_ellipse(&self, ellipse: &Ellipse) -> Ellipse {
+        let center = self.transform_point(&ellipse.center);
+        let (major_axis, ratio) = self.principal_axes(ellipse.major_axis, ellipse.minor_axis());
+        if ellipse.is_full() {
+            return Ellipse::new(center, major_axis, ratio);
+        }
+
+        let mut start = self.transform_point(&ellipse.start_point());
+        let mut end = self.transform_point(&ellipse.end_point());
+        if self.is_mirroring() {
+            std::mem::swap(&mut start, &mut end);
+        }
+        let start_param = Self::ellipse_param(center, major_axis, ratio, start);
+        let mut end_param = Self::ellipse_param(center, major_axis, ratio, end);
+        if end_param <= start_param {
+            end_param += 2.0 * std::f64::consts::PI;
+        }
+        Ellipse::arc(center, major_axis, ratio, start_param, end_param)
+    }
+}
+
 impl Default for Transform2D {
     fn default() -> Self {
         Self::identity()
@@ -215,5 +402,98 @@ mod tests {
         assert!(approx_eq(restored.x, p.x));
         assert!(approx_eq(restored.y, p.y));
     }
-}
 
+    #[test]
+    fn test_transform_geometry() {
+        use crate::geometry::Line;
+
+        // 旋转 90° + 平移
+        let t = Transform2D::translation(10.0, 0.0).then(&Transform2D::rotation(std::f64::consts::FRAC_PI_2));
+        match t.transform_geometry(&Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0)))) {
+            Geometry::Line(l) => {
+                assert!(approx_eq(l.end.x, 10.0));
+                assert!(approx_eq(l.end.y, 1.0));
+            }
+            _ => panic!("应为线段"),
+        }
+
+        // 非均匀缩放：圆变为椭圆
+        let t = Transform2D::scale(2.0, 1.0);
+        match t.transform_geometry(&Geometry::Circle(Circle::new(Point2::new(1.0, 1.0), 1.0))) {
+            Geometry::Ellipse(e) => {
+                assert!(approx_eq(e.center.x, 2.0));
+                assert!(approx_eq(e.major_axis.norm(), 2.0));
+                assert!(approx_eq(e.ratio, 0.5));
+            }
+            _ => panic!("应为椭圆"),
+        }
+
+        // 镜像：圆弧仍为逆时针，起止点互换
+        let arc = Arc::new(Point2::origin(), 1.0, 0.0, std::f64::consts::FRAC_PI_2);
+        match Transform2D::mirror_y().transform_geometry(&Geometry::Arc(arc)) {
+            Geometry::Arc(a) => {
+                assert!((a.start_point() - Point2::new(0.0, 1.0)).norm() < 1e-9);
+                assert!((a.end_point() - Point2::new(-1.0, 0.0)).norm() < 1e-9);
+            }
+            _ => panic!("应为圆弧"),
+        }
+    }
+
+    #[test]
+    fn test_mirror_arc_keeps_bulge_side() {
+        // 跨越 ±π 的大圆弧：从 135° 逆时针扫到 -135°（经过 180°）
+        let arc = Arc::new(Point2::new(3.0, 1.0), 2.0, 0.75 * std::f64::consts::PI, -0.75 * std::f64::consts::PI);
+        let t = Transform2D::mirror_y();
+        let Geometry::Arc(mirrored) = t.transform_geometry(&Geometry::Arc(arc.clone())) else {
+            panic!("应为圆弧");
+        };
+        // 镜像后的弧中点是原弧中点的镜像，包含角不变
+        assert!((mirrored.midpoint() - t.transform_point(&arc.midpoint())).norm() < 1e-9);
+        assert!((mirrored.midpoint() - Point2::new(-1.0, 1.0)).norm() < 1e-9);
+        assert!((mirrored.sweep_angle() - arc.sweep_angle()).abs() < 1e-9);
+
+        // 沿任意直线镜像同样成立
+        let t = Transform2D::mirror_line(Point2::new(0.0, -2.0), Point2::new(4.0, 5.0));
+        let Geometry::Arc(mirrored) = t.transform_geometry(&Geometry::Arc(arc.clone())) else {
+            panic!("应为圆弧");
+        };
+        assert!((mirrored.midpoint() - t.transform_point(&arc.midpoint())).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_uniform_scale_scales_annotation_heights() {
+        use crate::geometry::{Dimension, Leader, Text};
+
+        let t = Transform2D::scale_around(Point2::new(10.0, 10.0), 2.0, 2.0);
+        match t.transform_geometry(&Geometry::Text(Text::new(Point2::new(20.0, 10.0), "A", 2.5))) {
+            Geometry::Text(text) => {
+                assert!(approx_eq(text.height, 5.0));
+                assert!(approx_eq(text.position.x, 30.0));
+            }
+            _ => panic!("应为文字"),
+        }
+
+        let mut dim = Dimension::new(Point2::origin(), Point2::new(10.0, 0.0), Point2::new(5.0, 5.0));
+        dim.text_height = 3.0;
+        match t.transform_geometry(&Geometry::Dimension(dim)) {
+            Geometry::Dimension(d) => assert!(approx_eq(d.text_height, 6.0)),
+            _ => panic!("应为标注"),
+        }
+
+        let leader = Leader::new(vec![Point2::origin(), Point2::new(5.0, 5.0)]);
+        match t.transform_geometry(&Geometry::Leader(leader.clone())) {
+            Geometry::Leader(l) => {
+                assert!(approx_eq(l.arrow_size, leader.arrow_size * 2.0));
+                assert!(approx_eq(l.text_height, leader.text_height * 2.0));
+            }
+            _ => panic!("应为引线"),
+        }
+
+        // 旋转不改变文字高度
+        let rotated = Transform2D::rotation(1.0).transform_geometry(&Geometry::Text(Text::new(Point2::origin(), "A", 2.5)));
+        match rotated {
+            Geometry::Text(text) => assert!(approx_eq(text.height, 2.5)),
+            _ => panic!("应为文字"),
+        }
+    }
+}
@@ -0,0 +1,114 @@
+//! 用户坐标系（UCS）
+//!
+//! 由原点和 X 轴旋转角定义的局部坐标系，用于相对旋转的基准绘图：
+//! 键入的坐标、十字光标和网格按 UCS 解释和显示，图形始终以世界坐标存储。
+
+use crate::math::{Point2, Vector2, EPSILON};
+use crate::transform::Transform2D;
+use serde::{Deserialize, Serialize};
+
+/// 用户坐标系
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Ucs {
+    /// 原点（世界坐标）
+    pub origin: Point2,
+    /// X 轴相对世界 X 轴的旋转角（弧度，逆时针为正）
+    pub angle: f64,
+}
+
+impl Ucs {
+    /// 世界坐标系
+    pub fn world() -> Self {
+        Self {
+            origin: Point2::origin(),
+            angle: 0.0,
+        }
+    }
+
+    /// 由原点和旋转角创建
+    pub fn new(origin: Point2, angle: f64) -> Self {
+        Self { origin, angle }
+    }
+
+    /// 由原点和 X 轴正方向上的一点创建，两点重合时返回 `None`
+    pub fn from_points(origin: Point2, x_axis_point: Point2) -> Option<Self> {
+        let dir = x_axis_point - origin;
+        if dir.norm() < EPSILON {
+            return None;
+        }
+        Some(Self::new(origin, dir.y.atan2(dir.x)))
+    }
+
+    /// 是否与世界坐标系重合
+    pub fn is_world(&self) -> bool {
+        self.origin.coords.norm() < EPSILON && self.angle.abs() < EPSILON
+    }
+
+    /// X 轴单位向量（世界坐标）
+    pub fn x_axis(&self) -> Vector2 {
+        Vector2::new(self.angle.cos(), self.angle.sin())
+    }
+
+    /// Y 轴单位向量（世界坐标）
+    pub fn y_axis(&self) -> Vector2 {
+        Vector2::new(-self.angle.sin(), self.angle.cos())
+    }
+
+    /// UCS 坐标转世界坐标
+    pub fn to_world(&self, point: Point2) -> Point2 {
+        self.origin + self.vector_to_world(point.coords)
+    }
+
+    /// 世界坐标转 UCS 坐标
+    pub fn to_ucs(&self, point: Point2) -> Point2 {
+        let offset = point - self.origin;
+        Point2::new(offset.dot(&self.x_axis()), offset.dot(&self.y_axis()))
+    }
+
+    /// UCS 中的向量转为世界坐标中的向量（只旋转）
+    pub fn vector_to_world(&self, vector: Vector2) -> Vector2 {
+        self.x_axis() * vector.x + self.y_axis() * vector.y
+    }
+
+    /// UCS 到世界坐标的变换
+    pub fn to_world_transform(&self) -> Transform2D {
+        Transform2D::translation(self.origin.x, self.origin.y).then(&Transform2D::rotation(self.angle))
+    }
+}
+
+impl Default for Ucs {
+    fn default() -> Self {
+        Self::world()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_rotated_ucs_maps_to_world() {
+        // 原点 (100,50)，X 轴指向世界 Y 轴正向
+        let ucs = Ucs::new(Point2::new(100.0, 50.0), FRAC_PI_2);
+        let world = ucs.to_world(Point2::new(10.0, 0.0));
+        assert!((world - Point2::new(100.0, 60.0)).norm() < 1e-9);
+        let world = ucs.to_world(Point2::new(0.0, 10.0));
+        assert!((world - Point2::new(90.0, 50.0)).norm() < 1e-9);
+
+        // 与变换矩阵一致，且可逆
+        let p = Point2::new(3.0, -7.0);
+        let by_transform = ucs.to_world_transform().transform_point(&p);
+        assert!((by_transform - ucs.to_world(p)).norm() < 1e-9);
+        assert!((ucs.to_ucs(ucs.to_world(p)) - p).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_ucs_from_points() {
+        let ucs = Ucs::from_points(Point2::new(1.0, 1.0), Point2::new(2.0, 2.0)).unwrap();
+        assert!((ucs.angle - std::f64::consts::FRAC_PI_4).abs() < 1e-12);
+        assert!(!ucs.is_world());
+        assert!(Ucs::from_points(Point2::new(1.0, 1.0), Point2::new(1.0, 1.0)).is_none());
+        assert!(Ucs::default().is_world());
+    }
+}
@@ -26,6 +26,7 @@
 //! AutoCAD 在渲染时对每个图层过滤实体列表。
 //! **规避方案**：使用图层索引，按图层分组存储实体引用。
 
+use crate::math::{angle_between, Point2, Vector2};
 use serde::{Deserialize, Serialize};
 
 /// 绘图单位
@@ -469,10 +470,78 @@ pub fn format_angle(value: f64, format: AngleFormat, precision: u8) -> String {
     }
 }
 
+/// 界面中长度和角度的显示设置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DisplayFormat {
+    /// 绘图单位
+    pub unit: Unit,
+    /// 长度格式
+    pub linear_format: LinearFormat,
+    /// 长度精度（小数位数）
+    pub linear_precision: u8,
+    /// 角度格式
+    pub angle_format: AngleFormat,
+    /// 角度精度（小数位数）
+    pub angle_precision: u8,
+}
+
+impl Default for DisplayFormat {
+    fn default() -> Self {
+        Self {
+            unit: Unit::Millimeter,
+            linear_format: LinearFormat::Decimal,
+            linear_precision: 2,
+            angle_format: AngleFormat::DegreesDecimal,
+            angle_precision: 1,
+        }
+    }
+}
+
+impl DisplayFormat {
+    /// 格式化长度（不带单位符号）
+    pub fn length(&self, value: f64) -> String {
+        format_linear(value, self.unit, self.linear_format, self.linear_precision, false)
+    }
+
+    /// 格式化角度（弧度输入，归一化到 [0, 2π)）
+    pub fn angle(&self, radians: f64) -> String {
+        let radians = radians.rem_euclid(2.0 * std::f64::consts::PI);
+        format_angle(radians, self.angle_format, self.angle_precision)
+    }
+
+    /// 格式化线段的长度和方向角，如 `L=50.00 A=45.0°`
+    pub fn segment(&self, from: Point2, to: Point2) -> String {
+        let delta = to - from;
+        format!(
+            "L={} A={}",
+            self.length(delta.norm()),
+            self.angle(angle_between(&Vector2::x(), &delta).to_radians())
+        )
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_display_format_segment() {
+        let format = DisplayFormat::default();
+        let d = 50.0 / 2.0_f64.sqrt();
+        assert_eq!(format.segment(Point2::new(0.0, 0.0), Point2::new(d, d)), "L=50.00 A=45.0°");
+
+        // 负角度归一化到 [0, 360)
+        assert_eq!(format.segment(Point2::new(0.0, 0.0), Point2::new(0.0, -12.5)), "L=12.50 A=270.0°");
+
+        let radians = DisplayFormat {
+            linear_precision: 3,
+            angle_format: AngleFormat::Radians,
+            angle_precision: 2,
+            ..Default::default()
+        };
+        assert_eq!(radians.segment(Point2::new(1.0, 1.0), Point2::new(1.0, 3.0)), "L=2.000 A=1.57rad");
+    }
+
     #[test]
     fn test_unit_conversion() {
         assert!((convert(1.0, Unit::Inch, Unit::Millimeter) - 25.4).abs() < 0.001);
@@ -2,13 +2,21 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::OnceLock;
 use uuid::Uuid;
+use zcad_core::block::{Block, BlockTable};
 use zcad_core::entity::{Entity, EntityId};
-use zcad_core::layer::LayerManager;
-use zcad_core::layout::LayoutManager;
-use zcad_core::math::BoundingBox2;
+use zcad_core::geometry::{Geometry, HatchBoundary};
+use zcad_core::history::OperationType;
+use zcad_core::layer::{Layer, LayerManager};
+use zcad_core::layout::{LayoutId, LayoutManager};
+use zcad_core::math::{BoundingBox2, Point2, EPSILON};
+use zcad_core::pointstyle::PointStyle;
+use zcad_core::properties::{Color, LineType, LineWeight, Properties};
 use zcad_core::spatial::SpatialIndex;
+use zcad_core::textstyle::TextStyleManager;
+use zcad_core::transform::Transform2D;
 
 /// 文档元数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -62,6 +70,130 @@ pub struct SavedView {
     pub zoom: f64,
 }
 
+/// 视图状态：相机位置与绘图辅助开关，随原生文档保存
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ViewState {
+    pub center_x: f64,
+    pub center_y: f64,
+    pub zoom: f64,
+    pub show_grid: bool,
+    pub ortho_mode: bool,
+    pub snap_enabled: bool,
+}
+
+impl Default for ViewState {
+    fn default() -> Self {
+        Self {
+            center_x: 0.0,
+            center_y: 0.0,
+            zoom: 1.0,
+            show_grid: true,
+            ortho_mode: false,
+            snap_enabled: true,
+        }
+    }
+}
+
+/// 图纸统计信息
+#[derive(Debug, Clone, Default)]
+pub struct DrawingStats {
+    /// 各几何类型的实体数量（键为 `Geometry::type_name`）
+    pub counts_by_type: BTreeMap<&'static str, usize>,
+
+    /// 实体总数
+    pub entity_count: usize,
+
+    /// 图层数量
+    pub layer_count: usize,
+
+    /// 所有实体的包围盒
+    pub bounds: Option<BoundingBox2>,
+
+    /// 线性对象（直线、圆弧、圆、多段线、椭圆、样条等）的总长度
+    pub total_length: f64,
+}
+
+impl DrawingStats {
+    /// 获取某一几何类型的实体数量
+    pub fn count_of(&self, type_name: &str) -> usize {
+        self.counts_by_type.get(type_name).copied().unwrap_or(0)
+    }
+}
+
+/// 几何审核报告（见 [`Document::audit`] 和 [`Document::audit_and_repair`]）
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AuditReport {
+    /// 含 NaN 或无穷大坐标的实体
+    pub non_finite: Vec<EntityId>,
+    /// 零长度直线
+    pub zero_length_lines: Vec<EntityId>,
+    /// 半径为零的圆和圆弧
+    pub degenerate_arcs: Vec<EntityId>,
+    /// 含相邻重复顶点的多段线及重复顶点数
+    pub duplicate_vertices: Vec<(EntityId, usize)>,
+    /// 修复时删除的实体（无法修复的实体，以及合并重复顶点后不足两个顶点的多段线）
+    pub removed: Vec<EntityId>,
+    /// 是否已修复
+    pub repaired: bool,
+}
+
+impl AuditReport {
+    /// 发现的问题总数
+    pub fn error_count(&self) -> usize {
+        self.non_finite.len()
+            + self.zero_length_lines.len()
+            + self.degenerate_arcs.len()
+            + self.duplicate_vertices.len()
+    }
+
+    /// 是否没有发现问题
+    pub fn is_clean(&self) -> bool {
+        self.error_count() == 0
+    }
+}
+
+/// 单个实体的审核结果
+enum AuditIssue {
+    NonFinite,
+    ZeroLengthLine,
+    DegenerateArc,
+    /// 合并重复顶点后的多段线及删除的顶点数
+    DuplicateVertices(zcad_core::geometry::Polyline, usize),
+}
+
+/// 审核单个几何，没有问题时返回 `None`
+fn audit_geometry(geometry: &Geometry) -> Option<AuditIssue> {
+    if !geometry.is_finite() {
+        return Some(AuditIssue::NonFinite);
+    }
+    match geometry {
+        Geometry::Line(line) if line.length() < EPSILON => Some(AuditIssue::ZeroLengthLine),
+        Geometry::Circle(circle) if circle.radius < EPSILON => Some(AuditIssue::DegenerateArc),
+        Geometry::Arc(arc) if arc.radius < EPSILON => Some(AuditIssue::DegenerateArc),
+        Geometry::Polyline(polyline) => {
+            let mut repaired = polyline.clone();
+            let removed = repaired.remove_duplicate_vertices(EPSILON);
+            (removed > 0).then_some(AuditIssue::DuplicateVertices(repaired, removed))
+        }
+        _ => None,
+    }
+}
+
+/// 文档变更事件
+///
+/// 由修改文档的操作记录，面板和插件每帧取出处理，无需全量重新扫描。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeEvent {
+    /// 新增实体
+    EntityAdded(EntityId),
+    /// 删除实体
+    EntityRemoved(EntityId),
+    /// 修改实体
+    EntityModified(EntityId),
+    /// 图层属性或图层表发生变化
+    LayerChanged(EntityId),
+}
+
 /// CAD文档
 #[derive(Debug)]
 pub struct Document {
@@ -74,20 +206,50 @@ pub struct Document {
     /// 图层管理器
     pub layers: LayerManager,
 
+    /// 块定义表
+    pub blocks: BlockTable,
+
     /// 空间索引
     spatial_index: SpatialIndex,
 
     /// 保存的视图
     pub views: Vec<SavedView>,
 
+    /// 上次保存时的视图状态（旧文件中没有时为 None）
+    pub view_state: Option<ViewState>,
+
     /// 布局管理器
     pub layout_manager: LayoutManager,
 
+    /// 文字样式表
+    pub text_styles: TextStyleManager,
+
+    /// 点样式（PDMODE / PDSIZE）
+    pub point_style: PointStyle,
+
+    /// 编组（命名选择集），删除实体时自动移除其ID
+    groups: HashMap<String, Vec<EntityId>>,
+
+    /// 删除实体时移除的编组成员：实体ID → (编组名, 原位置)，撤销删除重新加入实体时恢复
+    removed_group_members: HashMap<EntityId, Vec<(String, usize)>>,
+
+    /// 关联填充反向索引：边界实体ID → 依赖它的填充ID
+    hatch_dependents: HashMap<EntityId, Vec<EntityId>>,
+
     /// 是否已修改
     modified: bool,
 
     /// 文件路径（如果已保存）
     file_path: Option<std::path::PathBuf>,
+
+    /// 尚未取出的变更事件
+    changes: Vec<ChangeEvent>,
+
+    /// 文档版本号，每次产生变更事件时递增
+    revision: u64,
+
+    /// 所有实体的总范围缓存（未初始化表示需要重新计算）
+    bounds_cache: OnceLock<Option<BoundingBox2>>,
 }
 
 impl Document {
@@ -97,11 +259,21 @@ impl Document {
             metadata: DocumentMetadata::default(),
             entities: HashMap::new(),
             layers: LayerManager::new(),
+            blocks: BlockTable::new(),
             spatial_index: SpatialIndex::default_grid(),
             views: Vec::new(),
+            view_state: None,
             layout_manager: LayoutManager::new(),
+            text_styles: TextStyleManager::new(),
+            point_style: PointStyle::default(),
+            groups: HashMap::new(),
+            removed_group_members: HashMap::new(),
+            hatch_dependents: HashMap::new(),
             modified: false,
             file_path: None,
+            changes: Vec::new(),
+            revision: 0,
+            bounds_cache: OnceLock::new(),
         }
     }
 
@@ -112,12 +284,28 @@ impl Document {
         match path.extension().and_then(|e| e.to_str()) {
             Some("zcad") => crate::native::load(path),
             Some("dxf") => crate::dxf_io::import(path),
+            Some(ext) if ext.eq_ignore_ascii_case("dwg") => crate::dwg::import(path),
             _ => Err(crate::FileError::InvalidFormat(
                 "Unknown file extension".to_string(),
             )),
         }
     }
 
+    /// 只加载指定范围内的实体（仅支持原生 .zcad 格式）
+    ///
+    /// 包围盒与 `bounds` 相交的实体被加载，图层、块和布局完整加载。
+    /// 得到的是原文件的子集，不应保存回原文件。
+    pub fn open_region(path: impl AsRef<std::path::Path>, bounds: &BoundingBox2) -> Result<Self, crate::FileError> {
+        let path = path.as_ref();
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("zcad") => crate::native::load_region(path, bounds),
+            _ => Err(crate::FileError::InvalidFormat(
+                "Partial open is only supported for .zcad files".to_string(),
+            )),
+        }
+    }
+
     /// 保存文件
     pub fn save(&mut self) -> Result<(), crate::FileError> {
         if let Some(path) = &self.file_path.clone() {
@@ -130,17 +318,28 @@ impl Document {
     }
 
     /// 另存为
+    ///
+    /// 先写入同目录下的临时文件，成功后再原子地重命名为目标文件；
+    /// 任何一步失败都不会改动文档的路径和修改标记，已有的目标文件也保持原样。
     pub fn save_as(&mut self, path: impl AsRef<std::path::Path>) -> Result<(), crate::FileError> {
         let path = path.as_ref();
+        let extension = path.extension().and_then(|e| e.to_str());
+        if !matches!(extension, Some("zcad") | Some("dxf")) {
+            return Err(crate::FileError::InvalidFormat(
+                "Unknown file extension".to_string(),
+            ));
+        }
 
-        match path.extension().and_then(|e| e.to_str()) {
-            Some("zcad") => crate::native::save(self, path)?,
-            Some("dxf") => crate::dxf_io::export(self, path)?,
-            _ => {
-                return Err(crate::FileError::InvalidFormat(
-                    "Unknown file extension".to_string(),
-                ))
-            }
+        // 临时文件与目标同目录，保证 rename 不跨文件系统
+        let file_name = path.file_name().and_then(|n| n.to_str()).unwrap_or("document");
+        let temp_path = path.with_file_name(format!(".{}.tmp", file_name));
+        let written = match extension {
+            Some("zcad") => crate::native::save(self, &temp_path),
+            _ => crate::dxf_io::export(self, &temp_path),
+        };
+        if let Err(e) = written.and_then(|_| std::fs::rename(&temp_path, path).map_err(Into::into)) {
+            std::fs::remove_file(&temp_path).ok();
+            return Err(e);
         }
 
         self.file_path = Some(path.to_path_buf());
@@ -150,23 +349,166 @@ impl Document {
         Ok(())
     }
 
+    /// 无界面批量转换：读取 DXF/zcad 文件并导出为指定格式
+    ///
+    /// 只导出可见实体，不依赖任何 UI 状态，可用于脚本或命令行批处理。
+    pub fn convert_file(
+        input: &std::path::Path,
+        output: &std::path::Path,
+        format: crate::export::ExportFormat,
+        page: crate::export::PageSetup,
+    ) -> Result<(), crate::FileError> {
+        let document = Self::open(input)?;
+        let entities = document.export_entities();
+
+        crate::export::export_entities(&entities, format, page, output)?;
+
+        tracing::info!(
+            "Converted {} ({} entities) to {:?} at {}",
+            input.display(),
+            entities.len(),
+            format,
+            output.display()
+        );
+
+        Ok(())
+    }
+
     /// 添加实体
     pub fn add_entity(&mut self, entity: Entity) -> EntityId {
         let id = entity.id;
         let bbox = entity.bounding_box();
 
+        let old = self.spatial_index.bbox(&id);
+        self.update_bounds_cache(old, Some(bbox));
         self.spatial_index.insert(id, bbox);
-        self.entities.insert(id, entity);
+        let boundary_ids = hatch_boundary_ids(&entity.geometry).to_vec();
+        if let Some(previous) = self.entities.insert(id, entity) {
+            self.unlink_hatch(id, hatch_boundary_ids(&previous.geometry));
+        }
+        self.link_hatch(id, &boundary_ids);
+        self.restore_group_members(id);
         self.modified = true;
+        self.record_change(ChangeEvent::EntityAdded(id));
+        // 撤销删除时边界实体重新出现，关联填充随之恢复
+        self.update_associative_hatches(id);
 
         id
     }
 
     /// 删除实体
     pub fn remove_entity(&mut self, id: &EntityId) -> Option<Entity> {
+        let old = self.spatial_index.bbox(id);
+        self.update_bounds_cache(old, None);
         self.spatial_index.remove(id);
         self.modified = true;
-        self.entities.remove(id)
+        let removed = self.entities.remove(id);
+        if let Some(entity) = &removed {
+            self.unlink_hatch(*id, hatch_boundary_ids(&entity.geometry));
+            let mut memberships = Vec::new();
+            for (name, members) in self.groups.iter_mut() {
+                if let Some(index) = members.iter().position(|member| member == id) {
+                    members.remove(index);
+                    memberships.push((name.clone(), index));
+                }
+            }
+            if !memberships.is_empty() {
+                self.removed_group_members.insert(*id, memberships);
+            }
+            self.record_change(ChangeEvent::EntityRemoved(*id));
+            self.update_associative_hatches(*id);
+        }
+        removed
+    }
+
+    /// 实体重新加入（撤销删除）时放回删除前所在的编组，编组已被删除的跳过
+    fn restore_group_members(&mut self, id: EntityId) {
+        let Some(memberships) = self.removed_group_members.remove(&id) else {
+            return;
+        };
+        for (name, index) in memberships {
+            if let Some(members) = self.groups.get_mut(&name) {
+                if !members.contains(&id) {
+                    members.insert(index.min(members.len()), id);
+                }
+            }
+        }
+    }
+
+    /// 批量删除实体，跳过被锁定的实体，返回实际删除的实体
+    pub fn delete_entities(&mut self, ids: &[EntityId]) -> Vec<Entity> {
+        let unlocked: Vec<EntityId> = ids
+            .iter()
+            .filter(|id| self.entities.get(id).is_some_and(|e| !e.locked))
+            .copied()
+            .collect();
+        unlocked.iter().filter_map(|id| self.remove_entity(id)).collect()
+    }
+
+    /// 在当前图层上创建新实体（尚未加入文档）
+    ///
+    /// 颜色、线型、线宽均为 ByLayer，显示时由 [`Document::resolve_properties`] 解析为图层属性。
+    pub fn new_entity(&self, geometry: Geometry) -> Entity {
+        Entity::new(geometry).with_layer(self.layers.current_layer().id)
+    }
+
+    /// 实体所在图层，图层不存在（如未指定图层）时为图层0
+    pub fn entity_layer(&self, entity: &Entity) -> &Layer {
+        self.layers
+            .get_layer_by_id(entity.layer_id)
+            .or_else(|| self.layers.get_layer("0"))
+            .unwrap_or_else(|| self.layers.current_layer())
+    }
+
+    /// 解析实体的实际属性：ByLayer 的颜色、线型、线宽取自所在图层
+    pub fn resolve_properties(&self, entity: &Entity) -> Properties {
+        let layer = self.entity_layer(entity);
+        let mut properties = entity.properties.clone();
+        if properties.color.is_by_layer() {
+            properties.color = layer.color;
+        }
+        if properties.line_type == LineType::ByLayer {
+            properties.line_type = layer.line_type.clone();
+        }
+        if properties.line_weight == LineWeight::ByLayer {
+            properties.line_weight = layer.line_weight;
+        }
+        properties
+    }
+
+    /// 实体的显示颜色（ByLayer 时取图层颜色）
+    pub fn effective_color(&self, entity: &Entity) -> Color {
+        if entity.properties.color.is_by_layer() {
+            self.entity_layer(entity).color
+        } else {
+            entity.properties.color
+        }
+    }
+
+    /// 实体是否应显示：自身可见，且所在图层未关闭、未冻结
+    pub fn is_displayed(&self, entity: &Entity) -> bool {
+        entity.visible && self.entity_layer(entity).should_display()
+    }
+
+    /// 用于导出的实体副本：只含显示的实体，ByLayer 属性已替换为图层的实际值
+    ///
+    /// 导出器只认实体自身的属性，与视口使用同一套解析规则，保证导出内容和颜色与屏幕一致。
+    pub fn export_entities(&self) -> Vec<Entity> {
+        self.entities
+            .values()
+            .filter(|e| self.is_displayed(e))
+            .map(|e| {
+                let mut entity = e.clone();
+                entity.properties = self.resolve_properties(e);
+                entity
+            })
+            .collect()
+    }
+
+    /// 复制实体（分配新ID），返回新实体的ID
+    pub fn duplicate_entity(&mut self, id: &EntityId) -> Option<EntityId> {
+        let copy = self.entities.get(id)?.duplicate();
+        Some(self.add_entity(copy))
     }
 
     /// 获取实体
@@ -174,18 +516,366 @@ impl Document {
         self.entities.get(id)
     }
 
-    /// 获取可变实体
+    /// 空间索引中记录的实体包围盒
+    pub fn entity_bounding_box(&self, id: &EntityId) -> Option<BoundingBox2> {
+        self.spatial_index.bbox(id)
+    }
+
+    /// 获取可变实体（视为一次修改）
+    ///
+    /// 调用方可能修改几何，总范围缓存随之作废。
     pub fn get_entity_mut(&mut self, id: &EntityId) -> Option<&mut Entity> {
         self.modified = true;
+        self.bounds_cache = OnceLock::new();
+        if !self.entities.contains_key(id) {
+            return None;
+        }
+        self.record_change(ChangeEvent::EntityModified(*id));
         self.entities.get_mut(id)
     }
 
     /// 更新实体（并更新空间索引）
     pub fn update_entity(&mut self, id: &EntityId, entity: Entity) {
         let bbox = entity.bounding_box();
+        self.update_entity_with_bbox(id, entity, bbox);
+    }
+
+    /// 用已知的包围盒更新实体，不再整体重新计算
+    ///
+    /// 用于夹点拖动等交互编辑：包围盒由 [`Document::entity_bounding_box`] 中的旧值增量得到。
+    pub fn update_entity_with_bbox(&mut self, id: &EntityId, entity: Entity, bbox: BoundingBox2) {
+        let old = self.spatial_index.bbox(id);
+        self.update_bounds_cache(old, Some(bbox));
         self.spatial_index.update(*id, bbox);
-        self.entities.insert(*id, entity);
+        let boundary_ids = hatch_boundary_ids(&entity.geometry).to_vec();
+        let previous = self.entities.insert(*id, entity);
+        let existed = previous.is_some();
+        if let Some(previous) = previous {
+            self.unlink_hatch(*id, hatch_boundary_ids(&previous.geometry));
+        }
+        self.link_hatch(*id, &boundary_ids);
+        self.modified = true;
+        self.record_change(if existed {
+            ChangeEvent::EntityModified(*id)
+        } else {
+            ChangeEvent::EntityAdded(*id)
+        });
+        self.update_associative_hatches(*id);
+    }
+
+    /// 撤销一个历史操作（反向执行）
+    ///
+    /// 分组操作按相反顺序撤销其子操作，使复合命令一步撤销。
+    pub fn undo_operation(&mut self, op_type: &OperationType) {
+        match op_type {
+            OperationType::CreateEntity { entity } => {
+                self.remove_entity(&entity.id);
+            }
+            OperationType::DeleteEntity { previous_entity: Some(entity), .. } => {
+                self.add_entity(entity.clone());
+            }
+            OperationType::ModifyEntity { entity_id, previous_geometry, .. } => {
+                self.set_geometry(entity_id, previous_geometry);
+            }
+            OperationType::BooleanOperation { result_entities, previous_entities, .. } => {
+                for entity in result_entities {
+                    self.remove_entity(&entity.id);
+                }
+                for entity in previous_entities {
+                    self.add_entity(entity.clone());
+                }
+            }
+            OperationType::GroupOperation { operations, .. } => {
+                for op in operations.iter().rev() {
+                    self.undo_operation(&op.operation_type);
+                }
+            }
+            // TODO: 移动、旋转、缩放的撤销需要额外的几何体变换支持
+            _ => {}
+        }
+    }
+
+    /// 重做一个历史操作（正向执行）
+    pub fn redo_operation(&mut self, op_type: &OperationType) {
+        match op_type {
+            OperationType::CreateEntity { entity } => {
+                self.add_entity(entity.clone());
+            }
+            OperationType::DeleteEntity { entity_id, .. } => {
+                self.remove_entity(entity_id);
+            }
+            OperationType::ModifyEntity { entity_id, new_geometry, .. } => {
+                self.set_geometry(entity_id, new_geometry);
+            }
+            OperationType::BooleanOperation { result_entities, previous_entities, .. } => {
+                for entity in previous_entities {
+                    self.remove_entity(&entity.id);
+                }
+                for entity in result_entities {
+                    self.add_entity(entity.clone());
+                }
+            }
+            OperationType::GroupOperation { operations, .. } => {
+                for op in operations {
+                    self.redo_operation(&op.operation_type);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// 替换实体几何，其余属性保持不变
+    fn set_geometry(&mut self, id: &EntityId, geometry: &Geometry) {
+        if let Some(entity) = self.get_entity(id) {
+            let mut updated = entity.clone();
+            updated.geometry = geometry.clone();
+            self.update_entity(id, updated);
+        }
+    }
+
+    /// 登记填充对其边界实体的依赖
+    fn link_hatch(&mut self, hatch_id: EntityId, boundary_ids: &[EntityId]) {
+        for boundary_id in boundary_ids {
+            let dependents = self.hatch_dependents.entry(*boundary_id).or_default();
+            if !dependents.contains(&hatch_id) {
+                dependents.push(hatch_id);
+            }
+        }
+    }
+
+    /// 移除填充对其边界实体的依赖
+    fn unlink_hatch(&mut self, hatch_id: EntityId, boundary_ids: &[EntityId]) {
+        for boundary_id in boundary_ids {
+            if let Some(dependents) = self.hatch_dependents.get_mut(boundary_id) {
+                dependents.retain(|id| *id != hatch_id);
+                if dependents.is_empty() {
+                    self.hatch_dependents.remove(boundary_id);
+                }
+            }
+        }
+    }
+
+    /// 边界实体变化后重新生成关联填充的边界
+    ///
+    /// 边界实体缺失（如已删除）或不再闭合时跳过重新生成，保留最后一次生成的边界和关联，
+    /// 撤销删除后填充继续跟随边界。
+    fn update_associative_hatches(&mut self, boundary_id: EntityId) {
+        let Some(hatch_ids) = self.hatch_dependents.get(&boundary_id).cloned() else {
+            return;
+        };
+
+        for hatch_id in hatch_ids {
+            let Some(Geometry::Hatch(hatch)) = self.entities.get(&hatch_id).map(|e| &e.geometry) else {
+                continue;
+            };
+            let boundaries: Option<Vec<HatchBoundary>> = hatch
+                .boundary_entity_ids
+                .iter()
+                .enumerate()
+                .map(|(i, id)| {
+                    self.entities
+                        .get(id)
+                        .and_then(|e| HatchBoundary::from_geometry(&e.geometry, i == 0))
+                })
+                .collect();
+
+            let Some(boundaries) = boundaries else {
+                continue;
+            };
+
+            let Some(entity) = self.entities.get_mut(&hatch_id) else {
+                continue;
+            };
+            if let Geometry::Hatch(hatch) = &mut entity.geometry {
+                hatch.boundaries = boundaries;
+            }
+            let bbox = entity.bounding_box();
+            let old = self.spatial_index.bbox(&hatch_id);
+            self.update_bounds_cache(old, Some(bbox));
+            self.spatial_index.update(hatch_id, bbox);
+            self.record_change(ChangeEvent::EntityModified(hatch_id));
+        }
+    }
+
+    /// 记录图层变化（图层管理器是公开字段，修改后由调用方通知）
+    pub fn notify_layer_changed(&mut self, layer_id: EntityId) {
         self.modified = true;
+        self.record_change(ChangeEvent::LayerChanged(layer_id));
+    }
+
+    /// 删除图层，其上的实体迁移到目标图层（默认为图层0），返回迁移的实体数量
+    pub fn delete_layer(
+        &mut self,
+        id: EntityId,
+        reassign_to: Option<EntityId>,
+    ) -> Result<usize, zcad_core::layer::LayerError> {
+        self.reassign_layer_entities(id, |layers, entities| {
+            layers.delete_layer_and_reassign(id, reassign_to, entities.values_mut())
+        })
+    }
+
+    /// 合并图层：`src` 上的实体迁移到 `dst` 后删除 `src`，返回迁移的实体数量
+    pub fn merge_layers(&mut self, src: &str, dst: &str) -> Result<usize, zcad_core::layer::LayerError> {
+        let src_id = self
+            .layers
+            .get_layer(src)
+            .map(|l| l.id)
+            .ok_or_else(|| zcad_core::layer::LayerError::LayerNotFound(src.to_string()))?;
+        self.reassign_layer_entities(src_id, |layers, entities| {
+            layers.merge_layers(src, dst, entities.values_mut())
+        })
+    }
+
+    /// 由 `reassign` 把 `layer_id` 上的实体迁走并删除该图层，然后记录被迁移实体和该图层的变更
+    fn reassign_layer_entities(
+        &mut self,
+        layer_id: EntityId,
+        reassign: impl FnOnce(&mut LayerManager, &mut HashMap<EntityId, Entity>) -> Result<usize, zcad_core::layer::LayerError>,
+    ) -> Result<usize, zcad_core::layer::LayerError> {
+        let moved: Vec<EntityId> = self
+            .entities
+            .values()
+            .filter(|e| e.layer_id == layer_id)
+            .map(|e| e.id)
+            .collect();
+        let count = reassign(&mut self.layers, &mut self.entities)?;
+
+        for entity_id in moved {
+            self.record_change(ChangeEvent::EntityModified(entity_id));
+        }
+        self.notify_layer_changed(layer_id);
+        Ok(count)
+    }
+
+    /// 重命名图层，实体按图层ID引用，无需更新
+    pub fn rename_layer(&mut self, old_name: &str, new_name: &str) -> Result<(), zcad_core::layer::LayerError> {
+        self.layers.rename_layer(old_name, new_name)?;
+        if let Some(id) = self.layers.get_layer(new_name).map(|l| l.id) {
+            self.notify_layer_changed(id);
+        }
+        Ok(())
+    }
+
+    /// 在布局的图纸空间插入标题栏，可选在可打印区域左下角插入比例尺
+    ///
+    /// 标题栏右下角对齐到可打印区域右下角，内容取自文档元数据和导出比例 1:`scale`。
+    /// 返回插入的实体数，布局不存在时为 0。
+    pub fn insert_title_block(&mut self, layout: LayoutId, scale: f64, with_scale_bar: bool) -> usize {
+        let metadata = self.metadata.clone();
+        let Some(layout) = self.layout_manager.get_layout_mut(layout) else {
+            return 0;
+        };
+        let (min, max) = layout.printable_bounds();
+        let mut geometries = crate::export::title_block(Point2::new(max.x, min.y), &metadata, scale);
+        if with_scale_bar {
+            geometries.extend(crate::export::scale_bar(Point2::new(min.x + 5.0, min.y + 5.0), scale));
+        }
+        let count = geometries.len();
+        for geometry in geometries {
+            layout.add_paper_entity(Entity::new(geometry));
+        }
+        self.modified = true;
+        count
+    }
+
+    /// 将另一个文档的实体插入到本文档
+    ///
+    /// 实体分配新ID，先按 `scale` 缩放、`rotation`（弧度）旋转，再平移到 `at`。
+    /// 图层按名称合并：同名图层沿用本文档的图层，缺少的图层连同属性一起复制过来。
+    /// 返回新实体的ID。
+    pub fn insert_document(
+        &mut self,
+        other: &Document,
+        at: Point2,
+        scale: f64,
+        rotation: f64,
+    ) -> Vec<EntityId> {
+        let transform = Transform2D::translation(at.x, at.y)
+            .then(&Transform2D::rotation(rotation))
+            .then(&Transform2D::uniform_scale(scale));
+
+        self.merge_entities_from(other)
+            .into_iter()
+            .map(|mut entity| {
+                entity.geometry = transform.transform_geometry(&entity.geometry);
+                self.add_entity(entity)
+            })
+            .collect()
+    }
+
+    /// 将另一个文档定义为块（基点为原点），图层按名称合并
+    ///
+    /// 之后可通过 `BlockReference` 以任意插入点、比例和旋转分解到文档中。
+    /// 同名块已存在时返回 false。
+    pub fn insert_document_as_block(&mut self, other: &Document, name: &str) -> bool {
+        if self.blocks.contains(name) {
+            return false;
+        }
+        let mut block = Block::new(name, Point2::origin());
+        block.add_entities(self.merge_entities_from(other));
+        self.blocks.add_block(block)
+    }
+
+    /// 复制另一个文档的实体（新ID），并把图层映射到本文档中的同名图层
+    fn merge_entities_from(&mut self, other: &Document) -> Vec<Entity> {
+        let mut layer_map: HashMap<EntityId, EntityId> = HashMap::new();
+        let mut layers_added = Vec::new();
+        for layer in other.layers.all_layers() {
+            let id = match self.layers.get_layer(&layer.name) {
+                Some(existing) => existing.id,
+                None => {
+                    let id = self.layers.add_layer(Layer { id: EntityId::new(), ..layer.clone() });
+                    layers_added.push(id);
+                    id
+                }
+            };
+            layer_map.insert(layer.id, id);
+        }
+        for id in layers_added {
+            self.notify_layer_changed(id);
+        }
+
+        let default_layer = self.layers.current_layer().id;
+        other
+            .all_entities()
+            .map(|entity| {
+                let mut copy = entity.duplicate();
+                copy.layer_id = layer_map.get(&entity.layer_id).copied().unwrap_or(default_layer);
+                copy
+            })
+            .collect()
+    }
+
+    /// 取出自上次调用以来累积的变更事件
+    pub fn drain_changes(&mut self) -> Vec<ChangeEvent> {
+        std::mem::take(&mut self.changes)
+    }
+
+    /// 文档版本号
+    ///
+    /// 任何产生变更事件的修改都会使其递增，可作为捕捉等派生结果缓存的键。
+    pub fn revision(&self) -> u64 {
+        self.revision
+    }
+
+    /// 显示版本号：文档版本号与各图层开关、冻结状态的组合摘要
+    ///
+    /// 图层可以经由公开的 `layers` 直接修改而不递增版本号，依赖实体是否显示的缓存
+    /// （如只在显示实体上搜索的捕捉）应以此为键。
+    pub fn display_revision(&self) -> u64 {
+        use std::hash::{Hash, Hasher};
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        self.revision.hash(&mut hasher);
+        for layer in self.layers.all_layers() {
+            (layer.id, layer.visible, layer.frozen).hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// 记录变更事件并递增版本号
+    fn record_change(&mut self, event: ChangeEvent) {
+        self.changes.push(event);
+        self.revision += 1;
     }
 
     /// 查询矩形区域内的实体
@@ -197,17 +887,197 @@ impl Document {
             .collect()
     }
 
+    /// 框选查询
+    ///
+    /// `crossing` 为 false 时是窗口选择，只返回包围盒完全位于框内的实体；
+    /// 为 true 时是交叉选择，返回包围盒与框相交的实体。
+    pub fn query_box(&self, rect: &BoundingBox2, crossing: bool) -> Vec<&Entity> {
+        self.query_rect(rect)
+            .into_iter()
+            .filter(|e| {
+                if crossing {
+                    return true;
+                }
+                let bbox = e.bounding_box();
+                rect.contains(&bbox.min) && rect.contains(&bbox.max)
+            })
+            .collect()
+    }
+
+    /// 视口裁剪查询：与可见区域相交的实体
+    ///
+    /// 渲染和捕捉每帧只需处理屏幕上的实体，不必遍历整个文档。
+    pub fn query_view(&self, view: &BoundingBox2) -> Vec<&Entity> {
+        self.query_box(view, true)
+    }
+
     /// 查询点附近的实体
+    ///
+    /// 结果按拾取优先级排序：离光标最近的曲线在前；距离相同时包围盒较小的在前，
+    /// 使叠在大实体上的小实体可以被选中。
     pub fn query_point(&self, point: &zcad_core::math::Point2, tolerance: f64) -> Vec<&Entity> {
         let rect = BoundingBox2::new(
             zcad_core::math::Point2::new(point.x - tolerance, point.y - tolerance),
             zcad_core::math::Point2::new(point.x + tolerance, point.y + tolerance),
         );
 
-        self.query_rect(&rect)
+        let mut hits: Vec<(f64, f64, &Entity)> = self
+            .query_rect(&rect)
             .into_iter()
             .filter(|e| e.geometry.contains_point(point, tolerance))
-            .collect()
+            .map(|e| {
+                let bbox = e.bounding_box();
+                (e.geometry.distance_to_point(point), bbox.width() * bbox.height(), e)
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            a.0.total_cmp(&b.0)
+                .then(a.1.total_cmp(&b.1))
+                .then(a.2.id.id.cmp(&b.2.id.id))
+        });
+        hits.into_iter().map(|(_, _, e)| e).collect()
+    }
+
+    /// 拾取点下的实体（点选和悬停预高亮共用）
+    ///
+    /// 按 `query_point` 的优先级取第一个显示的实体（见 [`Self::is_displayed`]），
+    /// 只检查光标附近的空间索引网格，可以每帧调用。
+    pub fn pick_entity(&self, point: &zcad_core::math::Point2, tolerance: f64) -> Option<&Entity> {
+        self.query_point(point, tolerance).into_iter().find(|e| self.is_displayed(e))
+    }
+
+    /// 按条件筛选实体
+    ///
+    /// 结果按实体 ID 排序，供脚本和面板得到稳定的顺序。
+    pub fn find<F: Fn(&Entity) -> bool>(&self, pred: F) -> Vec<&Entity> {
+        let mut found: Vec<&Entity> = self.entities.values().filter(|e| pred(e)).collect();
+        found.sort_by_key(|e| e.id.id);
+        found
+    }
+
+    /// 指定图层上的实体（未指定图层的实体视为在图层0上）
+    pub fn entities_on_layer(&self, name: &str) -> Vec<&Entity> {
+        self.find(|e| self.entity_layer(e).name == name)
+    }
+
+    /// 指定几何类型的实体，类型名同 [`Geometry::type_name`]，不区分大小写
+    pub fn entities_of_type(&self, type_name: &str) -> Vec<&Entity> {
+        self.find(|e| e.geometry.type_name().eq_ignore_ascii_case(type_name))
+    }
+
+    /// 所有编组
+    pub fn groups(&self) -> &HashMap<String, Vec<EntityId>> {
+        &self.groups
+    }
+
+    /// 创建编组，同名编组被替换；不存在的实体ID被忽略，返回编组成员数
+    pub fn add_group(&mut self, name: impl Into<String>, ids: impl IntoIterator<Item = EntityId>) -> usize {
+        let mut members: Vec<EntityId> = Vec::new();
+        for id in ids {
+            if self.entities.contains_key(&id) && !members.contains(&id) {
+                members.push(id);
+            }
+        }
+        let count = members.len();
+        self.groups.insert(name.into(), members);
+        self.modified = true;
+        count
+    }
+
+    /// 删除编组（不删除其中的实体）
+    pub fn remove_group(&mut self, name: &str) -> Option<Vec<EntityId>> {
+        let removed = self.groups.remove(name);
+        if removed.is_some() {
+            self.modified = true;
+        }
+        removed
+    }
+
+    /// 编组中的实体ID，用于重新选择；编组不存在时为空
+    pub fn select_group(&self, name: &str) -> Vec<EntityId> {
+        self.groups.get(name).cloned().unwrap_or_default()
+    }
+
+    /// 删除重合的实体，返回被删除实体的ID
+    ///
+    /// 同一图层上几何在容差内相等（见 [`Geometry::approx_eq`]）的实体只保留最早创建的一个。
+    /// 只与空间索引中包围盒外扩 `tol` 后相交的实体比较。
+    pub fn remove_duplicates(&mut self, tol: f64) -> Vec<EntityId> {
+        let mut duplicates = Vec::new();
+        let mut removed = HashSet::new();
+        for entity in self.find(|_| true) {
+            let layer = self.entity_layer(entity).name.as_str();
+            let duplicate = self
+                .query_rect(&entity.bounding_box().expanded(tol))
+                .into_iter()
+                .filter(|k| k.id.id < entity.id.id && !removed.contains(&k.id))
+                .any(|k| self.entity_layer(k).name == layer && k.geometry.approx_eq(&entity.geometry, tol));
+            if duplicate {
+                duplicates.push(entity.id);
+                removed.insert(entity.id);
+            }
+        }
+        for id in &duplicates {
+            self.remove_entity(id);
+        }
+        duplicates
+    }
+
+    /// 检查几何错误（零长度直线、零半径圆和圆弧、多段线重复顶点、NaN 坐标），不修改文档
+    pub fn audit(&self) -> AuditReport {
+        let mut report = AuditReport::default();
+        for entity in self.find(|_| true) {
+            match audit_geometry(&entity.geometry) {
+                Some(AuditIssue::NonFinite) => report.non_finite.push(entity.id),
+                Some(AuditIssue::ZeroLengthLine) => report.zero_length_lines.push(entity.id),
+                Some(AuditIssue::DegenerateArc) => report.degenerate_arcs.push(entity.id),
+                Some(AuditIssue::DuplicateVertices(_, count)) => {
+                    report.duplicate_vertices.push((entity.id, count))
+                }
+                None => {}
+            }
+        }
+        report
+    }
+
+    /// 检查并修复几何错误
+    ///
+    /// 删除含 NaN 坐标的实体、零长度直线和零半径的圆与圆弧，合并多段线的相邻重复顶点
+    /// （合并后不足两个顶点的多段线也被删除）。返回修复前的审核结果。
+    pub fn audit_and_repair(&mut self) -> AuditReport {
+        let mut report = self.audit();
+        let mut fixes: Vec<(EntityId, Option<Geometry>)> = report
+            .non_finite
+            .iter()
+            .chain(&report.zero_length_lines)
+            .chain(&report.degenerate_arcs)
+            .map(|id| (*id, None))
+            .collect();
+        for (id, _) in &report.duplicate_vertices {
+            if let Some(AuditIssue::DuplicateVertices(polyline, _)) =
+                self.get_entity(id).and_then(|e| audit_geometry(&e.geometry))
+            {
+                let keep = polyline.vertex_count() >= 2;
+                fixes.push((*id, keep.then_some(Geometry::Polyline(polyline))));
+            }
+        }
+
+        for (id, geometry) in fixes {
+            match geometry {
+                Some(geometry) => {
+                    if let Some(mut entity) = self.get_entity(&id).cloned() {
+                        entity.geometry = geometry;
+                        self.update_entity(&id, entity);
+                    }
+                }
+                None => {
+                    self.remove_entity(&id);
+                    report.removed.push(id);
+                }
+            }
+        }
+        report.repaired = true;
+        report
     }
 
     /// 获取所有实体
@@ -221,7 +1091,41 @@ impl Document {
     }
 
     /// 计算所有实体的包围盒
+    ///
+    /// 结果被缓存，增删改实体时增量维护或作废，`zoom_to_fit` 等频繁调用无需每次遍历。
     pub fn bounds(&self) -> Option<BoundingBox2> {
+        *self.bounds_cache.get_or_init(|| self.scan_bounds())
+    }
+
+    /// 实体包围盒变化后维护总范围缓存
+    ///
+    /// 新包围盒只会扩大范围，直接合并；旧包围盒贴着范围边界时，
+    /// 移除它可能使范围缩小，此时作废缓存，下次调用 `bounds` 时重新计算。
+    fn update_bounds_cache(&mut self, old: Option<BoundingBox2>, new: Option<BoundingBox2>) {
+        let Some(&cached) = self.bounds_cache.get() else {
+            return;
+        };
+        let shrinks = match (old, cached) {
+            (Some(old), Some(bounds)) => {
+                old.min.x <= bounds.min.x
+                    || old.min.y <= bounds.min.y
+                    || old.max.x >= bounds.max.x
+                    || old.max.y >= bounds.max.y
+            }
+            _ => false,
+        };
+        self.bounds_cache = OnceLock::new();
+        if !shrinks {
+            let bounds = match (cached, new) {
+                (Some(bounds), Some(new)) => Some(bounds.union(&new)),
+                (bounds, new) => bounds.or(new),
+            };
+            let _ = self.bounds_cache.set(bounds);
+        }
+    }
+
+    /// 遍历所有实体计算总范围
+    fn scan_bounds(&self) -> Option<BoundingBox2> {
         let mut iter = self.entities.values();
         let first = iter.next()?;
         let mut bbox = first.bounding_box();
@@ -233,6 +1137,34 @@ impl Document {
         Some(bbox)
     }
 
+    /// 统计图纸信息（一次遍历所有实体）
+    pub fn statistics(&self) -> DrawingStats {
+        let mut stats = DrawingStats {
+            entity_count: self.entities.len(),
+            layer_count: self.layers.count(),
+            ..Default::default()
+        };
+
+        for entity in self.entities.values() {
+            *stats
+                .counts_by_type
+                .entry(entity.geometry.type_name())
+                .or_insert(0) += 1;
+            // 填充边界通常与已有对象重合，不重复计入
+            if !matches!(entity.geometry, Geometry::Hatch(_)) {
+                stats.total_length += entity.geometry.perimeter_or_length();
+            }
+
+            let bbox = entity.bounding_box();
+            stats.bounds = Some(match stats.bounds {
+                Some(bounds) => bounds.union(&bbox),
+                None => bbox,
+            });
+        }
+
+        stats
+    }
+
     /// 是否已修改
     pub fn is_modified(&self) -> bool {
         self.modified
@@ -258,11 +1190,20 @@ impl Document {
         &mut self.entities
     }
 
-    /// 重建空间索引
+    /// 重建空间索引（同时重建关联填充索引）
     pub fn rebuild_spatial_index(&mut self) {
+        self.bounds_cache = OnceLock::new();
         self.spatial_index.clear();
+        self.hatch_dependents.clear();
+        let mut hatches = Vec::new();
         for (id, entity) in &self.entities {
             self.spatial_index.insert(*id, entity.bounding_box());
+            if let Geometry::Hatch(hatch) = &entity.geometry {
+                hatches.push((*id, hatch.boundary_entity_ids.clone()));
+            }
+        }
+        for (id, boundary_ids) in hatches {
+            self.link_hatch(id, &boundary_ids);
         }
     }
 }
@@ -273,3 +1214,769 @@ impl Default for Document {
     }
 }
 
+/// 关联填充引用的边界实体ID（非填充为空）
+fn hatch_boundary_ids(geometry: &Geometry) -> &[EntityId] {
+    match geometry {
+        Geometry::Hatch(hatch) => &hatch.boundary_entity_ids,
+        _ => &[],
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zcad_core::geometry::{Circle, Hatch, Line, Polyline, Text};
+    use zcad_core::math::Point2;
+
+    #[test]
+    fn test_delete_layer_moves_entities_to_layer_zero() {
+        let mut doc = Document::new();
+        let hidden = doc.layers.create_layer("Hidden");
+        let zero = doc.layers.get_layer("0").unwrap().id;
+        let id = doc.add_entity(
+            Entity::new(Geometry::Circle(Circle::new(Point2::new(0.0, 0.0), 1.0))).with_layer(hidden),
+        );
+        doc.drain_changes();
+        let revision = doc.revision();
+
+        assert_eq!(doc.delete_layer(hidden, None).unwrap(), 1);
+        assert!(doc.layers.get_layer("Hidden").is_none());
+        assert_eq!(doc.get_entity(&id).unwrap().layer_id, zero);
+        assert_eq!(
+            doc.drain_changes(),
+            vec![ChangeEvent::EntityModified(id), ChangeEvent::LayerChanged(hidden)]
+        );
+        // 每个变更事件都推进版本号，依赖版本号的缓存才会失效
+        assert_eq!(doc.revision(), revision + 2);
+    }
+
+    #[test]
+    fn test_audit_and_repair_fixes_imported_defects() {
+        let mut doc = Document::new();
+        let good = doc.add_entity(Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(5.0, 0.0)))));
+        let zero_line = doc.add_entity(Entity::new(Geometry::Line(Line::new(Point2::new(3.0, 3.0), Point2::new(3.0, 3.0)))));
+        let zero_arc = doc.add_entity(Entity::new(Geometry::Arc(zcad_core::geometry::Arc::new(
+            Point2::new(1.0, 1.0),
+            0.0,
+            0.0,
+            1.0,
+        ))));
+        let nan = doc.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(f64::NAN, 0.0), 2.0))));
+        let polyline = doc.add_entity(Entity::new(Geometry::Polyline(Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(10.0, 0.0), Point2::new(10.0, 5.0)],
+            false,
+        ))));
+        let collapsed = doc.add_entity(Entity::new(Geometry::Polyline(Polyline::from_points(
+            [Point2::new(7.0, 7.0), Point2::new(7.0, 7.0)],
+            false,
+        ))));
+
+        // 只检查不修改
+        let report = doc.audit();
+        assert_eq!(report.error_count(), 5);
+        assert_eq!(report.zero_length_lines, vec![zero_line]);
+        assert_eq!(report.degenerate_arcs, vec![zero_arc]);
+        assert_eq!(report.non_finite, vec![nan]);
+        assert_eq!(doc.entity_count(), 6);
+
+        let report = doc.audit_and_repair();
+        assert!(report.repaired);
+        assert_eq!(report.removed.len(), 4);
+        for id in [zero_line, zero_arc, nan, collapsed] {
+            assert!(report.removed.contains(&id) && doc.get_entity(&id).is_none());
+        }
+        assert!(report.duplicate_vertices.contains(&(polyline, 1)));
+        assert!(doc.get_entity(&good).is_some());
+        let Geometry::Polyline(fixed) = &doc.get_entity(&polyline).unwrap().geometry else {
+            panic!("应为多段线");
+        };
+        assert_eq!(fixed.vertex_count(), 3);
+        assert_eq!(doc.entity_count(), 2);
+        assert!(doc.audit().is_clean());
+    }
+
+    #[test]
+    fn test_merge_layers_reassigns_all_entities() {
+        let mut doc = Document::new();
+        let src = doc.layers.create_layer("Dim-Old");
+        let dst = doc.layers.create_layer("Dim");
+        for x in 0..3 {
+            let x = x as f64;
+            doc.add_entity(
+                Entity::new(Geometry::Line(Line::new(Point2::new(x, 0.0), Point2::new(x, 5.0)))).with_layer(src),
+            );
+        }
+        doc.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(0.0, 0.0), 1.0))).with_layer(dst));
+        doc.drain_changes();
+        let revision = doc.revision();
+
+        assert_eq!(doc.merge_layers("Dim-Old", "Dim").unwrap(), 3);
+        assert!(doc.layers.get_layer("Dim-Old").is_none());
+        assert!(doc.all_entities().all(|e| e.layer_id == dst));
+        let changes = doc.drain_changes();
+        assert_eq!(changes.len(), 4);
+        assert_eq!(changes.last(), Some(&ChangeEvent::LayerChanged(src)));
+        assert_eq!(doc.revision(), revision + 4);
+        assert!(doc.merge_layers("Dim-Old", "Dim").is_err());
+
+        // 重命名后实体仍在同一图层
+        doc.rename_layer("Dim", "Dimensions").unwrap();
+        assert_eq!(doc.entities_on_layer("Dimensions").len(), 4);
+    }
+
+    #[test]
+    fn test_insert_document_merges_layers() {
+        let mut target = Document::new();
+        let target_walls = target.layers.create_layer("Walls");
+        target.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(0.0, 0.0), 1.0))));
+
+        let mut source = Document::new();
+        let source_walls = source.layers.create_layer("Walls");
+        let doors = source.layers.create_layer("Doors");
+        source.add_entity(
+            Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0))))
+                .with_layer(source_walls),
+        );
+        source.add_entity(
+            Entity::new(Geometry::Circle(Circle::new(Point2::new(5.0, 0.0), 1.0))).with_layer(doors),
+        );
+
+        let ids = target.insert_document(&source, Point2::new(100.0, 0.0), 2.0, std::f64::consts::FRAC_PI_2);
+        assert_eq!(ids.len(), 2);
+        assert_eq!(target.entity_count(), 3);
+        assert_eq!(source.entity_count(), 2);
+
+        // 同名图层合并，缺少的图层被复制
+        assert_eq!(target.layers.count(), 3);
+        let target_doors = target.layers.get_layer("Doors").unwrap().id;
+        for id in &ids {
+            let entity = target.get_entity(id).unwrap();
+            match &entity.geometry {
+                Geometry::Line(line) => {
+                    assert_eq!(entity.layer_id, target_walls);
+                    // 缩放 2 倍、旋转 90°、平移到 (100, 0)
+                    assert!((line.start - Point2::new(100.0, 0.0)).norm() < 1e-9);
+                    assert!((line.end - Point2::new(100.0, 20.0)).norm() < 1e-9);
+                }
+                Geometry::Circle(circle) => {
+                    assert_eq!(entity.layer_id, target_doors);
+                    assert!((circle.center - Point2::new(100.0, 10.0)).norm() < 1e-9);
+                    assert!((circle.radius - 2.0).abs() < 1e-9);
+                }
+                other => panic!("意外的几何类型: {:?}", other),
+            }
+        }
+
+        // 作为块插入
+        assert!(target.insert_document_as_block(&source, "SOURCE"));
+        assert_eq!(target.blocks.get_block("SOURCE").unwrap().entity_count(), 2);
+        assert!(!target.insert_document_as_block(&source, "SOURCE"));
+    }
+
+    #[test]
+    fn test_query_point_prefers_small_entity_on_top() {
+        let mut doc = Document::new();
+        let rect = doc.add_entity(Entity::new(Geometry::Polyline(Polyline::from_points(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(100.0, 0.0),
+                Point2::new(100.0, 50.0),
+                Point2::new(0.0, 50.0),
+            ],
+            true,
+        ))));
+        // 小圆骑在矩形底边上
+        let circle = doc.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(50.0, 0.0), 2.0))));
+
+        // 两者都恰好经过 (52, 0)：距离相同，包围盒小的圆在前
+        let hits = doc.query_point(&Point2::new(52.0, 0.0), 1.0);
+        assert_eq!(hits.iter().map(|e| e.id).collect::<Vec<_>>(), vec![circle, rect]);
+
+        // 更靠近矩形边时矩形在前
+        let hits = doc.query_point(&Point2::new(47.5, 0.1), 1.0);
+        assert_eq!(hits.iter().map(|e| e.id).collect::<Vec<_>>(), vec![rect, circle]);
+    }
+
+    #[test]
+    fn test_pick_entity_for_hover() {
+        let mut doc = Document::new();
+        let rect = doc.add_entity(Entity::new(Geometry::Polyline(Polyline::from_points(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(100.0, 0.0),
+                Point2::new(100.0, 50.0),
+                Point2::new(0.0, 50.0),
+            ],
+            true,
+        ))));
+        let circle = doc.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(50.0, 0.0), 2.0))));
+
+        // 重叠处取叠在上面的小圆，其他位置取矩形，空白处无结果
+        assert_eq!(doc.pick_entity(&Point2::new(52.0, 0.0), 1.0).map(|e| e.id), Some(circle));
+        assert_eq!(doc.pick_entity(&Point2::new(100.0, 25.0), 1.0).map(|e| e.id), Some(rect));
+        assert!(doc.pick_entity(&Point2::new(50.0, 25.0), 1.0).is_none());
+
+        // 隐藏的实体不参与拾取
+        doc.get_entity_mut(&circle).unwrap().visible = false;
+        assert_eq!(doc.pick_entity(&Point2::new(52.0, 0.0), 1.0).map(|e| e.id), Some(rect));
+
+        // 关闭或冻结图层上的实体同样不参与拾取
+        doc.get_entity_mut(&circle).unwrap().visible = true;
+        let off = doc.layers.add_layer(Layer::new("Off"));
+        doc.get_entity_mut(&circle).unwrap().layer_id = off;
+        doc.layers.get_layer_mut("Off").unwrap().visible = false;
+        assert_eq!(doc.pick_entity(&Point2::new(52.0, 0.0), 1.0).map(|e| e.id), Some(rect));
+        doc.layers.get_layer_mut("Off").unwrap().visible = true;
+        doc.layers.get_layer_mut("Off").unwrap().frozen = true;
+        assert_eq!(doc.pick_entity(&Point2::new(52.0, 0.0), 1.0).map(|e| e.id), Some(rect));
+    }
+
+    #[test]
+    fn test_change_events() {
+        let mut doc = Document::new();
+        let a = doc.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(0.0, 0.0), 1.0))));
+        let b = doc.duplicate_entity(&a).unwrap();
+        doc.get_entity_mut(&a).unwrap().visible = false;
+        let mut moved = doc.get_entity(&b).unwrap().clone();
+        moved.geometry = Geometry::Circle(Circle::new(Point2::new(3.0, 0.0), 1.0));
+        doc.update_entity(&b, moved);
+        doc.remove_entity(&a);
+        // 删除不存在的实体不产生事件
+        doc.remove_entity(&a);
+        let layer_id = doc.layers.create_layer("新图层");
+        doc.notify_layer_changed(layer_id);
+
+        assert_eq!(
+            doc.drain_changes(),
+            vec![
+                ChangeEvent::EntityAdded(a),
+                ChangeEvent::EntityAdded(b),
+                ChangeEvent::EntityModified(a),
+                ChangeEvent::EntityModified(b),
+                ChangeEvent::EntityRemoved(a),
+                ChangeEvent::LayerChanged(layer_id),
+            ]
+        );
+
+        // 取出后清空
+        assert!(doc.drain_changes().is_empty());
+    }
+
+    #[test]
+    fn test_display_revision_tracks_direct_layer_edits() {
+        let mut doc = Document::new();
+        doc.layers.create_layer("Hidden");
+        let (revision, display) = (doc.revision(), doc.display_revision());
+        assert_eq!(doc.display_revision(), display);
+
+        // 直接修改图层不递增版本号，但显示版本号随之变化
+        doc.layers.get_layer_mut("Hidden").unwrap().visible = false;
+        assert_eq!(doc.revision(), revision);
+        assert_ne!(doc.display_revision(), display);
+        let hidden = doc.display_revision();
+        doc.layers.get_layer_mut("Hidden").unwrap().frozen = true;
+        assert_ne!(doc.display_revision(), hidden);
+    }
+
+    #[test]
+    fn test_delete_skips_locked_entities() {
+        let mut doc = Document::new();
+        let free = doc.add_entity(Entity::new(Geometry::Circle(Circle::new(
+            Point2::new(0.0, 0.0),
+            1.0,
+        ))));
+        let mut locked = Entity::new(Geometry::Circle(Circle::new(Point2::new(5.0, 0.0), 1.0)));
+        locked.locked = true;
+        let locked = doc.add_entity(locked);
+
+        let removed = doc.delete_entities(&[free, locked]);
+        assert_eq!(removed.len(), 1);
+        assert_eq!(removed[0].id, free);
+        assert!(doc.get_entity(&free).is_none());
+        assert!(doc.get_entity(&locked).is_some());
+    }
+
+    #[test]
+    fn test_query_box_window_and_crossing() {
+        let mut doc = Document::new();
+        let inside = doc.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(1.0, 1.0),
+            Point2::new(4.0, 4.0),
+        ))));
+        let partial = doc.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(5.0, 5.0),
+            Point2::new(20.0, 5.0),
+        ))));
+        doc.add_entity(Entity::new(Geometry::Circle(Circle::new(
+            Point2::new(50.0, 50.0),
+            1.0,
+        ))));
+
+        let rect = BoundingBox2::new(Point2::new(0.0, 0.0), Point2::new(10.0, 10.0));
+
+        let window: Vec<EntityId> = doc.query_box(&rect, false).iter().map(|e| e.id).collect();
+        assert_eq!(window, vec![inside]);
+
+        let crossing: Vec<EntityId> = doc.query_box(&rect, true).iter().map(|e| e.id).collect();
+        assert_eq!(crossing.len(), 2);
+        assert!(crossing.contains(&inside) && crossing.contains(&partial));
+    }
+
+    #[test]
+    fn test_query_view_culls_offscreen_entities() {
+        let mut doc = Document::new();
+        let on_screen = doc.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(10.0, 10.0), 2.0))));
+        // 穿过视图但端点都在视图外
+        let crossing = doc.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(-500.0, 20.0),
+            Point2::new(500.0, 20.0),
+        ))));
+        for i in 0..50 {
+            let x = 1000.0 + i as f64 * 10.0;
+            doc.add_entity(Entity::new(Geometry::Line(Line::new(Point2::new(x, 0.0), Point2::new(x, 5.0)))));
+        }
+
+        let view = BoundingBox2::new(Point2::new(0.0, 0.0), Point2::new(40.0, 30.0));
+        let mut visible: Vec<EntityId> = doc.query_view(&view).iter().map(|e| e.id).collect();
+        visible.sort_by_key(|id| id.id);
+        let mut expected = vec![on_screen, crossing];
+        expected.sort_by_key(|id| id.id);
+        assert_eq!(visible, expected);
+
+        // 缩小到能看到全部实体
+        let all = BoundingBox2::new(Point2::new(-1.0e6, -1.0e6), Point2::new(1.0e6, 1.0e6));
+        assert_eq!(doc.query_view(&all).len(), doc.entity_count());
+    }
+
+    #[test]
+    fn test_new_entity_on_red_layer_renders_red() {
+        let mut doc = Document::new();
+        doc.layers.add_layer(
+            Layer::new("Walls")
+                .with_color(Color::RED)
+                .with_line_type(LineType::Dashed),
+        );
+        doc.layers.set_current_layer("Walls");
+
+        let line = doc.new_entity(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0))));
+        assert!(line.properties.color.is_by_layer());
+        let id = doc.add_entity(line);
+        let line = doc.get_entity(&id).unwrap();
+        assert_eq!(doc.effective_color(line), Color::RED);
+        assert_eq!(doc.resolve_properties(line).line_type, LineType::Dashed);
+
+        // 修改图层颜色后随之变化；显式颜色不受图层影响
+        doc.layers.get_layer_mut("Walls").unwrap().color = Color::BLUE;
+        assert_eq!(doc.effective_color(doc.get_entity(&id).unwrap()), Color::BLUE);
+        let mut green = doc.new_entity(Geometry::Circle(Circle::new(Point2::origin(), 1.0)));
+        green.properties.color = Color::GREEN;
+        assert_eq!(doc.effective_color(&green), Color::GREEN);
+    }
+
+    #[test]
+    fn test_associative_hatch_follows_boundary() {
+        let mut doc = Document::new();
+        let square = |x: f64| {
+            Geometry::Polyline(Polyline::from_points(
+                [
+                    Point2::new(x, 0.0),
+                    Point2::new(x + 10.0, 0.0),
+                    Point2::new(x + 10.0, 10.0),
+                    Point2::new(x, 10.0),
+                ],
+                true,
+            ))
+        };
+        let boundary_id = doc.add_entity(Entity::new(square(0.0)));
+        let boundary = HatchBoundary::from_geometry(&square(0.0), true).unwrap();
+        let associative = doc.add_entity(Entity::new(Geometry::Hatch(
+            Hatch::solid(vec![boundary.clone()]).with_boundary_entities(vec![boundary_id]),
+        )));
+        let explicit = doc.add_entity(Entity::new(Geometry::Hatch(Hatch::solid(vec![boundary]))));
+
+        // 移动边界多段线
+        let mut entity = doc.get_entity(&boundary_id).unwrap().clone();
+        entity.geometry = square(50.0);
+        doc.update_entity(&boundary_id, entity);
+
+        let bbox = doc.get_entity(&associative).unwrap().bounding_box();
+        assert!((bbox.min.x - 50.0).abs() < 1e-9 && (bbox.max.x - 60.0).abs() < 1e-9);
+        // 空间索引同步更新
+        let view = BoundingBox2::new(Point2::new(54.0, 4.0), Point2::new(56.0, 6.0));
+        assert!(doc.query_view(&view).iter().any(|e| e.id == associative));
+        // 非关联填充保持原边界
+        let bbox = doc.get_entity(&explicit).unwrap().bounding_box();
+        assert!((bbox.max.x - 10.0).abs() < 1e-9);
+
+        // 删除边界后保留关联和最后一次生成的边界
+        let removed = doc.remove_entity(&boundary_id).unwrap();
+        let Geometry::Hatch(hatch) = &doc.get_entity(&associative).unwrap().geometry else {
+            panic!("应为填充");
+        };
+        assert!(hatch.is_associative());
+        assert!((hatch.bounding_box().min.x - 50.0).abs() < 1e-9);
+
+        // 撤销删除后填充继续跟随边界
+        doc.undo_operation(&OperationType::DeleteEntity {
+            entity_id: boundary_id,
+            previous_entity: Some(removed),
+        });
+        let mut entity = doc.get_entity(&boundary_id).unwrap().clone();
+        entity.geometry = square(100.0);
+        doc.update_entity(&boundary_id, entity);
+        let bbox = doc.get_entity(&associative).unwrap().bounding_box();
+        assert!((bbox.min.x - 100.0).abs() < 1e-9);
+
+        // 删除填充后不再跟踪
+        doc.remove_entity(&associative);
+        assert!(doc.hatch_dependents.is_empty());
+    }
+
+    #[test]
+    fn test_export_resolves_by_layer_color() {
+        let mut doc = Document::new();
+        doc.layers.add_layer(Layer::new("Green").with_color(Color::GREEN));
+        doc.layers.set_current_layer("Green");
+        let line = doc.new_entity(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0))));
+        doc.add_entity(line);
+
+        let entities = doc.export_entities();
+        assert_eq!(entities[0].properties.color, Color::GREEN);
+        let svg = crate::export::SvgExporter::new(Default::default()).export(&entities).unwrap();
+        assert!(svg.contains("rgb(0,255,0)"));
+    }
+
+    #[test]
+    fn test_export_skips_hidden_entities_and_layers() {
+        let mut doc = Document::new();
+        let line = |y: f64| Geometry::Line(Line::new(Point2::new(0.0, y), Point2::new(10.0, y)));
+        doc.add_entity(doc.new_entity(line(0.0)));
+        let mut hidden = doc.new_entity(line(1.0));
+        hidden.visible = false;
+        doc.add_entity(hidden);
+
+        doc.layers.add_layer(Layer::new("Off").with_color(Color::RED));
+        doc.layers.add_layer(Layer::new("Frozen").with_color(Color::BLUE));
+        doc.layers.set_current_layer("Off");
+        doc.add_entity(doc.new_entity(line(2.0)));
+        doc.layers.set_current_layer("Frozen");
+        doc.add_entity(doc.new_entity(line(3.0)));
+        doc.layers.set_current_layer("0");
+        doc.layers.get_layer_mut("Off").unwrap().visible = false;
+        doc.layers.get_layer_mut("Frozen").unwrap().frozen = true;
+
+        let entities = doc.export_entities();
+        assert_eq!(entities.len(), 1);
+        let svg = crate::export::SvgExporter::new(Default::default()).export(&entities).unwrap();
+        assert_eq!(svg.matches("<line").count(), 1);
+        assert!(!svg.contains("rgb(255,0,0)"));
+        assert!(!svg.contains("rgb(0,0,255)"));
+    }
+
+    #[test]
+    fn test_bounds_cache_matches_scan() {
+        let mut doc = Document::new();
+        assert!(doc.bounds().is_none());
+
+        let line = |x1: f64, y1: f64, x2: f64, y2: f64| {
+            Entity::new(Geometry::Line(Line::new(Point2::new(x1, y1), Point2::new(x2, y2))))
+        };
+        let same = |a: Option<BoundingBox2>, b: Option<BoundingBox2>| match (a, b) {
+            (Some(a), Some(b)) => a.min == b.min && a.max == b.max,
+            (None, None) => true,
+            _ => false,
+        };
+
+        let inner = doc.add_entity(line(0.0, 0.0, 10.0, 10.0));
+        assert!(same(doc.bounds(), doc.scan_bounds()));
+        let outer = doc.add_entity(line(-50.0, -20.0, 100.0, 40.0));
+        let middle = doc.add_entity(line(5.0, 5.0, 20.0, 20.0));
+        assert!(same(doc.bounds(), doc.scan_bounds()));
+        assert_eq!(doc.bounds().unwrap().max, Point2::new(100.0, 40.0));
+
+        // 移除内部实体不影响范围；移除边界实体后范围缩小
+        doc.remove_entity(&middle);
+        assert!(same(doc.bounds(), doc.scan_bounds()));
+        doc.remove_entity(&outer);
+        assert!(same(doc.bounds(), doc.scan_bounds()));
+        assert_eq!(doc.bounds().unwrap().max, Point2::new(10.0, 10.0));
+
+        // 修改实体：增大和缩小
+        let mut entity = doc.get_entity(&inner).unwrap().clone();
+        entity.geometry = Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(30.0, 5.0)));
+        doc.update_entity(&inner, entity);
+        assert!(same(doc.bounds(), doc.scan_bounds()));
+        doc.get_entity_mut(&inner).unwrap().geometry =
+            Geometry::Line(Line::new(Point2::new(1.0, 1.0), Point2::new(2.0, 2.0)));
+        assert!(same(doc.bounds(), doc.scan_bounds()));
+
+        doc.remove_entity(&inner);
+        assert!(doc.bounds().is_none());
+    }
+
+    #[test]
+    fn test_statistics_counts() {
+        let mut doc = Document::new();
+        doc.layers.create_layer("标注");
+
+        doc.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 0.0),
+        ))));
+        doc.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(0.0, 5.0),
+        ))));
+        doc.add_entity(Entity::new(Geometry::Circle(Circle::new(
+            Point2::new(20.0, 0.0),
+            1.0,
+        ))));
+        doc.add_entity(Entity::new(Geometry::Polyline(Polyline::from_points(
+            [Point2::new(0.0, 10.0), Point2::new(3.0, 10.0), Point2::new(3.0, 14.0)],
+            false,
+        ))));
+        doc.add_entity(Entity::new(Geometry::Text(Text::new(
+            Point2::new(-5.0, -5.0),
+            "注释",
+            2.5,
+        ))));
+
+        let stats = doc.statistics();
+        assert_eq!(stats.entity_count, 5);
+        assert_eq!(stats.layer_count, 2);
+        assert_eq!(stats.count_of("Line"), 2);
+        assert_eq!(stats.count_of("Circle"), 1);
+        assert_eq!(stats.count_of("Polyline"), 1);
+        assert_eq!(stats.count_of("Text"), 1);
+        assert_eq!(stats.count_of("Arc"), 0);
+
+        // 10 + 5 + 2π + 7，文字不计入长度
+        let expected = 22.0 + 2.0 * std::f64::consts::PI;
+        assert!((stats.total_length - expected).abs() < 1e-9);
+
+        let bounds = stats.bounds.unwrap();
+        assert!((bounds.max.x - 21.0).abs() < 1e-9);
+        assert!((bounds.max.y - 14.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_find_by_layer_and_type() {
+        let mut doc = Document::new();
+        let walls = doc.layers.create_layer("Walls");
+        let wall = doc.add_entity(
+            Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0))))
+                .with_layer(walls),
+        );
+        let line = doc.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(0.0, 5.0),
+            Point2::new(10.0, 5.0),
+        ))));
+        let circle = doc.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(20.0, 0.0), 1.0))));
+
+        let ids = |entities: Vec<&Entity>| entities.iter().map(|e| e.id).collect::<Vec<_>>();
+        assert_eq!(ids(doc.entities_on_layer("Walls")), vec![wall]);
+        // 未指定图层的实体归入图层0
+        assert_eq!(ids(doc.entities_on_layer("0")), vec![line, circle]);
+        assert!(doc.entities_on_layer("Missing").is_empty());
+
+        assert_eq!(ids(doc.entities_of_type("Line")), vec![wall, line]);
+        assert_eq!(ids(doc.entities_of_type("circle")), vec![circle]);
+        assert_eq!(ids(doc.find(|e| e.geometry.perimeter_or_length() > 8.0)), vec![wall, line]);
+    }
+
+    #[test]
+    fn test_remove_duplicates() {
+        let mut doc = Document::new();
+        let original = doc.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 0.0),
+        ))));
+        let copy = doc.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(10.0, 1e-9),
+            Point2::new(0.0, 0.0),
+        ))));
+        // 其他图层上的重合线段保留
+        let other = doc.layers.create_layer("Other");
+        let elsewhere = doc.add_entity(
+            Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0)))).with_layer(other),
+        );
+
+        assert_eq!(doc.remove_duplicates(1e-6), vec![copy]);
+        assert!(doc.get_entity(&original).is_some());
+        assert!(doc.get_entity(&elsewhere).is_some());
+        assert_eq!(doc.entity_count(), 2);
+        assert!(doc.remove_duplicates(1e-6).is_empty());
+    }
+
+    #[test]
+    fn test_group_prunes_deleted_members() {
+        let mut doc = Document::new();
+        let a = doc.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(0.0, 0.0), 1.0))));
+        let b = doc.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(5.0, 0.0), 1.0))));
+
+        // 不存在的ID不会加入编组
+        assert_eq!(doc.add_group("Bolts", [a, b, EntityId::new()]), 2);
+        assert_eq!(doc.select_group("Bolts"), vec![a, b]);
+
+        let removed = doc.remove_entity(&a);
+        assert_eq!(doc.select_group("Bolts"), vec![b]);
+        assert!(!doc.groups()["Bolts"].contains(&a));
+
+        // 撤销删除后实体回到原编组的原位置
+        doc.undo_operation(&OperationType::DeleteEntity {
+            entity_id: a,
+            previous_entity: removed,
+        });
+        assert_eq!(doc.select_group("Bolts"), vec![a, b]);
+        doc.remove_entity(&a);
+
+        assert_eq!(doc.remove_group("Bolts"), Some(vec![b]));
+        assert!(doc.select_group("Bolts").is_empty());
+        assert!(doc.get_entity(&b).is_some());
+    }
+
+    #[test]
+    fn test_revision_increments_on_change() {
+        let mut doc = Document::new();
+        let start = doc.revision();
+        let id = doc.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(0.0, 0.0), 1.0))));
+        assert!(doc.revision() > start);
+
+        // 只读访问不改变版本号
+        let after_add = doc.revision();
+        doc.get_entity(&id);
+        doc.bounds();
+        assert_eq!(doc.revision(), after_add);
+
+        doc.remove_entity(&id);
+        assert!(doc.revision() > after_add);
+    }
+
+    #[test]
+    fn test_duplicate_entity() {
+        let mut doc = Document::new();
+        let id = doc.add_entity(Entity::new(Geometry::Circle(Circle::new(
+            Point2::new(1.0, 2.0),
+            3.0,
+        ))));
+
+        let copy_id = doc.duplicate_entity(&id).unwrap();
+        assert_ne!(copy_id, id);
+        assert_eq!(doc.entity_count(), 2);
+
+        let original = doc.get_entity(&id).unwrap();
+        let copy = doc.get_entity(&copy_id).unwrap();
+        assert_eq!(copy.id, copy_id);
+        assert_eq!(copy.properties, original.properties);
+        match (&copy.geometry, &original.geometry) {
+            (Geometry::Circle(a), Geometry::Circle(b)) => {
+                assert_eq!(a.center, b.center);
+                assert_eq!(a.radius, b.radius);
+            }
+            _ => panic!("几何类型应保持一致"),
+        }
+
+        assert!(doc.duplicate_entity(&EntityId::new()).is_none());
+    }
+
+    #[test]
+    fn test_statistics_empty() {
+        let stats = Document::new().statistics();
+        assert_eq!(stats.entity_count, 0);
+        assert!(stats.counts_by_type.is_empty());
+        assert!(stats.bounds.is_none());
+        assert_eq!(stats.total_length, 0.0);
+    }
+
+    #[test]
+    fn test_save_as_failure_leaves_state_untouched() {
+        let mut doc = Document::new();
+        doc.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(0.0, 0.0), 1.0))));
+        assert!(doc.is_modified());
+
+        // 目录不存在，临时文件无法创建
+        let missing = std::env::temp_dir().join("zcad_missing_dir").join("drawing.zcad");
+        assert!(doc.save_as(&missing).is_err());
+        assert!(doc.file_path().is_none());
+        assert!(doc.is_modified());
+
+        // 目标是已存在的目录，写入临时文件成功但重命名失败，临时文件被清理
+        let dir = std::env::temp_dir().join("zcad_save_as_target.zcad");
+        std::fs::create_dir_all(&dir).unwrap();
+        assert!(doc.save_as(&dir).is_err());
+        assert!(doc.file_path().is_none());
+        assert!(doc.is_modified());
+        assert!(!std::env::temp_dir().join(".zcad_save_as_target.zcad.tmp").exists());
+        std::fs::remove_dir(&dir).ok();
+    }
+
+    #[test]
+    fn test_save_as_updates_path_and_clears_modified() {
+        let mut doc = Document::new();
+        doc.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(0.0, 0.0), 1.0))));
+        let path = std::env::temp_dir().join("test_save_as_atomic.zcad");
+
+        doc.save_as(&path).expect("Failed to save");
+        assert_eq!(doc.file_path(), Some(path.as_path()));
+        assert!(!doc.is_modified());
+        assert!(!std::env::temp_dir().join(".test_save_as_atomic.zcad.tmp").exists());
+        assert_eq!(Document::open(&path).unwrap().entity_count(), 1);
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_convert_dxf_to_svg() {
+        let dxf = "0\nSECTION\n2\nENTITIES\n\
+0\nLINE\n8\n0\n10\n0.0\n20\n0.0\n30\n0.0\n11\n100.0\n21\n50.0\n31\n0.0\n\
+0\nCIRCLE\n8\n0\n10\n50.0\n20\n25.0\n30\n0.0\n40\n10.0\n\
+0\nENDSEC\n0\nEOF\n";
+        let input = std::env::temp_dir().join("test_convert_input.dxf");
+        let output = std::env::temp_dir().join("test_convert_output.svg");
+        std::fs::write(&input, dxf).unwrap();
+
+        Document::convert_file(
+            &input,
+            &output,
+            crate::export::ExportFormat::Svg,
+            crate::export::PageSetup::default(),
+        )
+        .expect("Failed to convert");
+        let svg = std::fs::read_to_string(&output).unwrap();
+        std::fs::remove_file(&input).ok();
+        std::fs::remove_file(&output).ok();
+
+        // 结构完整：XML 声明、唯一的 svg 根元素、分组成对闭合
+        assert!(svg.starts_with("<?xml"));
+        assert_eq!(svg.matches("<svg").count(), 1);
+        assert!(svg.trim_end().ends_with("</svg>"));
+        assert_eq!(svg.matches("<g").count(), svg.matches("</g>").count());
+        assert_eq!(svg.matches("<line").count(), 1);
+        assert_eq!(svg.matches("<circle").count(), 1);
+    }
+
+    #[test]
+    fn test_insert_title_block_into_paper_space() {
+        let mut doc = Document::new();
+        doc.metadata.title = "一层平面图".to_string();
+        let layout = doc.layout_manager.add_layout("布局1");
+        let inserted = doc.insert_title_block(layout, 100.0, true);
+        assert!(inserted > 0);
+        assert_eq!(doc.insert_title_block(LayoutId::new(999), 100.0, true), 0);
+
+        let layout = doc.layout_manager.get_layout(layout).unwrap();
+        assert_eq!(layout.paper_space_entities.len(), inserted);
+        let texts: Vec<&str> = layout
+            .paper_space_entities
+            .iter()
+            .filter_map(|e| match &e.geometry {
+                Geometry::Text(text) => Some(text.content.as_str()),
+                _ => None,
+            })
+            .collect();
+        assert!(texts.contains(&"一层平面图"));
+        assert!(texts.contains(&"1:100"));
+        // 模型空间不受影响
+        assert_eq!(doc.entity_count(), 0);
+    }
+}
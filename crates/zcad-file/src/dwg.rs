@@ -0,0 +1,86 @@
+//! DWG 导入钩子
+//!
+//! ZCAD 本身不解析 DWG。外部转换器（如基于 ODA/Teigha 的工具）实现 [`DwgConverter`]
+//! 并通过 [`register_dwg_converter`] 注册后，`Document::open` 打开 `.dwg` 时交由它处理；
+//! 未注册时返回 [`FileError::Unsupported`]。
+
+use crate::document::Document;
+use crate::error::FileError;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+/// DWG 转换器
+pub trait DwgConverter: Send + Sync {
+    /// 将 DWG 文件转换为文档
+    fn convert(&self, path: &Path) -> Result<Document, FileError>;
+}
+
+/// 当前注册的转换器
+static CONVERTER: RwLock<Option<Arc<dyn DwgConverter>>> = RwLock::new(None);
+
+/// 注册 DWG 转换器，返回之前注册的转换器
+pub fn register_dwg_converter(converter: Arc<dyn DwgConverter>) -> Option<Arc<dyn DwgConverter>> {
+    CONVERTER
+        .write()
+        .unwrap_or_else(|e| e.into_inner())
+        .replace(converter)
+}
+
+/// 取消注册 DWG 转换器
+pub fn unregister_dwg_converter() -> Option<Arc<dyn DwgConverter>> {
+    CONVERTER.write().unwrap_or_else(|e| e.into_inner()).take()
+}
+
+/// 导入 DWG 文件
+pub(crate) fn import(path: &Path) -> Result<Document, FileError> {
+    // 先复制出转换器再调用，避免转换期间持有锁
+    let converter = CONVERTER.read().unwrap_or_else(|e| e.into_inner()).clone();
+    match converter {
+        Some(converter) => converter.convert(path),
+        None => Err(FileError::Unsupported("DWG".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use zcad_core::entity::Entity;
+    use zcad_core::geometry::{Geometry, Line};
+    use zcad_core::math::Point2;
+
+    /// 记录调用次数并返回一条直线的转换器
+    struct MockConverter {
+        calls: AtomicUsize,
+    }
+
+    impl DwgConverter for MockConverter {
+        fn convert(&self, path: &Path) -> Result<Document, FileError> {
+            assert_eq!(path, Path::new("drawing.DWG"));
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let mut document = Document::new();
+            document.add_entity(Entity::new(Geometry::Line(Line::new(
+                Point2::new(0.0, 0.0),
+                Point2::new(10.0, 0.0),
+            ))));
+            Ok(document)
+        }
+    }
+
+    // 注册表是全局的，两种情况放在同一个测试中顺序执行
+    #[test]
+    fn test_dwg_unsupported_and_registered_converter() {
+        unregister_dwg_converter();
+        let err = Document::open("drawing.dwg").unwrap_err();
+        assert!(matches!(&err, FileError::Unsupported(format) if format == "DWG"));
+
+        let converter = Arc::new(MockConverter { calls: AtomicUsize::new(0) });
+        assert!(register_dwg_converter(converter.clone()).is_none());
+        let document = Document::open("drawing.DWG").unwrap();
+        assert_eq!(converter.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(document.entity_count(), 1);
+
+        assert!(unregister_dwg_converter().is_some());
+        assert!(matches!(Document::open("drawing.dwg"), Err(FileError::Unsupported(_))));
+    }
+}
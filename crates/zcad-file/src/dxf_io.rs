@@ -4,19 +4,24 @@
 //! - 模型空间实体
 //! - 图纸空间（Layout）
 //! - 视口（Viewport）
+//! - 扩展实体数据（XDATA）
 
 use crate::document::Document;
 use crate::dxf_raw::{DxfRawParser, DxfWriter, parse_layouts, parse_viewports};
 use crate::error::FileError;
+use std::collections::HashMap;
 use std::path::Path;
-use zcad_core::entity::Entity;
+use zcad_core::block::{Block, BlockReference};
+use zcad_core::entity::{Entity, XData, XDataValue};
 use zcad_core::geometry::{
     Arc, Circle, Ellipse, Geometry, Leader, Line, Polyline, PolylineVertex, 
     Spline, Text,
 };
 use zcad_core::layout::{Layout, PaperSize, PaperOrientation, Viewport, ViewportId, ViewportStatus};
 use zcad_core::math::{Point2, Vector2};
-use zcad_core::properties::{Color, Properties};
+use zcad_core::pointstyle::PointStyle;
+use zcad_core::properties::{Color, LineType, LineWeight, Properties};
+use zcad_core::textstyle::TextStyle;
 
 /// 从DXF文件导入
 pub fn import(path: &Path) -> Result<Document, FileError> {
@@ -24,16 +29,51 @@ pub fn import(path: &Path) -> Result<Document, FileError> {
 
     let mut document = Document::new();
 
-    // 导入图层
+    // 点样式
+    document.point_style = PointStyle::new(
+        drawing.header.point_display_mode,
+        drawing.header.point_display_size,
+    );
+
+    // 导入图层（自定义线型的图案取自 LTYPE 表）
+    let line_type_patterns: HashMap<String, Vec<f64>> = drawing
+        .line_types()
+        .map(|lt| (lt.name.to_uppercase(), lt.dash_dot_space_lengths.clone()))
+        .collect();
     for layer in drawing.layers() {
         let color = aci_to_color(layer.color.index().unwrap_or(7) as u8);
-        let new_layer = zcad_core::layer::Layer::new(&layer.name).with_color(color);
+        let mut new_layer = zcad_core::layer::Layer::new(&layer.name)
+            .with_color(color)
+            .with_line_type(dxf_layer_line_type(&layer.line_type_name, &line_type_patterns));
+        new_layer.line_weight = dxf_to_line_weight(layer.line_weight.raw_value());
         document.layers.add_layer(new_layer);
     }
 
-    // 导入模型空间实体
+    // 导入文字样式
+    for style in drawing.styles() {
+        document.text_styles.add_style(convert_dxf_style(style));
+    }
+
+    // 导入块定义
+    let dxf_blocks: HashMap<&str, &dxf::Block> = drawing
+        .blocks()
+        .filter(|b| !is_layout_block(&b.name))
+        .map(|b| (b.name.as_str(), b))
+        .collect();
+    for dxf_block in dxf_blocks.values() {
+        document.blocks.add_block(convert_dxf_block(dxf_block, &dxf_blocks, 0));
+    }
+
+    // 导入模型空间实体（块参照分解到模型空间）
     for entity in drawing.entities() {
-        if let Some(zcad_entity) = convert_dxf_entity(entity) {
+        if let dxf::entities::EntityType::Insert(insert) = &entity.specific {
+            if let Some(block) = document.blocks.get_block(&insert.name) {
+                let exploded = convert_insert(insert).explode(block);
+                for zcad_entity in exploded {
+                    document.add_entity(zcad_entity);
+                }
+            }
+        } else if let Some(zcad_entity) = convert_dxf_entity(entity) {
             document.add_entity(zcad_entity);
         }
     }
@@ -52,6 +92,31 @@ pub fn import(path: &Path) -> Result<Document, FileError> {
     Ok(document)
 }
 
+/// 将 DXF STYLE 表项转换为文字样式
+fn convert_dxf_style(style: &dxf::tables::Style) -> TextStyle {
+    let font = if style.primary_font_file_name.is_empty() {
+        TextStyle::default().font
+    } else {
+        style.primary_font_file_name.clone()
+    };
+    TextStyle {
+        name: style.name.clone(),
+        font,
+        height: style.text_height,
+        width_factor: if style.width_factor > 0.0 { style.width_factor } else { 1.0 },
+        oblique_angle: style.oblique_angle.to_radians(),
+    }
+}
+
+/// 文本实体引用的样式名称，空名称表示使用当前样式
+fn text_style_ref(name: &str) -> Option<String> {
+    if name.trim().is_empty() {
+        None
+    } else {
+        Some(name.to_string())
+    }
+}
+
 /// 完整的布局导入（使用原始解析器）
 fn import_layouts_full(
     raw_parser: &mut DxfRawParser,
@@ -342,6 +407,60 @@ fn get_entity_bounds(entity: &dxf::entities::Entity) -> Option<(f64, f64, f64, f
     }
 }
 
+/// 块嵌套的最大深度（防止循环引用）
+const MAX_BLOCK_NESTING: usize = 16;
+
+/// 模型空间/图纸空间的布局块不作为普通块导入
+fn is_layout_block(name: &str) -> bool {
+    let upper = name.to_uppercase();
+    upper.starts_with("*MODEL_SPACE") || upper.starts_with("*PAPER_SPACE")
+}
+
+/// 将DXF块参照转换为块参照
+fn convert_insert(insert: &dxf::entities::Insert) -> BlockReference {
+    BlockReference::new(&insert.name, Point2::new(insert.location.x, insert.location.y))
+        .with_scale(insert.x_scale_factor, insert.y_scale_factor)
+        .with_rotation_degrees(insert.rotation)
+        .with_array(
+            insert.column_count as u32,
+            insert.row_count as u32,
+            insert.column_spacing,
+            insert.row_spacing,
+        )
+}
+
+/// 将DXF块定义转换为块（块内嵌套的块参照直接展开）
+fn convert_dxf_block(
+    dxf_block: &dxf::Block,
+    dxf_blocks: &HashMap<&str, &dxf::Block>,
+    depth: usize,
+) -> Block {
+    let mut block = Block::new(
+        dxf_block.name.clone(),
+        Point2::new(dxf_block.base_point.x, dxf_block.base_point.y),
+    );
+    if dxf_block.name.starts_with('*') {
+        block = block.anonymous();
+    }
+
+    for entity in &dxf_block.entities {
+        if let dxf::entities::EntityType::Insert(insert) = &entity.specific {
+            if depth >= MAX_BLOCK_NESTING {
+                tracing::warn!("Block nesting too deep, skipping insert of {}", insert.name);
+                continue;
+            }
+            if let Some(nested) = dxf_blocks.get(insert.name.as_str()) {
+                let nested_block = convert_dxf_block(nested, dxf_blocks, depth + 1);
+                block.add_entities(convert_insert(insert).explode(&nested_block));
+            }
+        } else if let Some(zcad_entity) = convert_dxf_entity(entity) {
+            block.add_entity(zcad_entity);
+        }
+    }
+
+    block
+}
+
 /// 将DXF实体转换为ZCAD实体
 fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
     let geometry = match &entity.specific {
@@ -364,10 +483,19 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
         }
 
         dxf::entities::EntityType::LwPolyline(lwpoly) => {
+            // 顶点未指定宽度时使用全局宽度（组码 43）
             let vertices: Vec<PolylineVertex> = lwpoly
                 .vertices
                 .iter()
-                .map(|v| PolylineVertex::with_bulge(Point2::new(v.x, v.y), v.bulge))
+                .map(|v| {
+                    let (start_width, end_width) = if v.starting_width == 0.0 && v.ending_width == 0.0 {
+                        (lwpoly.constant_width, lwpoly.constant_width)
+                    } else {
+                        (v.starting_width, v.ending_width)
+                    };
+                    PolylineVertex::with_bulge(Point2::new(v.x, v.y), v.bulge)
+                        .with_widths(start_width, end_width)
+                })
                 .collect();
 
             Geometry::Polyline(Polyline::new(vertices, lwpoly.is_closed()))
@@ -378,6 +506,7 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
                 .vertices()
                 .map(|v| {
                     PolylineVertex::with_bulge(Point2::new(v.location.x, v.location.y), v.bulge)
+                        .with_widths(v.starting_width, v.ending_width)
                 })
                 .collect();
 
@@ -390,6 +519,7 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
             let rotation = text.rotation.to_radians();
             let mut zcad_text = Text::new(position, text.value.clone(), height);
             zcad_text.rotation = rotation;
+            zcad_text.style = text_style_ref(&text.text_style_name);
             Geometry::Text(zcad_text)
         }
 
@@ -401,6 +531,7 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
             let content = mtext.text.replace("\\P", "\n"); // 简单的换行处理
             let mut zcad_text = Text::new(position, content, height);
             zcad_text.rotation = rotation;
+            zcad_text.style = text_style_ref(&mtext.text_style_name);
             Geometry::Text(zcad_text)
         }
 
@@ -449,7 +580,9 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
                 .map(|p| Point2::new(p.x, p.y))
                 .collect();
             
-            let zcad_leader = Leader::new(vertices);
+            // 路径类型 72：0 = 直线段，1 = 样条
+            let zcad_leader = Leader::new(vertices)
+                .with_spline(matches!(leader.path_type, dxf::enums::LeaderPathType::Spline));
             
             Geometry::Leader(zcad_leader)
         }
@@ -546,21 +679,133 @@ fn convert_dxf_entity(entity: &dxf::entities::Entity) -> Option<Entity> {
 
     let properties = Properties::with_color(color);
 
-    Some(Entity::new(geometry).with_properties(properties))
+    let mut zcad_entity = Entity::new(geometry)
+        .with_properties(properties)
+        .with_elevation(dxf_elevation(&entity.specific));
+    zcad_entity.xdata = entity.common.x_data.iter().map(convert_dxf_xdata).collect();
+    Some(zcad_entity)
+}
+
+/// DXF 实体的标高：取定位点的 Z 坐标，轻量多段线取组码 38
+fn dxf_elevation(specific: &dxf::entities::EntityType) -> f64 {
+    use dxf::entities::EntityType;
+    match specific {
+        EntityType::Line(line) => line.p1.z,
+        EntityType::Circle(circle) => circle.center.z,
+        EntityType::Arc(arc) => arc.center.z,
+        EntityType::LwPolyline(lwpoly) => lwpoly.elevation,
+        EntityType::Polyline(poly) => poly.vertices().next().map_or(poly.location.z, |v| v.location.z),
+        EntityType::Text(text) => text.location.z,
+        EntityType::MText(mtext) => mtext.insertion_point.z,
+        EntityType::ModelPoint(point) => point.location.z,
+        EntityType::Ellipse(ellipse) => ellipse.center.z,
+        EntityType::Spline(spline) => spline
+            .control_points
+            .first()
+            .or(spline.fit_points.first())
+            .map_or(0.0, |p| p.z),
+        EntityType::Leader(leader) => leader.vertices.first().map_or(0.0, |p| p.z),
+        EntityType::RotatedDimension(dim) => dim.definition_point_2.z,
+        EntityType::RadialDimension(dim) => dim.dimension_base.definition_point_1.z,
+        EntityType::DiameterDimension(dim) => dim.definition_point_2.z,
+        _ => 0.0,
+    }
+}
+
+/// DXF 扩展数据转换为实体扩展数据
+fn convert_dxf_xdata(x_data: &dxf::XData) -> XData {
+    XData {
+        application: x_data.application_name.clone(),
+        items: x_data.items.iter().map(convert_dxf_xdata_item).collect(),
+    }
+}
+
+fn convert_dxf_xdata_item(item: &dxf::XDataItem) -> XDataValue {
+    match item {
+        dxf::XDataItem::Str(s) => XDataValue::String(s.clone()),
+        dxf::XDataItem::ControlGroup(items) => {
+            XDataValue::ControlGroup(items.iter().map(convert_dxf_xdata_item).collect())
+        }
+        dxf::XDataItem::LayerName(name) => XDataValue::LayerName(name.clone()),
+        dxf::XDataItem::BinaryData(data) => XDataValue::Binary(data.clone()),
+        dxf::XDataItem::Handle(handle) => XDataValue::Handle(handle.0),
+        dxf::XDataItem::ThreeReals(x, y, z) => XDataValue::ThreeReals([*x, *y, *z]),
+        dxf::XDataItem::WorldSpacePosition(p) => XDataValue::WorldPosition([p.x, p.y, p.z]),
+        dxf::XDataItem::WorldSpaceDisplacement(p) => XDataValue::WorldDisplacement([p.x, p.y, p.z]),
+        dxf::XDataItem::WorldDirection(v) => XDataValue::WorldDirection([v.x, v.y, v.z]),
+        dxf::XDataItem::Real(value) => XDataValue::Real(*value),
+        dxf::XDataItem::Distance(value) => XDataValue::Distance(*value),
+        dxf::XDataItem::ScaleFactor(value) => XDataValue::ScaleFactor(*value),
+        dxf::XDataItem::Integer(value) => XDataValue::Integer(*value),
+        dxf::XDataItem::Long(value) => XDataValue::Long(*value),
+    }
+}
+
+/// 实体扩展数据转换为 DXF 扩展数据
+fn xdata_to_dxf(x_data: &XData) -> dxf::XData {
+    dxf::XData {
+        application_name: x_data.application.clone(),
+        items: x_data.items.iter().map(xdata_item_to_dxf).collect(),
+    }
+}
+
+fn xdata_item_to_dxf(item: &XDataValue) -> dxf::XDataItem {
+    match item {
+        XDataValue::String(s) => dxf::XDataItem::Str(s.clone()),
+        XDataValue::ControlGroup(items) => {
+            dxf::XDataItem::ControlGroup(items.iter().map(xdata_item_to_dxf).collect())
+        }
+        XDataValue::LayerName(name) => dxf::XDataItem::LayerName(name.clone()),
+        XDataValue::Binary(data) => dxf::XDataItem::BinaryData(data.clone()),
+        XDataValue::Handle(handle) => dxf::XDataItem::Handle(dxf::Handle(*handle)),
+        XDataValue::ThreeReals([x, y, z]) => dxf::XDataItem::ThreeReals(*x, *y, *z),
+        XDataValue::WorldPosition([x, y, z]) => dxf::XDataItem::WorldSpacePosition(dxf::Point::new(*x, *y, *z)),
+        XDataValue::WorldDisplacement([x, y, z]) => {
+            dxf::XDataItem::WorldSpaceDisplacement(dxf::Point::new(*x, *y, *z))
+        }
+        XDataValue::WorldDirection([x, y, z]) => dxf::XDataItem::WorldDirection(dxf::Vector::new(*x, *y, *z)),
+        XDataValue::Real(value) => dxf::XDataItem::Real(*value),
+        XDataValue::Distance(value) => dxf::XDataItem::Distance(*value),
+        XDataValue::ScaleFactor(value) => dxf::XDataItem::ScaleFactor(*value),
+        XDataValue::Integer(value) => dxf::XDataItem::Integer(*value),
+        XDataValue::Long(value) => dxf::XDataItem::Long(*value),
+    }
 }
 
 /// 导出到DXF文件
 pub fn export(document: &Document, path: &Path) -> Result<(), FileError> {
     let mut drawing = dxf::Drawing::new();
+    drawing.header.point_display_mode = document.point_style.mode;
+    drawing.header.point_display_size = document.point_style.size;
+
+    // 导出图层引用的线型
+    for line_type in used_layer_line_types(document) {
+        let mut dxf_line_type = dxf::tables::LineType::default();
+        dxf_line_type.name = line_type_to_dxf_name(&line_type);
+        dxf_line_type.description = dxf_line_type.name.clone();
+        dxf_line_type.dash_dot_space_lengths = line_type.pattern();
+        dxf_line_type.element_count = dxf_line_type.dash_dot_space_lengths.len() as i32;
+        dxf_line_type.total_pattern_length = line_type.pattern_length();
+        drawing.add_line_type(dxf_line_type);
+    }
 
     // 导出图层
     for layer in document.layers.all_layers() {
         let mut dxf_layer = dxf::tables::Layer::default();
         dxf_layer.name = layer.name.clone();
         dxf_layer.color = dxf::Color::from_index(color_to_aci(&layer.color));
+        dxf_layer.line_type_name = line_type_to_dxf_name(&layer.line_type);
+        dxf_layer.line_weight = dxf::LineWeight::from_raw_value(line_weight_to_dxf(&layer.line_weight));
         drawing.add_layer(dxf_layer);
     }
 
+    // 扩展数据引用的应用名需在 APPID 表中注册
+    for application in used_xdata_applications(document) {
+        let mut app_id = dxf::tables::AppId::default();
+        app_id.name = application;
+        drawing.add_app_id(app_id);
+    }
+
     // 导出模型空间实体
     for entity in document.all_entities() {
         if let Some(dxf_entity) = convert_to_dxf_entity(entity) {
@@ -599,7 +844,7 @@ pub fn export_full(document: &Document, path: &Path) -> Result<(), FileError> {
     let mut writer = DxfWriter::new();
     
     // 1. 写入 HEADER 段
-    write_header_section(&mut writer);
+    write_header_section(&mut writer, document);
     
     // 2. 写入 TABLES 段
     write_tables_section(&mut writer, document);
@@ -618,7 +863,7 @@ pub fn export_full(document: &Document, path: &Path) -> Result<(), FileError> {
 }
 
 /// 写入 HEADER 段
-fn write_header_section(writer: &mut DxfWriter) {
+fn write_header_section(writer: &mut DxfWriter, document: &Document) {
     writer.begin_section("HEADER");
     
     // AutoCAD 版本
@@ -628,6 +873,12 @@ fn write_header_section(writer: &mut DxfWriter) {
     // 默认图层
     writer.write_pair(9, "$CLAYER");
     writer.write_pair(8, "0");
+
+    // 点样式
+    writer.write_pair(9, "$PDMODE");
+    writer.write_pair(70, document.point_style.mode);
+    writer.write_pair(9, "$PDSIZE");
+    writer.write_pair(40, document.point_style.size);
     
     writer.end_section();
 }
@@ -644,10 +895,11 @@ fn write_tables_section(writer: &mut DxfWriter, document: &Document) {
     writer.write_pair(0, "ENDTAB");
     
     // LTYPE 表
+    let line_types = used_layer_line_types(document);
     writer.write_pair(0, "TABLE");
     writer.write_pair(2, "LTYPE");
     writer.write_handle_only();
-    writer.write_pair(70, 1);
+    writer.write_pair(70, 1 + line_types.len() as i32);
     
     // CONTINUOUS 线型
     writer.write_pair(0, "LTYPE");
@@ -658,6 +910,23 @@ fn write_tables_section(writer: &mut DxfWriter, document: &Document) {
     writer.write_pair(72, 65);
     writer.write_pair(73, 0);
     writer.write_pair(40, 0.0);
+
+    // 图层引用的其他线型
+    for line_type in &line_types {
+        let pattern = line_type.pattern();
+        writer.write_pair(0, "LTYPE");
+        writer.write_handle_only();
+        writer.write_pair(2, line_type_to_dxf_name(line_type));
+        writer.write_pair(70, 0);
+        writer.write_pair(3, "");
+        writer.write_pair(72, 65);
+        writer.write_pair(73, pattern.len() as i32);
+        writer.write_pair(40, line_type.pattern_length());
+        for element in pattern {
+            writer.write_pair(49, element);
+            writer.write_pair(74, 0);
+        }
+    }
     
     writer.write_pair(0, "ENDTAB");
     
@@ -673,7 +942,8 @@ fn write_tables_section(writer: &mut DxfWriter, document: &Document) {
         writer.write_pair(2, &layer.name);
         writer.write_pair(70, if layer.visible { 0 } else { 1 });
         writer.write_pair(62, color_to_aci(&layer.color) as i32);
-        writer.write_pair(6, "CONTINUOUS");
+        writer.write_pair(6, line_type_to_dxf_name(&layer.line_type));
+        writer.write_pair(370, line_weight_to_dxf(&layer.line_weight) as i32);
     }
     
     writer.write_pair(0, "ENDTAB");
@@ -812,10 +1082,18 @@ fn write_entity(writer: &mut DxfWriter, entity: &Entity, is_paper_space: bool) {
             writer.write_pair(8, "0");
             writer.write_pair(90, polyline.vertices.len() as i32);
             writer.write_pair(70, if polyline.closed { 1 } else { 0 });
+            let constant_width = polyline.constant_width();
+            if let Some(width) = constant_width {
+                writer.write_pair(43, width);
+            }
             
             for vertex in &polyline.vertices {
                 writer.write_pair(10, vertex.point.x);
                 writer.write_pair(20, vertex.point.y);
+                if constant_width.is_none() {
+                    writer.write_pair(40, vertex.start_width);
+                    writer.write_pair(41, vertex.end_width);
+                }
                 writer.write_pair(42, vertex.bulge);
             }
         }
@@ -958,24 +1236,26 @@ fn write_layout_object(
 
 /// 将ZCAD实体转换为DXF实体
 fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
+    // 二维几何的所有点都写在实体标高上
+    let z = entity.elevation;
     let specific = match &entity.geometry {
         Geometry::Line(line) => {
             let mut dxf_line = dxf::entities::Line::default();
-            dxf_line.p1 = dxf::Point::new(line.start.x, line.start.y, 0.0);
-            dxf_line.p2 = dxf::Point::new(line.end.x, line.end.y, 0.0);
+            dxf_line.p1 = dxf::Point::new(line.start.x, line.start.y, z);
+            dxf_line.p2 = dxf::Point::new(line.end.x, line.end.y, z);
             dxf::entities::EntityType::Line(dxf_line)
         }
 
         Geometry::Circle(circle) => {
             let mut dxf_circle = dxf::entities::Circle::default();
-            dxf_circle.center = dxf::Point::new(circle.center.x, circle.center.y, 0.0);
+            dxf_circle.center = dxf::Point::new(circle.center.x, circle.center.y, z);
             dxf_circle.radius = circle.radius;
             dxf::entities::EntityType::Circle(dxf_circle)
         }
 
         Geometry::Arc(arc) => {
             let mut dxf_arc = dxf::entities::Arc::default();
-            dxf_arc.center = dxf::Point::new(arc.center.x, arc.center.y, 0.0);
+            dxf_arc.center = dxf::Point::new(arc.center.x, arc.center.y, z);
             dxf_arc.radius = arc.radius;
             dxf_arc.start_angle = arc.start_angle.to_degrees();
             dxf_arc.end_angle = arc.end_angle.to_degrees();
@@ -985,6 +1265,8 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
         Geometry::Polyline(polyline) => {
             let mut lwpoly = dxf::entities::LwPolyline::default();
             lwpoly.set_is_closed(polyline.closed);
+            lwpoly.constant_width = polyline.constant_width().unwrap_or(0.0);
+            lwpoly.elevation = z;
             lwpoly.vertices = polyline
                 .vertices
                 .iter()
@@ -993,6 +1275,8 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
                     vertex.x = v.point.x;
                     vertex.y = v.point.y;
                     vertex.bulge = v.bulge;
+                    vertex.starting_width = v.start_width;
+                    vertex.ending_width = v.end_width;
                     vertex
                 })
                 .collect();
@@ -1001,13 +1285,13 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
 
         Geometry::Point(point) => {
             let mut dxf_point = dxf::entities::ModelPoint::default();
-            dxf_point.location = dxf::Point::new(point.position.x, point.position.y, 0.0);
+            dxf_point.location = dxf::Point::new(point.position.x, point.position.y, z);
             dxf::entities::EntityType::ModelPoint(dxf_point)
         }
 
         Geometry::Text(text) => {
             let mut dxf_text = dxf::entities::Text::default();
-            dxf_text.location = dxf::Point::new(text.position.x, text.position.y, 0.0);
+            dxf_text.location = dxf::Point::new(text.position.x, text.position.y, z);
             dxf_text.text_height = text.height;
             dxf_text.value = text.content.clone();
             dxf_text.rotation = text.rotation.to_degrees();
@@ -1029,20 +1313,20 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
 
             // 设置文本位置 (11) - 如果有自定义位置，使用它；否则使用默认计算位置
             let text_pos = dim.get_text_position();
-            base.text_mid_point = dxf::Point::new(text_pos.x, text_pos.y, 0.0);
+            base.text_mid_point = dxf::Point::new(text_pos.x, text_pos.y, z);
             
             match dim.dim_type {
                 zcad_core::geometry::DimensionType::Radius => {
                     base.dimension_type = dxf::enums::DimensionType::Radius;
                     
                     // 10: Center (p1)
-                    base.definition_point_1 = dxf::Point::new(dim.definition_point1.x, dim.definition_point1.y, 0.0);
+                    base.definition_point_1 = dxf::Point::new(dim.definition_point1.x, dim.definition_point1.y, z);
                     
                     let mut dxf_dim = dxf::entities::RadialDimension::default();
                     dxf_dim.dimension_base = base;
                     
                     // 15: Point on curve (p2)
-                    dxf_dim.definition_point_2 = dxf::Point::new(dim.definition_point2.x, dim.definition_point2.y, 0.0);
+                    dxf_dim.definition_point_2 = dxf::Point::new(dim.definition_point2.x, dim.definition_point2.y, z);
                     
                     dxf::entities::EntityType::RadialDimension(dxf_dim)
                 },
@@ -1051,19 +1335,19 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
                     
                     // 10: Opposite point
                     let opposite = dim.definition_point1 + (dim.definition_point1 - dim.definition_point2);
-                    base.definition_point_1 = dxf::Point::new(opposite.x, opposite.y, 0.0);
+                    base.definition_point_1 = dxf::Point::new(opposite.x, opposite.y, z);
                     
                     let mut dxf_dim = dxf::entities::DiameterDimension::default();
                     dxf_dim.dimension_base = base;
                     
                     // 15: Point on curve (p2)
-                    dxf_dim.definition_point_2 = dxf::Point::new(dim.definition_point2.x, dim.definition_point2.y, 0.0);
+                    dxf_dim.definition_point_2 = dxf::Point::new(dim.definition_point2.x, dim.definition_point2.y, z);
                     
                     dxf::entities::EntityType::DiameterDimension(dxf_dim)
                 },
                 _ => {
                     // definition_point_1 (10) = Dimension line definition point
-                    base.definition_point_1 = dxf::Point::new(dim.line_location.x, dim.line_location.y, 0.0);
+                    base.definition_point_1 = dxf::Point::new(dim.line_location.x, dim.line_location.y, z);
                     
                     let mut dxf_dim = dxf::entities::RotatedDimension::default();
                     
@@ -1076,12 +1360,12 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
                     dxf_dim.dimension_base = base;
                     
                     // definition_point_2 (13) = Extension line 1 origin (Start point)
-                    dxf_dim.definition_point_2 = dxf::Point::new(dim.definition_point1.x, dim.definition_point1.y, 0.0);
+                    dxf_dim.definition_point_2 = dxf::Point::new(dim.definition_point1.x, dim.definition_point1.y, z);
                     // definition_point_3 (14) = Extension line 2 origin (End point)
-                    dxf_dim.definition_point_3 = dxf::Point::new(dim.definition_point2.x, dim.definition_point2.y, 0.0);
+                    dxf_dim.definition_point_3 = dxf::Point::new(dim.definition_point2.x, dim.definition_point2.y, z);
                     
                     // insertion_point (12)
-                    dxf_dim.insertion_point = dxf::Point::new(dim.line_location.x, dim.line_location.y, 0.0);
+                    dxf_dim.insertion_point = dxf::Point::new(dim.line_location.x, dim.line_location.y, z);
                     
                     dxf::entities::EntityType::RotatedDimension(dxf_dim)
                 }
@@ -1090,7 +1374,7 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
 
         Geometry::Ellipse(ellipse) => {
             let mut dxf_ellipse = dxf::entities::Ellipse::default();
-            dxf_ellipse.center = dxf::Point::new(ellipse.center.x, ellipse.center.y, 0.0);
+            dxf_ellipse.center = dxf::Point::new(ellipse.center.x, ellipse.center.y, z);
             dxf_ellipse.major_axis = dxf::Vector::new(ellipse.major_axis.x, ellipse.major_axis.y, 0.0);
             dxf_ellipse.minor_axis_ratio = ellipse.ratio;
             dxf_ellipse.start_parameter = ellipse.start_param;
@@ -1104,13 +1388,13 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
             dxf_spline.control_points = spline
                 .control_points
                 .iter()
-                .map(|p| dxf::Point::new(p.x, p.y, 0.0))
+                .map(|p| dxf::Point::new(p.x, p.y, z))
                 .collect();
             dxf_spline.knot_values = spline.knots.clone();
             dxf_spline.fit_points = spline
                 .fit_points
                 .iter()
-                .map(|p| dxf::Point::new(p.x, p.y, 0.0))
+                .map(|p| dxf::Point::new(p.x, p.y, z))
                 .collect();
             if spline.closed {
                 dxf_spline.flags |= 1; // Closed spline
@@ -1129,8 +1413,11 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
             dxf_leader.vertices = leader
                 .vertices
                 .iter()
-                .map(|p| dxf::Point::new(p.x, p.y, 0.0))
+                .map(|p| dxf::Point::new(p.x, p.y, z))
                 .collect();
+            if leader.spline {
+                dxf_leader.path_type = dxf::enums::LeaderPathType::Spline;
+            }
             dxf::entities::EntityType::Leader(dxf_leader)
         }
     };
@@ -1142,6 +1429,7 @@ fn convert_to_dxf_entity(entity: &Entity) -> Option<dxf::entities::Entity> {
         dxf_entity.common.color =
             dxf::Color::from_index(color_to_aci(&entity.properties.color));
     }
+    dxf_entity.common.x_data = entity.xdata.iter().map(xdata_to_dxf).collect();
 
     Some(dxf_entity)
 }
@@ -1181,3 +1469,274 @@ fn color_to_aci(color: &Color) -> u8 {
     }
 }
 
+/// 图层引用的、需要写入 LTYPE 表的线型（不含 CONTINUOUS 和 ByLayer/ByBlock）
+fn used_layer_line_types(document: &Document) -> Vec<LineType> {
+    let mut line_types: Vec<LineType> = Vec::new();
+    for layer in document.layers.all_layers() {
+        let line_type = &layer.line_type;
+        if matches!(line_type, LineType::Continuous | LineType::ByLayer | LineType::ByBlock) {
+            continue;
+        }
+        if !line_types.contains(line_type) {
+            line_types.push(line_type.clone());
+        }
+    }
+    line_types
+}
+
+/// 模型空间和图纸空间实体的扩展数据引用的应用名（按首次出现顺序去重）
+fn used_xdata_applications(document: &Document) -> Vec<String> {
+    let paper_space = document
+        .layout_manager
+        .layouts()
+        .iter()
+        .flat_map(|layout| layout.paper_space_entities.iter());
+    let mut applications: Vec<String> = Vec::new();
+    for entity in document.all_entities().chain(paper_space) {
+        for x_data in &entity.xdata {
+            if !applications.iter().any(|a| a.eq_ignore_ascii_case(&x_data.application)) {
+                applications.push(x_data.application.clone());
+            }
+        }
+    }
+    applications
+}
+
+/// ZCAD 线型转 DXF 线型名
+fn line_type_to_dxf_name(line_type: &LineType) -> String {
+    match line_type {
+        LineType::Continuous => "CONTINUOUS".to_string(),
+        LineType::Dashed => "DASHED".to_string(),
+        LineType::Dotted => "DOT".to_string(),
+        LineType::DashDot => "DASHDOT".to_string(),
+        LineType::DashDotDot => "DIVIDE".to_string(),
+        LineType::Center => "CENTER".to_string(),
+        LineType::Hidden => "HIDDEN".to_string(),
+        LineType::Custom { name, .. } => name.clone(),
+        LineType::ByLayer => "ByLayer".to_string(),
+        LineType::ByBlock => "ByBlock".to_string(),
+    }
+}
+
+/// DXF 线型名转 ZCAD 线型（未知线型保留名称作为自定义线型）
+fn dxf_name_to_line_type(name: &str) -> LineType {
+    match name.to_uppercase().as_str() {
+        "" | "CONTINUOUS" => LineType::Continuous,
+        "DASHED" => LineType::Dashed,
+        "DOT" => LineType::Dotted,
+        "DASHDOT" => LineType::DashDot,
+        "DIVIDE" => LineType::DashDotDot,
+        "CENTER" => LineType::Center,
+        "HIDDEN" => LineType::Hidden,
+        "BYLAYER" => LineType::ByLayer,
+        "BYBLOCK" => LineType::ByBlock,
+        _ => LineType::Custom {
+            name: name.to_string(),
+            pattern: Vec::new(),
+        },
+    }
+}
+
+/// 图层引用的 DXF 线型，自定义线型按名称（不区分大小写）从 LTYPE 表取回图案
+fn dxf_layer_line_type(name: &str, patterns: &HashMap<String, Vec<f64>>) -> LineType {
+    match dxf_name_to_line_type(name) {
+        LineType::Custom { name, .. } => {
+            let pattern = patterns.get(&name.to_uppercase()).cloned().unwrap_or_default();
+            LineType::Custom { name, pattern }
+        }
+        line_type => line_type,
+    }
+}
+
+/// ZCAD 线宽转 DXF 线宽编码（1/100 毫米；-1 随层，-2 随块，-3 默认）
+fn line_weight_to_dxf(line_weight: &LineWeight) -> i16 {
+    match line_weight {
+        LineWeight::ByLayer => -1,
+        LineWeight::ByBlock => -2,
+        LineWeight::Default => -3,
+        LineWeight::Width(mm) => (mm * 100.0).round() as i16,
+    }
+}
+
+/// DXF 线宽编码转 ZCAD 线宽
+fn dxf_to_line_weight(raw: i16) -> LineWeight {
+    match raw {
+        -1 => LineWeight::ByLayer,
+        -2 => LineWeight::ByBlock,
+        raw if raw < 0 => LineWeight::Default,
+        raw => LineWeight::Width(raw as f64 / 100.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_line_weight_and_type_codes() {
+        assert_eq!(line_weight_to_dxf(&LineWeight::Width(0.5)), 50);
+        assert_eq!(dxf_to_line_weight(50), LineWeight::Width(0.5));
+        for weight in [LineWeight::ByLayer, LineWeight::ByBlock, LineWeight::Default] {
+            assert_eq!(dxf_to_line_weight(line_weight_to_dxf(&weight)), weight);
+        }
+
+        assert_eq!(dxf_name_to_line_type("hidden"), LineType::Hidden);
+        assert_eq!(dxf_name_to_line_type(&line_type_to_dxf_name(&LineType::Center)), LineType::Center);
+        assert!(matches!(dxf_name_to_line_type("BORDER"), LineType::Custom { name, .. } if name == "BORDER"));
+    }
+
+    #[test]
+    fn test_layer_line_weight_roundtrip() {
+        let path = std::env::temp_dir().join("test_layer_line_weight.dxf");
+        let mut document = Document::new();
+        let mut layer = zcad_core::layer::Layer::new("Walls").with_line_type(LineType::Dashed);
+        layer.line_weight = LineWeight::Width(0.5);
+        document.layers.add_layer(layer);
+
+        export(&document, &path).expect("Failed to export");
+        let imported = import(&path).expect("Failed to import");
+        std::fs::remove_file(&path).ok();
+
+        let walls = imported.layers.get_layer("Walls").expect("图层应保留");
+        assert_eq!(walls.line_weight, LineWeight::Width(0.5));
+        assert_eq!(walls.line_type, LineType::Dashed);
+    }
+
+    #[test]
+    fn test_layer_custom_line_type_roundtrip() {
+        let path = std::env::temp_dir().join("test_layer_custom_line_type.dxf");
+        let border = LineType::Custom {
+            name: "BORDER".to_string(),
+            pattern: vec![12.0, -3.0, 12.0, -3.0, 1.0, -3.0],
+        };
+        let mut document = Document::new();
+        document
+            .layers
+            .add_layer(zcad_core::layer::Layer::new("Frame").with_line_type(border.clone()));
+
+        export(&document, &path).expect("Failed to export");
+        let imported = import(&path).expect("Failed to import");
+        std::fs::remove_file(&path).ok();
+
+        let frame = imported.layers.get_layer("Frame").expect("图层应保留");
+        assert_eq!(frame.line_type, border);
+    }
+
+    #[test]
+    fn test_constant_width_polyline_roundtrip() {
+        let path = std::env::temp_dir().join("test_polyline_width.dxf");
+        let mut document = Document::new();
+        let mut polyline = Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(10.0, 5.0)],
+            false,
+        );
+        polyline.set_constant_width(0.5);
+        document.add_entity(Entity::new(Geometry::Polyline(polyline)));
+
+        export(&document, &path).expect("Failed to export");
+        let imported = import(&path).expect("Failed to import");
+        std::fs::remove_file(&path).ok();
+
+        let polyline = imported
+            .all_entities()
+            .find_map(|e| match &e.geometry {
+                Geometry::Polyline(p) => Some(p.clone()),
+                _ => None,
+            })
+            .expect("多段线应保留");
+        assert_eq!(polyline.constant_width(), Some(0.5));
+    }
+
+    /// 含一个块定义（单条线段）和一个块参照的最小DXF
+    const INSERT_DXF: &str = "0\nSECTION\n2\nBLOCKS\n\
+0\nBLOCK\n8\n0\n2\nBAR\n70\n0\n10\n1.0\n20\n0.0\n30\n0.0\n3\nBAR\n\
+0\nLINE\n8\n0\n10\n1.0\n20\n0.0\n30\n0.0\n11\n3.0\n21\n0.0\n31\n0.0\n\
+0\nENDBLK\n8\n0\n\
+0\nENDSEC\n\
+0\nSECTION\n2\nENTITIES\n\
+0\nINSERT\n8\n0\n2\nBAR\n10\n10.0\n20\n10.0\n30\n0.0\n41\n2.0\n42\n2.0\n43\n1.0\n50\n90.0\n\
+0\nENDSEC\n0\nEOF\n";
+
+    #[test]
+    fn test_import_insert() {
+        let path = std::env::temp_dir().join("test_import_insert.dxf");
+        std::fs::write(&path, INSERT_DXF).expect("Failed to write");
+
+        let document = import(&path).expect("Failed to import");
+        std::fs::remove_file(&path).ok();
+
+        assert!(document.blocks.contains("BAR"));
+        assert_eq!(document.entity_count(), 1);
+
+        let entity = document.all_entities().next().unwrap();
+        match &entity.geometry {
+            Geometry::Line(line) => {
+                // 基点 (1, 0) 对齐到插入点，缩放 2 倍并旋转 90°
+                assert!((line.start - Point2::new(10.0, 10.0)).norm() < 1e-9);
+                assert!((line.end - Point2::new(10.0, 14.0)).norm() < 1e-9);
+            }
+            other => panic!("应为线段: {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_elevation_roundtrip() {
+        let mut document = Document::new();
+        document.add_entity(
+            Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0)))).with_elevation(5.0),
+        );
+        document.add_entity(
+            Entity::new(Geometry::Polyline(Polyline::from_points(
+                [Point2::new(0.0, 0.0), Point2::new(5.0, 5.0)],
+                false,
+            )))
+            .with_elevation(5.0),
+        );
+        document.add_entity(Entity::new(Geometry::Circle(Circle::new(Point2::new(0.0, 0.0), 2.0))));
+
+        let path = std::env::temp_dir().join("test_elevation_roundtrip.dxf");
+        export(&document, &path).expect("Failed to export");
+        let reimported = import(&path).expect("Failed to import");
+        std::fs::remove_file(&path).ok();
+
+        assert_eq!(reimported.entity_count(), 3);
+        for entity in reimported.all_entities() {
+            let expected = if matches!(entity.geometry, Geometry::Circle(_)) { 0.0 } else { 5.0 };
+            assert_eq!(entity.elevation, expected, "{}", entity.geometry.type_name());
+        }
+    }
+
+    /// 一条带 XDATA（字符串、控制组、整数、实数、点）的线段
+    const XDATA_DXF: &str = "0\nSECTION\n2\nENTITIES\n\
+0\nLINE\n8\n0\n10\n0.0\n20\n0.0\n30\n0.0\n11\n10.0\n21\n0.0\n31\n0.0\n\
+1001\nZCAD_TEST\n1000\nhello\n1002\n{\n1070\n7\n1002\n}\n1040\n2.5\n1010\n1.0\n1020\n2.0\n1030\n3.0\n\
+0\nENDSEC\n0\nEOF\n";
+
+    #[test]
+    fn test_xdata_roundtrip() {
+        let source = std::env::temp_dir().join("test_xdata_source.dxf");
+        let exported = std::env::temp_dir().join("test_xdata_exported.dxf");
+        std::fs::write(&source, XDATA_DXF).expect("Failed to write");
+
+        let document = import(&source).expect("Failed to import");
+        let entity = document.all_entities().next().unwrap();
+        let xdata = entity.xdata_for("ZCAD_TEST").expect("导入时应保留 XDATA");
+        assert_eq!(
+            xdata.items,
+            vec![
+                XDataValue::String("hello".to_string()),
+                XDataValue::ControlGroup(vec![XDataValue::Integer(7)]),
+                XDataValue::Real(2.5),
+                XDataValue::ThreeReals([1.0, 2.0, 3.0]),
+            ]
+        );
+
+        export(&document, &exported).expect("Failed to export");
+        let reimported = import(&exported).expect("Failed to import");
+        std::fs::remove_file(&source).ok();
+        std::fs::remove_file(&exported).ok();
+
+        let entity = reimported.all_entities().next().unwrap();
+        assert_eq!(entity.xdata_for("ZCAD_TEST"), Some(xdata));
+    }
+}
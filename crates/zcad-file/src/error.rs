@@ -22,6 +22,9 @@ pub enum FileError {
     #[error("Invalid file format: {0}")]
     InvalidFormat(String),
 
+    #[error("Unsupported format: {0}")]
+    Unsupported(String),
+
     #[error("Unsupported version: {0}")]
     UnsupportedVersion(String),
 
@@ -1,11 +1,14 @@
 //! 导出模块
 //!
-//! 支持将 CAD 图纸导出为多种格式：PDF、SVG、PNG、JPG
+//! 支持将 CAD 图纸导出为多种格式：PDF、SVG、PNG、JPG；
+//! 并可在图纸空间生成比例尺和标题栏
 
+use crate::document::DocumentMetadata;
 use crate::error::FileError;
 use zcad_core::entity::Entity;
-use zcad_core::geometry::Geometry;
-use zcad_core::math::{Point2, Vector2};
+use zcad_core::geometry::{Geometry, Line, Polyline, Text, TextAlignment};
+use zcad_core::layout::STANDARD_SCALES;
+use zcad_core::math::{points_approx_eq, BoundingBox2, Point2, Vector2, EPSILON};
 use zcad_core::properties::Color;
 
 /// 纸张大小
@@ -61,6 +64,8 @@ pub struct PageSetup {
     pub fit_to_page: bool,
     /// 打印范围：None = 全部，Some = 指定区域
     pub print_area: Option<PrintArea>,
+    /// 分页平铺：None = 单页；Some = 按 `scale` 固定比例分页，值为相邻页的重叠宽度（毫米）
+    pub tile_overlap: Option<f64>,
 }
 
 impl Default for PageSetup {
@@ -72,6 +77,7 @@ impl Default for PageSetup {
             scale: 1.0,
             fit_to_page: true,
             print_area: None,
+            tile_overlap: None,
         }
     }
 }
@@ -87,6 +93,55 @@ impl PageSetup {
         let (top, right, bottom, left) = self.margins;
         (w - left - right, h - top - bottom)
     }
+
+    /// 按固定比例将内容范围划分为若干页
+    ///
+    /// 每页覆盖可打印区域除以 `scale` 的图纸范围，相邻页重叠 `tile_overlap` 毫米；
+    /// 页面按从上到下、从左到右的顺序排列。未开启分页时整个范围为一页。
+    pub fn page_grid(&self, bounds: &PrintArea) -> PageGrid {
+        let Some(overlap) = self.tile_overlap else {
+            return PageGrid { columns: 1, rows: 1, pages: vec![bounds.clone()] };
+        };
+        let (page_width, page_height) = self.printable_size();
+        let tile_width = page_width / self.scale;
+        let tile_height = page_height / self.scale;
+        // 重叠不能超过页面的一半，否则分页无法推进
+        let step_x = (page_width - overlap.clamp(0.0, page_width / 2.0)) / self.scale;
+        let step_y = (page_height - overlap.clamp(0.0, page_height / 2.0)) / self.scale;
+        let count = |extent: f64, tile: f64, step: f64| {
+            if extent <= tile + EPSILON {
+                1
+            } else {
+                ((extent - tile) / step - EPSILON).ceil() as usize + 1
+            }
+        };
+        let columns = count(bounds.width(), tile_width, step_x);
+        let rows = count(bounds.height(), tile_height, step_y);
+
+        let mut pages = Vec::with_capacity(columns * rows);
+        for row in 0..rows {
+            let top = bounds.max.y - row as f64 * step_y;
+            for column in 0..columns {
+                let left = bounds.min.x + column as f64 * step_x;
+                pages.push(PrintArea::new(
+                    Point2::new(left, top - tile_height),
+                    Point2::new(left + tile_width, top),
+                ));
+            }
+        }
+        PageGrid { columns, rows, pages }
+    }
+}
+
+/// 分页结果
+#[derive(Debug, Clone)]
+pub struct PageGrid {
+    /// 列数
+    pub columns: usize,
+    /// 行数
+    pub rows: usize,
+    /// 各页对应的图纸范围（从上到下、从左到右）
+    pub pages: Vec<PrintArea>,
 }
 
 /// 打印区域
@@ -117,6 +172,91 @@ impl PrintArea {
             (self.min.y + self.max.y) / 2.0,
         )
     }
+
+    /// 转换为包围盒
+    pub fn to_bounding_box(&self) -> BoundingBox2 {
+        BoundingBox2::new(self.min, self.max)
+    }
+}
+
+/// 曲线裁剪时的采样段数
+const CLIP_SAMPLE_SEGMENTS: usize = 64;
+
+/// 将几何体裁剪到打印区域
+///
+/// 完全在区域内的几何体原样返回，完全在区域外的返回空；
+/// 跨越边界的直线、多段线、圆弧、圆、椭圆和样条曲线被裁剪为若干段开放多段线。
+/// 其他类型（文字、标注等）无法裁剪，与区域相交时原样保留。
+fn clip_geometry(geometry: &Geometry, area: &BoundingBox2) -> Vec<Geometry> {
+    let bbox = geometry.bounding_box();
+    if area.contains_box(&bbox) {
+        return vec![geometry.clone()];
+    }
+    if !area.intersects(&bbox) {
+        return Vec::new();
+    }
+
+    let Some(paths) = clip_paths(geometry) else {
+        return vec![geometry.clone()];
+    };
+
+    let mut result = Vec::new();
+    for path in paths {
+        let mut chain: Vec<Point2> = Vec::new();
+        for w in path.windows(2) {
+            let Some((a, b)) = area.clip_segment(w[0], w[1]) else {
+                continue;
+            };
+            if chain.last().is_some_and(|last| points_approx_eq(last, &a)) {
+                chain.push(b);
+            } else {
+                flush_chain(&mut chain, &mut result);
+                chain.extend([a, b]);
+            }
+        }
+        flush_chain(&mut chain, &mut result);
+    }
+    result
+}
+
+/// 几何体的折线路径（用于裁剪），不支持的类型返回 None
+fn clip_paths(geometry: &Geometry) -> Option<Vec<Vec<Point2>>> {
+    let paths = match geometry {
+        Geometry::Line(line) => vec![vec![line.start, line.end]],
+        Geometry::Arc(arc) => {
+            let sweep = arc.sweep_angle();
+            let segments = ((sweep / std::f64::consts::TAU) * CLIP_SAMPLE_SEGMENTS as f64).ceil().max(1.0) as usize;
+            vec![(0..=segments)
+                .map(|i| {
+                    let angle = arc.start_angle + sweep * i as f64 / segments as f64;
+                    arc.center + Vector2::new(angle.cos(), angle.sin()) * arc.radius
+                })
+                .collect()]
+        }
+        Geometry::Circle(circle) => vec![(0..=CLIP_SAMPLE_SEGMENTS)
+            .map(|i| circle.point_at_angle(std::f64::consts::TAU * i as f64 / CLIP_SAMPLE_SEGMENTS as f64))
+            .collect()],
+        Geometry::Polyline(polyline) => polyline
+            .explode()
+            .iter()
+            .filter_map(clip_paths)
+            .flatten()
+            .collect(),
+        Geometry::Ellipse(ellipse) => vec![ellipse.sample_points(CLIP_SAMPLE_SEGMENTS)],
+        Geometry::Spline(spline) => vec![spline.sample_points(CLIP_SAMPLE_SEGMENTS)],
+        _ => return None,
+    };
+    Some(paths)
+}
+
+/// 将裁剪得到的点链输出为直线或开放多段线
+fn flush_chain(chain: &mut Vec<Point2>, result: &mut Vec<Geometry>) {
+    match chain.len() {
+        0 | 1 => {}
+        2 => result.push(Geometry::Line(Line::new(chain[0], chain[1]))),
+        _ => result.push(Geometry::Polyline(Polyline::from_points(chain.iter().copied(), false))),
+    }
+    chain.clear();
 }
 
 /// SVG 导出器
@@ -175,13 +315,20 @@ impl SvgExporter {
             0.0, 0.0, page_width / scale, page_height / scale, page_height / scale
         ));
 
-        // 渲染每个实体
+        // 渲染每个实体（指定打印区域时裁剪到区域内）
+        let clip_area = self.page_setup.print_area.as_ref().map(PrintArea::to_bounding_box);
         for entity in entities {
             let color = &entity.properties.color;
             let stroke_width = self.line_weight_to_mm(&entity.properties.line_weight).max(0.1);
-            
-            if let Some(svg_elem) = self.geometry_to_svg(&entity.geometry, color, stroke_width) {
-                svg.push_str(&format!("    {}\n", svg_elem));
+
+            let geometries = match &clip_area {
+                Some(area) => clip_geometry(&entity.geometry, area),
+                None => vec![entity.geometry.clone()],
+            };
+            for geometry in &geometries {
+                if let Some(svg_elem) = self.geometry_to_svg(geometry, color, stroke_width) {
+                    svg.push_str(&format!("    {}\n", svg_elem));
+                }
             }
         }
 
@@ -191,6 +338,28 @@ impl SvgExporter {
         Ok(svg)
     }
 
+    /// 按页面设置分页导出，每页一个 SVG 字符串
+    ///
+    /// 未开启分页时只有一页，与 [`Self::export`] 相同。
+    pub fn export_pages(&self, entities: &[Entity]) -> Result<Vec<String>, FileError> {
+        if self.page_setup.tile_overlap.is_none() {
+            return Ok(vec![self.export(entities)?]);
+        }
+        let grid = self.page_setup.page_grid(&self.calculate_bounds(entities));
+        grid.pages
+            .into_iter()
+            .map(|area| {
+                let page_setup = PageSetup {
+                    print_area: Some(area),
+                    fit_to_page: false,
+                    tile_overlap: None,
+                    ..self.page_setup.clone()
+                };
+                SvgExporter::new(page_setup).export(entities)
+            })
+            .collect()
+    }
+
     /// 计算所有实体的包围盒
     fn calculate_bounds(&self, entities: &[Entity]) -> PrintArea {
         if entities.is_empty() {
@@ -351,8 +520,10 @@ impl SvgExporter {
                     return None;
                 }
                 
+                // 样条引线按采样点输出
                 let mut path = String::new();
-                for (i, vertex) in leader.vertices.iter().enumerate() {
+                let points = leader.path_points();
+                for (i, vertex) in points.iter().enumerate() {
                     if i == 0 {
                         path.push_str(&format!("M {:.4} {:.4}", vertex.x, vertex.y));
                     } else {
@@ -361,10 +532,9 @@ impl SvgExporter {
                 }
                 
                 // 添加箭头
-                if leader.vertices.len() >= 2 {
+                if let Some(arrow_dir) = leader.arrow_direction() {
                     let p0 = &leader.vertices[0];
-                    let p1 = &leader.vertices[1];
-                    let dir = Vector2::new(p1.x - p0.x, p1.y - p0.y).normalize();
+                    let dir = -arrow_dir;
                     let arrow_len = 3.0;
                     let arrow_width = 1.0;
                     
@@ -387,18 +557,14 @@ impl SvgExporter {
                 Some(format!(r#"<path d="{}" {}/>"#, path, style))
             }
             Geometry::Dimension(dim) => {
-                // 标注渲染较复杂，这里简化处理
-                let p1 = dim.definition_point1;
-                let p2 = dim.definition_point2;
                 let text_pos = dim.get_text_position();
                 
-                let mut elements = vec![];
-                
-                // 绘制标注线
-                elements.push(format!(
-                    r#"<line x1="{:.4}" y1="{:.4}" x2="{:.4}" y2="{:.4}" {}/>"#,
-                    p1.x, p1.y, p2.x, p2.y, style
-                ));
+                // 绘制标注图形（尺寸线、尺寸界线、箭头）
+                let mut elements: Vec<String> = dim
+                    .render_segments()
+                    .iter()
+                    .filter_map(|segment| self.geometry_to_svg(segment, color, stroke_width))
+                    .collect();
                 
                 // 绘制文本
                 elements.push(format!(
@@ -418,13 +584,31 @@ impl SvgExporter {
     }
 
     /// 导出到文件
+    ///
+    /// 分页时每页写入一个文件，文件名追加页码（如 `drawing-1.svg`）。
     pub fn export_to_file(&self, entities: &[Entity], path: &std::path::Path) -> Result<(), FileError> {
-        let svg = self.export(entities)?;
-        std::fs::write(path, svg)?;
+        let pages = self.export_pages(entities)?;
+        if pages.len() == 1 && self.page_setup.tile_overlap.is_none() {
+            std::fs::write(path, &pages[0])?;
+            return Ok(());
+        }
+        for (index, svg) in pages.iter().enumerate() {
+            std::fs::write(page_file_path(path, index + 1), svg)?;
+        }
         Ok(())
     }
 }
 
+/// 分页导出时第 `page` 页的文件路径：在文件名和扩展名之间插入页码
+fn page_file_path(path: &std::path::Path, page: usize) -> std::path::PathBuf {
+    let stem = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+    let name = match path.extension() {
+        Some(ext) => format!("{}-{}.{}", stem, page, ext.to_string_lossy()),
+        None => format!("{}-{}", stem, page),
+    };
+    path.with_file_name(name)
+}
+
 /// PDF 导出器（使用 SVG 转换）
 pub struct PdfExporter {
     page_setup: PageSetup,
@@ -440,9 +624,15 @@ impl PdfExporter {
     /// 注意：实际的 PDF 生成需要额外的库（如 printpdf）
     /// 这里提供一个简化的接口
     pub fn export(&self, entities: &[Entity]) -> Result<Vec<u8>, FileError> {
-        // 首先生成 SVG
+        // 首先生成 SVG（分页时每页一个）
         let svg_exporter = SvgExporter::new(self.page_setup.clone());
-        let svg_content = svg_exporter.export(entities)?;
+        let pages = svg_exporter.export_pages(entities)?;
+        let svg_content = pages
+            .iter()
+            .enumerate()
+            .map(|(index, svg)| format!("--- Page {} ---\n{}", index + 1, svg))
+            .collect::<Vec<_>>()
+            .join("\n");
         
         // 注意：完整的 PDF 导出需要使用 printpdf 或类似的库
         // 这里返回 SVG 内容的字节，作为占位符
@@ -453,11 +643,13 @@ impl PdfExporter {
             "PDF Export Placeholder\n\
              Paper: {:?}\n\
              Orientation: {:?}\n\
-             Entities: {}\n\n\
+             Entities: {}\n\
+             Pages: {}\n\n\
              SVG Content:\n{}",
             self.page_setup.paper_size,
             self.page_setup.orientation,
             entities.len(),
+            pages.len(),
             svg_content
         );
         
@@ -472,6 +664,117 @@ impl PdfExporter {
     }
 }
 
+// ========== 比例尺与标题栏 ==========
+
+/// 比例尺在图纸上的目标长度（毫米），实际长度取不超过它的整齐模型长度
+const SCALE_BAR_TARGET_LENGTH: f64 = 50.0;
+/// 比例尺分格数
+const SCALE_BAR_DIVISIONS: usize = 4;
+/// 比例尺高度（毫米）
+const SCALE_BAR_HEIGHT: f64 = 2.0;
+/// 图纸注释文字高度（毫米）
+const ANNOTATION_TEXT_HEIGHT: f64 = 2.5;
+/// 标题栏宽度（毫米）
+const TITLE_BLOCK_WIDTH: f64 = 120.0;
+/// 标题栏标签列宽度（毫米）
+const TITLE_BLOCK_LABEL_WIDTH: f64 = 25.0;
+/// 标题栏行高（毫米）
+const TITLE_BLOCK_ROW_HEIGHT: f64 = 8.0;
+
+/// 生成图纸空间的比例尺
+///
+/// `scale` 为导出比例 1:scale，即图纸上 1 mm 对应 `scale` 个模型单位。比例尺从 `origin`
+/// 向右展开，总长对应 1、2、5×10ⁿ 的整齐模型长度，每个刻度上方标注该处的模型长度。
+pub fn scale_bar(origin: Point2, scale: f64) -> Vec<Geometry> {
+    let model_length = nice_length(SCALE_BAR_TARGET_LENGTH * scale);
+    let step = model_length / scale / SCALE_BAR_DIVISIONS as f64;
+    let mut geometries = Vec::with_capacity(SCALE_BAR_DIVISIONS * 2 + 1);
+    for i in 0..SCALE_BAR_DIVISIONS {
+        let x = origin.x + step * i as f64;
+        geometries.push(Geometry::Polyline(Polyline::from_points(
+            [
+                Point2::new(x, origin.y),
+                Point2::new(x + step, origin.y),
+                Point2::new(x + step, origin.y + SCALE_BAR_HEIGHT),
+                Point2::new(x, origin.y + SCALE_BAR_HEIGHT),
+            ],
+            true,
+        )));
+    }
+    for i in 0..=SCALE_BAR_DIVISIONS {
+        let position = Point2::new(origin.x + step * i as f64, origin.y + SCALE_BAR_HEIGHT + 1.0);
+        let value = model_length * i as f64 / SCALE_BAR_DIVISIONS as f64;
+        geometries.push(Geometry::Text(
+            Text::new(position, format_length(value), ANNOTATION_TEXT_HEIGHT).with_alignment(TextAlignment::Center),
+        ));
+    }
+    geometries
+}
+
+/// 生成标题栏：右下角对齐到 `corner`，三行依次为图名、日期和比例
+pub fn title_block(corner: Point2, metadata: &DocumentMetadata, scale: f64) -> Vec<Geometry> {
+    let rows = [
+        ("名称", metadata.title.clone()),
+        ("日期", metadata.modified_at.format("%Y-%m-%d").to_string()),
+        ("比例", scale_label(scale)),
+    ];
+    let row_count = rows.len();
+    let height = TITLE_BLOCK_ROW_HEIGHT * row_count as f64;
+    let left = corner.x - TITLE_BLOCK_WIDTH;
+    let divider = left + TITLE_BLOCK_LABEL_WIDTH;
+
+    let mut geometries = vec![
+        Geometry::Polyline(Polyline::from_points(
+            [
+                Point2::new(left, corner.y),
+                corner,
+                Point2::new(corner.x, corner.y + height),
+                Point2::new(left, corner.y + height),
+            ],
+            true,
+        )),
+        Geometry::Line(Line::new(Point2::new(divider, corner.y), Point2::new(divider, corner.y + height))),
+    ];
+    // 第一行在最上方
+    for (i, (label, value)) in rows.into_iter().enumerate() {
+        let bottom = corner.y + height - TITLE_BLOCK_ROW_HEIGHT * (i + 1) as f64;
+        if i + 1 < row_count {
+            geometries.push(Geometry::Line(Line::new(Point2::new(left, bottom), Point2::new(corner.x, bottom))));
+        }
+        let baseline = bottom + (TITLE_BLOCK_ROW_HEIGHT - ANNOTATION_TEXT_HEIGHT) / 2.0;
+        geometries.push(Geometry::Text(Text::new(Point2::new(left + 2.0, baseline), label, ANNOTATION_TEXT_HEIGHT)));
+        geometries.push(Geometry::Text(Text::new(Point2::new(divider + 2.0, baseline), value, ANNOTATION_TEXT_HEIGHT)));
+    }
+    geometries
+}
+
+/// 比例的显示名称：标准比例用其名称，否则为 `1:scale`
+fn scale_label(scale: f64) -> String {
+    STANDARD_SCALES
+        .iter()
+        .find(|(_, s)| (s - scale).abs() < 1e-9)
+        .map_or_else(|| format!("1:{}", format_length(scale)), |(name, _)| name.to_string())
+}
+
+/// 不超过 `value` 的最大 1、2、5×10ⁿ
+fn nice_length(value: f64) -> f64 {
+    let magnitude = 10f64.powf((value.log10() + 1e-9).floor());
+    [5.0, 2.0, 1.0]
+        .into_iter()
+        .map(|m| m * magnitude)
+        .find(|&length| length <= value * (1.0 + 1e-9))
+        .unwrap_or(magnitude)
+}
+
+/// 长度标注：整数不带小数，否则保留至多 3 位小数
+fn format_length(value: f64) -> String {
+    if (value - value.round()).abs() < 1e-9 {
+        format!("{}", value.round())
+    } else {
+        format!("{:.3}", value).trim_end_matches('0').to_string()
+    }
+}
+
 /// 导出格式
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum ExportFormat {
@@ -533,4 +836,107 @@ mod tests {
         assert_eq!(w, 190.0);
         assert_eq!(h, 277.0);
     }
+
+    #[test]
+    fn test_page_grid_tiles_large_drawing() {
+        // A4 纵向可打印 190×277，1:1 比例，重叠 10mm：每页前进 180×267
+        let setup = PageSetup {
+            orientation: Orientation::Portrait,
+            fit_to_page: false,
+            tile_overlap: Some(10.0),
+            ..Default::default()
+        };
+        let bounds = PrintArea::new(Point2::new(0.0, 0.0), Point2::new(500.0, 300.0));
+        let grid = setup.page_grid(&bounds);
+        assert_eq!((grid.columns, grid.rows), (3, 2));
+        assert_eq!(grid.pages.len(), 6);
+
+        // 第一页从左上角开始，相邻页重叠 10
+        let first = &grid.pages[0];
+        assert_eq!((first.min.x, first.max.y), (0.0, 300.0));
+        assert!((first.width() - 190.0).abs() < 1e-9 && (first.height() - 277.0).abs() < 1e-9);
+        assert!((grid.pages[1].min.x - 180.0).abs() < 1e-9);
+        assert!((grid.pages[3].max.y - 33.0).abs() < 1e-9);
+        // 所有页合起来覆盖整个范围
+        let last = grid.pages.last().unwrap();
+        assert!(last.max.x >= 500.0 && last.min.y <= 0.0);
+
+        // 比例 1:2 时每页覆盖的图纸范围加倍，一页即可容纳
+        let half = PageSetup { scale: 0.5, ..setup.clone() };
+        let grid = half.page_grid(&PrintArea::new(Point2::new(0.0, 0.0), Point2::new(380.0, 554.0)));
+        assert_eq!((grid.columns, grid.rows), (1, 1));
+    }
+
+    #[test]
+    fn test_svg_export_pages() {
+        let setup = PageSetup {
+            fit_to_page: false,
+            tile_overlap: Some(0.0),
+            ..Default::default()
+        };
+        // A4 横向可打印 277×190，线段横跨两页
+        let entities = vec![Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(400.0, 100.0))))];
+        let pages = SvgExporter::new(setup).export_pages(&entities).unwrap();
+        assert_eq!(pages.len(), 2);
+        for svg in &pages {
+            assert_eq!(svg.matches("<polyline").count() + svg.matches("<line").count(), 1);
+        }
+        assert_eq!(
+            page_file_path(std::path::Path::new("/tmp/plan.svg"), 2),
+            std::path::PathBuf::from("/tmp/plan-2.svg")
+        );
+    }
+
+    #[test]
+    fn test_svg_clips_to_print_area() {
+        let setup = PageSetup {
+            print_area: Some(PrintArea::new(Point2::new(0.0, 0.0), Point2::new(10.0, 10.0))),
+            ..Default::default()
+        };
+        let entities = vec![
+            // 部分在打印区域外的直线
+            Entity::new(Geometry::Line(Line::new(Point2::new(-5.0, 5.0), Point2::new(5.0, 5.0)))),
+            // 完全在打印区域外的直线
+            Entity::new(Geometry::Line(Line::new(Point2::new(20.0, 20.0), Point2::new(30.0, 30.0)))),
+        ];
+
+        let svg = SvgExporter::new(setup).export(&entities).unwrap();
+        assert_eq!(svg.matches("<line").count(), 1);
+        assert!(svg.contains(r#"x1="0.0000" y1="5.0000" x2="5.0000" y2="5.0000""#));
+    }
+
+    #[test]
+    fn test_clip_circle_to_area() {
+        let area = BoundingBox2::new(Point2::new(0.0, -10.0), Point2::new(10.0, 10.0));
+        let circle = Geometry::Circle(zcad_core::geometry::Circle::new(Point2::origin(), 5.0));
+
+        let pieces = clip_geometry(&circle, &area);
+        assert_eq!(pieces.len(), 2);
+        for piece in &pieces {
+            let bbox = piece.bounding_box();
+            assert!(bbox.min.x >= -1e-9);
+        }
+    }
+
+    #[test]
+    fn test_scale_bar_label_matches_export_scale() {
+        for (scale, expected) in [(1.0, 50.0), (20.0, 1000.0), (100.0, 5000.0), (250.0, 10000.0), (0.5, 20.0)] {
+            let bar = scale_bar(Point2::origin(), scale);
+            let bounds = bar
+                .iter()
+                .filter(|g| matches!(g, Geometry::Polyline(_)))
+                .fold(BoundingBox2::empty(), |b, g| b.union(&g.bounding_box()));
+            let last_label = bar
+                .iter()
+                .rev()
+                .find_map(|g| match g {
+                    Geometry::Text(text) => Some(text.content.parse::<f64>().unwrap()),
+                    _ => None,
+                })
+                .unwrap();
+            // 末端标注的模型长度 = 图纸长度 × 比例
+            assert_eq!(last_label, expected);
+            assert!((bounds.width() * scale - expected).abs() < 1e-9, "1:{} 比例尺长度不符", scale);
+        }
+    }
 }
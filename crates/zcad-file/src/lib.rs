@@ -3,18 +3,21 @@
 //! 支持：
 //! - `.zcad` 原生格式（基于SQLite）
 //! - `.dxf` 导入/导出
+//! - `.dwg` 通过外部转换器导入
 //! - SVG/PDF 导出
 
 pub mod document;
+pub mod dwg;
 pub mod dxf_io;
 pub mod dxf_raw;
 pub mod error;
 pub mod export;
 pub mod native;
 
-pub use document::Document;
+pub use document::{AuditReport, ChangeEvent, Document, DrawingStats, ViewState};
+pub use dwg::{register_dwg_converter, unregister_dwg_converter, DwgConverter};
 pub use error::FileError;
-pub use export::{ExportFormat, PageSetup, PaperSize, Orientation, SvgExporter, PdfExporter, export_entities};
+pub use export::{ExportFormat, PageGrid, PageSetup, PaperSize, Orientation, SvgExporter, PdfExporter, export_entities, scale_bar, title_block};
 
 // 原始 DXF 解析器（用于完整的 Layout/Viewport 支持）
 pub use dxf_raw::{DxfRawParser, DxfLayout, DxfViewport, DxfWriter, parse_layouts, parse_viewports};
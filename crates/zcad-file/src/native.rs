@@ -18,17 +18,20 @@
 //! | 版本历史 | 可扩展 | 不支持 |
 //! | 自定义数据 | 原生支持 | 需要 XDATA |
 
-use crate::document::{Document, DocumentMetadata, SavedView};
+use crate::document::{Document, DocumentMetadata, SavedView, ViewState};
 use crate::error::FileError;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::{BufReader, BufWriter, Read, Write};
 use std::path::Path;
-use zcad_core::entity::Entity;
+use zcad_core::entity::{Entity, EntityId};
 use zcad_core::layer::Layer;
 use zcad_core::layout::{Layout, LayoutId, PaperSize, PaperOrientation, Viewport, ViewportId, ViewportStatus, SpaceType};
-use zcad_core::math::Point2;
+use zcad_core::math::{BoundingBox2, Point2};
 use zcad_core::dimstyle::DimStyle;
+use zcad_core::textstyle::TextStyleManager;
+use zcad_core::pointstyle::PointStyle;
 use zcad_core::units::Unit;
 use zcad_core::block::Block;
 
@@ -39,7 +42,11 @@ const MAGIC: &[u8; 4] = b"ZCAD";
 /// - v1: 基础实体和图层
 /// - v2: 添加视图
 /// - v3: 添加布局、视口、标注样式、块定义、单位设置
-const FORMAT_VERSION: u32 = 3;
+/// - v4: 添加视图状态（相机、网格/正交/捕捉开关）和当前图层
+/// - v5: 按字段名编码（MessagePack map），新增字段只需提供默认值即可兼容旧文件
+///
+/// 读取旧版本文件时先按结构默认值补齐缺失字段，再由 [`migrate`] 逐版本升级。
+const FORMAT_VERSION: u32 = 5;
 
 /// Zstd 压缩级别（1-22，3 是默认值，平衡速度和压缩比）
 const COMPRESSION_LEVEL: i32 = 3;
@@ -116,6 +123,8 @@ struct SerializableViewport {
     view_center: (f64, f64),
     scale: f64,
     rotation: f64,
+    #[serde(default)]
+    annotation_scale: Option<f64>,
     status: u8, // 0=Hidden, 1=Active, 2=Inactive, 3=Locked
 }
 
@@ -130,6 +139,7 @@ impl From<&Viewport> for SerializableViewport {
             view_center: (vp.view_center.x, vp.view_center.y),
             scale: vp.scale,
             rotation: vp.rotation,
+            annotation_scale: vp.annotation_scale,
             status: match vp.status {
                 ViewportStatus::Hidden => 0,
                 ViewportStatus::Active => 1,
@@ -152,6 +162,7 @@ impl SerializableViewport {
         vp.view_center = Point2::new(self.view_center.0, self.view_center.1);
         vp.scale = self.scale;
         vp.rotation = self.rotation;
+        vp.annotation_scale = self.annotation_scale;
         vp.status = match self.status {
             0 => ViewportStatus::Hidden,
             1 => ViewportStatus::Active,
@@ -253,7 +264,8 @@ struct FileContent {
     layers: Vec<Layer>,
     /// 所有实体（模型空间）
     entities: Vec<Entity>,
-    /// 保存的视图
+    /// 保存的视图（v2 新增）
+    #[serde(default)]
     views: Vec<SavedView>,
     
     // === v3 新增字段 ===
@@ -281,6 +293,28 @@ struct FileContent {
     /// 绘图单位
     #[serde(default = "default_unit")]
     drawing_unit: String,
+
+    // === v4 新增字段 ===
+
+    /// 视图状态
+    #[serde(default)]
+    view_state: Option<ViewState>,
+
+    /// 当前图层名称
+    #[serde(default)]
+    current_layer: Option<String>,
+
+    /// 文字样式表
+    #[serde(default)]
+    text_styles: TextStyleManager,
+
+    /// 点样式
+    #[serde(default)]
+    point_style: PointStyle,
+
+    /// 编组
+    #[serde(default)]
+    groups: HashMap<String, Vec<EntityId>>,
 }
 
 fn default_space_type() -> SerializableSpaceType {
@@ -291,6 +325,21 @@ fn default_unit() -> String {
     "Millimeter".to_string()
 }
 
+/// 将旧版本的文件内容升级到当前版本
+///
+/// 结构上缺失的字段已由 serde 默认值补齐，这里处理需要依据其他字段推导的部分。
+fn migrate(content: &mut FileContent, version: u32) {
+    if version < 3 {
+        // v3 之前没有单独的绘图单位字段，沿用元数据中的单位
+        content.drawing_unit = content.metadata.units.clone();
+    }
+
+    if version < FORMAT_VERSION {
+        tracing::info!("Migrated document from format v{} to v{}", version, FORMAT_VERSION);
+    }
+    content.metadata.format_version = FORMAT_VERSION;
+}
+
 /// 保存文档到文件
 pub fn save(document: &Document, path: &Path) -> Result<(), FileError> {
     // 收集布局数据
@@ -316,14 +365,21 @@ pub fn save(document: &Document, path: &Path) -> Result<(), FileError> {
         // v3 新增
         layouts,
         current_space,
-        blocks: Vec::new(), // TODO: 从 document 获取块定义
+        blocks: document.blocks.iter().cloned().collect(),
         dim_styles: Vec::new(), // TODO: 从 document 获取标注样式
         current_dim_style: "Standard".to_string(),
         drawing_unit: document.metadata.units.clone(),
+
+        // v4 新增
+        view_state: document.view_state.clone(),
+        current_layer: Some(document.layers.current_layer().name.clone()),
+        text_styles: document.text_styles.clone(),
+        point_style: document.point_style,
+        groups: document.groups().clone(),
     };
 
-    // 序列化为 MessagePack
-    let msgpack_data = rmp_serde::to_vec(&content)?;
+    // 序列化为 MessagePack（按字段名编码，便于以后增加字段）
+    let msgpack_data = rmp_serde::to_vec_named(&content)?;
 
     // 使用 Zstd 压缩
     let compressed_data = zstd::encode_all(msgpack_data.as_slice(), COMPRESSION_LEVEL)?;
@@ -354,6 +410,19 @@ pub fn save(document: &Document, path: &Path) -> Result<(), FileError> {
 
 /// 从文件加载文档
 pub fn load(path: &Path) -> Result<Document, FileError> {
+    load_filtered(path, |_| true)
+}
+
+/// 只加载包围盒与 `bounds` 相交的模型空间实体
+///
+/// 文件整体压缩，仍需完整解压和反序列化，但范围外的实体不会进入文档和空间索引。
+/// 图层、块、布局等非实体数据照常加载。
+pub fn load_region(path: &Path, bounds: &BoundingBox2) -> Result<Document, FileError> {
+    load_filtered(path, |entity| bounds.intersects(&entity.bounding_box()))
+}
+
+/// 加载文档，只保留满足 `keep` 的模型空间实体
+fn load_filtered(path: &Path, keep: impl Fn(&Entity) -> bool) -> Result<Document, FileError> {
     let file = File::open(path)?;
     let mut reader = BufReader::new(file);
 
@@ -375,8 +444,9 @@ pub fn load(path: &Path) -> Result<Document, FileError> {
     // 解压缩
     let msgpack_data = zstd::decode_all(compressed_data.as_slice())?;
 
-    // 反序列化
-    let content: FileContent = rmp_serde::from_slice(&msgpack_data)?;
+    // 反序列化（v5 之前按位置编码，缺失的尾部字段取默认值），再升级到当前版本
+    let mut content: FileContent = rmp_serde::from_slice(&msgpack_data)?;
+    migrate(&mut content, header.version);
 
     // 重建文档
     let mut document = Document::new();
@@ -390,13 +460,30 @@ pub fn load(path: &Path) -> Result<Document, FileError> {
     }
 
     // 加载实体（模型空间）
-    for entity in content.entities {
+    for entity in content.entities.into_iter().filter(|e| keep(e)) {
         document.entities_mut().insert(entity.id, entity);
     }
 
     // 加载视图
     document.views = content.views;
 
+    // === v4: 恢复视图状态和当前图层 ===
+    document.view_state = content.view_state;
+    if let Some(name) = &content.current_layer {
+        document.layers.set_current_layer(name);
+    }
+    document.text_styles = content.text_styles;
+    document.point_style = content.point_style;
+    // 编组在实体之后恢复，丢弃已不存在的成员
+    for (name, members) in content.groups {
+        document.add_group(name, members);
+    }
+
+    // 加载块定义
+    for block in content.blocks {
+        document.blocks.add_block(block);
+    }
+
     // === v3: 加载布局 ===
     if !content.layouts.is_empty() {
         // 清除默认布局
@@ -429,6 +516,7 @@ pub fn load(path: &Path) -> Result<Document, FileError> {
 
     // 重建空间索引
     document.rebuild_spatial_index();
+    document.mark_saved();
 
     tracing::info!(
         "Loaded {} entities, {} layers, {} layouts from {}",
@@ -480,6 +568,161 @@ mod tests {
         std::fs::remove_file(&file_path).ok();
     }
 
+    #[test]
+    fn test_view_state_roundtrip() {
+        let file_path = std::env::temp_dir().join("test_view_state.zcad");
+
+        let mut doc = Document::new();
+        doc.layers.create_layer("标注");
+        doc.layers.set_current_layer("标注");
+        let view_state = ViewState {
+            center_x: 120.5,
+            center_y: -40.0,
+            zoom: 2.5,
+            show_grid: false,
+            ortho_mode: true,
+            snap_enabled: false,
+        };
+        doc.view_state = Some(view_state.clone());
+        doc.point_style = PointStyle::new(35, -5.0);
+        let id = doc.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(1.0, 2.0),
+        ))));
+        doc.add_group("Marks", [id]);
+
+        save(&doc, &file_path).expect("Failed to save");
+        let loaded = load(&file_path).expect("Failed to load");
+
+        assert_eq!(loaded.select_group("Marks"), vec![id]);
+        assert!(!loaded.is_modified());
+
+        assert_eq!(loaded.view_state, Some(view_state));
+        assert_eq!(loaded.point_style, PointStyle::new(35, -5.0));
+        assert_eq!(loaded.layers.current_layer().name, "标注");
+
+        // 没有视图状态的文档加载后为 None，由界面使用默认视图
+        let doc = Document::new();
+        save(&doc, &file_path).expect("Failed to save");
+        let loaded = load(&file_path).expect("Failed to load");
+        assert_eq!(loaded.view_state, None);
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    /// v1 格式的实体：没有可见/锁定标记，颜色没有 alpha，属性没有透明度
+    #[derive(Serialize)]
+    struct LegacyColor {
+        r: u8,
+        g: u8,
+        b: u8,
+    }
+
+    #[derive(Serialize)]
+    struct LegacyProperties {
+        color: LegacyColor,
+        line_type: zcad_core::properties::LineType,
+        line_weight: zcad_core::properties::LineWeight,
+    }
+
+    #[derive(Serialize)]
+    struct LegacyEntity {
+        id: zcad_core::entity::EntityId,
+        geometry: Geometry,
+        properties: LegacyProperties,
+        layer_id: zcad_core::entity::EntityId,
+    }
+
+    #[derive(Serialize)]
+    struct LegacyContent {
+        metadata: DocumentMetadata,
+        layers: Vec<Layer>,
+        entities: Vec<LegacyEntity>,
+    }
+
+    #[test]
+    fn test_load_v1_document_defaults_fields() {
+        let file_path = std::env::temp_dir().join("test_legacy_v1.zcad");
+
+        let mut metadata = DocumentMetadata::default();
+        metadata.format_version = 1;
+        metadata.units = "inch".to_string();
+        let legacy = LegacyContent {
+            metadata,
+            layers: vec![Layer::default_layer()],
+            entities: vec![LegacyEntity {
+                id: zcad_core::entity::EntityId::from_raw(42, 0),
+                geometry: Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(5.0, 0.0))),
+                properties: LegacyProperties {
+                    color: LegacyColor { r: 255, g: 0, b: 0 },
+                    line_type: zcad_core::properties::LineType::Continuous,
+                    line_weight: zcad_core::properties::LineWeight::ByLayer,
+                },
+                layer_id: zcad_core::entity::EntityId::from_raw(1, 0),
+            }],
+        };
+
+        // v1 按位置编码
+        let compressed = zstd::encode_all(rmp_serde::to_vec(&legacy).unwrap().as_slice(), COMPRESSION_LEVEL).unwrap();
+        let mut file = File::create(&file_path).unwrap();
+        FileHeader {
+            magic: *MAGIC,
+            version: 1,
+            flags: 0,
+            compressed_size: compressed.len() as u32,
+        }
+        .write(&mut file)
+        .unwrap();
+        file.write_all(&compressed).unwrap();
+        drop(file);
+
+        let loaded = load(&file_path).expect("Failed to load v1 document");
+        let entity = loaded.get_entity(&zcad_core::entity::EntityId::from_raw(42, 0)).unwrap();
+        assert!(entity.visible);
+        assert!(!entity.locked);
+        assert_eq!(entity.properties.color.a, 255);
+        assert_eq!(entity.properties.color.r, 255);
+        assert_eq!(entity.properties.transparency, 0);
+        assert!(loaded.views.is_empty());
+        assert!(loaded.view_state.is_none());
+        assert_eq!(loaded.metadata.format_version, FORMAT_VERSION);
+
+        std::fs::remove_file(&file_path).ok();
+    }
+
+    #[test]
+    fn test_load_region_keeps_intersecting_entities() {
+        let file_path = std::env::temp_dir().join("test_load_region.zcad");
+        let mut doc = Document::new();
+        // 沿 x 轴每隔 100 放一条竖线
+        let ids: Vec<EntityId> = (0..10)
+            .map(|i| {
+                let x = i as f64 * 100.0;
+                doc.add_entity(Entity::new(Geometry::Line(Line::new(Point2::new(x, 0.0), Point2::new(x, 50.0)))))
+            })
+            .collect();
+        // 横跨区域边界的长线
+        let crossing = doc.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(-50.0, 200.0),
+            Point2::new(1000.0, 25.0),
+        ))));
+        save(&doc, &file_path).expect("Failed to save");
+
+        let region = BoundingBox2::new(Point2::new(150.0, -10.0), Point2::new(450.0, 60.0));
+        let loaded = load_region(&file_path, &region).expect("Failed to load");
+        std::fs::remove_file(&file_path).ok();
+
+        assert_eq!(loaded.entity_count(), 4);
+        for id in &ids[2..5] {
+            assert!(loaded.get_entity(id).is_some());
+        }
+        assert!(loaded.get_entity(&crossing).is_some());
+        assert!(loaded.get_entity(&ids[0]).is_none() && loaded.get_entity(&ids[5]).is_none());
+        // 空间索引只包含加载的实体
+        let everything = BoundingBox2::new(Point2::new(-100.0, -100.0), Point2::new(2000.0, 300.0));
+        assert_eq!(loaded.query_rect(&everything).len(), 4);
+    }
+
     #[test]
     fn test_invalid_magic() {
         let temp_dir = std::env::temp_dir();
@@ -5,6 +5,21 @@
 use crate::vertex::CameraUniform;
 use zcad_core::math::{BoundingBox2, Point2, Vector2};
 
+/// 惯性平移停止阈值（屏幕像素/秒）
+pub const INERTIA_STOP_SPEED: f64 = 5.0;
+
+/// 惯性速度衰减
+///
+/// `friction` 为每秒保留的速度比例（0~1），速度低于 [`INERTIA_STOP_SPEED`] 时归零。
+pub fn decay_velocity(velocity: Vector2, friction: f64, dt: f64) -> Vector2 {
+    let decayed = velocity * friction.clamp(0.0, 1.0).powf(dt.max(0.0));
+    if decayed.norm() < INERTIA_STOP_SPEED {
+        Vector2::zeros()
+    } else {
+        decayed
+    }
+}
+
 /// 2D相机
 #[derive(Debug, Clone)]
 pub struct Camera2D {
@@ -25,6 +40,15 @@ pub struct Camera2D {
 
     /// 最大缩放
     pub max_zoom: f64,
+
+    /// 是否启用惯性平移
+    pub inertia_enabled: bool,
+
+    /// 惯性摩擦系数（每秒保留的速度比例）
+    pub inertia_friction: f64,
+
+    /// 当前惯性速度（屏幕像素/秒）
+    pan_velocity: Vector2,
 }
 
 impl Camera2D {
@@ -37,6 +61,9 @@ impl Camera2D {
             viewport_height,
             min_zoom: 0.001,
             max_zoom: 10000.0,
+            inertia_enabled: true,
+            inertia_friction: 0.02,
+            pan_velocity: Vector2::zeros(),
         }
     }
 
@@ -51,6 +78,41 @@ impl Camera2D {
         self.center += delta / self.zoom;
     }
 
+    /// 按屏幕拖动量平移（图形跟随鼠标移动，屏幕 Y 轴向下）
+    pub fn pan_screen(&mut self, screen_delta: Vector2) {
+        self.center.x -= screen_delta.x / self.zoom;
+        self.center.y += screen_delta.y / self.zoom;
+    }
+
+    /// 结束拖动平移，以松开时的速度（屏幕像素/秒）开始惯性滑动
+    pub fn end_pan(&mut self, release_velocity: Vector2) {
+        self.pan_velocity = if self.inertia_enabled {
+            decay_velocity(release_velocity, 1.0, 0.0)
+        } else {
+            Vector2::zeros()
+        };
+    }
+
+    /// 立即停止惯性滑动
+    pub fn stop_inertia(&mut self) {
+        self.pan_velocity = Vector2::zeros();
+    }
+
+    /// 是否处于惯性滑动中
+    pub fn is_coasting(&self) -> bool {
+        self.pan_velocity.norm() > 0.0
+    }
+
+    /// 推进惯性滑动，返回本帧是否发生了移动
+    pub fn update_inertia(&mut self, dt: f64) -> bool {
+        if !self.is_coasting() {
+            return false;
+        }
+        self.pan_screen(self.pan_velocity * dt);
+        self.pan_velocity = decay_velocity(self.pan_velocity, self.inertia_friction, dt);
+        true
+    }
+
     /// 缩放相机（以指定屏幕点为中心）
     pub fn zoom_at(&mut self, screen_point: Point2, factor: f64) {
         let world_before = self.screen_to_world(screen_point);
@@ -124,6 +186,7 @@ impl Camera2D {
     pub fn reset(&mut self) {
         self.center = Point2::origin();
         self.zoom = 1.0;
+        self.pan_velocity = Vector2::zeros();
     }
 
     /// 获取当前单位像素比（1单位对应多少像素）
@@ -154,5 +217,37 @@ mod tests {
         assert!(approx_eq(world.x, back.x));
         assert!(approx_eq(world.y, back.y));
     }
+
+    #[test]
+    fn test_inertia_decay() {
+        let mut camera = Camera2D::new(800, 600);
+        camera.end_pan(Vector2::new(600.0, 0.0));
+
+        let dt = 1.0 / 60.0;
+        let mut last_offset = f64::MAX;
+        let mut frames = 0;
+        while camera.is_coasting() {
+            let before = camera.center;
+            assert!(camera.update_inertia(dt));
+            let offset = (camera.center - before).norm();
+            assert!(offset > 0.0 && offset < last_offset);
+            last_offset = offset;
+            frames += 1;
+            assert!(frames < 1000, "inertia never stopped");
+        }
+        // 停止后不再移动
+        let before = camera.center;
+        assert!(!camera.update_inertia(dt));
+        assert_eq!(camera.center, before);
+    }
+
+    #[test]
+    fn test_inertia_disabled() {
+        let mut camera = Camera2D::new(800, 600);
+        camera.inertia_enabled = false;
+        camera.end_pan(Vector2::new(600.0, 0.0));
+        assert!(!camera.is_coasting());
+        assert_eq!(decay_velocity(Vector2::new(1.0, 0.0), 0.5, 1.0), Vector2::zeros());
+    }
 }
 
@@ -13,11 +13,13 @@ pub mod camera;
 pub mod compute;
 pub mod pipeline;
 pub mod renderer;
+pub mod tessellation;
 pub mod tile;
 pub mod vertex;
 
 pub use camera::Camera2D;
 pub use compute::{BooleanOp, ComputeShader};
-pub use renderer::Renderer;
+pub use renderer::{Renderer, RendererOptions};
+pub use tessellation::arc_segment_count;
 pub use tile::{Tile, TileManager};
 
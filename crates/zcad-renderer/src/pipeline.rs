@@ -8,8 +8,19 @@ pub struct LinePipeline {
     pub camera_bind_group_layout: wgpu::BindGroupLayout,
 }
 
+/// 在支持的采样数中选取不超过请求值的最大值（1、2、4、8、16）
+///
+/// 请求值不是 2 的幂时向下取整；1 总是可用，表示关闭 MSAA。
+pub fn msaa_sample_count(requested: u32, supported: impl Fn(u32) -> bool) -> u32 {
+    [16, 8, 4, 2]
+        .into_iter()
+        .find(|&count| count <= requested && supported(count))
+        .unwrap_or(1)
+}
+
 impl LinePipeline {
-    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat) -> Self {
+    /// 创建线条管线，`sample_count` 为 1 时不使用 MSAA
+    pub fn new(device: &wgpu::Device, format: wgpu::TextureFormat, sample_count: u32) -> Self {
         // 着色器
         let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
             label: Some("Line Shader"),
@@ -68,7 +79,7 @@ impl LinePipeline {
             },
             depth_stencil: None,
             multisample: wgpu::MultisampleState {
-                count: 1, // 无MSAA，更好地与egui集成
+                count: sample_count,
                 mask: !0,
                 alpha_to_coverage_enabled: false,
             },
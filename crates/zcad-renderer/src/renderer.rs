@@ -5,7 +5,8 @@
 
 use crate::camera::Camera2D;
 use crate::compute::{ComputeShader, BooleanOp};
-use crate::pipeline::LinePipeline;
+use crate::pipeline::{msaa_sample_count, LinePipeline};
+use crate::tessellation::arc_segment_count;
 use crate::tile::TileManager;
 use crate::vertex::{CameraUniform, LineVertex};
 use thiserror::Error;
@@ -34,6 +35,20 @@ pub enum RenderError {
     Compute(String),
 }
 
+/// 渲染器选项
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RendererOptions {
+    /// MSAA 采样数，1 表示关闭；超出设备支持时自动降到可用的最大值
+    pub msaa_samples: u32,
+}
+
+impl Default for RendererOptions {
+    fn default() -> Self {
+        // 默认无MSAA，更好地与egui集成
+        Self { msaa_samples: 1 }
+    }
+}
+
 /// GPU渲染器（保留用于未来的高性能渲染需求）
 pub struct Renderer {
     device: wgpu::Device,
@@ -41,6 +56,10 @@ pub struct Renderer {
     surface: wgpu::Surface<'static>,
     surface_config: wgpu::SurfaceConfiguration,
 
+    // MSAA：实际采样数与多重采样颜色缓冲（采样数为 1 时为 None）
+    msaa_samples: u32,
+    msaa_view: Option<wgpu::TextureView>,
+
     line_pipeline: LinePipeline,
     camera_buffer: wgpu::Buffer,
     camera_bind_group: wgpu::BindGroup,
@@ -58,11 +77,23 @@ pub struct Renderer {
     grid_visible: bool,
     grid_spacing: f64,
     grid_color: Color,
+
+    // 曲线细分：当前缩放级别与最大弦高误差（像素）
+    zoom: f64,
+    chord_error: f64,
 }
 
 impl Renderer {
     /// 创建新的渲染器
     pub async fn new(window: std::sync::Arc<winit::window::Window>) -> Result<Self, RenderError> {
+        Self::with_options(window, RendererOptions::default()).await
+    }
+
+    /// 按指定选项创建渲染器
+    pub async fn with_options(
+        window: std::sync::Arc<winit::window::Window>,
+        options: RendererOptions,
+    ) -> Result<Self, RenderError> {
         let size = window.inner_size();
 
         let instance = wgpu::Instance::new(&wgpu::InstanceDescriptor {
@@ -112,7 +143,12 @@ impl Renderer {
         surface.configure(&device, &surface_config);
 
         // 创建管线
-        let line_pipeline = LinePipeline::new(&device, surface_format);
+        let format_features = adapter.get_texture_format_features(surface_format);
+        let msaa_samples = msaa_sample_count(options.msaa_samples, |count| {
+            format_features.flags.sample_count_supported(count)
+        });
+        let msaa_view = create_msaa_view(&device, &surface_config, msaa_samples);
+        let line_pipeline = LinePipeline::new(&device, surface_format, msaa_samples);
 
         // 创建计算着色器
         let compute_shader = ComputeShader::new(&device, &queue).map_err(|e| RenderError::Compute(e.to_string()))?;
@@ -142,6 +178,8 @@ impl Renderer {
             queue,
             surface,
             surface_config,
+            msaa_samples,
+            msaa_view,
             line_pipeline,
             camera_buffer,
             camera_bind_group,
@@ -151,6 +189,8 @@ impl Renderer {
             grid_visible: true,
             grid_spacing: 50.0,
             grid_color: Color::new(60, 60, 70),
+            zoom: 1.0,
+            chord_error: crate::tessellation::DEFAULT_CHORD_ERROR,
         })
     }
 
@@ -160,17 +200,24 @@ impl Renderer {
             self.surface_config.width = width;
             self.surface_config.height = height;
             self.surface.configure(&self.device, &self.surface_config);
+            self.msaa_view = create_msaa_view(&self.device, &self.surface_config, self.msaa_samples);
             self.tile_manager.resize(width, height);
         }
     }
 
     /// 更新相机
     pub fn update_camera(&mut self, camera: &Camera2D) {
+        self.zoom = camera.zoom;
         let uniform = camera.to_uniform();
         self.queue
             .write_buffer(&self.camera_buffer, 0, bytemuck::cast_slice(&[uniform]));
     }
 
+    /// 设置圆/圆弧细分的最大弦高误差（屏幕像素）
+    pub fn set_chord_error(&mut self, chord_error: f64) {
+        self.chord_error = chord_error;
+    }
+
     /// 设置网格可见性
     pub fn set_grid_visible(&mut self, visible: bool) {
         self.grid_visible = visible;
@@ -329,7 +376,7 @@ impl Renderer {
     }
 
     fn draw_circle(&mut self, circle: &Circle, color: [f32; 4]) {
-        let segments = (circle.radius * 2.0).clamp(32.0, 256.0) as usize;
+        let segments = arc_segment_count(circle.radius * self.zoom, std::f64::consts::TAU, self.chord_error);
         let angle_step = 2.0 * std::f64::consts::PI / segments as f64;
 
         for i in 0..segments {
@@ -348,7 +395,7 @@ impl Renderer {
 
     fn draw_arc(&mut self, arc: &Arc, color: [f32; 4]) {
         let sweep = arc.sweep_angle();
-        let segments = ((arc.radius * sweep.abs()).clamp(8.0, 128.0)) as usize;
+        let segments = arc_segment_count(arc.radius * self.zoom, sweep, self.chord_error);
         let angle_step = sweep / segments as f64;
 
         for i in 0..segments {
@@ -501,25 +548,15 @@ impl Renderer {
     }
 
     fn draw_leader(&mut self, leader: &zcad_core::geometry::Leader, color: [f32; 4]) {
-        // 绘制引线线段
-        for i in 0..leader.vertices.len().saturating_sub(1) {
-            self.line_vertices.push(LineVertex::new(
-                leader.vertices[i].x as f32,
-                leader.vertices[i].y as f32,
-                color,
-            ));
-            self.line_vertices.push(LineVertex::new(
-                leader.vertices[i + 1].x as f32,
-                leader.vertices[i + 1].y as f32,
-                color,
-            ));
+        // 绘制引线线段（样条引线按采样点绘制）
+        for w in leader.path_points().windows(2) {
+            self.line_vertices.push(LineVertex::new(w[0].x as f32, w[0].y as f32, color));
+            self.line_vertices.push(LineVertex::new(w[1].x as f32, w[1].y as f32, color));
         }
 
         // 绘制箭头
-        if leader.vertices.len() >= 2 {
+        if let Some(dir) = leader.arrow_direction() {
             let arrow_pt = leader.vertices[0];
-            let next_pt = leader.vertices[1];
-            let dir = (arrow_pt - next_pt).normalize();
             let perp = zcad_core::math::Vector2::new(-dir.y, dir.x);
             let arrow_size = leader.arrow_size;
 
@@ -550,8 +587,9 @@ impl Renderer {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Tile-based Render Pass"),
                 color_attachments: &[Some(wgpu::RenderPassColorAttachment {
-                    view: &view,
-                    resolve_target: None,
+                    // 开启 MSAA 时画到多重采样缓冲，再解析到交换链纹理
+                    view: self.msaa_view.as_ref().unwrap_or(&view),
+                    resolve_target: self.msaa_view.as_ref().map(|_| &view),
                     ops: wgpu::Operations {
                         load: wgpu::LoadOp::Clear(wgpu::Color {
                             r: clear[0] as f64,
@@ -608,6 +646,11 @@ impl Renderer {
         Ok(())
     }
 
+    /// 实际使用的 MSAA 采样数
+    pub fn msaa_samples(&self) -> u32 {
+        self.msaa_samples
+    }
+
     /// 获取设备引用
     pub fn device(&self) -> &wgpu::Device {
         &self.device
@@ -707,7 +750,7 @@ impl Renderer {
                 ));
             }
             Geometry::Circle(circle) => {
-                let segments = (circle.radius * 2.0).clamp(32.0, 256.0) as usize;
+                let segments = arc_segment_count(circle.radius * self.zoom, std::f64::consts::TAU, self.chord_error);
                 let angle_step = 2.0 * std::f64::consts::PI / segments as f64;
 
                 for i in 0..segments {
@@ -723,7 +766,7 @@ impl Renderer {
             }
             Geometry::Arc(arc) => {
                 let sweep = arc.sweep_angle();
-                let segments = ((arc.radius * sweep.abs()).clamp(8.0, 128.0)) as usize;
+                let segments = arc_segment_count(arc.radius * self.zoom, sweep, self.chord_error);
                 let angle_step = sweep / segments as f64;
 
                 for i in 0..segments {
@@ -819,11 +862,37 @@ impl Renderer {
                 }
             }
             Geometry::Leader(leader) => {
-                for i in 0..leader.vertices.len().saturating_sub(1) {
-                    vertices.push(LineVertex::new(leader.vertices[i].x as f32, leader.vertices[i].y as f32, color_arr));
-                    vertices.push(LineVertex::new(leader.vertices[i + 1].x as f32, leader.vertices[i + 1].y as f32, color_arr));
+                for w in leader.path_points().windows(2) {
+                    vertices.push(LineVertex::new(w[0].x as f32, w[0].y as f32, color_arr));
+                    vertices.push(LineVertex::new(w[1].x as f32, w[1].y as f32, color_arr));
                 }
             }
         }
     }
 }
+
+/// 创建与交换链同尺寸的多重采样颜色缓冲，采样数为 1 时不需要
+fn create_msaa_view(
+    device: &wgpu::Device,
+    config: &wgpu::SurfaceConfiguration,
+    sample_count: u32,
+) -> Option<wgpu::TextureView> {
+    if sample_count <= 1 {
+        return None;
+    }
+    let texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("MSAA Color Texture"),
+        size: wgpu::Extent3d {
+            width: config.width,
+            height: config.height,
+            depth_or_array_layers: 1,
+        },
+        mip_level_count: 1,
+        sample_count,
+        dimension: wgpu::TextureDimension::D2,
+        format: config.format,
+        usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+        view_formats: &[],
+    });
+    Some(texture.create_view(&wgpu::TextureViewDescriptor::default()))
+}
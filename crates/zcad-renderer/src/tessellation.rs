@@ -0,0 +1,67 @@
+//! 曲线细分
+//!
+//! 根据屏幕上的半径和允许的最大弦高误差计算圆/圆弧的分段数，
+//! 使大圆不再呈现明显的折线，同时限制分段数上限以保证性能。
+
+/// 默认最大弦高误差（屏幕像素）
+pub const DEFAULT_CHORD_ERROR: f64 = 0.25;
+
+/// 整圆的最少分段数
+pub const MIN_CIRCLE_SEGMENTS: usize = 16;
+
+/// 整圆的最多分段数
+pub const MAX_CIRCLE_SEGMENTS: usize = 1024;
+
+/// 计算圆弧的分段数
+///
+/// - `screen_radius`: 屏幕上的半径（像素）
+/// - `sweep`: 圆弧扫过的角度（弧度），整圆为 2π
+/// - `max_chord_error`: 允许的最大弦高误差（像素）
+///
+/// 每段对应的圆心角 θ 满足 r·(1 - cos(θ/2)) ≤ e，即 θ = 2·acos(1 - e/r)。
+/// 上下限按扫角占整圆的比例缩放，且至少为 1 段。
+pub fn arc_segment_count(screen_radius: f64, sweep: f64, max_chord_error: f64) -> usize {
+    let fraction = (sweep.abs() / std::f64::consts::TAU).min(1.0);
+    let min_segments = ((MIN_CIRCLE_SEGMENTS as f64 * fraction).ceil() as usize).max(1);
+    let max_segments = ((MAX_CIRCLE_SEGMENTS as f64 * fraction).ceil() as usize).max(min_segments);
+
+    if !screen_radius.is_finite() || screen_radius <= 0.0 || max_chord_error <= 0.0 {
+        return min_segments;
+    }
+    if max_chord_error >= screen_radius {
+        return min_segments;
+    }
+
+    let segment_angle = 2.0 * (1.0 - max_chord_error / screen_radius).acos();
+    let segments = (sweep.abs() / segment_angle).ceil() as usize;
+    segments.clamp(min_segments, max_segments)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::f64::consts::{PI, TAU};
+
+    #[test]
+    fn test_segment_count_grows_with_screen_radius() {
+        let small = arc_segment_count(10.0, TAU, DEFAULT_CHORD_ERROR);
+        let medium = arc_segment_count(100.0, TAU, DEFAULT_CHORD_ERROR);
+        let large = arc_segment_count(1000.0, TAU, DEFAULT_CHORD_ERROR);
+        assert!(small < medium && medium < large);
+
+        // 分段后的弦高误差不超过容差
+        let r = 100.0;
+        let theta = TAU / medium as f64;
+        assert!(r * (1.0 - (theta / 2.0).cos()) <= DEFAULT_CHORD_ERROR + 1e-9);
+    }
+
+    #[test]
+    fn test_segment_count_limits() {
+        assert_eq!(arc_segment_count(0.5, TAU, DEFAULT_CHORD_ERROR), MIN_CIRCLE_SEGMENTS);
+        assert_eq!(arc_segment_count(1e9, TAU, DEFAULT_CHORD_ERROR), MAX_CIRCLE_SEGMENTS);
+        // 半圆的上限减半
+        assert_eq!(arc_segment_count(1e9, PI, DEFAULT_CHORD_ERROR), MAX_CIRCLE_SEGMENTS / 2);
+        // 容差越大分段越少
+        assert!(arc_segment_count(500.0, TAU, 2.0) < arc_segment_count(500.0, TAU, 0.1));
+    }
+}
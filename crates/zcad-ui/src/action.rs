@@ -4,8 +4,11 @@
 //! 采用状态机模式处理用户交互。
 
 use zcad_core::entity::{Entity, EntityId};
-use zcad_core::geometry::Geometry;
-use zcad_core::math::Point2;
+use zcad_core::geometry::{Geometry, Line, Polyline};
+use zcad_core::history::{operations, HistoryTree, Operation};
+use zcad_core::input_parser::{InputParser, InputValue, ParseError};
+use zcad_core::math::{BoundingBox2, Point2};
+use zcad_file::Document;
 
 /// Action 执行结果
 #[derive(Debug, Clone)]
@@ -18,8 +21,24 @@ pub enum ActionResult {
     ModifyEntities(Vec<(EntityId, Geometry)>),
     /// 修改单个实体
     ModifyEntity(EntityId, Geometry),
+    /// 完成当前 action，用多个几何替换一个实体（如修剪中间段后一分为二）
+    ReplaceEntity(EntityId, Vec<Geometry>),
     /// 完成当前 action，删除实体
     DeleteEntities(Vec<EntityId>),
+    /// 完成当前 action，删除一组实体并加入新实体（如区域布尔运算）
+    ///
+    /// 新实体已分配 ID，与 action 记录的历史操作一致。
+    ReplaceEntities {
+        removed: Vec<EntityId>,
+        added: Vec<Entity>,
+    },
+    /// 完成当前 action，修改已有实体并加入新实体（如圆角修剪两条线并加入圆弧）
+    ///
+    /// 新实体已分配 ID，与 action 记录的历史操作一致。
+    ModifyAndCreate {
+        modified: Vec<(EntityId, Geometry)>,
+        added: Vec<Entity>,
+    },
     /// 取消当前 action
     Cancel,
     /// 切换到另一个 action
@@ -55,11 +74,16 @@ pub enum ActionType {
     Rotate,
     Scale,
     Mirror,
+    Stretch,
     Offset,
     Trim,
     Extend,
+    Lengthen,
+    Break,
+    PolylineClose,
     Fillet,
     Chamfer,
+    RegionBoolean,
     Erase,
     
     // 夹点编辑
@@ -92,11 +116,16 @@ impl ActionType {
             ActionType::Rotate => "Rotate",
             ActionType::Scale => "Scale",
             ActionType::Mirror => "Mirror",
+            ActionType::Stretch => "Stretch",
             ActionType::Offset => "Offset",
             ActionType::Trim => "Trim",
             ActionType::Extend => "Extend",
+            ActionType::Lengthen => "Lengthen",
+            ActionType::Break => "Break",
+            ActionType::PolylineClose => "Polyline Close",
             ActionType::Fillet => "Fillet",
             ActionType::Chamfer => "Chamfer",
+            ActionType::RegionBoolean => "Region Boolean",
             ActionType::Erase => "Erase",
             ActionType::GripEdit => "Grip Edit",
             ActionType::None => "None",
@@ -125,11 +154,16 @@ impl ActionType {
             ActionType::Rotate => Some("RO"),
             ActionType::Scale => Some("SC"),
             ActionType::Mirror => Some("MI"),
+            ActionType::Stretch => Some("S"),
             ActionType::Offset => Some("O"),
             ActionType::Trim => Some("TR"),
             ActionType::Extend => Some("EX"),
+            ActionType::Lengthen => Some("LEN"),
+            ActionType::Break => Some("BR"),
+            ActionType::PolylineClose => Some("PC"),
             ActionType::Fillet => Some("F"),
             ActionType::Chamfer => Some("CHA"),
+            ActionType::RegionBoolean => Some("RB"),
             ActionType::Erase => Some("E"),
             ActionType::GripEdit => Some("G"),
             ActionType::None => None,
@@ -153,6 +187,8 @@ pub struct ActionContext<'a> {
     pub reference_point: Option<Point2>,
     /// 当前缩放级别
     pub zoom: f64,
+    /// Ctrl 键是否按下（用于夹点插入顶点等修饰操作）
+    pub ctrl_pressed: bool,
 }
 
 impl<'a> ActionContext<'a> {
@@ -160,6 +196,35 @@ impl<'a> ActionContext<'a> {
     pub fn effective_point(&self) -> Point2 {
         self.snap_pos.unwrap_or(self.mouse_pos)
     }
+
+    /// 实体是否被锁定（锁定实体仍可被捕捉，但不参与编辑）
+    pub fn is_locked(&self, id: &EntityId) -> bool {
+        self.entities.iter().any(|e| e.id == *id && e.locked)
+    }
+
+    /// 当前选择中未锁定的实体
+    pub fn unlocked_selection(&self) -> Vec<EntityId> {
+        self.selected_entities
+            .iter()
+            .copied()
+            .filter(|id| !self.is_locked(id))
+            .collect()
+    }
+}
+
+/// 测试用上下文：无选择、无捕捉、非正交，缩放为 1
+#[cfg(test)]
+pub(crate) fn test_context(entities: &[Entity], mouse_pos: Point2) -> ActionContext<'_> {
+    ActionContext {
+        mouse_pos,
+        snap_pos: None,
+        selected_entities: &[],
+        entities,
+        ortho_mode: false,
+        reference_point: None,
+        zoom: 1.0,
+        ctrl_pressed: false,
+    }
 }
 
 /// 预览几何体
@@ -183,8 +248,36 @@ impl PreviewGeometry {
             is_reference: true,
         }
     }
+
+    /// 基点标记：方框加十字，屏幕上的大小不随缩放变化
+    pub fn base_point_marker(point: Point2, zoom: f64) -> Vec<Self> {
+        let half = BASE_MARKER_SIZE / zoom.max(f64::EPSILON);
+        let square = Polyline::from_points(
+            [
+                Point2::new(point.x - half, point.y - half),
+                Point2::new(point.x + half, point.y - half),
+                Point2::new(point.x + half, point.y + half),
+                Point2::new(point.x - half, point.y + half),
+            ],
+            true,
+        );
+        vec![
+            Self::reference(Geometry::Polyline(square)),
+            Self::reference(Geometry::Line(Line::new(
+                Point2::new(point.x - 2.0 * half, point.y),
+                Point2::new(point.x + 2.0 * half, point.y),
+            ))),
+            Self::reference(Geometry::Line(Line::new(
+                Point2::new(point.x, point.y - 2.0 * half),
+                Point2::new(point.x, point.y + 2.0 * half),
+            ))),
+        ]
+    }
 }
 
+/// 基点标记方框的半宽（像素）
+const BASE_MARKER_SIZE: f64 = 4.0;
+
 /// 鼠标按钮
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum MouseButton {
@@ -230,6 +323,11 @@ pub trait Action: Send {
         ActionResult::Continue
     }
 
+    /// 尺寸输入（`宽,高`），不接受尺寸的 action 返回 `None`，输入按绝对坐标处理
+    fn on_dimensions(&mut self, _ctx: &ActionContext, _width: f64, _height: f64) -> Option<ActionResult> {
+        None
+    }
+
     // ========== UI 提示 ==========
 
     /// 获取当前状态的提示文本
@@ -262,6 +360,202 @@ pub trait Action: Send {
 
     /// 重做
     fn redo(&mut self) {}
+
+    /// 当前的基点（移动/复制的基点、旋转/缩放的中心）
+    ///
+    /// 宿主据此在预览中绘制基点标记，提醒用户基点位置
+    fn base_point(&self) -> Option<Point2> {
+        None
+    }
+
+    /// 取出待提交到文档历史（`HistoryTree`）的操作
+    ///
+    /// 宿主在处理完每个事件后调用，使 action 产生的修改可以被全局撤销
+    fn take_operations(&mut self) -> Vec<Operation> {
+        Vec::new()
+    }
+
+    /// 刚返回的 `ModifyEntity` 结果的包围盒，由修改前的包围盒 `previous` 增量计算
+    ///
+    /// 宿主据此更新空间索引；返回 `None` 时按新几何整体重新计算
+    fn modified_bounding_box(&self, _id: &EntityId, _previous: &BoundingBox2) -> Option<BoundingBox2> {
+        None
+    }
+}
+
+/// 将命令行输入分发给 action
+///
+/// 依次尝试子命令、尺寸（`宽,高`，仅对接受尺寸的 action）、坐标和数值，
+/// 使绘图过程中可以直接键入半径、长度或矩形尺寸完成当前步骤。
+pub fn dispatch_input(
+    action: &mut dyn Action,
+    ctx: &ActionContext,
+    input: &str,
+) -> Result<ActionResult, ParseError> {
+    if let Some(result) = action.on_command(ctx, input) {
+        return Ok(result);
+    }
+
+    let input = input.trim();
+    if !input.starts_with('@') {
+        if let Ok((width, height)) = InputParser::parse_dimensions(input) {
+            if let Some(result) = action.on_dimensions(ctx, width, height) {
+                return Ok(result);
+            }
+        }
+    }
+
+    Ok(match InputParser::parse(input, ctx.reference_point)? {
+        InputValue::Point(point) => action.on_coordinate(ctx, point),
+        InputValue::Length(value) => action.on_value(ctx, value),
+        // 绝对极坐标 "长度<角度" 以原点为基准
+        InputValue::LengthAngle { length, angle } => {
+            action.on_coordinate(ctx, Point2::new(length * angle.cos(), length * angle.sin()))
+        }
+        InputValue::Dimensions { width, height } => action
+            .on_dimensions(ctx, width, height)
+            .unwrap_or(ActionResult::Continue),
+        InputValue::Angle(_) => ActionResult::Continue,
+    })
+}
+
+/// 把 action 返回的结果应用到文档，并把对应的操作提交到历史
+///
+/// 宿主每次把事件交给 action 后调用。action 通过 `take_operations` 记录了操作时
+/// 直接提交这些操作；否则按结果生成创建、修改、删除操作，多个操作合并为一个分组，
+/// 一次撤销即可还原。返回 action 是否继续运行（取消或切换到其他 action 时为 `false`）。
+pub fn apply_action_result(
+    action: &mut dyn Action,
+    result: ActionResult,
+    document: &mut Document,
+    history: &mut HistoryTree,
+) -> bool {
+    let name = action.name().to_string();
+    let mut generated = Vec::new();
+    let running = match result {
+        ActionResult::Continue | ActionResult::NeedSelection => true,
+        ActionResult::Cancel | ActionResult::SwitchTo(_) => false,
+        ActionResult::CreateEntities(geometries) => {
+            for geometry in geometries {
+                let entity = document.new_entity(geometry);
+                document.add_entity(entity.clone());
+                generated.push(operations::create_entity(entity, name.as_str()));
+            }
+            true
+        }
+        ActionResult::ModifyEntity(id, geometry) => {
+            let bbox = document
+                .entity_bounding_box(&id)
+                .and_then(|previous| action.modified_bounding_box(&id, &previous));
+            generated.extend(replace_geometry(document, id, geometry, bbox, &name));
+            true
+        }
+        ActionResult::ModifyEntities(modified) => {
+            for (id, geometry) in modified {
+                generated.extend(replace_geometry(document, id, geometry, None, &name));
+            }
+            true
+        }
+        ActionResult::ReplaceEntity(id, geometries) => {
+            let Some(previous) = document.remove_entity(&id) else {
+                return true;
+            };
+            for geometry in geometries {
+                let mut entity = previous.duplicate();
+                entity.geometry = geometry;
+                document.add_entity(entity.clone());
+                generated.push(operations::create_entity(entity, name.as_str()));
+            }
+            generated.insert(0, operations::delete_entity(id, Some(previous), name.as_str()));
+            true
+        }
+        ActionResult::DeleteEntities(ids) => {
+            for entity in document.delete_entities(&ids) {
+                generated.push(operations::delete_entity(entity.id, Some(entity), name.as_str()));
+            }
+            true
+        }
+        ActionResult::ReplaceEntities { removed, added } => {
+            for id in removed {
+                if let Some(entity) = document.remove_entity(&id) {
+                    generated.push(operations::delete_entity(id, Some(entity), name.as_str()));
+                }
+            }
+            for entity in added {
+                document.add_entity(entity.clone());
+                generated.push(operations::create_entity(entity, name.as_str()));
+            }
+            true
+        }
+        ActionResult::ModifyAndCreate { modified, added } => {
+            for (id, geometry) in modified {
+                generated.extend(replace_geometry(document, id, geometry, None, &name));
+            }
+            for entity in added {
+                document.add_entity(entity.clone());
+                generated.push(operations::create_entity(entity, name.as_str()));
+            }
+            true
+        }
+    };
+
+    let recorded = action.take_operations();
+    let committed = if recorded.is_empty() {
+        match generated.len() {
+            0 => Vec::new(),
+            1 => generated,
+            count => vec![operations::group_operation(name.as_str(), generated, format!("{} ({})", name, count))],
+        }
+    } else {
+        recorded
+    };
+    for operation in committed {
+        if let Err(e) = history.add_operation(operation) {
+            tracing::warn!("Failed to record {} in history: {}", name, e);
+        }
+    }
+
+    running
+}
+
+/// 替换实体几何，返回对应的修改操作（实体不存在时为 `None`）
+///
+/// 给出 `bbox` 时直接用它更新空间索引，否则按新几何重新计算。
+fn replace_geometry(
+    document: &mut Document,
+    id: EntityId,
+    geometry: Geometry,
+    bbox: Option<BoundingBox2>,
+    name: &str,
+) -> Option<Operation> {
+    let mut entity = document.get_entity(&id)?.clone();
+    let previous = std::mem::replace(&mut entity.geometry, geometry.clone());
+    match bbox {
+        Some(bbox) => document.update_entity_with_bbox(&id, entity, bbox),
+        None => document.update_entity(&id, entity),
+    }
+    Some(operations::modify_entity(id, previous, geometry, name))
+}
+
+/// 把一次复合编辑（修改若干已有实体并加入新实体）记录为单个分组操作
+///
+/// 修改前的几何取自 `entities`。撤销分组时子操作按相反顺序还原，
+/// 因此圆角、倒角等命令只需一次撤销即可恢复全部改动。
+pub fn compound_operation(
+    name: &str,
+    entities: &[Entity],
+    modified: &[(EntityId, Geometry)],
+    added: &[Entity],
+) -> Operation {
+    let mut ops: Vec<Operation> = modified
+        .iter()
+        .filter_map(|(id, geometry)| {
+            let previous = entities.iter().find(|e| e.id == *id)?;
+            Some(operations::modify_entity(*id, previous.geometry.clone(), geometry.clone(), name))
+        })
+        .collect();
+    ops.extend(added.iter().map(|entity| operations::create_entity(entity.clone(), name)));
+    operations::group_operation(name, ops, name)
 }
 
 /// Action 历史记录项
@@ -348,3 +642,31 @@ impl<T: Clone> Default for ActionHistory<T> {
         Self::new()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::actions::DrawPointAction;
+    use zcad_core::geometry::Point;
+    use zcad_core::history::OperationType;
+
+    #[test]
+    fn test_multiple_created_entities_undo_as_one_group() {
+        let mut document = Document::new();
+        let mut history = HistoryTree::new(100);
+        let mut action = DrawPointAction::new();
+
+        // 阵列、多重复制等一次生成多个实体的结果
+        let geometries = (0..3)
+            .map(|i| Geometry::Point(Point::new(i as f64 * 10.0, 0.0)))
+            .collect();
+        apply_action_result(&mut action, ActionResult::CreateEntities(geometries), &mut document, &mut history);
+        assert_eq!(document.entity_count(), 3);
+
+        let op = history.undo().unwrap().clone();
+        assert!(matches!(&op.operation_type, OperationType::GroupOperation { operations, .. } if operations.len() == 3));
+        document.undo_operation(&op.operation_type);
+        assert!(history.undo().is_none());
+        assert_eq!(document.entity_count(), 0);
+    }
+}
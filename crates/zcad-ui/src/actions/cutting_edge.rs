@@ -0,0 +1,115 @@
+//! 修剪/延伸共用的边界边求交
+//!
+//! 边界边可以是直线、圆、圆弧或多段线（多段线按线段/弧段逐段求交）。
+
+use zcad_core::geometry::{Arc, Geometry, Line};
+use zcad_core::math::{Point2, EPSILON};
+
+/// 计算直线（按无限长直线处理）与边界边的交点参数
+///
+/// 返回的参数 t 满足 `交点 = line.start + (line.end - line.start) * t`，
+/// 由调用方根据修剪/延伸的需要筛选范围。
+pub(crate) fn line_edge_params(line: &Line, edge: &Geometry) -> Vec<f64> {
+    match edge {
+        Geometry::Line(edge_line) => line_segment_param(line, edge_line).into_iter().collect(),
+        Geometry::Circle(circle) => line_circle_params(line, circle.center, circle.radius),
+        Geometry::Arc(arc) => line_arc_params(line, arc),
+        Geometry::Polyline(polyline) => polyline
+            .explode()
+            .iter()
+            .flat_map(|segment| line_edge_params(line, segment))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+/// 参数对应的直线上的点
+pub(crate) fn point_at(line: &Line, t: f64) -> Point2 {
+    line.start + (line.end - line.start) * t
+}
+
+/// 直线与线段（边界线段必须在 [0, 1] 范围内相交）
+fn line_segment_param(line: &Line, edge: &Line) -> Option<f64> {
+    let d1 = line.end - line.start;
+    let d2 = edge.end - edge.start;
+
+    let cross = d1.x * d2.y - d1.y * d2.x;
+    if cross.abs() < EPSILON {
+        return None;
+    }
+
+    let d = edge.start - line.start;
+    let t1 = (d.x * d2.y - d.y * d2.x) / cross;
+    let t2 = (d.x * d1.y - d.y * d1.x) / cross;
+
+    if (-EPSILON..=1.0 + EPSILON).contains(&t2) {
+        Some(t1)
+    } else {
+        None
+    }
+}
+
+/// 直线与圆（两个根都返回）
+fn line_circle_params(line: &Line, center: Point2, radius: f64) -> Vec<f64> {
+    let d = line.end - line.start;
+    let f = line.start - center;
+
+    let a = d.dot(&d);
+    if a < EPSILON {
+        return vec![];
+    }
+    let b = 2.0 * f.dot(&d);
+    let c = f.dot(&f) - radius * radius;
+
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant < 0.0 {
+        return vec![];
+    }
+
+    let sqrt_disc = discriminant.sqrt();
+    let t1 = (-b - sqrt_disc) / (2.0 * a);
+    let t2 = (-b + sqrt_disc) / (2.0 * a);
+
+    if (t2 - t1).abs() > EPSILON {
+        vec![t1, t2]
+    } else {
+        vec![t1]
+    }
+}
+
+/// 直线与圆弧（只保留落在弧角度范围内的根）
+fn line_arc_params(line: &Line, arc: &Arc) -> Vec<f64> {
+    line_circle_params(line, arc.center, arc.radius)
+        .into_iter()
+        .filter(|&t| {
+            let p = point_at(line, t);
+            arc.contains_angle((p.y - arc.center.y).atan2(p.x - arc.center.x))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zcad_core::geometry::Polyline;
+
+    #[test]
+    fn test_polyline_edge_params() {
+        // 矩形多段线：水平线穿过左右两边
+        let rect = Polyline::from_points(
+            [
+                Point2::new(2.0, -1.0),
+                Point2::new(4.0, -1.0),
+                Point2::new(4.0, 1.0),
+                Point2::new(2.0, 1.0),
+            ],
+            true,
+        );
+        let line = Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0));
+        let mut params = line_edge_params(&line, &Geometry::Polyline(rect));
+        params.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(params.len(), 2);
+        assert!((params[0] - 0.2).abs() < 1e-9);
+        assert!((params[1] - 0.4).abs() < 1e-9);
+    }
+}
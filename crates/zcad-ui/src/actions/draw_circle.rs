@@ -1,9 +1,12 @@
 //! 绘制圆 Action
+//!
+//! 支持圆心-半径、两点（直径）、三点（外接圆）和相切-相切-半径（TTR）四种方式，
+//! 在指定圆心前输入 2P / 3P / T 切换。
 
 use crate::action::{
     Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
 };
-use zcad_core::geometry::{Circle, Geometry};
+use zcad_core::geometry::{Circle, Geometry, Line};
 use zcad_core::math::Point2;
 
 /// 圆绘制状态
@@ -13,12 +16,24 @@ enum Status {
     SetCenter,
     /// 等待设置半径
     SetRadius,
+    /// 两点方式：等待直径的两个端点
+    TwoPoint,
+    /// 三点方式：等待圆上的三个点
+    ThreePoint,
+    /// TTR 方式：等待选择两个相切对象
+    SelectTangent,
+    /// TTR 方式：等待输入半径
+    TangentRadius,
 }
 
 /// 绘制圆 Action
 pub struct DrawCircleAction {
     status: Status,
     center: Option<Point2>,
+    /// 两点、三点方式已指定的点
+    points: Vec<Point2>,
+    /// TTR 方式已选择的相切对象及拾取点
+    tangents: Vec<(Geometry, Point2)>,
 }
 
 impl DrawCircleAction {
@@ -26,8 +41,36 @@ impl DrawCircleAction {
         Self {
             status: Status::SetCenter,
             center: None,
+            points: Vec::new(),
+            tangents: Vec::new(),
         }
     }
+
+    /// 拾取可作为相切对象的直线、圆或圆弧
+    fn find_tangent_at_point(&self, ctx: &ActionContext, point: Point2) -> Option<Geometry> {
+        let tolerance = 5.0;
+        ctx.entities
+            .iter()
+            .find(|e| {
+                matches!(e.geometry, Geometry::Line(_) | Geometry::Circle(_) | Geometry::Arc(_))
+                    && e.geometry.contains_point(&point, tolerance)
+            })
+            .map(|e| e.geometry.clone())
+    }
+
+    /// 以已指定的点加上 `point` 构造两点或三点圆
+    fn circle_through(&self, point: Point2) -> Option<Circle> {
+        match (self.status, self.points.as_slice()) {
+            (Status::TwoPoint, [first]) => Circle::from_two_points(*first, point),
+            (Status::ThreePoint, [first, second]) => Circle::from_three_points(*first, *second, point),
+            _ => None,
+        }
+    }
+
+    fn create(&mut self, circle: Circle) -> ActionResult {
+        self.reset();
+        ActionResult::CreateEntities(vec![Geometry::Circle(circle)])
+    }
 }
 
 impl Default for DrawCircleAction {
@@ -44,6 +87,8 @@ impl Action for DrawCircleAction {
     fn reset(&mut self) {
         self.status = Status::SetCenter;
         self.center = None;
+        self.points.clear();
+        self.tangents.clear();
     }
 
     fn on_mouse_move(&mut self, _ctx: &ActionContext) -> ActionResult {
@@ -57,7 +102,7 @@ impl Action for DrawCircleAction {
                 self.on_coordinate(ctx, point)
             }
             MouseButton::Right => {
-                if self.status == Status::SetRadius {
+                if self.status != Status::SetCenter {
                     self.reset();
                     ActionResult::Continue
                 } else {
@@ -68,7 +113,7 @@ impl Action for DrawCircleAction {
         }
     }
 
-    fn on_coordinate(&mut self, _ctx: &ActionContext, coord: Point2) -> ActionResult {
+    fn on_coordinate(&mut self, ctx: &ActionContext, coord: Point2) -> ActionResult {
         match self.status {
             Status::SetCenter => {
                 self.center = Some(coord);
@@ -79,54 +124,176 @@ impl Action for DrawCircleAction {
                 if let Some(center) = self.center {
                     let radius = (coord - center).norm();
                     if radius > 1e-6 {
-                        let circle = Circle::new(center, radius);
-                        self.reset();
-                        return ActionResult::CreateEntities(vec![Geometry::Circle(circle)]);
+                        return self.create(Circle::new(center, radius));
                     }
                 }
                 ActionResult::Continue
             }
+            Status::TwoPoint | Status::ThreePoint => {
+                if let Some(circle) = self.circle_through(coord) {
+                    return self.create(circle);
+                }
+                // 重合的点不计入
+                if self.points.last().is_none_or(|last| (last - coord).norm() > 1e-6) {
+                    self.points.push(coord);
+                }
+                ActionResult::Continue
+            }
+            Status::SelectTangent => {
+                if let Some(geometry) = self.find_tangent_at_point(ctx, coord) {
+                    self.tangents.push((geometry, coord));
+                    if self.tangents.len() == 2 {
+                        self.status = Status::TangentRadius;
+                    }
+                }
+                ActionResult::Continue
+            }
+            Status::TangentRadius => ActionResult::Continue,
         }
     }
 
-    fn on_command(&mut self, _ctx: &ActionContext, _cmd: &str) -> Option<ActionResult> {
-        None
+    fn on_command(&mut self, _ctx: &ActionContext, cmd: &str) -> Option<ActionResult> {
+        if self.status != Status::SetCenter {
+            return None;
+        }
+        self.status = match cmd.to_uppercase().as_str() {
+            "2P" => Status::TwoPoint,
+            "3P" => Status::ThreePoint,
+            "T" | "TTR" => Status::SelectTangent,
+            _ => return None,
+        };
+        Some(ActionResult::Continue)
     }
 
     fn on_value(&mut self, _ctx: &ActionContext, value: f64) -> ActionResult {
         // 直接输入半径值
-        if self.status == Status::SetRadius {
-            if let Some(center) = self.center {
-                if value > 1e-6 {
-                    let circle = Circle::new(center, value);
-                    self.reset();
-                    return ActionResult::CreateEntities(vec![Geometry::Circle(circle)]);
+        match self.status {
+            Status::SetRadius => {
+                if let Some(center) = self.center {
+                    if value > 1e-6 {
+                        return self.create(Circle::new(center, value));
+                    }
+                }
+            }
+            Status::TangentRadius => {
+                if let [(first, first_pick), (second, second_pick)] = self.tangents.as_slice() {
+                    if let Some(circle) =
+                        Circle::tangent_tangent_radius(first, *first_pick, second, *second_pick, value)
+                    {
+                        return self.create(circle);
+                    }
                 }
             }
+            _ => {}
         }
         ActionResult::Continue
     }
 
     fn get_prompt(&self) -> &str {
         match self.status {
-            Status::SetCenter => "指定圆心:",
+            Status::SetCenter => "指定圆心 或 [两点(2P)/三点(3P)/切点、切点、半径(T)]:",
             Status::SetRadius => "指定半径 或 [直径(D)]:",
+            Status::TwoPoint if self.points.is_empty() => "指定圆直径的第一个端点:",
+            Status::TwoPoint => "指定圆直径的第二个端点:",
+            Status::ThreePoint => match self.points.len() {
+                0 => "指定圆上的第一个点:",
+                1 => "指定圆上的第二个点:",
+                _ => "指定圆上的第三个点:",
+            },
+            Status::SelectTangent if self.tangents.is_empty() => "指定对象与圆的第一个切点:",
+            Status::SelectTangent => "指定对象与圆的第二个切点:",
+            Status::TangentRadius => "指定圆的半径:",
+        }
+    }
+
+    fn get_available_commands(&self) -> Vec<&str> {
+        match self.status {
+            Status::SetCenter => vec!["2P", "3P", "TTR"],
+            _ => vec![],
         }
     }
 
     fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
         let mut previews = Vec::new();
-        
-        if self.status == Status::SetRadius {
-            if let Some(center) = self.center {
-                let radius = (ctx.effective_point() - center).norm();
-                if radius > 1e-6 {
-                    let circle = Circle::new(center, radius);
+        let point = ctx.effective_point();
+
+        match self.status {
+            Status::SetRadius => {
+                if let Some(center) = self.center {
+                    let radius = (point - center).norm();
+                    if radius > 1e-6 {
+                        let circle = Circle::new(center, radius);
+                        previews.push(PreviewGeometry::new(Geometry::Circle(circle)));
+                    }
+                }
+            }
+            Status::TwoPoint | Status::ThreePoint => {
+                if let Some(circle) = self.circle_through(point) {
                     previews.push(PreviewGeometry::new(Geometry::Circle(circle)));
+                } else if let Some(&last) = self.points.last() {
+                    previews.push(PreviewGeometry::reference(Geometry::Line(Line::new(last, point))));
                 }
             }
+            _ => {}
         }
-        
+
         previews
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::{dispatch_input, test_context};
+    use zcad_core::entity::Entity;
+
+    fn created_circle(result: ActionResult) -> Circle {
+        let ActionResult::CreateEntities(geometries) = result else {
+            panic!("应创建圆");
+        };
+        let Geometry::Circle(circle) = &geometries[0] else {
+            panic!("应为圆");
+        };
+        circle.clone()
+    }
+
+    #[test]
+    fn test_typed_radius_creates_circle() {
+        let ctx = test_context(&[], Point2::origin());
+        let mut action = DrawCircleAction::new();
+        action.on_coordinate(&ctx, Point2::new(10.0, 20.0));
+
+        let circle = created_circle(dispatch_input(&mut action, &ctx, "25").unwrap());
+        assert_eq!(circle.center, Point2::new(10.0, 20.0));
+        assert_eq!(circle.radius, 25.0);
+    }
+
+    #[test]
+    fn test_three_point_circle_uses_circumcenter() {
+        let ctx = test_context(&[], Point2::origin());
+        let mut action = DrawCircleAction::new();
+        dispatch_input(&mut action, &ctx, "3P").unwrap();
+        action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
+        action.on_coordinate(&ctx, Point2::new(8.0, 0.0));
+        let circle = created_circle(action.on_coordinate(&ctx, Point2::new(2.0, 6.0)));
+        assert!((circle.center - Point2::new(4.0, 2.0)).norm() < 1e-9);
+        assert!((circle.radius - 20.0_f64.sqrt()).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ttr_circle_tangent_to_two_lines() {
+        let entities = vec![
+            Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(20.0, 0.0)))),
+            Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(0.0, 20.0)))),
+        ];
+        let ctx = test_context(&entities, Point2::origin());
+        let mut action = DrawCircleAction::new();
+        dispatch_input(&mut action, &ctx, "T").unwrap();
+        action.on_coordinate(&ctx, Point2::new(10.0, 0.0));
+        action.on_coordinate(&ctx, Point2::new(0.0, 10.0));
+        let circle = created_circle(dispatch_input(&mut action, &ctx, "4").unwrap());
+        // 圆心到两条直线的距离都等于半径
+        assert!((circle.center - Point2::new(4.0, 4.0)).norm() < 1e-9);
+        assert_eq!(circle.radius, 4.0);
+    }
+}
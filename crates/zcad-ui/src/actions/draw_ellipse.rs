@@ -1,35 +1,137 @@
 //! 绘制椭圆 Action
+//!
+//! 参考 AutoCAD 的 ELLIPSE：默认以一条轴的两个端点加另一条半轴长度绘制，
+//! 输入 C 改为中心点方式，输入 A 绘制椭圆弧（再依次指定起止角度）。
 
 use crate::action::{
     Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
 };
-use zcad_core::geometry::{Ellipse, Geometry};
+use zcad_core::geometry::{Ellipse, Geometry, Line};
 use zcad_core::math::{Point2, Vector2};
 
 /// 椭圆绘制状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Status {
-    /// 等待设置中心
+    /// 轴端点方式：等待第一条轴的第一个端点
+    SetAxisStart,
+    /// 轴端点方式：等待第一条轴的另一个端点
+    SetAxisEnd,
+    /// 中心点方式：等待设置中心
     SetCenter,
-    /// 等待设置长轴端点
-    SetMajorAxis,
-    /// 等待设置短轴比例
-    SetMinorRatio,
+    /// 中心点方式：等待第一条轴的端点
+    SetAxisEndpoint,
+    /// 等待另一条半轴长度
+    OtherAxis,
+    /// 椭圆弧：等待起始角度
+    SetStartAngle,
+    /// 椭圆弧：等待终止角度
+    SetEndAngle,
 }
 
 /// 绘制椭圆 Action
 pub struct DrawEllipseAction {
     status: Status,
+    /// 是否绘制椭圆弧
+    arc: bool,
+    /// 轴端点方式的第一个端点
+    axis_start: Option<Point2>,
     center: Option<Point2>,
-    major_axis: Option<Vector2>,
+    /// 第一条轴的半轴向量（相对于中心）
+    axis: Option<Vector2>,
+    /// 椭圆弧方式下已确定的完整椭圆
+    ellipse: Option<Ellipse>,
+    /// 椭圆弧起始参数
+    start_param: Option<f64>,
 }
 
 impl DrawEllipseAction {
     pub fn new() -> Self {
         Self {
-            status: Status::SetCenter,
+            status: Status::SetAxisStart,
+            arc: false,
+            axis_start: None,
             center: None,
-            major_axis: None,
+            axis: None,
+            ellipse: None,
+            start_param: None,
+        }
+    }
+
+    /// 记录第一条轴（中心和半轴向量），进入指定另一条半轴
+    fn set_axis(&mut self, center: Point2, axis: Vector2) {
+        if axis.norm() > 1e-6 {
+            self.center = Some(center);
+            self.axis = Some(axis);
+            self.status = Status::OtherAxis;
+        }
+    }
+
+    /// 由第一条轴和另一条半轴长度构造椭圆，较长的一条作为长轴
+    fn ellipse_with_other_axis(&self, distance: f64) -> Option<Ellipse> {
+        let (center, axis) = (self.center?, self.axis?);
+        let length = axis.norm();
+        if distance < 1e-6 {
+            return None;
+        }
+        if distance <= length {
+            Some(Ellipse::new(center, axis, distance / length))
+        } else {
+            let perpendicular = Vector2::new(-axis.y, axis.x) / length;
+            Some(Ellipse::new(center, perpendicular * distance, length / distance))
+        }
+    }
+
+    /// 点到第一条轴所在直线的距离
+    fn distance_to_axis(&self, point: Point2) -> Option<f64> {
+        let (center, axis) = (self.center?, self.axis?);
+        let direction = axis / axis.norm();
+        let offset = point - center;
+        Some((offset.x * direction.y - offset.y * direction.x).abs())
+    }
+
+    /// 点所在方向对应的椭圆参数
+    fn param_toward(ellipse: &Ellipse, point: Point2) -> f64 {
+        let offset = point - ellipse.center;
+        ellipse.param_at_geometric_angle(offset.y.atan2(offset.x))
+    }
+
+    /// 椭圆弧：以 `end_param` 结束，逆时针从起始参数扫过
+    fn ellipse_arc(&self, end_param: f64) -> Option<Ellipse> {
+        let (ellipse, start) = (self.ellipse.as_ref()?, self.start_param?);
+        let mut end = end_param;
+        while end <= start + 1e-9 {
+            end += 2.0 * std::f64::consts::PI;
+        }
+        Some(Ellipse::arc(ellipse.center, ellipse.major_axis, ellipse.ratio, start, end))
+    }
+
+    /// 第一条轴和另一条半轴确定后：绘制椭圆，或进入椭圆弧的角度指定
+    fn finish_axes(&mut self, ellipse: Ellipse) -> ActionResult {
+        if self.arc {
+            self.ellipse = Some(ellipse);
+            self.status = Status::SetStartAngle;
+            return ActionResult::Continue;
+        }
+        self.reset();
+        ActionResult::CreateEntities(vec![Geometry::Ellipse(ellipse)])
+    }
+
+    /// 按指定参数设置起点或完成椭圆弧
+    fn set_param(&mut self, param: f64) -> ActionResult {
+        match self.status {
+            Status::SetStartAngle => {
+                self.start_param = Some(param);
+                self.status = Status::SetEndAngle;
+                ActionResult::Continue
+            }
+            Status::SetEndAngle => match self.ellipse_arc(param) {
+                Some(arc) => {
+                    self.reset();
+                    ActionResult::CreateEntities(vec![Geometry::Ellipse(arc)])
+                }
+                None => ActionResult::Continue,
+            },
+            _ => ActionResult::Continue,
         }
     }
 }
@@ -46,9 +148,13 @@ impl Action for DrawEllipseAction {
     }
 
     fn reset(&mut self) {
-        self.status = Status::SetCenter;
+        self.status = Status::SetAxisStart;
+        self.arc = false;
+        self.axis_start = None;
         self.center = None;
-        self.major_axis = None;
+        self.axis = None;
+        self.ellipse = None;
+        self.start_param = None;
     }
 
     fn on_mouse_move(&mut self, _ctx: &ActionContext) -> ActionResult {
@@ -64,7 +170,7 @@ impl Action for DrawEllipseAction {
             MouseButton::Right => {
                 // 右键取消当前步骤或整个操作
                 match self.status {
-                    Status::SetCenter => ActionResult::Cancel,
+                    Status::SetAxisStart => ActionResult::Cancel,
                     _ => {
                         self.reset();
                         ActionResult::Continue
@@ -77,93 +183,124 @@ impl Action for DrawEllipseAction {
 
     fn on_coordinate(&mut self, _ctx: &ActionContext, coord: Point2) -> ActionResult {
         match self.status {
+            Status::SetAxisStart => {
+                self.axis_start = Some(coord);
+                self.status = Status::SetAxisEnd;
+                ActionResult::Continue
+            }
+            Status::SetAxisEnd => {
+                if let Some(start) = self.axis_start {
+                    let center = Point2::from((start.coords + coord.coords) / 2.0);
+                    self.set_axis(center, coord - center);
+                }
+                ActionResult::Continue
+            }
             Status::SetCenter => {
                 self.center = Some(coord);
-                self.status = Status::SetMajorAxis;
+                self.status = Status::SetAxisEndpoint;
                 ActionResult::Continue
             }
-            Status::SetMajorAxis => {
+            Status::SetAxisEndpoint => {
                 if let Some(center) = self.center {
-                    let major_axis = coord - center;
-                    if major_axis.norm() > 1e-6 {
-                        self.major_axis = Some(major_axis);
-                        self.status = Status::SetMinorRatio;
-                    }
+                    self.set_axis(center, coord - center);
                 }
                 ActionResult::Continue
             }
-            Status::SetMinorRatio => {
-                if let (Some(center), Some(major_axis)) = (self.center, self.major_axis) {
-                    // 计算短轴比例：鼠标到长轴的距离 / 长轴长度
-                    let major_len = major_axis.norm();
-                    let major_dir = major_axis / major_len;
-                    let minor_dir = Vector2::new(-major_dir.y, major_dir.x);
-                    let to_mouse = coord - center;
-                    let minor_len = to_mouse.dot(&minor_dir).abs();
-                    let ratio = (minor_len / major_len).clamp(0.01, 1.0);
-                    
-                    let ellipse = Ellipse::new(center, major_axis, ratio);
-                    self.reset();
-                    return ActionResult::CreateEntities(vec![Geometry::Ellipse(ellipse)]);
+            Status::OtherAxis => {
+                let ellipse = self.distance_to_axis(coord).and_then(|d| self.ellipse_with_other_axis(d));
+                match ellipse {
+                    Some(ellipse) => self.finish_axes(ellipse),
+                    None => ActionResult::Continue,
                 }
-                ActionResult::Continue
             }
+            Status::SetStartAngle | Status::SetEndAngle => match &self.ellipse {
+                Some(ellipse) => {
+                    let param = Self::param_toward(ellipse, coord);
+                    self.set_param(param)
+                }
+                None => ActionResult::Continue,
+            },
         }
     }
 
-    fn on_command(&mut self, _ctx: &ActionContext, _cmd: &str) -> Option<ActionResult> {
-        None
+    fn on_command(&mut self, _ctx: &ActionContext, cmd: &str) -> Option<ActionResult> {
+        if !matches!(self.status, Status::SetAxisStart | Status::SetCenter) {
+            return None;
+        }
+        match cmd.to_uppercase().as_str() {
+            "A" | "ARC" => self.arc = true,
+            "C" | "CENTER" => self.status = Status::SetCenter,
+            _ => return None,
+        }
+        Some(ActionResult::Continue)
     }
 
     fn on_value(&mut self, _ctx: &ActionContext, value: f64) -> ActionResult {
         match self.status {
-            Status::SetMajorAxis => {
-                // 输入长轴长度
-                if let Some(center) = self.center {
-                    if value > 1e-6 {
-                        // 默认水平方向
-                        self.major_axis = Some(Vector2::new(value, 0.0));
-                        self.status = Status::SetMinorRatio;
-                    }
+            Status::SetAxisEnd => {
+                // 输入轴长，默认水平方向
+                if let Some(start) = self.axis_start {
+                    self.set_axis(start + Vector2::new(value / 2.0, 0.0), Vector2::new(value / 2.0, 0.0));
                 }
                 ActionResult::Continue
             }
-            Status::SetMinorRatio => {
-                // 输入短轴比例或长度
-                if let (Some(center), Some(major_axis)) = (self.center, self.major_axis) {
-                    let major_len = major_axis.norm();
-                    // 如果输入值 <= 1，视为比例；否则视为短轴长度
-                    let ratio = if value <= 1.0 {
-                        value.clamp(0.01, 1.0)
-                    } else {
-                        (value / major_len).clamp(0.01, 1.0)
-                    };
-                    
-                    let ellipse = Ellipse::new(center, major_axis, ratio);
-                    self.reset();
-                    return ActionResult::CreateEntities(vec![Geometry::Ellipse(ellipse)]);
+            Status::SetAxisEndpoint => {
+                // 输入半轴长度，默认水平方向
+                if let Some(center) = self.center {
+                    self.set_axis(center, Vector2::new(value, 0.0));
                 }
                 ActionResult::Continue
             }
+            Status::OtherAxis => match self.ellipse_with_other_axis(value) {
+                Some(ellipse) => self.finish_axes(ellipse),
+                None => ActionResult::Continue,
+            },
+            Status::SetStartAngle | Status::SetEndAngle => match &self.ellipse {
+                // 角度（度）从长轴方向起逆时针量取
+                Some(ellipse) => {
+                    let param = ellipse.param_at_geometric_angle(ellipse.rotation() + value.to_radians());
+                    self.set_param(param)
+                }
+                None => ActionResult::Continue,
+            },
             _ => ActionResult::Continue,
         }
     }
 
     fn get_prompt(&self) -> &str {
         match self.status {
-            Status::SetCenter => "指定椭圆中心点",
-            Status::SetMajorAxis => "指定长轴端点或输入长度",
-            Status::SetMinorRatio => "指定短轴端点或输入比例",
+            Status::SetAxisStart if self.arc => "指定椭圆弧的轴端点 或 [中心点(C)]:",
+            Status::SetAxisStart => "指定椭圆的轴端点 或 [圆弧(A)/中心点(C)]:",
+            Status::SetAxisEnd => "指定轴的另一个端点或输入轴长:",
+            Status::SetCenter => "指定椭圆的中心点:",
+            Status::SetAxisEndpoint => "指定轴的端点或输入半轴长度:",
+            Status::OtherAxis => "指定到另一条轴的距离:",
+            Status::SetStartAngle => "指定起点角度:",
+            Status::SetEndAngle => "指定端点角度:",
+        }
+    }
+
+    fn get_available_commands(&self) -> Vec<&str> {
+        match self.status {
+            Status::SetAxisStart if !self.arc => vec!["Arc", "Center"],
+            Status::SetAxisStart => vec!["Center"],
+            Status::SetCenter if !self.arc => vec!["Arc"],
+            _ => vec![],
         }
     }
 
     fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
         let mut previews = Vec::new();
-        
+        let mouse = ctx.effective_point();
+
         match self.status {
-            Status::SetMajorAxis => {
+            Status::SetAxisEnd => {
+                if let Some(start) = self.axis_start {
+                    previews.push(PreviewGeometry::reference(Geometry::Line(Line::new(start, mouse))));
+                }
+            }
+            Status::SetAxisEndpoint => {
                 if let Some(center) = self.center {
-                    let mouse = ctx.effective_point();
                     let major_axis = mouse - center;
                     if major_axis.norm() > 1e-6 {
                         // 预览椭圆（默认比例 0.5）
@@ -172,23 +309,85 @@ impl Action for DrawEllipseAction {
                     }
                 }
             }
-            Status::SetMinorRatio => {
-                if let (Some(center), Some(major_axis)) = (self.center, self.major_axis) {
-                    let mouse = ctx.effective_point();
-                    let major_len = major_axis.norm();
-                    let major_dir = major_axis / major_len;
-                    let minor_dir = Vector2::new(-major_dir.y, major_dir.x);
-                    let to_mouse = mouse - center;
-                    let minor_len = to_mouse.dot(&minor_dir).abs();
-                    let ratio = (minor_len / major_len).clamp(0.01, 1.0);
-                    
-                    let ellipse = Ellipse::new(center, major_axis, ratio);
+            Status::OtherAxis => {
+                if let Some(ellipse) = self.distance_to_axis(mouse).and_then(|d| self.ellipse_with_other_axis(d)) {
                     previews.push(PreviewGeometry::new(Geometry::Ellipse(ellipse)));
                 }
             }
+            Status::SetStartAngle | Status::SetEndAngle => {
+                if let Some(ellipse) = &self.ellipse {
+                    previews.push(PreviewGeometry::reference(Geometry::Line(Line::new(ellipse.center, mouse))));
+                    let arc = self.ellipse_arc(Self::param_toward(ellipse, mouse));
+                    let shown = arc.unwrap_or_else(|| ellipse.clone());
+                    if self.status == Status::SetStartAngle {
+                        previews.push(PreviewGeometry::reference(Geometry::Ellipse(shown)));
+                    } else {
+                        previews.push(PreviewGeometry::new(Geometry::Ellipse(shown)));
+                    }
+                }
+            }
             _ => {}
         }
-        
+
         previews
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::{dispatch_input, test_context};
+
+    fn created_ellipse(result: ActionResult) -> Ellipse {
+        let ActionResult::CreateEntities(geometries) = result else {
+            panic!("应创建椭圆");
+        };
+        let Geometry::Ellipse(ellipse) = &geometries[0] else {
+            panic!("应为椭圆");
+        };
+        ellipse.clone()
+    }
+
+    #[test]
+    fn test_axis_endpoint_ellipse_matches_picked_points() {
+        let ctx = test_context(&[], Point2::origin());
+        let mut action = DrawEllipseAction::new();
+        action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
+        action.on_coordinate(&ctx, Point2::new(10.0, 0.0));
+        let ellipse = created_ellipse(action.on_coordinate(&ctx, Point2::new(7.0, 3.0)));
+        assert_eq!(ellipse.center, Point2::new(5.0, 0.0));
+        assert_eq!(ellipse.major_axis, Vector2::new(5.0, 0.0));
+        assert!((ellipse.minor_radius() - 3.0).abs() < 1e-9);
+        assert!(ellipse.is_full());
+
+        // 另一条半轴更长时成为长轴
+        action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
+        action.on_coordinate(&ctx, Point2::new(0.0, 4.0));
+        let ellipse = created_ellipse(action.on_coordinate(&ctx, Point2::new(8.0, 1.0)));
+        assert_eq!(ellipse.center, Point2::new(0.0, 2.0));
+        assert!((ellipse.major_radius() - 8.0).abs() < 1e-9);
+        assert!((ellipse.minor_radius() - 2.0).abs() < 1e-9);
+        assert!(ellipse.major_axis.y.abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_center_ellipse_arc_with_angles() {
+        let ctx = test_context(&[], Point2::origin());
+        let mut action = DrawEllipseAction::new();
+        dispatch_input(&mut action, &ctx, "A").unwrap();
+        dispatch_input(&mut action, &ctx, "C").unwrap();
+        action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
+        action.on_coordinate(&ctx, Point2::new(10.0, 0.0));
+        assert!(matches!(dispatch_input(&mut action, &ctx, "5").unwrap(), ActionResult::Continue));
+
+        // 起点拾取正上方，终点输入 180 度
+        action.on_coordinate(&ctx, Point2::new(0.0, 20.0));
+        let arc = created_ellipse(dispatch_input(&mut action, &ctx, "180").unwrap());
+        assert_eq!(arc.major_axis, Vector2::new(10.0, 0.0));
+        assert!((arc.ratio - 0.5).abs() < 1e-9);
+        assert!((arc.start_param - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+        assert!((arc.end_param - std::f64::consts::PI).abs() < 1e-9);
+        assert!((arc.start_point() - Point2::new(0.0, 5.0)).norm() < 1e-9);
+        assert!((arc.end_point() - Point2::new(-10.0, 0.0)).norm() < 1e-9);
+    }
+}
@@ -315,22 +315,12 @@ impl Action for DrawLineAction {
 #[cfg(test)]
 mod tests {
     use super::*;
-
-    fn create_ctx() -> ActionContext<'static> {
-        ActionContext {
-            mouse_pos: Point2::new(0.0, 0.0),
-            snap_pos: None,
-            selected_entities: &[],
-            entities: &[],
-            ortho_mode: false,
-            reference_point: None,
-        }
-    }
+    use crate::action::test_context;
 
     #[test]
     fn test_draw_line_basic() {
         let mut action = DrawLineAction::new();
-        let mut ctx = create_ctx();
+        let mut ctx = test_context(&[], Point2::origin());
 
         // 设置起点
         let result = action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
@@ -346,7 +336,7 @@ mod tests {
     #[test]
     fn test_draw_line_undo() {
         let mut action = DrawLineAction::new();
-        let ctx = create_ctx();
+        let ctx = test_context(&[], Point2::origin());
 
         // 设置起点
         action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
@@ -3,8 +3,8 @@
 use crate::action::{
     Action, ActionContext, ActionHistory, ActionResult, ActionType, MouseButton, PreviewGeometry,
 };
-use zcad_core::geometry::{Geometry, Line, Polyline, PolylineVertex};
-use zcad_core::math::Point2;
+use zcad_core::geometry::{Geometry, Polyline, PolylineVertex};
+use zcad_core::math::{Point2, Vector2};
 
 /// 多段线绘制状态
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -22,10 +22,117 @@ enum HistoryAction {
     Close,
 }
 
+/// 逐点构造多段线
+///
+/// 记录已确定的顶点，凸度记在弧线段的起始顶点上；圆弧模式下新线段为与上一段
+/// 在末点相切的圆弧。绘制多段线的 action 和应用的绘图流程共用。
+#[derive(Debug, Clone, Default)]
+pub struct PolylineBuilder {
+    vertices: Vec<PolylineVertex>,
+    /// 圆弧模式：新线段为与上一段相切的圆弧
+    pub arc_mode: bool,
+}
+
+impl PolylineBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 已确定的顶点
+    pub fn vertices(&self) -> &[PolylineVertex] {
+        &self.vertices
+    }
+
+    /// 顶点数量
+    pub fn len(&self) -> usize {
+        self.vertices.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vertices.is_empty()
+    }
+
+    /// 最后一个顶点
+    pub fn last_point(&self) -> Option<Point2> {
+        self.vertices.last().map(|v| v.point)
+    }
+
+    /// 最后一个顶点处的切线方向
+    ///
+    /// 取上一段在终点处的切线；只有一个顶点时沿 X 轴正方向。
+    pub fn last_tangent(&self) -> Vector2 {
+        let n = self.vertices.len();
+        if n >= 2 {
+            let polyline = Polyline::new(self.vertices[n - 2..].to_vec(), false);
+            if let Some(tangent) = polyline.segment_end_tangent(0) {
+                return tangent;
+            }
+        }
+        Vector2::new(1.0, 0.0)
+    }
+
+    /// 从最后一个顶点到 `point` 的新线段凸度（直线模式为 0）
+    pub fn next_bulge(&self, point: Point2) -> f64 {
+        match self.vertices.last() {
+            Some(last) if self.arc_mode => {
+                Polyline::bulge_from_tangent(last.point, self.last_tangent(), point)
+            }
+            _ => 0.0,
+        }
+    }
+
+    /// 加入下一个顶点，到它的线段按当前模式确定凸度
+    pub fn push(&mut self, point: Point2) {
+        let bulge = self.next_bulge(point);
+        if let Some(last) = self.vertices.last_mut() {
+            last.bulge = bulge;
+        }
+        self.vertices.push(PolylineVertex::new(point));
+    }
+
+    /// 去掉最后一个顶点，新的末顶点之后不再有线段
+    pub fn pop(&mut self) -> Option<Point2> {
+        let removed = self.vertices.pop()?;
+        if let Some(last) = self.vertices.last_mut() {
+            last.bulge = 0.0;
+        }
+        Some(removed.point)
+    }
+
+    /// 清空顶点并回到直线模式
+    pub fn clear(&mut self) {
+        self.vertices.clear();
+        self.arc_mode = false;
+    }
+
+    /// 已确定的线段加上到 `cursor` 的当前线段（圆弧模式下为相切圆弧）
+    pub fn preview(&self, cursor: Point2) -> Polyline {
+        let mut preview = self.clone();
+        preview.push(cursor);
+        preview.build(false)
+    }
+
+    /// 按已确定的顶点生成多段线；闭合时闭合段为直线
+    pub fn build(&self, closed: bool) -> Polyline {
+        Polyline::new(self.vertices.clone(), closed)
+    }
+
+    /// 最后一个顶点回到起点时闭合：去掉重复的终点，闭合段沿用到终点那一段的凸度
+    ///
+    /// 顶点不足（去掉终点后少于 3 个）时返回 `None`，不修改顶点。
+    pub fn close_at_start(&self) -> Option<Polyline> {
+        let n = self.vertices.len();
+        if n < 4 {
+            return None;
+        }
+        Some(Polyline::new(self.vertices[..n - 1].to_vec(), true))
+    }
+}
+
 /// 绘制多段线 Action
 pub struct DrawPolylineAction {
     status: Status,
-    vertices: Vec<Point2>,
+    builder: PolylineBuilder,
     history: ActionHistory<HistoryAction>,
 }
 
@@ -33,18 +140,14 @@ impl DrawPolylineAction {
     pub fn new() -> Self {
         Self {
             status: Status::SetFirstPoint,
-            vertices: Vec::new(),
+            builder: PolylineBuilder::new(),
             history: ActionHistory::new(),
         }
     }
 
     fn close(&mut self) -> ActionResult {
-        if self.vertices.len() >= 3 {
-            let vertices: Vec<PolylineVertex> = self.vertices
-                .iter()
-                .map(|&p| PolylineVertex::new(p))
-                .collect();
-            let polyline = Polyline::new(vertices, true);
+        if self.builder.len() >= 3 {
+            let polyline = self.builder.build(true);
             self.reset();
             return ActionResult::CreateEntities(vec![Geometry::Polyline(polyline)]);
         }
@@ -52,12 +155,8 @@ impl DrawPolylineAction {
     }
 
     fn finish(&mut self) -> ActionResult {
-        if self.vertices.len() >= 2 {
-            let vertices: Vec<PolylineVertex> = self.vertices
-                .iter()
-                .map(|&p| PolylineVertex::new(p))
-                .collect();
-            let polyline = Polyline::new(vertices, false);
+        if self.builder.len() >= 2 {
+            let polyline = self.builder.build(false);
             self.reset();
             return ActionResult::CreateEntities(vec![Geometry::Polyline(polyline)]);
         }
@@ -78,7 +177,7 @@ impl Action for DrawPolylineAction {
 
     fn reset(&mut self) {
         self.status = Status::SetFirstPoint;
-        self.vertices.clear();
+        self.builder.clear();
         self.history.clear();
     }
 
@@ -107,19 +206,19 @@ impl Action for DrawPolylineAction {
     fn on_coordinate(&mut self, _ctx: &ActionContext, coord: Point2) -> ActionResult {
         match self.status {
             Status::SetFirstPoint => {
-                self.vertices.push(coord);
+                self.builder.push(coord);
                 self.history.push(HistoryAction::AddPoint { point: coord });
                 self.status = Status::SetNextPoint;
                 ActionResult::Continue
             }
             Status::SetNextPoint => {
                 // 检查是否与上一个点重合
-                if let Some(&last) = self.vertices.last() {
+                if let Some(last) = self.builder.last_point() {
                     if (coord - last).norm() < 1e-6 {
                         return ActionResult::Continue;
                     }
                 }
-                self.vertices.push(coord);
+                self.builder.push(coord);
                 self.history.push(HistoryAction::AddPoint { point: coord });
                 ActionResult::Continue
             }
@@ -131,12 +230,20 @@ impl Action for DrawPolylineAction {
         
         match cmd_upper.as_str() {
             "C" | "CLOSE" => {
-                if self.vertices.len() >= 3 {
+                if self.builder.len() >= 3 {
                     Some(self.close())
                 } else {
                     None
                 }
             }
+            "A" | "ARC" if self.status == Status::SetNextPoint => {
+                self.builder.arc_mode = true;
+                Some(ActionResult::Continue)
+            }
+            "L" | "LINE" if self.status == Status::SetNextPoint => {
+                self.builder.arc_mode = false;
+                Some(ActionResult::Continue)
+            }
             "U" | "UNDO" => {
                 if self.can_undo() {
                     self.undo();
@@ -152,13 +259,12 @@ impl Action for DrawPolylineAction {
     fn get_prompt(&self) -> &str {
         match self.status {
             Status::SetFirstPoint => "指定起点:",
-            Status::SetNextPoint => {
-                if self.vertices.len() >= 3 {
-                    "指定下一点 或 [闭合(C)/放弃(U)]:"
-                } else {
-                    "指定下一点 或 [放弃(U)]:"
-                }
-            }
+            Status::SetNextPoint => match (self.builder.arc_mode, self.builder.len() >= 3) {
+                (false, true) => "指定下一点 或 [圆弧(A)/闭合(C)/放弃(U)]:",
+                (false, false) => "指定下一点 或 [圆弧(A)/放弃(U)]:",
+                (true, true) => "指定圆弧端点 或 [直线(L)/闭合(C)/放弃(U)]:",
+                (true, false) => "指定圆弧端点 或 [直线(L)/放弃(U)]:",
+            },
         }
     }
 
@@ -166,8 +272,8 @@ impl Action for DrawPolylineAction {
         match self.status {
             Status::SetFirstPoint => vec![],
             Status::SetNextPoint => {
-                let mut cmds = vec!["undo"];
-                if self.vertices.len() >= 3 {
+                let mut cmds = vec![if self.builder.arc_mode { "line" } else { "arc" }, "undo"];
+                if self.builder.len() >= 3 {
                     cmds.push("close");
                 }
                 cmds
@@ -176,38 +282,88 @@ impl Action for DrawPolylineAction {
     }
 
     fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
-        let mut previews = Vec::new();
-        
-        // 已确定的线段
-        for i in 0..self.vertices.len().saturating_sub(1) {
-            let line = Line::new(self.vertices[i], self.vertices[i + 1]);
-            previews.push(PreviewGeometry::new(Geometry::Line(line)));
-        }
-        
-        // 当前正在绘制的线段
-        if self.status == Status::SetNextPoint {
-            if let Some(&last) = self.vertices.last() {
-                let current = ctx.effective_point();
-                let line = Line::new(last, current);
-                previews.push(PreviewGeometry::new(Geometry::Line(line)));
-            }
-        }
-        
-        previews
+        // 已确定的线段加上当前正在绘制的线段（圆弧模式下按相切凸度预览为圆弧）
+        let polyline = match self.status {
+            Status::SetNextPoint => self.builder.preview(ctx.effective_point()),
+            Status::SetFirstPoint => self.builder.build(false),
+        };
+
+        polyline
+            .explode()
+            .into_iter()
+            .map(PreviewGeometry::new)
+            .collect()
     }
 
     fn can_undo(&self) -> bool {
-        self.history.can_undo() && self.vertices.len() > 1
+        self.history.can_undo() && self.builder.len() > 1
     }
 
     fn undo(&mut self) {
-        if self.vertices.len() > 1 {
-            self.vertices.pop();
+        if self.builder.len() > 1 {
+            self.builder.pop();
             self.history.undo();
-        } else if self.vertices.len() == 1 {
-            self.vertices.pop();
+        } else if self.builder.len() == 1 {
+            self.builder.pop();
             self.status = Status::SetFirstPoint;
             self.history.undo();
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::test_context;
+
+    #[test]
+    fn test_arc_mode_commits_tangent_bulge() {
+        let ctx = test_context(&[], Point2::origin());
+        let mut action = DrawPolylineAction::new();
+        action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
+        action.on_coordinate(&ctx, Point2::new(10.0, 0.0));
+        assert!(action.on_command(&ctx, "A").is_some());
+
+        // 预览为与上一段相切的圆弧
+        let preview = action.get_preview(&test_context(&[], Point2::new(15.0, 5.0)));
+        assert_eq!(preview.len(), 2);
+        assert!(matches!(preview[1].geometry, Geometry::Arc(_)));
+
+        action.on_coordinate(&ctx, Point2::new(15.0, 5.0));
+        let polyline = match action.finish() {
+            ActionResult::CreateEntities(mut geoms) => match geoms.remove(0) {
+                Geometry::Polyline(pl) => pl,
+                _ => panic!("应为多段线"),
+            },
+            _ => panic!("应创建实体"),
+        };
+        assert_eq!(polyline.vertices[0].bulge, 0.0);
+        let quarter = (std::f64::consts::PI / 8.0).tan();
+        assert!((polyline.vertices[1].bulge - quarter).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_builder_closes_at_start_with_tangent_arc() {
+        let mut builder = PolylineBuilder::new();
+        builder.push(Point2::new(0.0, 0.0));
+        builder.push(Point2::new(10.0, 0.0));
+        builder.push(Point2::new(10.0, 10.0));
+        assert!(builder.close_at_start().is_none());
+
+        // 圆弧模式下回到起点：闭合段为与上一段相切的圆弧
+        builder.arc_mode = true;
+        builder.push(Point2::new(0.0, 0.0));
+        let closed = builder.close_at_start().unwrap();
+        assert!(closed.closed);
+        assert_eq!(closed.vertices.len(), 3);
+        let expected = Polyline::bulge_from_tangent(Point2::new(10.0, 10.0), Vector2::new(0.0, 1.0), Point2::origin());
+        assert!((closed.vertices[2].bulge - expected).abs() < 1e-9);
+        assert!(closed.vertices[2].bulge != 0.0);
+
+        // 放弃最后一点后末顶点不再带凸度
+        assert_eq!(builder.pop(), Some(Point2::new(0.0, 0.0)));
+        assert_eq!(builder.vertices()[2].bulge, 0.0);
+        builder.clear();
+        assert!(builder.is_empty() && !builder.arc_mode);
+    }
+}
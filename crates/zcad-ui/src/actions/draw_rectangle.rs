@@ -1,4 +1,6 @@
 //! 绘制矩形 Action
+//!
+//! 参考 AutoCAD 的 RECTANG：输入 `F` 设置圆角半径后绘制圆角矩形。
 
 use crate::action::{
     Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
@@ -13,12 +15,16 @@ enum Status {
     SetCorner1,
     /// 等待对角点
     SetCorner2,
+    /// 等待输入圆角半径
+    SetFilletRadius,
 }
 
 /// 绘制矩形 Action
 pub struct DrawRectangleAction {
     status: Status,
     corner1: Option<Point2>,
+    /// 圆角半径（0 为直角）
+    fillet_radius: f64,
 }
 
 impl DrawRectangleAction {
@@ -26,10 +32,19 @@ impl DrawRectangleAction {
         Self {
             status: Status::SetCorner1,
             corner1: None,
+            fillet_radius: 0.0,
         }
     }
 
+    /// 当前圆角半径
+    pub fn fillet_radius(&self) -> f64 {
+        self.fillet_radius
+    }
+
     fn create_rectangle(&self, p1: Point2, p2: Point2) -> Polyline {
+        if self.fillet_radius > 0.0 {
+            return Polyline::rounded_rectangle(p1, p2, self.fillet_radius);
+        }
         let vertices = vec![
             PolylineVertex::new(Point2::new(p1.x, p1.y)),
             PolylineVertex::new(Point2::new(p2.x, p1.y)),
@@ -67,11 +82,11 @@ impl Action for DrawRectangleAction {
                 self.on_coordinate(ctx, point)
             }
             MouseButton::Right => {
-                if self.status == Status::SetCorner2 {
+                if self.status == Status::SetCorner1 {
+                    ActionResult::Cancel
+                } else {
                     self.reset();
                     ActionResult::Continue
-                } else {
-                    ActionResult::Cancel
                 }
             }
             MouseButton::Middle => ActionResult::Continue,
@@ -96,20 +111,54 @@ impl Action for DrawRectangleAction {
                 }
                 ActionResult::Continue
             }
+            Status::SetFilletRadius => ActionResult::Continue,
         }
     }
 
-    fn on_command(&mut self, _ctx: &ActionContext, _cmd: &str) -> Option<ActionResult> {
-        None
+    fn on_command(&mut self, _ctx: &ActionContext, cmd: &str) -> Option<ActionResult> {
+        match cmd.to_uppercase().as_str() {
+            "F" | "FILLET" => {
+                self.status = Status::SetFilletRadius;
+                Some(ActionResult::Continue)
+            }
+            _ => None,
+        }
+    }
+
+    fn on_value(&mut self, _ctx: &ActionContext, value: f64) -> ActionResult {
+        if self.status == Status::SetFilletRadius && value >= 0.0 {
+            self.fillet_radius = value;
+            self.status = if self.corner1.is_some() { Status::SetCorner2 } else { Status::SetCorner1 };
+        }
+        ActionResult::Continue
+    }
+
+    fn on_dimensions(&mut self, _ctx: &ActionContext, width: f64, height: f64) -> Option<ActionResult> {
+        // 从第一个角点按宽高确定对角点（负值向左/向下）
+        if self.status != Status::SetCorner2 {
+            return None;
+        }
+        let c1 = self.corner1?;
+        if width.abs() <= 1e-6 || height.abs() <= 1e-6 {
+            return Some(ActionResult::Continue);
+        }
+        let rect = self.create_rectangle(c1, Point2::new(c1.x + width, c1.y + height));
+        self.reset();
+        Some(ActionResult::CreateEntities(vec![Geometry::Polyline(rect)]))
     }
 
     fn get_prompt(&self) -> &str {
         match self.status {
-            Status::SetCorner1 => "指定第一个角点:",
-            Status::SetCorner2 => "指定对角点:",
+            Status::SetCorner1 => "指定第一个角点 或 [圆角(F)]:",
+            Status::SetCorner2 => "指定对角点 或 输入尺寸 (宽,高):",
+            Status::SetFilletRadius => "指定矩形的圆角半径:",
         }
     }
 
+    fn get_available_commands(&self) -> Vec<&str> {
+        vec!["Fillet"]
+    }
+
     fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
         let mut previews = Vec::new();
         
@@ -124,3 +173,60 @@ impl Action for DrawRectangleAction {
         previews
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::{dispatch_input, test_context};
+
+    #[test]
+    fn test_typed_dimensions_size_rectangle() {
+        let ctx = test_context(&[], Point2::origin());
+        let mut action = DrawRectangleAction::new();
+        // 第一个角点之前 "w,h" 仍是坐标
+        dispatch_input(&mut action, &ctx, "10,5").unwrap();
+
+        let result = dispatch_input(&mut action, &ctx, "30,20").unwrap();
+        let ActionResult::CreateEntities(geometries) = result else {
+            panic!("输入尺寸后应创建矩形");
+        };
+        let Geometry::Polyline(rect) = &geometries[0] else {
+            panic!("应为多段线");
+        };
+        assert_eq!(rect.vertices[0].point, Point2::new(10.0, 5.0));
+        assert_eq!(rect.vertices[2].point, Point2::new(40.0, 25.0));
+    }
+
+    #[test]
+    fn test_rounded_rectangle_sub_mode() {
+        let ctx = test_context(&[], Point2::origin());
+        let mut action = DrawRectangleAction::new();
+        dispatch_input(&mut action, &ctx, "F").unwrap();
+        dispatch_input(&mut action, &ctx, "2").unwrap();
+        assert_eq!(action.fillet_radius(), 2.0);
+
+        action.on_coordinate(&ctx, Point2::new(30.0, 20.0));
+        let ActionResult::CreateEntities(geometries) = action.on_coordinate(&ctx, Point2::new(0.0, 0.0)) else {
+            panic!("应创建圆角矩形");
+        };
+        let Geometry::Polyline(rect) = &geometries[0] else {
+            panic!("应为多段线");
+        };
+        assert!(rect.closed);
+        let segments = rect.explode();
+        assert_eq!(segments.iter().filter(|g| matches!(g, Geometry::Arc(_))).count(), 4);
+        assert_eq!(segments.iter().filter(|g| matches!(g, Geometry::Line(_))).count(), 4);
+
+        // 总体尺寸不变，圆角为半径 2 的四分之一圆
+        let bbox = Geometry::Polyline(rect.clone()).bounding_box();
+        assert!((bbox.width() - 30.0).abs() < 1e-9 && (bbox.height() - 20.0).abs() < 1e-9);
+        for segment in &segments {
+            if let Geometry::Arc(arc) = segment {
+                assert!((arc.radius - 2.0).abs() < 1e-9);
+                assert!((arc.sweep_angle() - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+            }
+        }
+        let perimeter = 2.0 * (30.0 + 20.0) - 8.0 * 2.0 + std::f64::consts::TAU * 2.0;
+        assert!((rect.length() - perimeter).abs() < 1e-9);
+    }
+}
@@ -7,8 +7,12 @@ use crate::action::{
 };
 use zcad_core::entity::EntityId;
 use zcad_core::geometry::{Geometry, Line};
-use zcad_core::grip::{get_grips_for_geometry, update_geometry_by_grip, Grip, GripType};
-use zcad_core::math::Point2;
+use zcad_core::grip::{
+    bounding_box_after_grip_move, get_grips_for_geometry, insert_vertex_at_grip, update_geometry_by_grip, Grip,
+    GripType,
+};
+use zcad_core::history::{operations as hist_ops, Operation};
+use zcad_core::math::{BoundingBox2, Point2};
 
 /// 夹点编辑状态
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -19,7 +23,9 @@ enum Status {
     DraggingGrip,
 }
 
-/// 夹点编辑历史操作
+/// 夹点编辑内部历史操作
+///
+/// 几何修改不在这里记录，而是作为 `ModifyEntity` 操作提交到文档历史
 #[derive(Debug, Clone)]
 enum HistoryAction {
     /// 选择了夹点
@@ -27,10 +33,6 @@ enum HistoryAction {
         entity_id: EntityId,
         grip_index: usize,
     },
-    /// 移动了夹点
-    MoveGrip {
-        old_geometry: Geometry,
-    },
 }
 
 /// 夹点编辑 Action
@@ -46,6 +48,10 @@ pub struct GripEditAction {
     preview_geometry: Option<Geometry>,
     /// 历史记录
     history: Vec<HistoryAction>,
+    /// 待提交到文档历史的操作
+    pending_operations: Vec<Operation>,
+    /// 最近一次提交的夹点移动（实体、旧几何、新几何、夹点），用于增量更新包围盒
+    last_move: Option<(EntityId, Geometry, Geometry, Grip)>,
     /// 夹点选择容差（屏幕像素）
     grip_tolerance: f64,
 }
@@ -59,6 +65,8 @@ impl GripEditAction {
             original_grip_position: None,
             preview_geometry: None,
             history: Vec::new(),
+            pending_operations: Vec::new(),
+            last_move: None,
             grip_tolerance: 10.0, // 10 像素容差
         }
     }
@@ -90,6 +98,19 @@ impl GripEditAction {
         }
     }
     
+    /// 提交一次几何修改：记录文档历史并以新几何重新生成夹点
+    fn commit_edit(&mut self, entity_id: EntityId, old_geometry: Geometry, new_geometry: Geometry, description: &str) -> ActionResult {
+        self.last_move = None;
+        self.pending_operations.push(hist_ops::modify_entity(
+            entity_id,
+            old_geometry,
+            new_geometry.clone(),
+            description,
+        ));
+        self.set_entity(entity_id, new_geometry.clone());
+        ActionResult::ModifyEntity(entity_id, new_geometry)
+    }
+
     /// 更新预览
     fn update_preview(&mut self, ctx: &ActionContext) {
         if let (Some((_, geometry, _)), Some(grip)) = (&self.selected_entity, &self.active_grip) {
@@ -157,6 +178,13 @@ impl Action for GripEditAction {
     fn on_coordinate(&mut self, ctx: &ActionContext, coord: Point2) -> ActionResult {
         match self.status {
             Status::SelectGrip => {
+                // 锁定的实体不允许夹点编辑
+                if let Some((entity_id, _, _)) = &self.selected_entity {
+                    if ctx.is_locked(entity_id) {
+                        return ActionResult::Continue;
+                    }
+                }
+
                 // 将屏幕容差转换为世界坐标容差
                 let world_tolerance = self.grip_tolerance / ctx.zoom.max(0.001);
                 
@@ -165,6 +193,16 @@ impl Action for GripEditAction {
                     .map(|g| (g.clone(), g.index));
                 
                 if let Some((grip, grip_index)) = grip_data {
+                    // Ctrl+点击多段线的中点或凸度夹点：在该线段上插入新顶点
+                    if ctx.ctrl_pressed && matches!(grip.grip_type, GripType::Midpoint | GripType::Bulge) {
+                        if let Some((entity_id, geometry, _)) = &self.selected_entity {
+                            if let Some(new_geometry) = insert_vertex_at_grip(geometry, &grip) {
+                                let (entity_id, old_geometry) = (*entity_id, geometry.clone());
+                                return self.commit_edit(entity_id, old_geometry, new_geometry, "插入顶点");
+                            }
+                        }
+                    }
+
                     // 选中夹点
                     let grip_position = grip.position;
                     self.active_grip = Some(grip);
@@ -182,26 +220,22 @@ impl Action for GripEditAction {
             }
             Status::DraggingGrip => {
                 // 完成拖动，应用修改
-                if let Some((entity_id, geometry, _)) = &self.selected_entity {
-                    if let Some(grip) = &self.active_grip {
-                        if let Some(new_geometry) = update_geometry_by_grip(geometry, grip, coord) {
-                            // 保存历史
-                            self.history.push(HistoryAction::MoveGrip {
-                                old_geometry: geometry.clone(),
-                            });
-                            
-                            // 返回修改结果
-                            let result = ActionResult::ModifyEntity(*entity_id, new_geometry);
-                            
-                            // 重置状态，准备下一次编辑
-                            self.status = Status::SelectGrip;
-                            self.active_grip = None;
-                            self.original_grip_position = None;
-                            self.preview_geometry = None;
-                            
-                            return result;
-                        }
-                    }
+                let edit = match (&self.selected_entity, &self.active_grip) {
+                    (Some((entity_id, geometry, _)), Some(grip)) => update_geometry_by_grip(geometry, grip, coord)
+                        .map(|new_geometry| (*entity_id, geometry.clone(), new_geometry)),
+                    _ => None,
+                };
+
+                if let Some((entity_id, old_geometry, new_geometry)) = edit {
+                    // 重置状态，准备下一次编辑
+                    self.status = Status::SelectGrip;
+                    let grip = self.active_grip.take();
+                    self.original_grip_position = None;
+                    self.preview_geometry = None;
+
+                    let result = self.commit_edit(entity_id, old_geometry.clone(), new_geometry.clone(), "夹点编辑");
+                    self.last_move = grip.map(|grip| (entity_id, old_geometry, new_geometry, grip));
+                    return result;
                 }
                 ActionResult::Continue
             }
@@ -231,7 +265,7 @@ impl Action for GripEditAction {
     
     fn get_prompt(&self) -> &str {
         match self.status {
-            Status::SelectGrip => "选择夹点进行编辑",
+            Status::SelectGrip => "选择夹点进行编辑（Ctrl+点击中点插入顶点）",
             Status::DraggingGrip => "指定新位置或输入距离",
         }
     }
@@ -312,13 +346,94 @@ impl Action for GripEditAction {
                     self.status = Status::SelectGrip;
                     self.preview_geometry = None;
                 }
-                HistoryAction::MoveGrip { old_geometry } => {
-                    if let Some((entity_id, _, grips)) = &self.selected_entity {
-                        let new_grips = get_grips_for_geometry(&old_geometry);
-                        self.selected_entity = Some((*entity_id, old_geometry, new_grips));
-                    }
-                }
             }
         }
     }
+
+    fn take_operations(&mut self) -> Vec<Operation> {
+        std::mem::take(&mut self.pending_operations)
+    }
+
+    fn modified_bounding_box(&self, id: &EntityId, previous: &BoundingBox2) -> Option<BoundingBox2> {
+        let (entity_id, old_geometry, new_geometry, grip) = self.last_move.as_ref()?;
+        (entity_id == id).then(|| bounding_box_after_grip_move(old_geometry, previous, new_geometry, grip))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::{apply_action_result, test_context};
+    use zcad_core::entity::Entity;
+    use zcad_core::geometry::Polyline;
+    use zcad_core::history::HistoryTree;
+    use zcad_file::Document;
+
+    #[test]
+    fn test_grip_move_undo_restores_geometry() {
+        let original = Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0)));
+        let mut document = Document::new();
+        let id = document.add_entity(Entity::new(original.clone()));
+        let mut history = HistoryTree::new(100);
+
+        let entities: Vec<Entity> = document.all_entities().cloned().collect();
+        let ctx = test_context(&entities, Point2::origin());
+        let mut action = GripEditAction::new();
+        action.set_entity(id, original.clone());
+
+        // 拖动起点夹点到 (0, 5)，经由宿主应用到文档并提交历史
+        let result = action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
+        assert!(apply_action_result(&mut action, result, &mut document, &mut history));
+        let result = action.on_coordinate(&ctx, Point2::new(0.0, 5.0));
+        assert!(matches!(result, ActionResult::ModifyEntity(..)));
+        assert!(apply_action_result(&mut action, result, &mut document, &mut history));
+        assert!(action.take_operations().is_empty());
+        match &document.get_entity(&id).unwrap().geometry {
+            Geometry::Line(line) => assert!((line.start - Point2::new(0.0, 5.0)).norm() < 1e-9),
+            _ => panic!("应为线段"),
+        }
+
+        // 撤销：只记录了一次修改，恢复原始几何
+        let op = history.undo().unwrap().clone();
+        assert!(history.undo().is_none());
+        document.undo_operation(&op.operation_type);
+
+        match &document.get_entity(&id).unwrap().geometry {
+            Geometry::Line(line) => {
+                assert!((line.start - Point2::new(0.0, 0.0)).norm() < 1e-9);
+                assert!((line.end - Point2::new(10.0, 0.0)).norm() < 1e-9);
+            }
+            _ => panic!("应为线段"),
+        }
+    }
+
+    #[test]
+    fn test_grip_move_updates_index_incrementally() {
+        let polyline = Polyline::from_points(
+            [Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(10.0, 10.0)],
+            false,
+        );
+        let original = Geometry::Polyline(polyline);
+        let mut document = Document::new();
+        let id = document.add_entity(Entity::new(original.clone()));
+        let mut history = HistoryTree::new(100);
+
+        let entities: Vec<Entity> = document.all_entities().cloned().collect();
+        let ctx = test_context(&entities, Point2::origin());
+        let mut action = GripEditAction::new();
+        action.set_entity(id, original);
+
+        // 把中间顶点拖到 (30, -5)
+        let result = action.on_coordinate(&ctx, Point2::new(10.0, 0.0));
+        apply_action_result(&mut action, result, &mut document, &mut history);
+        let result = action.on_coordinate(&ctx, Point2::new(30.0, -5.0));
+        apply_action_result(&mut action, result, &mut document, &mut history);
+
+        // 空间索引中的增量包围盒与整体重新计算一致
+        let indexed = document.entity_bounding_box(&id).unwrap();
+        let full = document.get_entity(&id).unwrap().bounding_box();
+        assert_eq!((indexed.min, indexed.max), (full.min, full.max));
+        assert_eq!(indexed.max.x, 30.0);
+        assert_eq!(indexed.min.y, -5.0);
+    }
 }
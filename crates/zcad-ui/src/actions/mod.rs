@@ -15,31 +15,42 @@ mod modify_copy;
 mod modify_rotate;
 mod modify_scale;
 mod modify_mirror;
+mod modify_stretch;
 mod modify_offset;
 mod modify_trim;
 mod modify_extend;
+mod modify_lengthen;
+mod modify_break;
+mod modify_polyline_close;
 mod modify_fillet;
 mod modify_chamfer;
+mod modify_region_boolean;
 mod grip_edit;
+mod cutting_edge;
 
 pub use draw_line::DrawLineAction;
 pub use draw_circle::DrawCircleAction;
 pub use draw_arc::DrawArcAction;
-pub use draw_polyline::DrawPolylineAction;
+pub use draw_polyline::{DrawPolylineAction, PolylineBuilder};
 pub use draw_rectangle::DrawRectangleAction;
 pub use draw_point::DrawPointAction;
 pub use draw_ellipse::DrawEllipseAction;
-pub use select::SelectAction;
+pub use select::{BoxSelectMode, SelectAction};
 pub use modify_move::MoveAction;
 pub use modify_copy::CopyAction;
 pub use modify_rotate::RotateAction;
 pub use modify_scale::ScaleAction;
 pub use modify_mirror::MirrorAction;
+pub use modify_stretch::StretchAction;
 pub use modify_offset::OffsetAction;
 pub use modify_trim::TrimAction;
 pub use modify_extend::ExtendAction;
+pub use modify_lengthen::{LengthenAction, LengthenMode};
+pub use modify_break::BreakAction;
+pub use modify_polyline_close::PolylineCloseAction;
 pub use modify_fillet::FilletAction;
 pub use modify_chamfer::ChamferAction;
+pub use modify_region_boolean::RegionBooleanAction;
 pub use grip_edit::GripEditAction;
 
 use crate::action::{Action, ActionType};
@@ -60,11 +71,16 @@ pub fn create_action(action_type: ActionType) -> Box<dyn Action> {
         ActionType::Rotate => Box::new(RotateAction::new()),
         ActionType::Scale => Box::new(ScaleAction::new()),
         ActionType::Mirror => Box::new(MirrorAction::new()),
+        ActionType::Stretch => Box::new(StretchAction::new()),
         ActionType::Offset => Box::new(OffsetAction::new()),
         ActionType::Trim => Box::new(TrimAction::new()),
         ActionType::Extend => Box::new(ExtendAction::new()),
+        ActionType::Lengthen => Box::new(LengthenAction::new()),
+        ActionType::Break => Box::new(BreakAction::new()),
+        ActionType::PolylineClose => Box::new(PolylineCloseAction::new()),
         ActionType::Fillet => Box::new(FilletAction::new()),
         ActionType::Chamfer => Box::new(ChamferAction::new()),
+        ActionType::RegionBoolean => Box::new(RegionBooleanAction::new()),
         ActionType::GripEdit => Box::new(GripEditAction::new()),
         _ => Box::new(SelectAction::new()),
     }
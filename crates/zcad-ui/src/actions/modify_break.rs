@@ -0,0 +1,222 @@
+//! 打断 Action
+//!
+//! 参考 AutoCAD 的 BREAK：选择对象时的拾取点作为第一打断点，再指定第二打断点，
+//! 删除两点之间的部分；第二点输入 `@` 时在第一点处一分为二。
+//! 支持直线、圆弧、圆和多段线，圆只能在两点间打断，结果为圆弧。
+
+use crate::action::{
+    Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
+};
+use zcad_core::entity::{Entity, EntityId};
+use zcad_core::geometry::Geometry;
+use zcad_core::math::Point2;
+
+/// 打断状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Status {
+    /// 选择要打断的对象
+    SelectObject,
+    /// 等待指定第一打断点（输入 F 后）
+    FirstPoint,
+    /// 等待指定第二打断点
+    SecondPoint,
+}
+
+/// 打断 Action
+pub struct BreakAction {
+    status: Status,
+    /// 要打断的对象
+    entity: Option<EntityId>,
+    /// 第一打断点
+    first_point: Option<Point2>,
+}
+
+impl BreakAction {
+    pub fn new() -> Self {
+        Self {
+            status: Status::SelectObject,
+            entity: None,
+            first_point: None,
+        }
+    }
+
+    fn find_entity_at_point<'a>(&self, ctx: &'a ActionContext, point: Point2) -> Option<&'a Entity> {
+        let tolerance = 5.0;
+        ctx.entities.iter().find(|e| {
+            !e.locked
+                && matches!(
+                    e.geometry,
+                    Geometry::Line(_) | Geometry::Arc(_) | Geometry::Circle(_) | Geometry::Polyline(_)
+                )
+                && e.geometry.contains_point(&point, tolerance)
+        })
+    }
+
+    /// 在第一点和 `second` 之间打断选中对象
+    fn break_at(&mut self, ctx: &ActionContext, second: Point2) -> ActionResult {
+        let target = self
+            .entity
+            .and_then(|id| ctx.entities.iter().find(|e| e.id == id));
+        let pieces = target.zip(self.first_point).and_then(|(entity, first)| {
+            entity.geometry.break_between(first, second).map(|pieces| (entity.id, pieces))
+        });
+        self.reset();
+        match pieces {
+            Some((id, mut pieces)) if pieces.len() == 1 => {
+                ActionResult::ModifyEntities(vec![(id, pieces.remove(0))])
+            }
+            Some((id, pieces)) => ActionResult::ReplaceEntity(id, pieces),
+            None => ActionResult::Continue,
+        }
+    }
+}
+
+impl Default for BreakAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Action for BreakAction {
+    fn action_type(&self) -> ActionType {
+        ActionType::Break
+    }
+
+    fn reset(&mut self) {
+        self.status = Status::SelectObject;
+        self.entity = None;
+        self.first_point = None;
+    }
+
+    fn on_mouse_move(&mut self, _ctx: &ActionContext) -> ActionResult {
+        ActionResult::Continue
+    }
+
+    fn on_mouse_click(&mut self, ctx: &ActionContext, button: MouseButton) -> ActionResult {
+        match button {
+            MouseButton::Left => {
+                let point = ctx.effective_point();
+                self.on_coordinate(ctx, point)
+            }
+            MouseButton::Right => match self.status {
+                Status::SelectObject => ActionResult::Cancel,
+                _ => {
+                    self.reset();
+                    ActionResult::Continue
+                }
+            },
+            MouseButton::Middle => ActionResult::Continue,
+        }
+    }
+
+    fn on_coordinate(&mut self, ctx: &ActionContext, coord: Point2) -> ActionResult {
+        match self.status {
+            Status::SelectObject => {
+                if let Some(entity) = self.find_entity_at_point(ctx, coord) {
+                    self.entity = Some(entity.id);
+                    self.first_point = Some(coord);
+                    self.status = Status::SecondPoint;
+                }
+                ActionResult::Continue
+            }
+            Status::FirstPoint => {
+                self.first_point = Some(coord);
+                self.status = Status::SecondPoint;
+                ActionResult::Continue
+            }
+            Status::SecondPoint => self.break_at(ctx, coord),
+        }
+    }
+
+    fn on_command(&mut self, ctx: &ActionContext, cmd: &str) -> Option<ActionResult> {
+        if self.status != Status::SecondPoint {
+            return None;
+        }
+        match cmd.to_uppercase().as_str() {
+            "F" | "FIRST" => {
+                self.status = Status::FirstPoint;
+                Some(ActionResult::Continue)
+            }
+            // 第二点与第一点相同：在该点一分为二
+            "@" | "A" | "AT" => {
+                let first = self.first_point?;
+                Some(self.break_at(ctx, first))
+            }
+            _ => None,
+        }
+    }
+
+    fn get_prompt(&self) -> &str {
+        match self.status {
+            Status::SelectObject => "选择要打断的对象:",
+            Status::FirstPoint => "指定第一个打断点:",
+            Status::SecondPoint => "指定第二个打断点 或 [第一点(F)/在此打断(@)]:",
+        }
+    }
+
+    fn get_available_commands(&self) -> Vec<&str> {
+        match self.status {
+            Status::SecondPoint => vec!["First", "@"],
+            _ => vec![],
+        }
+    }
+
+    fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
+        if self.status != Status::SecondPoint {
+            return Vec::new();
+        }
+        let point = ctx.effective_point();
+        self.entity
+            .and_then(|id| ctx.entities.iter().find(|e| e.id == id))
+            .zip(self.first_point)
+            .and_then(|(entity, first)| entity.geometry.break_between(first, point))
+            .map(|pieces| pieces.into_iter().map(PreviewGeometry::new).collect())
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::test_context;
+    use zcad_core::geometry::{Circle, Line};
+
+    #[test]
+    fn test_break_line_at_midpoint() {
+        let entities = vec![Entity::new(Geometry::Line(Line::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 0.0),
+        )))];
+        let ctx = test_context(&entities, Point2::origin());
+        let mut action = BreakAction::new();
+        action.on_coordinate(&ctx, Point2::new(5.0, 0.0));
+        let Some(ActionResult::ReplaceEntity(id, pieces)) = action.on_command(&ctx, "@") else {
+            panic!("在点处打断应替换为两段");
+        };
+        assert_eq!(id, entities[0].id);
+        assert_eq!(pieces.len(), 2);
+        for piece in &pieces {
+            let Geometry::Line(line) = piece else {
+                panic!("应为线段");
+            };
+            assert!((line.length() - 5.0).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_break_circle_between_points_yields_arc() {
+        let entities = vec![Entity::new(Geometry::Circle(Circle::new(Point2::origin(), 10.0)))];
+        let ctx = test_context(&entities, Point2::origin());
+        let mut action = BreakAction::new();
+        action.on_coordinate(&ctx, Point2::new(10.0, 0.0));
+        let ActionResult::ModifyEntities(modified) = action.on_coordinate(&ctx, Point2::new(0.0, 10.0)) else {
+            panic!("圆应被修改为圆弧");
+        };
+        let Geometry::Arc(arc) = &modified[0].1 else {
+            panic!("应为圆弧");
+        };
+        // 删除第一点逆时针到第二点的四分之一圆
+        assert!((arc.sweep_angle() - 1.5 * std::f64::consts::PI).abs() < 1e-9);
+        assert!((arc.start_point() - Point2::new(0.0, 10.0)).norm() < 1e-9);
+    }
+}
@@ -5,8 +5,10 @@
 use crate::action::{
     Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
 };
-use zcad_core::entity::EntityId;
+use crate::action::compound_operation;
+use zcad_core::entity::{Entity, EntityId};
 use zcad_core::geometry::{Geometry, Line};
+use zcad_core::history::Operation;
 use zcad_core::math::{Point2, EPSILON};
 
 /// 倒角状态
@@ -29,6 +31,8 @@ pub struct ChamferAction {
     distance2: f64,
     first_entity: Option<EntityId>,
     first_line: Option<Line>,
+    /// 待提交到文档历史的操作
+    pending_operations: Vec<Operation>,
 }
 
 impl ChamferAction {
@@ -39,6 +43,7 @@ impl ChamferAction {
             distance2: 10.0,
             first_entity: None,
             first_line: None,
+            pending_operations: Vec::new(),
         }
     }
 }
@@ -83,7 +88,7 @@ impl Action for ChamferAction {
                     Status::SelectSecond => {
                         if let Some(entity) = self.find_line_at_point(ctx, point) {
                             if let Geometry::Line(line2) = &entity.geometry {
-                                if let Some(result) = self.create_chamfer(&self.first_line.clone().unwrap(), line2, self.first_entity.unwrap(), entity.id) {
+                                if let Some(result) = self.create_chamfer(ctx, &self.first_line.clone().unwrap(), line2, self.first_entity.unwrap(), entity.id) {
                                     self.first_entity = None;
                                     self.first_line = None;
                                     self.status = Status::SelectFirst;
@@ -144,17 +149,28 @@ impl Action for ChamferAction {
     fn get_preview(&self, _ctx: &ActionContext) -> Vec<PreviewGeometry> {
         Vec::new()
     }
+
+    fn take_operations(&mut self) -> Vec<Operation> {
+        std::mem::take(&mut self.pending_operations)
+    }
 }
 
 impl ChamferAction {
-    fn find_line_at_point<'a>(&self, ctx: &'a ActionContext, point: Point2) -> Option<&'a zcad_core::entity::Entity> {
+    fn find_line_at_point<'a>(&self, ctx: &'a ActionContext, point: Point2) -> Option<&'a Entity> {
         let tolerance = 5.0;
         ctx.entities.iter().find(|e| {
             matches!(&e.geometry, Geometry::Line(_)) && e.geometry.contains_point(&point, tolerance)
         })
     }
 
-    fn create_chamfer(&self, line1: &Line, line2: &Line, id1: EntityId, id2: EntityId) -> Option<ActionResult> {
+    fn create_chamfer(
+        &mut self,
+        ctx: &ActionContext,
+        line1: &Line,
+        line2: &Line,
+        id1: EntityId,
+        id2: EntityId,
+    ) -> Option<ActionResult> {
         // 找到两条线的交点
         let intersection = self.find_intersection(line1, line2)?;
         
@@ -162,10 +178,9 @@ impl ChamferAction {
             // 距离为0：只修剪到交点
             let new_line1 = self.trim_line_to_point(line1, intersection);
             let new_line2 = self.trim_line_to_point(line2, intersection);
-            return Some(ActionResult::ModifyEntities(vec![
-                (id1, Geometry::Line(new_line1)),
-                (id2, Geometry::Line(new_line2)),
-            ]));
+            let modified = vec![(id1, Geometry::Line(new_line1)), (id2, Geometry::Line(new_line2))];
+            self.pending_operations.push(compound_operation("倒角", ctx.entities, &modified, &[]));
+            return Some(ActionResult::ModifyEntities(modified));
         }
         
         // 计算倒角点
@@ -180,15 +195,13 @@ impl ChamferAction {
         let new_line1 = self.trim_line_to_point(line1, chamfer_pt1);
         let new_line2 = self.trim_line_to_point(line2, chamfer_pt2);
         
-        // 创建倒角线
-        let _chamfer_line = Line::new(chamfer_pt1, chamfer_pt2);
-        
-        // 返回修改操作
-        Some(ActionResult::ModifyEntities(vec![
-            (id1, Geometry::Line(new_line1)),
-            (id2, Geometry::Line(new_line2)),
-        ]))
-        // TODO: 需要同时创建倒角线
+        // 倒角线继承第一条线的图层和属性；修剪和新建记录为一个分组操作，一次撤销
+        let mut chamfer_entity = ctx.entities.iter().find(|e| e.id == id1)?.duplicate();
+        chamfer_entity.geometry = Geometry::Line(Line::new(chamfer_pt1, chamfer_pt2));
+        let modified = vec![(id1, Geometry::Line(new_line1)), (id2, Geometry::Line(new_line2))];
+        let added = vec![chamfer_entity];
+        self.pending_operations.push(compound_operation("倒角", ctx.entities, &modified, &added));
+        Some(ActionResult::ModifyAndCreate { modified, added })
     }
 
     fn find_intersection(&self, l1: &Line, l2: &Line) -> Option<Point2> {
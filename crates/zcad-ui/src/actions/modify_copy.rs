@@ -172,11 +172,16 @@ impl Action for CopyAction {
         }
     }
 
+    fn base_point(&self) -> Option<Point2> {
+        self.base_point
+    }
+
     fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
         let mut previews = Vec::new();
         
         if self.status == Status::SetDestination {
             if let Some(base) = self.base_point {
+                previews.extend(PreviewGeometry::base_point_marker(base, ctx.zoom));
                 let dest = ctx.effective_point();
                 let line = Line::new(base, dest);
                 previews.push(PreviewGeometry::reference(Geometry::Line(line)));
@@ -9,6 +9,8 @@ use zcad_core::entity::EntityId;
 use zcad_core::geometry::{Geometry, Line};
 use zcad_core::math::{Point2, EPSILON};
 
+use super::cutting_edge::{line_edge_params, point_at};
+
 /// 延伸状态
 #[derive(Debug, Clone, PartialEq)]
 enum Status {
@@ -127,112 +129,79 @@ impl ExtendAction {
         }
     }
 
+    /// 延伸线段到最近的边界（直线、圆、圆弧或多段线）
     fn extend_line(&self, ctx: &ActionContext, line: &Line, click_point: Point2) -> Option<Geometry> {
         // 确定延伸哪一端（离点击点更近的那端）
         let start_dist = (line.start - click_point).norm();
         let end_dist = (line.end - click_point).norm();
-        
         let extend_from_start = start_dist < end_dist;
-        
-        // 找到与边界的最近交点
-        let mut best_intersection: Option<Point2> = None;
-        let mut best_dist = f64::MAX;
-        
-        // 延伸线段方向
-        let dir = line.direction();
-        let extend_dir = if extend_from_start { -dir } else { dir };
-        let extend_point = if extend_from_start { line.start } else { line.end };
-        
-        // 创建一个延伸射线（足够长）
-        let ray_end = extend_point + extend_dir * 10000.0;
-        let ray = Line::new(extend_point, ray_end);
-        
+
+        // 沿延伸方向找最近的交点：终点方向取 t > 1 的最小值，起点方向取 t < 0 的最大值
+        let mut best: Option<f64> = None;
         for boundary_id in &self.boundary_entities {
             if let Some(boundary) = ctx.entities.iter().find(|e| e.id == *boundary_id) {
-                let intersections = self.find_intersections(&Geometry::Line(ray.clone()), &boundary.geometry);
-                
-                for p in intersections {
-                    let dist = (p - extend_point).norm();
-                    if dist > EPSILON && dist < best_dist {
-                        best_dist = dist;
-                        best_intersection = Some(p);
+                for t in line_edge_params(line, &boundary.geometry) {
+                    if extend_from_start {
+                        if t < -EPSILON && best.is_none_or(|b| t > b) {
+                            best = Some(t);
+                        }
+                    } else if t > 1.0 + EPSILON && best.is_none_or(|b| t < b) {
+                        best = Some(t);
                     }
                 }
             }
         }
-        
-        if let Some(new_point) = best_intersection {
-            if extend_from_start {
-                Some(Geometry::Line(Line::new(new_point, line.end)))
-            } else {
-                Some(Geometry::Line(Line::new(line.start, new_point)))
-            }
-        } else {
-            None
-        }
-    }
-
-    fn find_intersections(&self, geom1: &Geometry, geom2: &Geometry) -> Vec<Point2> {
-        match (geom1, geom2) {
-            (Geometry::Line(l1), Geometry::Line(l2)) => {
-                self.line_line_intersection(l1, l2).into_iter().collect()
-            }
-            (Geometry::Line(line), Geometry::Circle(circle)) => {
-                self.line_circle_intersection(line, circle)
-            }
-            _ => vec![],
-        }
-    }
-
-    fn line_line_intersection(&self, l1: &Line, l2: &Line) -> Option<Point2> {
-        let d1 = l1.end - l1.start;
-        let d2 = l2.end - l2.start;
-
-        let cross = d1.x * d2.y - d1.y * d2.x;
-        
-        if cross.abs() < EPSILON {
-            return None;
-        }
 
-        let d = l2.start - l1.start;
-        let t1 = (d.x * d2.y - d.y * d2.x) / cross;
-        let t2 = (d.x * d1.y - d.y * d1.x) / cross;
-
-        // t1 可以 > 1（射线延伸），t2 必须在 [0, 1] 内（边界线段内）
-        if t1 >= 0.0 && t2 >= 0.0 && t2 <= 1.0 {
-            Some(l1.start + d1 * t1)
+        let new_point = point_at(line, best?);
+        if extend_from_start {
+            Some(Geometry::Line(Line::new(new_point, line.end)))
         } else {
-            None
+            Some(Geometry::Line(Line::new(line.start, new_point)))
         }
     }
+}
 
-    fn line_circle_intersection(&self, line: &Line, circle: &zcad_core::geometry::Circle) -> Vec<Point2> {
-        let d = line.end - line.start;
-        let f = line.start - circle.center;
-
-        let a = d.dot(&d);
-        let b = 2.0 * f.dot(&d);
-        let c = f.dot(&f) - circle.radius * circle.radius;
-
-        let discriminant = b * b - 4.0 * a * c;
-
-        if discriminant < 0.0 {
-            return vec![];
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::test_context;
+    use zcad_core::entity::Entity;
+    use zcad_core::geometry::Arc;
+
+    #[test]
+    fn test_extend_line_to_arc() {
+        use std::f64::consts::PI;
+
+        // 左半圆弧：延长线与整圆交于 (7, 0) 和 (13, 0)，只有 (7, 0) 在弧上
+        let left = Entity::new(Geometry::Arc(Arc::new(Point2::new(10.0, 0.0), 3.0, PI / 2.0, 3.0 * PI / 2.0)));
+        let entities = vec![left.clone()];
+        let ctx = test_context(&entities, Point2::origin());
+        let mut action = ExtendAction::new();
+        action.boundary_entities.push(left.id);
+
+        let line = Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(5.0, 0.0)));
+        match action.extend_entity(&ctx, &line, Point2::new(4.5, 0.0)) {
+            Some(Geometry::Line(extended)) => {
+                assert!((extended.start - Point2::new(0.0, 0.0)).norm() < 1e-9);
+                assert!((extended.end - Point2::new(7.0, 0.0)).norm() < 1e-9);
+            }
+            other => panic!("应延伸为线段: {:?}", other),
         }
 
-        let mut intersections = Vec::new();
-        let sqrt_disc = discriminant.sqrt();
-        
-        let t1 = (-b - sqrt_disc) / (2.0 * a);
-        let t2 = (-b + sqrt_disc) / (2.0 * a);
-
-        if t1 >= 0.0 {
-            intersections.push(line.start + d * t1);
-        }
-        if t2 >= 0.0 && (t2 - t1).abs() > EPSILON {
-            intersections.push(line.start + d * t2);
+        // 右半圆弧：只能延伸到 (13, 0)
+        let right = Entity::new(Geometry::Arc(Arc::new(Point2::new(10.0, 0.0), 3.0, -PI / 2.0, PI / 2.0)));
+        let entities = vec![right.clone()];
+        let ctx = test_context(&entities, Point2::origin());
+        let mut action = ExtendAction::new();
+        action.boundary_entities.push(right.id);
+        match action.extend_entity(&ctx, &line, Point2::new(4.5, 0.0)) {
+            Some(Geometry::Line(extended)) => {
+                assert!((extended.end - Point2::new(13.0, 0.0)).norm() < 1e-9);
+            }
+            other => panic!("应延伸为线段: {:?}", other),
         }
 
-        intersections
+        // 从起点一侧延伸：边界不在起点方向，不延伸
+        assert!(action.extend_entity(&ctx, &line, Point2::new(0.5, 0.0)).is_none());
     }
 }
@@ -1,12 +1,14 @@
 //! 圆角命令 Action
 //!
-//! 在两条线段之间创建圆角
+//! 在两条线段之间创建圆角，或对整条多段线的所有拐角倒圆角
 
 use crate::action::{
     Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
 };
-use zcad_core::entity::EntityId;
+use crate::action::compound_operation;
+use zcad_core::entity::{Entity, EntityId};
 use zcad_core::geometry::{Arc, Geometry, Line};
+use zcad_core::history::Operation;
 use zcad_core::math::{Point2, Vector2, EPSILON};
 
 /// 圆角状态
@@ -18,6 +20,8 @@ enum Status {
     SelectFirst,
     /// 选择第二条线
     SelectSecond,
+    /// 选择多段线（对所有拐角倒圆角）
+    SelectPolyline,
 }
 
 /// 圆角命令 Action
@@ -26,6 +30,8 @@ pub struct FilletAction {
     radius: f64,
     first_entity: Option<EntityId>,
     first_line: Option<Line>,
+    /// 待提交到文档历史的操作
+    pending_operations: Vec<Operation>,
 }
 
 impl FilletAction {
@@ -35,6 +41,7 @@ impl FilletAction {
             radius: 10.0, // 默认半径
             first_entity: None,
             first_line: None,
+            pending_operations: Vec::new(),
         }
     }
 }
@@ -79,7 +86,7 @@ impl Action for FilletAction {
                     Status::SelectSecond => {
                         if let Some(entity) = self.find_line_at_point(ctx, point) {
                             if let Geometry::Line(line2) = &entity.geometry {
-                                if let Some(result) = self.create_fillet(&self.first_line.clone().unwrap(), line2, self.first_entity.unwrap(), entity.id) {
+                                if let Some(result) = self.create_fillet(ctx, &self.first_line.clone().unwrap(), line2, self.first_entity.unwrap(), entity.id) {
                                     self.first_entity = None;
                                     self.first_line = None;
                                     self.status = Status::SelectFirst;
@@ -89,6 +96,21 @@ impl Action for FilletAction {
                         }
                         ActionResult::Continue
                     }
+                    Status::SelectPolyline => {
+                        let tolerance = 5.0;
+                        let hit = ctx.entities.iter().find(|e| {
+                            matches!(&e.geometry, Geometry::Polyline(_))
+                                && !e.locked
+                                && e.geometry.contains_point(&point, tolerance)
+                        });
+                        if let Some(entity) = hit {
+                            if let Geometry::Polyline(polyline) = &entity.geometry {
+                                let filleted = polyline.fillet_all(self.radius);
+                                return ActionResult::ModifyEntity(entity.id, Geometry::Polyline(filleted));
+                            }
+                        }
+                        ActionResult::Continue
+                    }
                 }
             }
             MouseButton::Right => ActionResult::Cancel,
@@ -106,6 +128,12 @@ impl Action for FilletAction {
             self.status = Status::SetRadius;
             return Some(ActionResult::Continue);
         }
+        if cmd_upper == "P" || cmd_upper == "POLYLINE" {
+            self.first_entity = None;
+            self.first_line = None;
+            self.status = Status::SelectPolyline;
+            return Some(ActionResult::Continue);
+        }
         None
     }
 
@@ -124,23 +152,39 @@ impl Action for FilletAction {
             Status::SetRadius => "输入圆角半径或按 Enter 接受当前值",
             Status::SelectFirst => "选择第一条线",
             Status::SelectSecond => "选择第二条线",
+            Status::SelectPolyline => "选择多段线，对所有拐角倒圆角",
         }
     }
 
+    fn get_available_commands(&self) -> Vec<&str> {
+        vec!["R", "P"]
+    }
+
     fn get_preview(&self, _ctx: &ActionContext) -> Vec<PreviewGeometry> {
         Vec::new()
     }
+
+    fn take_operations(&mut self) -> Vec<Operation> {
+        std::mem::take(&mut self.pending_operations)
+    }
 }
 
 impl FilletAction {
-    fn find_line_at_point<'a>(&self, ctx: &'a ActionContext, point: Point2) -> Option<&'a zcad_core::entity::Entity> {
+    fn find_line_at_point<'a>(&self, ctx: &'a ActionContext, point: Point2) -> Option<&'a Entity> {
         let tolerance = 5.0;
         ctx.entities.iter().find(|e| {
             matches!(&e.geometry, Geometry::Line(_)) && e.geometry.contains_point(&point, tolerance)
         })
     }
 
-    fn create_fillet(&self, line1: &Line, line2: &Line, id1: EntityId, id2: EntityId) -> Option<ActionResult> {
+    fn create_fillet(
+        &mut self,
+        ctx: &ActionContext,
+        line1: &Line,
+        line2: &Line,
+        id1: EntityId,
+        id2: EntityId,
+    ) -> Option<ActionResult> {
         // 找到两条线的交点
         let intersection = self.find_intersection(line1, line2)?;
         
@@ -148,10 +192,9 @@ impl FilletAction {
             // 半径为0：只修剪到交点
             let new_line1 = self.trim_line_to_point(line1, intersection);
             let new_line2 = self.trim_line_to_point(line2, intersection);
-            return Some(ActionResult::ModifyEntities(vec![
-                (id1, Geometry::Line(new_line1)),
-                (id2, Geometry::Line(new_line2)),
-            ]));
+            let modified = vec![(id1, Geometry::Line(new_line1)), (id2, Geometry::Line(new_line2))];
+            self.pending_operations.push(compound_operation("圆角", ctx.entities, &modified, &[]));
+            return Some(ActionResult::ModifyEntities(modified));
         }
         
         // 计算两条线的方向
@@ -192,14 +235,13 @@ impl FilletAction {
         // 创建圆弧
         let arc = Arc::new(center, self.radius, start_angle, end_angle);
         
-        // 返回修改和创建操作
-        // 注意：ActionResult 需要能同时修改和创建实体
-        // 这里简化处理，只修改线段，圆弧需要单独创建
-        Some(ActionResult::ModifyEntities(vec![
-            (id1, Geometry::Line(new_line1)),
-            (id2, Geometry::Line(new_line2)),
-        ]))
-        // TODO: 需要同时创建圆弧，目前 ActionResult 不支持同时修改和创建
+        // 圆弧继承第一条线的图层和属性；修剪和新建记录为一个分组操作，一次撤销
+        let mut arc_entity = ctx.entities.iter().find(|e| e.id == id1)?.duplicate();
+        arc_entity.geometry = Geometry::Arc(arc);
+        let modified = vec![(id1, Geometry::Line(new_line1)), (id2, Geometry::Line(new_line2))];
+        let added = vec![arc_entity];
+        self.pending_operations.push(compound_operation("圆角", ctx.entities, &modified, &added));
+        Some(ActionResult::ModifyAndCreate { modified, added })
     }
 
     fn find_intersection(&self, l1: &Line, l2: &Line) -> Option<Point2> {
@@ -229,3 +271,55 @@ impl FilletAction {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::{apply_action_result, test_context};
+    use zcad_core::history::HistoryTree;
+    use zcad_file::Document;
+
+    fn line_of(document: &Document, id: &EntityId) -> Line {
+        match &document.get_entity(id).unwrap().geometry {
+            Geometry::Line(line) => line.clone(),
+            _ => panic!("应为线段"),
+        }
+    }
+
+    #[test]
+    fn test_fillet_undoes_in_one_step() {
+        let mut document = Document::new();
+        let horizontal = document.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(100.0, 0.0),
+        ))));
+        let vertical = document.add_entity(Entity::new(Geometry::Line(Line::new(
+            Point2::new(100.0, 0.0),
+            Point2::new(100.0, 100.0),
+        ))));
+        let mut history = HistoryTree::new(100);
+
+        let entities: Vec<Entity> = document.all_entities().cloned().collect();
+        let mut action = FilletAction::new();
+        action.on_value(&test_context(&entities, Point2::origin()), 10.0);
+        action.on_mouse_click(&test_context(&entities, Point2::new(50.0, 0.0)), MouseButton::Left);
+        let result = action.on_mouse_click(&test_context(&entities, Point2::new(100.0, 50.0)), MouseButton::Left);
+        assert!(matches!(result, ActionResult::ModifyAndCreate { .. }), "圆角应同时修剪线段并加入圆弧");
+        apply_action_result(&mut action, result, &mut document, &mut history);
+        assert_eq!(document.entity_count(), 3);
+
+        // 一次撤销同时恢复两条线并删除圆弧
+        let op = history.undo().unwrap().clone();
+        document.undo_operation(&op.operation_type);
+        assert!(history.undo().is_none());
+        assert_eq!(document.entity_count(), 2);
+        let restored = line_of(&document, &horizontal);
+        assert_eq!((restored.start, restored.end), (Point2::new(0.0, 0.0), Point2::new(100.0, 0.0)));
+        let restored = line_of(&document, &vertical);
+        assert_eq!((restored.start, restored.end), (Point2::new(100.0, 0.0), Point2::new(100.0, 100.0)));
+
+        // 重做再次应用全部改动
+        document.redo_operation(&history.redo().unwrap().clone().operation_type);
+        assert_eq!(document.entity_count(), 3);
+    }
+}
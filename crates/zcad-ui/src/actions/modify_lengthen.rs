@@ -0,0 +1,285 @@
+//! 拉长 Action
+//!
+//! 参考 AutoCAD 的 LENGTHEN：按增量、百分比或总长修改直线和圆弧的长度，
+//! 默认调整离拾取点较近的一端，也可两端对称调整。
+
+use crate::action::{
+    Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
+};
+use zcad_core::entity::Entity;
+use zcad_core::geometry::{Arc, Geometry, Line};
+use zcad_core::math::{Point2, EPSILON};
+
+/// 拉长方式
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LengthenMode {
+    /// 增加指定长度（负值缩短）
+    Delta(f64),
+    /// 按原长度的百分比
+    Percent(f64),
+    /// 改为指定总长
+    Total(f64),
+}
+
+impl LengthenMode {
+    /// 由原长度计算新长度
+    fn new_length(&self, length: f64) -> f64 {
+        match *self {
+            LengthenMode::Delta(delta) => length + delta,
+            LengthenMode::Percent(percent) => length * percent / 100.0,
+            LengthenMode::Total(total) => total,
+        }
+    }
+}
+
+/// 拉长状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Status {
+    /// 选择要修改的对象
+    SelectObject,
+    /// 等待输入增量
+    SetDelta,
+    /// 等待输入百分比
+    SetPercent,
+    /// 等待输入总长
+    SetTotal,
+}
+
+/// 拉长 Action
+pub struct LengthenAction {
+    status: Status,
+    /// 当前拉长方式
+    mode: LengthenMode,
+    /// 是否两端对称调整
+    both_ends: bool,
+}
+
+impl LengthenAction {
+    pub fn new() -> Self {
+        Self {
+            status: Status::SelectObject,
+            mode: LengthenMode::Delta(0.0),
+            both_ends: false,
+        }
+    }
+
+    /// 当前拉长方式
+    pub fn mode(&self) -> LengthenMode {
+        self.mode
+    }
+
+    fn find_entity_at_point<'a>(&self, ctx: &'a ActionContext, point: Point2) -> Option<&'a Entity> {
+        let tolerance = 5.0;
+        ctx.entities.iter().find(|e| {
+            !e.locked
+                && matches!(e.geometry, Geometry::Line(_) | Geometry::Arc(_))
+                && e.geometry.contains_point(&point, tolerance)
+        })
+    }
+}
+
+impl Default for LengthenAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// 按拉长方式修改直线或圆弧，调整离拾取点较近的一端（`both_ends` 时两端各调整一半）
+///
+/// 新长度不为正或圆弧扫掠角不小于整圆时返回 `None`。
+fn lengthen_geometry(
+    geometry: &Geometry,
+    mode: LengthenMode,
+    pick: Point2,
+    both_ends: bool,
+) -> Option<Geometry> {
+    match geometry {
+        Geometry::Line(line) => {
+            let length = line.length();
+            let new_length = mode.new_length(length);
+            if length < EPSILON || new_length < EPSILON {
+                return None;
+            }
+            let direction = (line.end - line.start) / length;
+            let change = new_length - length;
+            let (start, end) = if both_ends {
+                (line.start - direction * (change / 2.0), line.end + direction * (change / 2.0))
+            } else if (line.end - pick).norm() <= (line.start - pick).norm() {
+                (line.start, line.start + direction * new_length)
+            } else {
+                (line.end - direction * new_length, line.end)
+            };
+            Some(Geometry::Line(Line::new(start, end)))
+        }
+        Geometry::Arc(arc) => {
+            // 长度换算为扫掠角
+            let sweep = arc.sweep_angle();
+            let new_sweep = mode.new_length(arc.length()) / arc.radius;
+            if arc.radius < EPSILON || new_sweep < EPSILON || new_sweep >= std::f64::consts::TAU {
+                return None;
+            }
+            let change = new_sweep - sweep;
+            let (start_angle, end_angle) = if both_ends {
+                (arc.start_angle - change / 2.0, arc.end_angle + change / 2.0)
+            } else if (arc.end_point() - pick).norm() <= (arc.start_point() - pick).norm() {
+                (arc.start_angle, arc.start_angle + new_sweep)
+            } else {
+                (arc.end_angle - new_sweep, arc.end_angle)
+            };
+            Some(Geometry::Arc(Arc::new(arc.center, arc.radius, start_angle, end_angle)))
+        }
+        _ => None,
+    }
+}
+
+impl Action for LengthenAction {
+    fn action_type(&self) -> ActionType {
+        ActionType::Lengthen
+    }
+
+    fn reset(&mut self) {
+        self.status = Status::SelectObject;
+    }
+
+    fn on_mouse_move(&mut self, _ctx: &ActionContext) -> ActionResult {
+        ActionResult::Continue
+    }
+
+    fn on_mouse_click(&mut self, ctx: &ActionContext, button: MouseButton) -> ActionResult {
+        match button {
+            MouseButton::Left => {
+                let point = ctx.effective_point();
+                self.on_coordinate(ctx, point)
+            }
+            MouseButton::Right => match self.status {
+                Status::SelectObject => ActionResult::Cancel,
+                _ => {
+                    self.reset();
+                    ActionResult::Continue
+                }
+            },
+            MouseButton::Middle => ActionResult::Continue,
+        }
+    }
+
+    fn on_coordinate(&mut self, ctx: &ActionContext, coord: Point2) -> ActionResult {
+        if self.status != Status::SelectObject {
+            return ActionResult::Continue;
+        }
+        // 可连续选择多个对象
+        self.find_entity_at_point(ctx, coord)
+            .and_then(|entity| {
+                lengthen_geometry(&entity.geometry, self.mode, coord, self.both_ends)
+                    .map(|geometry| ActionResult::ModifyEntities(vec![(entity.id, geometry)]))
+            })
+            .unwrap_or(ActionResult::Continue)
+    }
+
+    fn on_command(&mut self, _ctx: &ActionContext, cmd: &str) -> Option<ActionResult> {
+        match cmd.to_uppercase().as_str() {
+            "DE" | "DELTA" => self.status = Status::SetDelta,
+            "P" | "PERCENT" => self.status = Status::SetPercent,
+            "T" | "TOTAL" => self.status = Status::SetTotal,
+            "B" | "BOTH" => self.both_ends = !self.both_ends,
+            _ => return None,
+        }
+        Some(ActionResult::Continue)
+    }
+
+    fn on_value(&mut self, _ctx: &ActionContext, value: f64) -> ActionResult {
+        let mode = match self.status {
+            Status::SetDelta => LengthenMode::Delta(value),
+            Status::SetPercent if value > EPSILON => LengthenMode::Percent(value),
+            Status::SetTotal if value > EPSILON => LengthenMode::Total(value),
+            _ => return ActionResult::Continue,
+        };
+        self.mode = mode;
+        self.status = Status::SelectObject;
+        ActionResult::Continue
+    }
+
+    fn get_prompt(&self) -> &str {
+        match self.status {
+            Status::SelectObject => "选择要修改的对象 或 [增量(DE)/百分比(P)/总长(T)/两端(B)]:",
+            Status::SetDelta => "输入长度增量:",
+            Status::SetPercent => "输入长度百分比:",
+            Status::SetTotal => "指定总长度:",
+        }
+    }
+
+    fn get_available_commands(&self) -> Vec<&str> {
+        vec!["Delta", "Percent", "Total", "Both"]
+    }
+
+    fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
+        if self.status != Status::SelectObject {
+            return Vec::new();
+        }
+        let point = ctx.effective_point();
+        self.find_entity_at_point(ctx, point)
+            .and_then(|entity| lengthen_geometry(&entity.geometry, self.mode, point, self.both_ends))
+            .map(|geometry| vec![PreviewGeometry::new(geometry)])
+            .unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::test_context;
+
+    /// 设置拉长方式后点击 `pick`，返回修改后的线段
+    fn lengthen_line(commands: &[&str], value: f64, pick: Point2) -> Line {
+        let entities = vec![Entity::new(Geometry::Line(Line::new(
+            Point2::new(0.0, 0.0),
+            Point2::new(10.0, 0.0),
+        )))];
+        let ctx = test_context(&entities, Point2::origin());
+        let mut action = LengthenAction::new();
+        for cmd in commands {
+            action.on_command(&ctx, cmd);
+        }
+        action.on_value(&ctx, value);
+        let ActionResult::ModifyEntities(modified) = action.on_coordinate(&ctx, pick) else {
+            panic!("应修改线段");
+        };
+        let Geometry::Line(line) = &modified[0].1 else {
+            panic!("应为线段");
+        };
+        line.clone()
+    }
+
+    #[test]
+    fn test_lengthen_line_delta_percent_total() {
+        // 增量：拾取点靠近终点，终点延长 5
+        let line = lengthen_line(&["DE"], 5.0, Point2::new(9.0, 0.0));
+        assert_eq!(line.start, Point2::new(0.0, 0.0));
+        assert!((line.end - Point2::new(15.0, 0.0)).norm() < 1e-9);
+
+        // 百分比：拾取点靠近起点，起点移动，长度变为一半
+        let line = lengthen_line(&["P"], 50.0, Point2::new(1.0, 0.0));
+        assert!((line.start - Point2::new(5.0, 0.0)).norm() < 1e-9);
+        assert_eq!(line.end, Point2::new(10.0, 0.0));
+
+        // 总长：两端对称调整到 20
+        let line = lengthen_line(&["B", "T"], 20.0, Point2::new(9.0, 0.0));
+        assert!((line.start - Point2::new(-5.0, 0.0)).norm() < 1e-9);
+        assert!((line.end - Point2::new(15.0, 0.0)).norm() < 1e-9);
+        assert!((line.length() - 20.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_lengthen_arc_adjusts_sweep() {
+        // 半径 10 的四分之一圆弧，终点方向增加 π*10/4 的弧长
+        let arc = Geometry::Arc(Arc::new(Point2::origin(), 10.0, 0.0, std::f64::consts::FRAC_PI_2));
+        let delta = LengthenMode::Delta(10.0 * std::f64::consts::FRAC_PI_4);
+        let Some(Geometry::Arc(lengthened)) = lengthen_geometry(&arc, delta, Point2::new(0.0, 10.0), false) else {
+            panic!("应为圆弧");
+        };
+        assert!(lengthened.start_angle.abs() < 1e-9);
+        assert!((lengthened.sweep_angle() - 3.0 * std::f64::consts::FRAC_PI_4).abs() < 1e-9);
+
+        // 缩短到非正长度时无效
+        assert!(lengthen_geometry(&arc, LengthenMode::Delta(-100.0), Point2::new(0.0, 10.0), false).is_none());
+    }
+}
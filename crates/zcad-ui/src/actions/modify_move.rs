@@ -105,8 +105,10 @@ impl Action for MoveAction {
         match self.status {
             Status::SelectObjects => {
                 // 如果有预选对象，使用它们
-                if !ctx.selected_entities.is_empty() {
-                    self.entity_ids = ctx.selected_entities.to_vec();
+                // 锁定的实体不参与编辑
+                let unlocked = ctx.unlocked_selection();
+                if !unlocked.is_empty() {
+                    self.entity_ids = unlocked;
                     self.status = Status::SetBasePoint;
                 } else {
                     return ActionResult::NeedSelection;
@@ -125,7 +127,7 @@ impl Action for MoveAction {
                     // 返回移动结果（由外部处理实际的实体移动）
                     // 这里返回一个特殊的结果类型来通知外部需要移动实体
                     let result = ActionResult::ModifyEntities(
-                        self.entity_ids.iter().map(|&id| {
+                        self.entity_ids.iter().filter(|id| !ctx.is_locked(id)).map(|&id| {
                             // 创建一个占位几何体，实际移动由外部处理
                             // 这里需要外部知道偏移量
                             (id, Geometry::Line(Line::new(base, coord)))
@@ -152,11 +154,16 @@ impl Action for MoveAction {
         }
     }
 
+    fn base_point(&self) -> Option<Point2> {
+        self.base_point
+    }
+
     fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
         let mut previews = Vec::new();
         
         if self.status == Status::SetDestination {
             if let Some(base) = self.base_point {
+                previews.extend(PreviewGeometry::base_point_marker(base, ctx.zoom));
                 let dest = ctx.effective_point();
                 // 绘制从基点到目标点的参考线
                 let line = Line::new(base, dest);
@@ -167,3 +174,85 @@ impl Action for MoveAction {
         previews
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zcad_core::entity::Entity;
+
+    #[test]
+    fn test_locked_entity_not_moved() {
+        let free = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0))));
+        let mut locked = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 1.0), Point2::new(1.0, 1.0))));
+        locked.locked = true;
+        let entities = vec![free.clone(), locked.clone()];
+        let selected = vec![free.id, locked.id];
+        let ctx = ActionContext {
+            mouse_pos: Point2::new(0.0, 0.0),
+            snap_pos: None,
+            selected_entities: &selected,
+            entities: &entities,
+            ortho_mode: false,
+            reference_point: None,
+            zoom: 1.0,
+            ctrl_pressed: false,
+        };
+
+        let mut action = MoveAction::new();
+        action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
+        action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
+        let result = action.on_coordinate(&ctx, Point2::new(5.0, 5.0));
+        let ActionResult::ModifyEntities(modified) = result else {
+            panic!("应返回移动结果");
+        };
+        let ids: Vec<EntityId> = modified.iter().map(|(id, _)| *id).collect();
+        assert_eq!(ids, vec![free.id]);
+
+        // 预选中只有锁定实体时需要重新选择
+        let selected = vec![locked.id];
+        let ctx = ActionContext { selected_entities: &selected, ..ctx };
+        let mut action = MoveAction::new();
+        assert!(matches!(action.on_coordinate(&ctx, Point2::new(0.0, 0.0)), ActionResult::NeedSelection));
+    }
+
+    #[test]
+    fn test_base_point_tracked_through_move_states() {
+        let entities = vec![Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(1.0, 0.0))))];
+        let selected = vec![entities[0].id];
+        let mut ctx = ActionContext {
+            mouse_pos: Point2::new(0.0, 0.0),
+            snap_pos: None,
+            selected_entities: &selected,
+            entities: &entities,
+            ortho_mode: false,
+            reference_point: None,
+            zoom: 2.0,
+            ctrl_pressed: false,
+        };
+
+        let mut action = MoveAction::with_selection(selected.clone());
+        assert_eq!(action.base_point(), None);
+
+        // 指定基点后预览中带有基点标记（4 像素 / 缩放 2 = 半宽 2）
+        let base = Point2::new(3.0, 4.0);
+        action.on_coordinate(&ctx, base);
+        assert_eq!(action.base_point(), Some(base));
+        ctx.mouse_pos = Point2::new(10.0, 4.0);
+        let previews = action.get_preview(&ctx);
+        let marker = previews
+            .iter()
+            .find(|p| p.is_reference && matches!(p.geometry, Geometry::Polyline(_)))
+            .map(|p| p.geometry.bounding_box())
+            .expect("应绘制基点标记");
+        assert!((marker.center() - base).norm() < 1e-9);
+        assert!((marker.width() - 4.0).abs() < 1e-9);
+
+        // 右键回到指定基点，基点清除；重新指定后完成移动也会清除
+        action.on_mouse_click(&ctx, MouseButton::Right);
+        assert_eq!(action.base_point(), None);
+        action.on_coordinate(&ctx, Point2::new(1.0, 1.0));
+        assert_eq!(action.base_point(), Some(Point2::new(1.0, 1.0)));
+        assert!(matches!(action.on_coordinate(&ctx, Point2::new(2.0, 2.0)), ActionResult::ModifyEntities(_)));
+        assert_eq!(action.base_point(), None);
+    }
+}
@@ -0,0 +1,128 @@
+//! 多段线闭合 / 打开 Action
+//!
+//! 对应 AutoCAD PEDIT 的闭合(C)/打开(O)：点选多段线，切换其闭合状态，可连续选择。
+
+use crate::action::{
+    Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
+};
+use zcad_core::entity::Entity;
+use zcad_core::geometry::Geometry;
+use zcad_core::math::Point2;
+
+/// 多段线闭合 / 打开 Action
+pub struct PolylineCloseAction {
+    /// 上一次选择的多段线不足三个顶点，无法闭合
+    rejected: bool,
+}
+
+impl PolylineCloseAction {
+    pub fn new() -> Self {
+        Self { rejected: false }
+    }
+
+    fn find_polyline_at_point<'a>(&self, ctx: &'a ActionContext, point: Point2) -> Option<&'a Entity> {
+        let tolerance = 5.0;
+        ctx.entities.iter().find(|e| {
+            !e.locked
+                && matches!(e.geometry, Geometry::Polyline(_))
+                && e.geometry.contains_point(&point, tolerance)
+        })
+    }
+}
+
+impl Default for PolylineCloseAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Action for PolylineCloseAction {
+    fn action_type(&self) -> ActionType {
+        ActionType::PolylineClose
+    }
+
+    fn reset(&mut self) {
+        self.rejected = false;
+    }
+
+    fn on_mouse_move(&mut self, _ctx: &ActionContext) -> ActionResult {
+        ActionResult::Continue
+    }
+
+    fn on_mouse_click(&mut self, ctx: &ActionContext, button: MouseButton) -> ActionResult {
+        match button {
+            MouseButton::Left => {
+                let point = ctx.effective_point();
+                self.on_coordinate(ctx, point)
+            }
+            MouseButton::Right => ActionResult::Cancel,
+            MouseButton::Middle => ActionResult::Continue,
+        }
+    }
+
+    fn on_coordinate(&mut self, ctx: &ActionContext, coord: Point2) -> ActionResult {
+        let Some(entity) = self.find_polyline_at_point(ctx, coord) else {
+            return ActionResult::Continue;
+        };
+        let Geometry::Polyline(polyline) = &entity.geometry else {
+            return ActionResult::Continue;
+        };
+        let mut toggled = polyline.clone();
+        self.rejected = !toggled.toggle_closed();
+        if self.rejected {
+            return ActionResult::Continue;
+        }
+        ActionResult::ModifyEntities(vec![(entity.id, Geometry::Polyline(toggled))])
+    }
+
+    fn on_command(&mut self, _ctx: &ActionContext, _cmd: &str) -> Option<ActionResult> {
+        None
+    }
+
+    fn get_prompt(&self) -> &str {
+        if self.rejected {
+            "多段线顶点不足，无法闭合。选择要闭合或打开的多段线:"
+        } else {
+            "选择要闭合或打开的多段线:"
+        }
+    }
+
+    fn get_preview(&self, _ctx: &ActionContext) -> Vec<PreviewGeometry> {
+        Vec::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::test_context;
+    use zcad_core::geometry::Polyline;
+
+    #[test]
+    fn test_toggle_closes_and_rejects_two_vertex_polyline() {
+        let entities = vec![
+            Entity::new(Geometry::Polyline(Polyline::from_points(
+                [Point2::new(0.0, 0.0), Point2::new(10.0, 0.0), Point2::new(10.0, 10.0)],
+                false,
+            ))),
+            Entity::new(Geometry::Polyline(Polyline::from_points(
+                [Point2::new(0.0, 50.0), Point2::new(10.0, 50.0)],
+                false,
+            ))),
+        ];
+        let ctx = test_context(&entities, Point2::origin());
+        let mut action = PolylineCloseAction::new();
+
+        let ActionResult::ModifyEntities(modified) = action.on_coordinate(&ctx, Point2::new(5.0, 0.0)) else {
+            panic!("应闭合多段线");
+        };
+        let Geometry::Polyline(closed) = &modified[0].1 else {
+            panic!("应为多段线");
+        };
+        assert!(closed.closed);
+        assert_eq!(closed.segment_count(), 3);
+
+        assert!(matches!(action.on_coordinate(&ctx, Point2::new(5.0, 50.0)), ActionResult::Continue));
+        assert!(action.get_prompt().contains("无法闭合"));
+    }
+}
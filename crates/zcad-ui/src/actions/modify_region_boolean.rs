@@ -0,0 +1,260 @@
+//! 区域布尔运算 Action
+//!
+//! 依次选择两个闭合对象，按当前运算（并集 / 差集 / 交集）生成新的闭合多段线，
+//! 原对象按设置删除或保留。
+
+use crate::action::{
+    Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
+};
+use zcad_core::boolean::{region_boolean, region_outline};
+use zcad_core::entity::{Entity, EntityId};
+use zcad_core::geometry::Geometry;
+use zcad_core::history::{operations, Operation};
+use zcad_core::math::Point2;
+use zcad_core::parametric::BooleanOp;
+
+/// 区域布尔运算状态
+#[derive(Debug, Clone, PartialEq)]
+enum Status {
+    /// 选择第一个对象（差集中被减的对象）
+    SelectFirst,
+    /// 选择第二个对象
+    SelectSecond,
+}
+
+/// 区域布尔运算 Action
+pub struct RegionBooleanAction {
+    status: Status,
+    /// 当前运算
+    operation: BooleanOp,
+    /// 是否删除原对象
+    delete_originals: bool,
+    first_entity: Option<EntityId>,
+    /// 待提交到文档历史的操作
+    pending_operations: Vec<Operation>,
+}
+
+impl RegionBooleanAction {
+    pub fn new() -> Self {
+        Self {
+            status: Status::SelectFirst,
+            operation: BooleanOp::Union,
+            delete_originals: true,
+            first_entity: None,
+            pending_operations: Vec::new(),
+        }
+    }
+
+    /// 当前运算
+    pub fn operation(&self) -> BooleanOp {
+        self.operation
+    }
+
+    /// 拾取点击位置处的闭合对象
+    fn find_region_at_point<'a>(&self, ctx: &'a ActionContext, point: Point2) -> Option<&'a Entity> {
+        let tolerance = 5.0;
+        ctx.entities.iter().find(|e| {
+            !e.locked
+                && region_outline(&e.geometry).is_some()
+                && e.geometry.contains_point(&point, tolerance)
+        })
+    }
+
+    /// 对两个对象执行运算，结果实体继承第一个对象的图层和属性
+    fn apply(&mut self, first: &Entity, second: &Entity) -> Option<ActionResult> {
+        let polylines = region_boolean(&first.geometry, &second.geometry, self.operation)?;
+        let added: Vec<Entity> = polylines
+            .into_iter()
+            .map(|polyline| {
+                let mut entity = first.duplicate();
+                entity.geometry = Geometry::Polyline(polyline);
+                entity
+            })
+            .collect();
+        let removed = if self.delete_originals {
+            vec![first.clone(), second.clone()]
+        } else {
+            Vec::new()
+        };
+
+        self.pending_operations.push(operations::boolean_operation(
+            self.operation,
+            first.id,
+            second.id,
+            added.clone(),
+            removed.clone(),
+            format!("Region {:?}", self.operation),
+        ));
+        Some(ActionResult::ReplaceEntities {
+            removed: removed.iter().map(|e| e.id).collect(),
+            added,
+        })
+    }
+}
+
+impl Default for RegionBooleanAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Action for RegionBooleanAction {
+    fn action_type(&self) -> ActionType {
+        ActionType::RegionBoolean
+    }
+
+    fn reset(&mut self) {
+        self.status = Status::SelectFirst;
+        self.first_entity = None;
+    }
+
+    fn on_mouse_move(&mut self, _ctx: &ActionContext) -> ActionResult {
+        ActionResult::Continue
+    }
+
+    fn on_mouse_click(&mut self, ctx: &ActionContext, button: MouseButton) -> ActionResult {
+        match button {
+            MouseButton::Left => {
+                let point = ctx.effective_point();
+                self.on_coordinate(ctx, point)
+            }
+            MouseButton::Right => match self.status {
+                Status::SelectFirst => ActionResult::Cancel,
+                Status::SelectSecond => {
+                    self.reset();
+                    ActionResult::Continue
+                }
+            },
+            MouseButton::Middle => ActionResult::Continue,
+        }
+    }
+
+    fn on_coordinate(&mut self, ctx: &ActionContext, coord: Point2) -> ActionResult {
+        let Some(entity) = self.find_region_at_point(ctx, coord) else {
+            return ActionResult::Continue;
+        };
+        match self.status {
+            Status::SelectFirst => {
+                self.first_entity = Some(entity.id);
+                self.status = Status::SelectSecond;
+                ActionResult::Continue
+            }
+            Status::SelectSecond => {
+                let first = self
+                    .first_entity
+                    .and_then(|id| ctx.entities.iter().find(|e| e.id == id));
+                let Some(first) = first else {
+                    self.reset();
+                    return ActionResult::Continue;
+                };
+                if first.id == entity.id {
+                    return ActionResult::Continue;
+                }
+                let result = self.apply(first, entity);
+                self.reset();
+                result.unwrap_or(ActionResult::Continue)
+            }
+        }
+    }
+
+    fn on_command(&mut self, _ctx: &ActionContext, cmd: &str) -> Option<ActionResult> {
+        match cmd.to_uppercase().as_str() {
+            "U" | "UNION" => self.operation = BooleanOp::Union,
+            "S" | "SUBTRACT" => self.operation = BooleanOp::Difference,
+            "I" | "INTERSECT" => self.operation = BooleanOp::Intersection,
+            "D" | "DELETE" => self.delete_originals = true,
+            "K" | "KEEP" => self.delete_originals = false,
+            _ => return None,
+        }
+        Some(ActionResult::Continue)
+    }
+
+    fn get_prompt(&self) -> &str {
+        match (&self.status, self.operation) {
+            (Status::SelectFirst, BooleanOp::Difference) => "选择要从中减去的对象:",
+            (Status::SelectFirst, _) => "选择第一个闭合对象:",
+            (Status::SelectSecond, BooleanOp::Difference) => "选择要减去的对象:",
+            (Status::SelectSecond, _) => "选择第二个闭合对象:",
+        }
+    }
+
+    fn get_available_commands(&self) -> Vec<&str> {
+        vec!["Union", "Subtract", "Intersect", "Delete", "Keep"]
+    }
+
+    fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
+        // 高亮已选中的第一个对象
+        self.first_entity
+            .and_then(|id| ctx.entities.iter().find(|e| e.id == id))
+            .map(|e| vec![PreviewGeometry::reference(e.geometry.clone())])
+            .unwrap_or_default()
+    }
+
+    fn take_operations(&mut self) -> Vec<Operation> {
+        std::mem::take(&mut self.pending_operations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::{apply_action_result, test_context};
+    use zcad_core::geometry::Polyline;
+    use zcad_core::history::{HistoryTree, OperationType};
+    use zcad_file::Document;
+
+    fn rectangle(x: f64, y: f64, width: f64, height: f64) -> Geometry {
+        Geometry::Polyline(Polyline::from_points(
+            [
+                Point2::new(x, y),
+                Point2::new(x + width, y),
+                Point2::new(x + width, y + height),
+                Point2::new(x, y + height),
+            ],
+            true,
+        ))
+    }
+
+    #[test]
+    fn test_subtract_replaces_originals_with_difference() {
+        let mut document = Document::new();
+        let plate = document.add_entity(Entity::new(rectangle(0.0, 0.0, 100.0, 60.0)));
+        let notch = document.add_entity(Entity::new(rectangle(80.0, 40.0, 40.0, 40.0)));
+        let mut history = HistoryTree::new(100);
+
+        let entities: Vec<Entity> = document.all_entities().cloned().collect();
+        let ctx = test_context(&entities, Point2::origin());
+        let mut action = RegionBooleanAction::new();
+        action.on_command(&ctx, "S");
+        assert_eq!(action.operation(), BooleanOp::Difference);
+
+        // 点击两个矩形的边
+        assert!(matches!(action.on_coordinate(&ctx, Point2::new(50.0, 0.0)), ActionResult::Continue));
+        let result = action.on_coordinate(&ctx, Point2::new(120.0, 60.0));
+        let ActionResult::ReplaceEntities { removed, .. } = &result else {
+            panic!("第二次选择后应生成布尔运算结果");
+        };
+        assert_eq!(removed.len(), 2);
+        assert!(apply_action_result(&mut action, result, &mut document, &mut history));
+
+        // 100×60 减去重叠的 20×20 角部
+        assert!(document.get_entity(&plate).is_none() && document.get_entity(&notch).is_none());
+        let areas: Vec<f64> = document.all_entities().filter_map(|e| e.geometry.area()).collect();
+        assert_eq!(areas.len(), 1);
+        assert!((areas[0] - (6000.0 - 400.0)).abs() < 1e-9);
+
+        let op = history.undo().unwrap().clone();
+        assert!(history.undo().is_none());
+        let OperationType::BooleanOperation { operation, entity1, previous_entities, .. } = &op.operation_type else {
+            panic!("应记录为 BooleanOperation");
+        };
+        assert_eq!(*operation, BooleanOp::Difference);
+        assert_eq!(*entity1, plate);
+        assert_eq!(previous_entities.len(), 2);
+
+        // 撤销恢复两个原对象并删除结果
+        document.undo_operation(&op.operation_type);
+        assert_eq!(document.entity_count(), 2);
+        assert!(document.get_entity(&plate).is_some() && document.get_entity(&notch).is_some());
+    }
+}
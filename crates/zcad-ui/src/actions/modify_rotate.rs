@@ -6,8 +6,9 @@ use crate::action::{
     Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
 };
 use zcad_core::entity::EntityId;
-use zcad_core::geometry::{Geometry, Line};
-use zcad_core::math::Point2;
+use zcad_core::geometry::{Arc, Geometry, Line};
+use zcad_core::math::{Point2, EPSILON};
+use zcad_core::transform::Transform2D;
 
 /// 旋转状态
 #[derive(Debug, Clone, PartialEq)]
@@ -50,6 +51,49 @@ impl RotateAction {
     fn angle_to_point(&self, center: Point2, point: Point2) -> f64 {
         (point.y - center.y).atan2(point.x - center.x)
     }
+
+    /// 参考角度模式下的辅助线：基点到光标的当前角度线，锁定参考点后再加上基点到参考点的参考线
+    fn guide_lines(&self, cursor: Point2) -> Vec<Line> {
+        let Some(center) = self.center else {
+            return Vec::new();
+        };
+        let mut lines = vec![Line::new(center, cursor)];
+        if let Some(ref_point) = self.reference_point {
+            lines.push(Line::new(center, ref_point));
+        }
+        lines
+    }
+
+    /// 从参考角度扫到当前角度的圆弧，取两者中较短的辅助线一半为半径，按较小的转角绘制
+    fn angle_arc(&self, cursor: Point2) -> Option<Arc> {
+        let (center, ref_point) = (self.center?, self.reference_point?);
+        let radius = (cursor - center).norm().min((ref_point - center).norm()) / 2.0;
+        if radius < EPSILON {
+            return None;
+        }
+        let ref_angle = self.angle_to_point(center, ref_point);
+        let pi = std::f64::consts::PI;
+        let sweep = (self.angle_to_point(center, cursor) - ref_angle + pi).rem_euclid(std::f64::consts::TAU) - pi;
+        if sweep.abs() < EPSILON {
+            return None;
+        }
+        let (start, end) = if sweep > 0.0 {
+            (ref_angle, ref_angle + sweep)
+        } else {
+            (ref_angle + sweep, ref_angle)
+        };
+        Some(Arc::new(center, radius, start, end))
+    }
+
+    /// 按给定角度旋转后的选中实体几何（用于预览）
+    fn rotated_selection(&self, ctx: &ActionContext, center: Point2, angle: f64) -> Vec<Geometry> {
+        let transform = Transform2D::rotation_around(center, angle);
+        ctx.entities
+            .iter()
+            .filter(|e| self.entity_ids.contains(&e.id) && !e.locked)
+            .map(|e| transform.transform_geometry(&e.geometry))
+            .collect()
+    }
 }
 
 impl Default for RotateAction {
@@ -96,8 +140,10 @@ impl Action for RotateAction {
     fn on_coordinate(&mut self, ctx: &ActionContext, coord: Point2) -> ActionResult {
         match self.status {
             Status::SelectObjects => {
-                if !ctx.selected_entities.is_empty() {
-                    self.entity_ids = ctx.selected_entities.to_vec();
+                // 锁定的实体不参与编辑
+                let unlocked = ctx.unlocked_selection();
+                if !unlocked.is_empty() {
+                    self.entity_ids = unlocked;
                     self.status = Status::SetCenter;
                 } else {
                     return ActionResult::NeedSelection;
@@ -122,7 +168,7 @@ impl Action for RotateAction {
                     
                     // 返回旋转结果
                     let result = ActionResult::ModifyEntities(
-                        self.entity_ids.iter().map(|&id| {
+                        self.entity_ids.iter().filter(|id| !ctx.is_locked(id)).map(|&id| {
                             // 使用 Line 来传递中心点和目标点（外部需要解析）
                             (id, Geometry::Line(Line::new(center, coord)))
                         }).collect()
@@ -148,7 +194,7 @@ impl Action for RotateAction {
         }
     }
 
-    fn on_value(&mut self, _ctx: &ActionContext, value: f64) -> ActionResult {
+    fn on_value(&mut self, ctx: &ActionContext, value: f64) -> ActionResult {
         // 直接输入角度值（度数）
         if self.status == Status::SetReferenceAngle || self.status == Status::SetTargetAngle {
             if let Some(center) = self.center {
@@ -156,7 +202,7 @@ impl Action for RotateAction {
                 
                 // 返回旋转结果
                 let result = ActionResult::ModifyEntities(
-                    self.entity_ids.iter().map(|&id| {
+                    self.entity_ids.iter().filter(|id| !ctx.is_locked(id)).map(|&id| {
                         (id, Geometry::Line(Line::new(center, center)))
                     }).collect()
                 );
@@ -185,23 +231,119 @@ impl Action for RotateAction {
         }
     }
 
+    fn base_point(&self) -> Option<Point2> {
+        self.center
+    }
+
     fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
         let mut previews = Vec::new();
         
         if let Some(center) = self.center {
+            previews.extend(PreviewGeometry::base_point_marker(center, ctx.zoom));
             let current = ctx.effective_point();
-            
-            // 从中心到当前点的参考线
-            let line = Line::new(center, current);
-            previews.push(PreviewGeometry::reference(Geometry::Line(line)));
-            
-            // 如果有参考点，也画一条线
+
+            // 当前角度线、锁定的参考线以及两者之间的转角
+            previews.extend(
+                self.guide_lines(current)
+                    .into_iter()
+                    .map(|line| PreviewGeometry::reference(Geometry::Line(line))),
+            );
+            if let Some(arc) = self.angle_arc(current) {
+                previews.push(PreviewGeometry::reference(Geometry::Arc(arc)));
+            }
+
             if let Some(ref_point) = self.reference_point {
-                let ref_line = Line::new(center, ref_point);
-                previews.push(PreviewGeometry::reference(Geometry::Line(ref_line)));
+                // 按当前光标角度旋转后的选中对象
+                if self.status == Status::SetTargetAngle {
+                    let angle = self.angle_to_point(center, current) - self.angle_to_point(center, ref_point);
+                    previews.extend(
+                        self.rotated_selection(ctx, center, angle).into_iter().map(PreviewGeometry::new),
+                    );
+                }
             }
         }
         
         previews
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zcad_core::entity::Entity;
+
+    #[test]
+    fn test_preview_rotates_selection_by_cursor_angle() {
+        let line = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0))));
+        let entities = vec![line.clone()];
+        let selected = vec![line.id];
+        let mut ctx = ActionContext {
+            mouse_pos: Point2::new(0.0, 0.0),
+            snap_pos: None,
+            selected_entities: &selected,
+            entities: &entities,
+            ortho_mode: false,
+            reference_point: None,
+            zoom: 1.0,
+            ctrl_pressed: false,
+        };
+
+        let mut action = RotateAction::new();
+        action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
+        action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
+        action.on_coordinate(&ctx, Point2::new(10.0, 0.0));
+
+        // 光标位于正上方：旋转 90°
+        ctx.mouse_pos = Point2::new(0.0, 5.0);
+        let previews = action.get_preview(&ctx);
+        let rotated: Vec<&Line> = previews
+            .iter()
+            .filter(|p| !p.is_reference)
+            .filter_map(|p| match &p.geometry {
+                Geometry::Line(line) => Some(line),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(rotated.len(), 1);
+        assert!((rotated[0].start - Point2::new(0.0, 0.0)).norm() < 1e-9);
+        assert!((rotated[0].end - Point2::new(0.0, 10.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_guide_lines_follow_base_and_cursor() {
+        let entities = vec![Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0))))];
+        let selected = vec![entities[0].id];
+        let ctx = ActionContext {
+            mouse_pos: Point2::new(0.0, 0.0),
+            snap_pos: None,
+            selected_entities: &selected,
+            entities: &entities,
+            ortho_mode: false,
+            reference_point: None,
+            zoom: 1.0,
+            ctrl_pressed: false,
+        };
+        let base = Point2::new(1.0, 1.0);
+        let cursor = Point2::new(1.0, 5.0);
+
+        let mut action = RotateAction::new();
+        action.on_coordinate(&ctx, base);
+        action.on_coordinate(&ctx, base);
+        // 指定参考角度前只有当前角度线
+        let lines = action.guide_lines(cursor);
+        assert_eq!(lines.len(), 1);
+        assert_eq!((lines[0].start, lines[0].end), (base, cursor));
+
+        action.on_coordinate(&ctx, Point2::new(7.0, 1.0));
+        let lines = action.guide_lines(cursor);
+        assert_eq!(lines.len(), 2);
+        assert_eq!((lines[0].start, lines[0].end), (base, cursor));
+        assert_eq!((lines[1].start, lines[1].end), (base, Point2::new(7.0, 1.0)));
+
+        // 转角弧从参考方向逆时针扫过 90°，半径为较短辅助线的一半
+        let arc = action.angle_arc(cursor).unwrap();
+        assert!((arc.radius - 2.0).abs() < 1e-9);
+        assert!(arc.start_angle.abs() < 1e-9);
+        assert!((arc.sweep_angle() - std::f64::consts::FRAC_PI_2).abs() < 1e-9);
+    }
+}
@@ -8,6 +8,7 @@ use crate::action::{
 use zcad_core::entity::EntityId;
 use zcad_core::geometry::{Geometry, Line};
 use zcad_core::math::Point2;
+use zcad_core::transform::Transform2D;
 
 /// 缩放状态
 #[derive(Debug, Clone, PartialEq)]
@@ -45,6 +46,16 @@ impl ScaleAction {
             copy_mode: false,
         }
     }
+
+    /// 按给定比例缩放后的选中实体几何（用于预览）
+    fn scaled_selection(&self, ctx: &ActionContext, center: Point2, factor: f64) -> Vec<Geometry> {
+        let transform = Transform2D::scale_around(center, factor, factor);
+        ctx.entities
+            .iter()
+            .filter(|e| self.entity_ids.contains(&e.id) && !e.locked)
+            .map(|e| transform.transform_geometry(&e.geometry))
+            .collect()
+    }
 }
 
 impl Default for ScaleAction {
@@ -91,8 +102,10 @@ impl Action for ScaleAction {
     fn on_coordinate(&mut self, ctx: &ActionContext, coord: Point2) -> ActionResult {
         match self.status {
             Status::SelectObjects => {
-                if !ctx.selected_entities.is_empty() {
-                    self.entity_ids = ctx.selected_entities.to_vec();
+                // 锁定的实体不参与编辑
+                let unlocked = ctx.unlocked_selection();
+                if !unlocked.is_empty() {
+                    self.entity_ids = unlocked;
                     self.status = Status::SetCenter;
                 } else {
                     return ActionResult::NeedSelection;
@@ -119,7 +132,7 @@ impl Action for ScaleAction {
                         
                         // 返回缩放结果
                         let result = ActionResult::ModifyEntities(
-                            self.entity_ids.iter().map(|&id| {
+                            self.entity_ids.iter().filter(|id| !ctx.is_locked(id)).map(|&id| {
                                 (id, Geometry::Line(Line::new(center, coord)))
                             }).collect()
                         );
@@ -145,7 +158,7 @@ impl Action for ScaleAction {
         }
     }
 
-    fn on_value(&mut self, _ctx: &ActionContext, value: f64) -> ActionResult {
+    fn on_value(&mut self, ctx: &ActionContext, value: f64) -> ActionResult {
         // 直接输入缩放比例
         if self.status == Status::SetReferencePoint || self.status == Status::SetTargetPoint {
             if let Some(center) = self.center {
@@ -154,7 +167,7 @@ impl Action for ScaleAction {
                     
                     // 返回缩放结果
                     let result = ActionResult::ModifyEntities(
-                        self.entity_ids.iter().map(|&id| {
+                        self.entity_ids.iter().filter(|id| !ctx.is_locked(id)).map(|&id| {
                             (id, Geometry::Line(Line::new(center, center)))
                         }).collect()
                     );
@@ -184,10 +197,15 @@ impl Action for ScaleAction {
         }
     }
 
+    fn base_point(&self) -> Option<Point2> {
+        self.center
+    }
+
     fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
         let mut previews = Vec::new();
         
         if let Some(center) = self.center {
+            previews.extend(PreviewGeometry::base_point_marker(center, ctx.zoom));
             let current = ctx.effective_point();
             
             // 从中心到当前点的参考线
@@ -198,9 +216,62 @@ impl Action for ScaleAction {
             if let Some(ref_point) = self.reference_point {
                 let ref_line = Line::new(center, ref_point);
                 previews.push(PreviewGeometry::reference(Geometry::Line(ref_line)));
+
+                // 按当前光标距离缩放后的选中对象
+                let ref_dist = (ref_point - center).norm();
+                let target_dist = (current - center).norm();
+                if self.status == Status::SetTargetPoint && ref_dist > 1e-6 && target_dist > 1e-6 {
+                    previews.extend(
+                        self.scaled_selection(ctx, center, target_dist / ref_dist)
+                            .into_iter()
+                            .map(PreviewGeometry::new),
+                    );
+                }
             }
         }
         
         previews
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zcad_core::entity::Entity;
+
+    #[test]
+    fn test_preview_scales_selection_by_cursor_distance() {
+        let line = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(10.0, 0.0))));
+        let entities = vec![line.clone()];
+        let selected = vec![line.id];
+        let mut ctx = ActionContext {
+            mouse_pos: Point2::new(0.0, 0.0),
+            snap_pos: None,
+            selected_entities: &selected,
+            entities: &entities,
+            ortho_mode: false,
+            reference_point: None,
+            zoom: 1.0,
+            ctrl_pressed: false,
+        };
+
+        let mut action = ScaleAction::new();
+        action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
+        action.on_coordinate(&ctx, Point2::new(0.0, 0.0));
+        action.on_coordinate(&ctx, Point2::new(5.0, 0.0));
+
+        // 光标距离为参考距离的 2 倍
+        ctx.mouse_pos = Point2::new(0.0, 10.0);
+        let previews = action.get_preview(&ctx);
+        let scaled: Vec<&Line> = previews
+            .iter()
+            .filter(|p| !p.is_reference)
+            .filter_map(|p| match &p.geometry {
+                Geometry::Line(line) => Some(line),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(scaled.len(), 1);
+        assert!((scaled[0].end - Point2::new(20.0, 0.0)).norm() < 1e-9);
+    }
+}
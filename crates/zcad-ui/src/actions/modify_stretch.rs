@@ -0,0 +1,260 @@
+//! 拉伸 Action
+//!
+//! 参考 AutoCAD 的 STRETCH：用交叉窗口框选，窗口内的端点和顶点随位移移动，
+//! 窗口外的保持不动；完全在窗口内的对象整体平移。
+
+use crate::action::{
+    Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry,
+};
+use zcad_core::entity::EntityId;
+use zcad_core::geometry::{Geometry, Line, Polyline};
+use zcad_core::grip::stretch_geometry;
+use zcad_core::math::{BoundingBox2, Point2};
+
+/// 拉伸状态
+#[derive(Debug, Clone, PartialEq)]
+enum Status {
+    /// 等待指定交叉窗口第一角点
+    SetFirstCorner,
+    /// 等待指定交叉窗口对角点
+    SetSecondCorner,
+    /// 等待指定基点
+    SetBasePoint,
+    /// 等待指定第二点（位移终点）
+    SetDestination,
+}
+
+/// 拉伸 Action
+pub struct StretchAction {
+    status: Status,
+    /// 交叉窗口第一角点
+    first_corner: Option<Point2>,
+    /// 交叉窗口
+    window: Option<BoundingBox2>,
+    /// 基点
+    base_point: Option<Point2>,
+}
+
+impl StretchAction {
+    pub fn new() -> Self {
+        Self {
+            status: Status::SetFirstCorner,
+            first_corner: None,
+            window: None,
+            base_point: None,
+        }
+    }
+
+    /// 按当前窗口和位移计算所有受影响实体的新几何
+    fn stretched(&self, ctx: &ActionContext, destination: Point2) -> Vec<(EntityId, Geometry)> {
+        let (Some(window), Some(base)) = (self.window, self.base_point) else {
+            return Vec::new();
+        };
+        let offset = destination - base;
+        ctx.entities
+            .iter()
+            .filter(|e| !e.locked && window.intersects(&e.bounding_box()))
+            .filter_map(|e| stretch_geometry(&e.geometry, &window, offset).map(|g| (e.id, g)))
+            .collect()
+    }
+}
+
+impl Default for StretchAction {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Action for StretchAction {
+    fn action_type(&self) -> ActionType {
+        ActionType::Stretch
+    }
+
+    fn reset(&mut self) {
+        self.status = Status::SetFirstCorner;
+        self.first_corner = None;
+        self.window = None;
+        self.base_point = None;
+    }
+
+    fn on_mouse_move(&mut self, _ctx: &ActionContext) -> ActionResult {
+        ActionResult::Continue
+    }
+
+    fn on_mouse_click(&mut self, ctx: &ActionContext, button: MouseButton) -> ActionResult {
+        match button {
+            MouseButton::Left => {
+                let point = ctx.effective_point();
+                self.on_coordinate(ctx, point)
+            }
+            MouseButton::Right => match self.status {
+                Status::SetFirstCorner => ActionResult::Cancel,
+                _ => {
+                    self.reset();
+                    ActionResult::Continue
+                }
+            },
+            MouseButton::Middle => ActionResult::Continue,
+        }
+    }
+
+    fn on_coordinate(&mut self, ctx: &ActionContext, coord: Point2) -> ActionResult {
+        match self.status {
+            Status::SetFirstCorner => {
+                self.first_corner = Some(coord);
+                self.status = Status::SetSecondCorner;
+                ActionResult::Continue
+            }
+            Status::SetSecondCorner => {
+                if let Some(first) = self.first_corner {
+                    let window = BoundingBox2::from_points([first, coord]);
+                    if window.width() > 0.0 && window.height() > 0.0 {
+                        self.window = Some(window);
+                        self.status = Status::SetBasePoint;
+                    }
+                }
+                ActionResult::Continue
+            }
+            Status::SetBasePoint => {
+                self.base_point = Some(coord);
+                self.status = Status::SetDestination;
+                ActionResult::Continue
+            }
+            Status::SetDestination => {
+                let modified = self.stretched(ctx, coord);
+                self.reset();
+                if modified.is_empty() {
+                    ActionResult::Continue
+                } else {
+                    ActionResult::ModifyEntities(modified)
+                }
+            }
+        }
+    }
+
+    fn on_command(&mut self, _ctx: &ActionContext, _cmd: &str) -> Option<ActionResult> {
+        None
+    }
+
+    fn get_prompt(&self) -> &str {
+        match self.status {
+            Status::SetFirstCorner => "以交叉窗口选择要拉伸的对象，指定第一个角点:",
+            Status::SetSecondCorner => "指定对角点:",
+            Status::SetBasePoint => "指定基点:",
+            Status::SetDestination => "指定第二个点:",
+        }
+    }
+
+    fn get_preview(&self, ctx: &ActionContext) -> Vec<PreviewGeometry> {
+        let mut previews = Vec::new();
+        let point = ctx.effective_point();
+
+        match self.status {
+            Status::SetSecondCorner => {
+                if let Some(first) = self.first_corner {
+                    let window = Polyline::from_points(
+                        [first, Point2::new(point.x, first.y), point, Point2::new(first.x, point.y)],
+                        true,
+                    );
+                    previews.push(PreviewGeometry::reference(Geometry::Polyline(window)));
+                }
+            }
+            Status::SetDestination => {
+                if let Some(base) = self.base_point {
+                    previews.push(PreviewGeometry::reference(Geometry::Line(Line::new(base, point))));
+                }
+                previews.extend(self.stretched(ctx, point).into_iter().map(|(_, g)| PreviewGeometry::new(g)));
+            }
+            _ => {}
+        }
+
+        previews
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::test_context;
+    use zcad_core::entity::Entity;
+
+    fn stretch(entities: &[Entity], corners: [Point2; 2], from: Point2, to: Point2) -> ActionResult {
+        let ctx = test_context(entities, Point2::origin());
+        let mut action = StretchAction::new();
+        action.on_coordinate(&ctx, corners[0]);
+        action.on_coordinate(&ctx, corners[1]);
+        action.on_coordinate(&ctx, from);
+        action.on_coordinate(&ctx, to)
+    }
+
+    #[test]
+    fn test_stretch_rectangle_right_edge() {
+        let rect = Polyline::from_points(
+            [
+                Point2::new(0.0, 0.0),
+                Point2::new(100.0, 0.0),
+                Point2::new(100.0, 50.0),
+                Point2::new(0.0, 50.0),
+            ],
+            true,
+        );
+        let entities = vec![Entity::new(Geometry::Polyline(rect))];
+
+        // 交叉窗口只框住右侧两个顶点，向右拉伸 20
+        let result = stretch(
+            &entities,
+            [Point2::new(90.0, -10.0), Point2::new(110.0, 60.0)],
+            Point2::new(100.0, 0.0),
+            Point2::new(120.0, 0.0),
+        );
+        let ActionResult::ModifyEntities(modified) = result else {
+            panic!("拉伸后应修改实体");
+        };
+        assert_eq!(modified.len(), 1);
+        let Geometry::Polyline(stretched) = &modified[0].1 else {
+            panic!("应为多段线");
+        };
+        let points: Vec<Point2> = stretched.vertices.iter().map(|v| v.point).collect();
+        assert_eq!(
+            points,
+            vec![
+                Point2::new(0.0, 0.0),
+                Point2::new(120.0, 0.0),
+                Point2::new(120.0, 50.0),
+                Point2::new(0.0, 50.0),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_stretch_moves_inside_lines_and_stretches_crossing_lines() {
+        let inside = Entity::new(Geometry::Line(Line::new(Point2::new(95.0, 10.0), Point2::new(98.0, 20.0))));
+        let crossing = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(100.0, 0.0))));
+        let outside = Entity::new(Geometry::Line(Line::new(Point2::new(0.0, 30.0), Point2::new(50.0, 30.0))));
+        let entities = vec![inside.clone(), crossing.clone(), outside];
+
+        let result = stretch(
+            &entities,
+            [Point2::new(90.0, -10.0), Point2::new(110.0, 60.0)],
+            Point2::new(0.0, 0.0),
+            Point2::new(0.0, 5.0),
+        );
+        let ActionResult::ModifyEntities(modified) = result else {
+            panic!("拉伸后应修改实体");
+        };
+        assert_eq!(modified.len(), 2);
+        for (id, geometry) in modified {
+            let Geometry::Line(line) = geometry else {
+                panic!("应为线段");
+            };
+            if id == inside.id {
+                assert_eq!(line.start, Point2::new(95.0, 15.0));
+                assert_eq!(line.end, Point2::new(98.0, 25.0));
+            } else {
+                assert_eq!(id, crossing.id);
+                assert_eq!(line.start, Point2::new(0.0, 0.0));
+                assert_eq!(line.end, Point2::new(100.0, 5.0));
+            }
+        }
+    }
+}
@@ -9,6 +9,8 @@ use zcad_core::entity::EntityId;
 use zcad_core::geometry::{Arc, Geometry, Line};
 use zcad_core::math::{Point2, EPSILON};
 
+use super::cutting_edge::{line_edge_params, point_at};
+
 /// 修剪状态
 #[derive(Debug, Clone, PartialEq)]
 enum Status {
@@ -71,8 +73,11 @@ impl Action for TrimAction {
                     Status::SelectToTrim => {
                         // 选择要修剪的对象并执行修剪
                         if let Some(entity) = self.find_entity_at_point(ctx, point) {
-                            if let Some(trimmed) = self.trim_entity(ctx, &entity.geometry, point) {
-                                return ActionResult::ModifyEntities(vec![(entity.id, trimmed)]);
+                            if let Some(mut pieces) = self.trim_entity(ctx, &entity.geometry, point) {
+                                if pieces.len() == 1 {
+                                    return ActionResult::ModifyEntities(vec![(entity.id, pieces.remove(0))]);
+                                }
+                                return ActionResult::ReplaceEntity(entity.id, pieces);
                             }
                         }
                         ActionResult::Continue
@@ -124,8 +129,8 @@ impl TrimAction {
         ctx.entities.iter().find(|e| e.geometry.contains_point(&point, tolerance))
     }
 
-    /// 修剪实体
-    fn trim_entity(&self, ctx: &ActionContext, geometry: &Geometry, click_point: Point2) -> Option<Geometry> {
+    /// 修剪实体，返回修剪后保留的部分
+    fn trim_entity(&self, ctx: &ActionContext, geometry: &Geometry, click_point: Point2) -> Option<Vec<Geometry>> {
         match geometry {
             Geometry::Line(line) => self.trim_line(ctx, line, click_point),
             Geometry::Arc(arc) => self.trim_arc(ctx, arc, click_point),
@@ -134,143 +139,95 @@ impl TrimAction {
     }
 
     /// 修剪线段
-    fn trim_line(&self, ctx: &ActionContext, line: &Line, click_point: Point2) -> Option<Geometry> {
-        // 找到所有与边界的交点
-        let mut intersections = Vec::new();
-        
-        for boundary_id in &self.boundary_entities {
-            if let Some(boundary) = ctx.entities.iter().find(|e| e.id == *boundary_id) {
-                let points = self.find_intersections(&Geometry::Line(line.clone()), &boundary.geometry);
-                intersections.extend(points);
-            }
-        }
-        
-        if intersections.is_empty() {
-            return None;
-        }
-        
-        // 按到起点的参数排序
+    ///
+    /// 边界可以是直线、圆、圆弧或多段线；删除点击位置两侧最近交点之间的部分。
+    fn trim_line(&self, ctx: &ActionContext, line: &Line, click_point: Point2) -> Option<Vec<Geometry>> {
         let line_vec = line.end - line.start;
-        let line_len = line_vec.norm();
-        
-        let mut params: Vec<f64> = intersections
-            .iter()
-            .map(|p| {
-                let v = *p - line.start;
-                v.dot(&line_vec) / (line_len * line_len)
-            })
-            .filter(|&t| t > EPSILON && t < 1.0 - EPSILON)
-            .collect();
-        
-        if params.is_empty() {
+        let line_len_sq = line_vec.norm_squared();
+        if line_len_sq < EPSILON {
             return None;
         }
-        
-        params.sort_by(|a, b| a.partial_cmp(b).unwrap());
-        
-        // 确定点击位置的参数
-        let click_vec = click_point - line.start;
-        let click_t = click_vec.dot(&line_vec) / (line_len * line_len);
-        
-        // 找到包含点击位置的段
-        let mut segment_start = 0.0;
-        let mut segment_end = 1.0;
-        
-        for &t in &params {
-            if click_t < t {
-                segment_end = t;
-                break;
+
+        // 收集线段内部的所有交点参数
+        let mut params = Vec::new();
+        for boundary_id in &self.boundary_entities {
+            if let Some(boundary) = ctx.entities.iter().find(|e| e.id == *boundary_id) {
+                params.extend(
+                    line_edge_params(line, &boundary.geometry)
+                        .into_iter()
+                        .filter(|&t| t > EPSILON && t < 1.0 - EPSILON),
+                );
             }
-            segment_start = t;
         }
-        
-        // 删除点击的段，保留剩余部分
-        // 这里简化处理：返回离点击点较远的那一段
-        let start_dist = (click_point - line.start).norm();
-        let end_dist = (click_point - line.end).norm();
-        
-        if start_dist < end_dist && segment_start > EPSILON {
-            // 保留终点侧
-            let new_start = line.start + line_vec * segment_start;
-            Some(Geometry::Line(Line::new(new_start, line.end)))
-        } else if segment_end < 1.0 - EPSILON {
-            // 保留起点侧
-            let new_end = line.start + line_vec * segment_end;
-            Some(Geometry::Line(Line::new(line.start, new_end)))
-        } else {
-            None
+
+        // 点击位置两侧最近的交点
+        let click_t = (click_point - line.start).dot(&line_vec) / line_len_sq;
+        let before = params.iter().copied().filter(|&t| t <= click_t).reduce(f64::max);
+        let after = params.iter().copied().filter(|&t| t > click_t).reduce(f64::min);
+
+        match (before, after) {
+            // 删除起点一侧
+            (None, Some(t)) => Some(vec![Geometry::Line(Line::new(point_at(line, t), line.end))]),
+            // 删除终点一侧
+            (Some(t), None) => Some(vec![Geometry::Line(Line::new(line.start, point_at(line, t)))]),
+            // 删除中间段，一分为二
+            (Some(t1), Some(t2)) => Some(vec![
+                Geometry::Line(Line::new(line.start, point_at(line, t1))),
+                Geometry::Line(Line::new(point_at(line, t2), line.end)),
+            ]),
+            (None, None) => None,
         }
     }
 
     /// 修剪圆弧
-    fn trim_arc(&self, _ctx: &ActionContext, arc: &Arc, _click_point: Point2) -> Option<Geometry> {
+    fn trim_arc(&self, _ctx: &ActionContext, arc: &Arc, _click_point: Point2) -> Option<Vec<Geometry>> {
         // 简化实现：暂不支持圆弧修剪
-        Some(Geometry::Arc(arc.clone()))
-    }
-
-    /// 计算两个几何体的交点
-    fn find_intersections(&self, geom1: &Geometry, geom2: &Geometry) -> Vec<Point2> {
-        match (geom1, geom2) {
-            (Geometry::Line(l1), Geometry::Line(l2)) => {
-                self.line_line_intersection(l1, l2).into_iter().collect()
-            }
-            (Geometry::Line(line), Geometry::Circle(circle)) => {
-                self.line_circle_intersection(line, circle)
-            }
-            _ => vec![],
-        }
+        Some(vec![Geometry::Arc(arc.clone())])
     }
+}
 
-    /// 线段-线段交点
-    fn line_line_intersection(&self, l1: &Line, l2: &Line) -> Option<Point2> {
-        let d1 = l1.end - l1.start;
-        let d2 = l2.end - l2.start;
-
-        let cross = d1.x * d2.y - d1.y * d2.x;
-        
-        if cross.abs() < EPSILON {
-            return None;
-        }
-
-        let d = l2.start - l1.start;
-        let t1 = (d.x * d2.y - d.y * d2.x) / cross;
-        let t2 = (d.x * d1.y - d.y * d1.x) / cross;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::action::test_context;
+    use zcad_core::entity::Entity;
+    use zcad_core::geometry::Circle;
 
-        if t1 >= 0.0 && t1 <= 1.0 && t2 >= 0.0 && t2 <= 1.0 {
-            Some(l1.start + d1 * t1)
-        } else {
-            None
+    fn as_line(geometry: &Geometry) -> &Line {
+        match geometry {
+            Geometry::Line(line) => line,
+            _ => panic!("应为线段"),
         }
     }
 
-    /// 线段-圆交点
-    fn line_circle_intersection(&self, line: &Line, circle: &zcad_core::geometry::Circle) -> Vec<Point2> {
-        let d = line.end - line.start;
-        let f = line.start - circle.center;
-
-        let a = d.dot(&d);
-        let b = 2.0 * f.dot(&d);
-        let c = f.dot(&f) - circle.radius * circle.radius;
-
-        let discriminant = b * b - 4.0 * a * c;
-
-        if discriminant < 0.0 {
-            return vec![];
-        }
-
-        let mut intersections = Vec::new();
-        let sqrt_disc = discriminant.sqrt();
-        
-        let t1 = (-b - sqrt_disc) / (2.0 * a);
-        let t2 = (-b + sqrt_disc) / (2.0 * a);
-
-        if t1 >= 0.0 && t1 <= 1.0 {
-            intersections.push(line.start + d * t1);
-        }
-        if t2 >= 0.0 && t2 <= 1.0 && (t2 - t1).abs() > EPSILON {
-            intersections.push(line.start + d * t2);
-        }
-
-        intersections
+    #[test]
+    fn test_trim_line_against_circle() {
+        // 线段与圆有两个交点 (5, 0) 和 (15, 0)
+        let circle = Entity::new(Geometry::Circle(Circle::new(Point2::new(10.0, 0.0), 5.0)));
+        let entities = vec![circle.clone()];
+        let ctx = test_context(&entities, Point2::origin());
+        let mut action = TrimAction::new();
+        action.boundary_entities.push(circle.id);
+
+        let line = Geometry::Line(Line::new(Point2::new(0.0, 0.0), Point2::new(20.0, 0.0)));
+
+        // 点击终点一侧：保留到最近的交点 (15, 0)，而不是 (5, 0)
+        let pieces = action.trim_entity(&ctx, &line, Point2::new(18.0, 0.0)).unwrap();
+        assert_eq!(pieces.len(), 1);
+        let trimmed = as_line(&pieces[0]);
+        assert!((trimmed.start - Point2::new(0.0, 0.0)).norm() < 1e-9);
+        assert!((trimmed.end - Point2::new(15.0, 0.0)).norm() < 1e-9);
+
+        // 点击起点一侧
+        let pieces = action.trim_entity(&ctx, &line, Point2::new(2.0, 0.0)).unwrap();
+        let trimmed = as_line(&pieces[0]);
+        assert!((trimmed.start - Point2::new(5.0, 0.0)).norm() < 1e-9);
+        assert!((trimmed.end - Point2::new(20.0, 0.0)).norm() < 1e-9);
+
+        // 点击圆内：删除中间段，一分为二
+        let pieces = action.trim_entity(&ctx, &line, Point2::new(10.0, 0.0)).unwrap();
+        assert_eq!(pieces.len(), 2);
+        assert!((as_line(&pieces[0]).end - Point2::new(5.0, 0.0)).norm() < 1e-9);
+        assert!((as_line(&pieces[1]).start - Point2::new(15.0, 0.0)).norm() < 1e-9);
     }
 }
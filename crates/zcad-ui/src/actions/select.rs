@@ -5,6 +5,34 @@ use crate::action::{
 };
 use zcad_core::math::Point2;
 
+/// 框选模式
+///
+/// 从左向右拖动为窗口选择（蓝色，仅选中完全位于框内的对象），
+/// 从右向左拖动为交叉选择（绿色，选中与框相交的对象）。
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BoxSelectMode {
+    /// 窗口选择
+    Window,
+    /// 交叉选择
+    Crossing,
+}
+
+impl BoxSelectMode {
+    /// 根据拖动起点和终点的 x 坐标顺序确定框选模式
+    pub fn from_drag(start: Point2, end: Point2) -> Self {
+        if end.x < start.x {
+            BoxSelectMode::Crossing
+        } else {
+            BoxSelectMode::Window
+        }
+    }
+
+    /// 是否为交叉选择
+    pub fn is_crossing(&self) -> bool {
+        *self == BoxSelectMode::Crossing
+    }
+}
+
 /// 选择状态
 #[derive(Debug, Clone, Copy, PartialEq)]
 enum Status {
@@ -38,6 +66,11 @@ impl SelectAction {
     pub fn is_box_selecting(&self) -> bool {
         matches!(self.status, Status::BoxSelect { .. })
     }
+
+    /// 以当前光标位置为对角点时的框选模式
+    pub fn box_select_mode(&self, current: Point2) -> Option<BoxSelectMode> {
+        self.box_start.map(|start| BoxSelectMode::from_drag(start, current))
+    }
 }
 
 impl Default for SelectAction {
@@ -109,3 +142,24 @@ impl Action for SelectAction {
         vec![]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_box_select_mode_from_drag_direction() {
+        let start = Point2::new(10.0, 10.0);
+        // 从左向右：窗口选择，与 y 方向无关
+        assert_eq!(BoxSelectMode::from_drag(start, Point2::new(20.0, 0.0)), BoxSelectMode::Window);
+        assert_eq!(BoxSelectMode::from_drag(start, Point2::new(20.0, 30.0)), BoxSelectMode::Window);
+        // 从右向左：交叉选择
+        assert_eq!(BoxSelectMode::from_drag(start, Point2::new(0.0, 0.0)), BoxSelectMode::Crossing);
+        assert!(BoxSelectMode::from_drag(start, Point2::new(5.0, 30.0)).is_crossing());
+
+        let mut action = SelectAction::new();
+        assert_eq!(action.box_select_mode(Point2::new(0.0, 0.0)), None);
+        action.box_start = Some(start);
+        assert_eq!(action.box_select_mode(Point2::new(0.0, 0.0)), Some(BoxSelectMode::Crossing));
+    }
+}
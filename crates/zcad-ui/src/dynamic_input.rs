@@ -0,0 +1,160 @@
+//! 动态输入
+//!
+//! 绘图时在光标旁直接输入长度和角度：按数字键聚焦长度框，Tab 切换到角度框，
+//! 回车后按命令行极坐标格式 `@长度<角度` 解析出下一点。
+//! 长度框内输入 `x,y`（如矩形的 `宽,高`）时按相对直角坐标 `@x,y` 解析。
+
+use zcad_core::input_parser::{InputParser, ParseError};
+use zcad_core::math::Point2;
+
+/// 动态输入框
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DynamicField {
+    /// 长度
+    #[default]
+    Length,
+    /// 角度（度）
+    Angle,
+}
+
+/// 动态输入状态
+#[derive(Debug, Clone, Default)]
+pub struct DynamicInput {
+    /// 长度框内容
+    pub length: String,
+    /// 角度框内容（度）
+    pub angle: String,
+    /// 当前聚焦的输入框
+    pub focused: DynamicField,
+    active: bool,
+}
+
+impl DynamicInput {
+    /// 是否正在输入
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// 输入一个字符，返回是否被动态输入接收
+    ///
+    /// 未激活时只有数字和小数点会激活输入并聚焦长度框；
+    /// 逗号只能输入到长度框，用于 `x,y` 相对坐标。
+    pub fn push_char(&mut self, c: char) -> bool {
+        if !self.active {
+            if !(c.is_ascii_digit() || c == '.') {
+                return false;
+            }
+            self.active = true;
+            self.focused = DynamicField::Length;
+        }
+
+        let comma = c == ',' && self.focused == DynamicField::Length;
+        if !(c.is_ascii_digit() || c == '.' || c == '-' || comma) {
+            return false;
+        }
+        self.focused_text_mut().push(c);
+        true
+    }
+
+    /// 删除当前输入框的最后一个字符
+    pub fn backspace(&mut self) {
+        self.focused_text_mut().pop();
+    }
+
+    /// 切换输入框（Tab）
+    pub fn switch_field(&mut self) {
+        self.active = true;
+        self.focused = match self.focused {
+            DynamicField::Length => DynamicField::Angle,
+            DynamicField::Angle => DynamicField::Length,
+        };
+    }
+
+    /// 清空并退出动态输入
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// 转换为命令行格式 `@长度<角度`
+    ///
+    /// 角度框为空时使用 `default_angle`（度），长度框为空时返回 `None`。
+    /// 长度框为 `x,y` 时忽略角度，返回 `@x,y`。
+    pub fn to_command_string(&self, default_angle: f64) -> Option<String> {
+        let length = self.length.trim();
+        if length.is_empty() {
+            return None;
+        }
+        if length.contains(',') {
+            return Some(format!("@{}", length));
+        }
+        let angle = self.angle.trim();
+        if angle.is_empty() {
+            Some(format!("@{}<{}", length, default_angle))
+        } else {
+            Some(format!("@{}<{}", length, angle))
+        }
+    }
+
+    /// 提交输入，计算从参考点出发的下一点
+    ///
+    /// 未输入角度时沿参考点指向光标的方向。成功后清空输入。
+    pub fn commit(&mut self, reference: Point2, cursor: Point2) -> Result<Point2, ParseError> {
+        let dir = cursor - reference;
+        let default_angle = if dir.norm() > 0.0 {
+            dir.y.atan2(dir.x).to_degrees()
+        } else {
+            0.0
+        };
+        let input = self
+            .to_command_string(default_angle)
+            .ok_or_else(|| ParseError::MissingValue("Length required".to_string()))?;
+        let point = InputParser::parse_point(&input, Some(reference))?;
+        self.clear();
+        Ok(point)
+    }
+
+    fn focused_text_mut(&mut self) -> &mut String {
+        match self.focused {
+            DynamicField::Length => &mut self.length,
+            DynamicField::Angle => &mut self.angle,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_length_and_angle() {
+        let mut input = DynamicInput::default();
+        assert!(!input.push_char('x'));
+        for c in "50".chars() {
+            assert!(input.push_char(c));
+        }
+        input.switch_field();
+        for c in "90".chars() {
+            input.push_char(c);
+        }
+        assert_eq!(input.to_command_string(0.0).as_deref(), Some("@50<90"));
+
+        let point = input.commit(Point2::new(10.0, 20.0), Point2::new(100.0, 0.0)).unwrap();
+        assert!((point - Point2::new(10.0, 70.0)).norm() < 1e-9);
+        assert!(!input.is_active());
+    }
+
+    #[test]
+    fn test_commit_length_uses_cursor_direction() {
+        let mut input = DynamicInput::default();
+        for c in "10".chars() {
+            input.push_char(c);
+        }
+        let reference = Point2::new(1.0, 1.0);
+        let point = input.commit(reference, Point2::new(4.0, 5.0)).unwrap();
+        assert!((point - Point2::new(7.0, 9.0)).norm() < 1e-9);
+
+        // 没有长度时无法提交
+        let mut empty = DynamicInput::default();
+        assert!(empty.commit(reference, Point2::new(4.0, 5.0)).is_err());
+    }
+}
@@ -0,0 +1,289 @@
+//! 快捷键映射
+//!
+//! 将按键 + 修饰键组合统一映射到快捷操作。
+//! 修饰键精确匹配：Ctrl+C 只会触发复制，不会同时触发 C（圆）。
+
+use crate::state::DrawingTool;
+use egui::Key;
+use std::collections::HashMap;
+
+/// 修饰键状态
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct KeyModifiers {
+    /// Ctrl（macOS 上为 Cmd）
+    pub command: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl KeyModifiers {
+    /// 无修饰键
+    pub const NONE: Self = Self {
+        command: false,
+        shift: false,
+        alt: false,
+    };
+
+    /// Ctrl
+    pub const COMMAND: Self = Self {
+        command: true,
+        shift: false,
+        alt: false,
+    };
+
+    /// Ctrl+Shift
+    pub const COMMAND_SHIFT: Self = Self {
+        command: true,
+        shift: true,
+        alt: false,
+    };
+}
+
+impl From<egui::Modifiers> for KeyModifiers {
+    fn from(modifiers: egui::Modifiers) -> Self {
+        Self {
+            command: modifiers.command,
+            shift: modifiers.shift,
+            alt: modifiers.alt,
+        }
+    }
+}
+
+/// 按键绑定（按键 + 修饰键）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub key: Key,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// 不带修饰键的按键
+    pub fn plain(key: Key) -> Self {
+        Self {
+            key,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    /// Ctrl + 按键
+    pub fn command(key: Key) -> Self {
+        Self {
+            key,
+            modifiers: KeyModifiers::COMMAND,
+        }
+    }
+
+    /// Ctrl+Shift + 按键
+    pub fn command_shift(key: Key) -> Self {
+        Self {
+            key,
+            modifiers: KeyModifiers::COMMAND_SHIFT,
+        }
+    }
+
+    /// 显示文本（如 "Ctrl+Shift+S"）
+    pub fn label(&self) -> String {
+        let mut parts = Vec::new();
+        if self.modifiers.command {
+            parts.push("Ctrl");
+        }
+        if self.modifiers.shift {
+            parts.push("Shift");
+        }
+        if self.modifiers.alt {
+            parts.push("Alt");
+        }
+        parts.push(self.key.name());
+        parts.join("+")
+    }
+}
+
+/// 快捷操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShortcutAction {
+    NewDocument,
+    Open,
+    Save,
+    SaveAs,
+    Undo,
+    Redo,
+    Copy,
+    Paste,
+    Delete,
+    Cancel,
+    SetTool(DrawingTool),
+    ZoomExtents,
+    ToggleGrid,
+    ToggleSnap,
+    ToggleOrtho,
+}
+
+/// 快捷键映射表
+///
+/// 可通过 `bind` / `unbind` 重新映射
+#[derive(Debug, Clone)]
+pub struct Keymap {
+    bindings: HashMap<KeyBinding, ShortcutAction>,
+}
+
+impl Keymap {
+    /// 创建空的映射表
+    pub fn empty() -> Self {
+        Self {
+            bindings: HashMap::new(),
+        }
+    }
+
+    /// 绑定快捷键（覆盖已有的同一组合）
+    pub fn bind(&mut self, binding: KeyBinding, action: ShortcutAction) {
+        self.bindings.insert(binding, action);
+    }
+
+    /// 解除绑定
+    pub fn unbind(&mut self, binding: &KeyBinding) -> Option<ShortcutAction> {
+        self.bindings.remove(binding)
+    }
+
+    /// 解析按键：修饰键必须完全一致
+    pub fn resolve(&self, key: Key, modifiers: KeyModifiers) -> Option<ShortcutAction> {
+        self.bindings.get(&KeyBinding { key, modifiers }).copied()
+    }
+
+    /// 查找操作对应的快捷键
+    pub fn bindings_for(&self, action: ShortcutAction) -> Vec<KeyBinding> {
+        self.bindings
+            .iter()
+            .filter(|(_, a)| **a == action)
+            .map(|(b, _)| *b)
+            .collect()
+    }
+
+    /// 收集本帧按下的所有快捷操作
+    ///
+    /// egui-winit 会把 Ctrl+C/X/V 转成剪贴板事件而不发送按键事件，
+    /// 这里按对应的按键组合解析。注意只有系统剪贴板中有文本时才会产生粘贴事件，
+    /// 宿主在复制时需同时向系统剪贴板写入文本；其他后端发送的原始按键同样可以解析。
+    pub fn pressed_actions(&self, input: &egui::InputState) -> Vec<ShortcutAction> {
+        input
+            .events
+            .iter()
+            .filter_map(|event| match event {
+                egui::Event::Key {
+                    key,
+                    pressed: true,
+                    modifiers,
+                    ..
+                } => self.resolve(*key, (*modifiers).into()),
+                egui::Event::Copy => self.resolve(Key::C, KeyModifiers::COMMAND),
+                egui::Event::Cut => self.resolve(Key::X, KeyModifiers::COMMAND),
+                egui::Event::Paste(_) => self.resolve(Key::V, KeyModifiers::COMMAND),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl Default for Keymap {
+    fn default() -> Self {
+        let mut keymap = Self::empty();
+
+        // 文件
+        keymap.bind(KeyBinding::command(Key::N), ShortcutAction::NewDocument);
+        keymap.bind(KeyBinding::command(Key::O), ShortcutAction::Open);
+        keymap.bind(KeyBinding::command(Key::S), ShortcutAction::Save);
+        keymap.bind(KeyBinding::command_shift(Key::S), ShortcutAction::SaveAs);
+
+        // 编辑
+        keymap.bind(KeyBinding::command(Key::Z), ShortcutAction::Undo);
+        keymap.bind(KeyBinding::command(Key::Y), ShortcutAction::Redo);
+        keymap.bind(KeyBinding::command_shift(Key::Z), ShortcutAction::Redo);
+        keymap.bind(KeyBinding::command(Key::C), ShortcutAction::Copy);
+        keymap.bind(KeyBinding::command(Key::V), ShortcutAction::Paste);
+        keymap.bind(KeyBinding::plain(Key::Delete), ShortcutAction::Delete);
+        keymap.bind(KeyBinding::plain(Key::Backspace), ShortcutAction::Delete);
+        keymap.bind(KeyBinding::plain(Key::Escape), ShortcutAction::Cancel);
+
+        // 绘图工具
+        keymap.bind(KeyBinding::plain(Key::L), ShortcutAction::SetTool(DrawingTool::Line));
+        keymap.bind(KeyBinding::plain(Key::C), ShortcutAction::SetTool(DrawingTool::Circle));
+        keymap.bind(KeyBinding::plain(Key::R), ShortcutAction::SetTool(DrawingTool::Rectangle));
+        keymap.bind(KeyBinding::plain(Key::A), ShortcutAction::SetTool(DrawingTool::Arc));
+        keymap.bind(KeyBinding::plain(Key::P), ShortcutAction::SetTool(DrawingTool::Polyline));
+
+        // 视图
+        keymap.bind(KeyBinding::plain(Key::Z), ShortcutAction::ZoomExtents);
+        keymap.bind(KeyBinding::plain(Key::G), ShortcutAction::ToggleGrid);
+        keymap.bind(KeyBinding::plain(Key::F3), ShortcutAction::ToggleSnap);
+        keymap.bind(KeyBinding::plain(Key::F8), ShortcutAction::ToggleOrtho);
+        // 空格保留给按住平移，不绑定快捷操作
+
+        keymap
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_modifiers_resolve_exactly() {
+        let keymap = Keymap::default();
+
+        assert_eq!(
+            keymap.resolve(Key::C, KeyModifiers::NONE),
+            Some(ShortcutAction::SetTool(DrawingTool::Circle))
+        );
+        assert_eq!(keymap.resolve(Key::C, KeyModifiers::COMMAND), Some(ShortcutAction::Copy));
+        assert_eq!(keymap.resolve(Key::Z, KeyModifiers::COMMAND), Some(ShortcutAction::Undo));
+        assert_eq!(keymap.resolve(Key::Z, KeyModifiers::COMMAND_SHIFT), Some(ShortcutAction::Redo));
+        // 未绑定的组合不会退化为无修饰键的绑定
+        assert_eq!(keymap.resolve(Key::C, KeyModifiers::COMMAND_SHIFT), None);
+    }
+
+    #[test]
+    fn test_remap() {
+        let mut keymap = Keymap::default();
+        keymap.bind(KeyBinding::plain(Key::C), ShortcutAction::SetTool(DrawingTool::Arc));
+        assert_eq!(
+            keymap.resolve(Key::C, KeyModifiers::NONE),
+            Some(ShortcutAction::SetTool(DrawingTool::Arc))
+        );
+
+        assert_eq!(keymap.unbind(&KeyBinding::command(Key::C)), Some(ShortcutAction::Copy));
+        assert_eq!(keymap.resolve(Key::C, KeyModifiers::COMMAND), None);
+        assert_eq!(KeyBinding::command_shift(Key::S).label(), "Ctrl+Shift+S");
+    }
+
+    #[test]
+    fn test_raw_command_v_triggers_paste() {
+        let keymap = Keymap::default();
+        let mut input = egui::InputState::default();
+        // 没有粘贴事件，只有原始按键
+        input.events = vec![egui::Event::Key {
+            key: Key::V,
+            physical_key: None,
+            pressed: true,
+            repeat: false,
+            modifiers: egui::Modifiers::COMMAND,
+        }];
+
+        assert_eq!(keymap.pressed_actions(&input), vec![ShortcutAction::Paste]);
+    }
+
+    #[test]
+    fn test_clipboard_events_trigger_shortcuts() {
+        let keymap = Keymap::default();
+        let mut input = egui::InputState::default();
+        input.events = vec![
+            egui::Event::Copy,
+            egui::Event::Paste("text".into()),
+            egui::Event::Cut,
+        ];
+
+        // Cut 未绑定，不产生操作
+        assert_eq!(
+            keymap.pressed_actions(&input),
+            vec![ShortcutAction::Copy, ShortcutAction::Paste]
+        );
+    }
+}
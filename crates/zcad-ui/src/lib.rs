@@ -6,14 +6,23 @@ pub mod action;
 pub mod actions;
 pub mod command_line;
 pub mod command_registry;
+pub mod dynamic_input;
+pub mod keymap;
 pub mod layers_panel;
 pub mod main_menu;
 pub mod properties_panel;
+pub mod scene_cache;
+pub mod settings;
 pub mod state;
+pub mod theme;
 pub mod toolbar;
 
-pub use action::{Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry};
+pub use action::{apply_action_result, dispatch_input, Action, ActionContext, ActionResult, ActionType, MouseButton, PreviewGeometry};
 pub use actions::create_action;
 pub use command_registry::CommandRegistry;
-pub use state::{DrawingTool, EditState, SnapMode, SnapState, UiState};
+pub use dynamic_input::{DynamicField, DynamicInput};
+pub use keymap::{KeyBinding, KeyModifiers, Keymap, ShortcutAction};
+pub use settings::Settings;
+pub use state::{DrawLayer, DrawingTool, EditState, SnapMode, SnapState, UiState};
+pub use theme::{Theme, ThemePreset};
 
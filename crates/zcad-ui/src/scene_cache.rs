@@ -0,0 +1,105 @@
+//! 静态场景缓存
+//!
+//! 大图纸每帧逐段重新生成线段开销很大。未变化的实体图形缓存为 egui 形状列表，
+//! 只有文档修订号、相机或显示设置变化时才重建；预览、选中高亮等动态内容每帧单独绘制。
+
+use crate::theme::Theme;
+use zcad_core::math::Point2;
+
+/// 决定缓存是否仍然有效的状态
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SceneKey {
+    /// 文档修订号
+    pub revision: u64,
+    /// 相机中心（世界坐标）
+    pub center: Point2,
+    /// 缩放级别（像素/单位）
+    pub zoom: f64,
+    /// 绘图区屏幕矩形
+    pub rect: egui::Rect,
+    /// 当前配色
+    pub theme: Theme,
+    /// 其他影响显示的设置（图层颜色与可见性、点样式、弧线精度等）的摘要
+    pub style: u64,
+}
+
+/// 静态场景缓存
+#[derive(Default)]
+pub struct SceneCache {
+    key: Option<SceneKey>,
+    shapes: Vec<egui::Shape>,
+    rebuilds: u64,
+}
+
+impl SceneCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 缓存的形状；键与上次不同时先调用 `build` 重建
+    pub fn shapes(&mut self, key: SceneKey, build: impl FnOnce() -> Vec<egui::Shape>) -> &[egui::Shape] {
+        if self.key != Some(key) {
+            self.shapes = build();
+            self.key = Some(key);
+            self.rebuilds += 1;
+        }
+        &self.shapes
+    }
+
+    /// 强制下次重建（例如切换了文档）
+    pub fn invalidate(&mut self) {
+        self.key = None;
+    }
+
+    /// 累计重建次数
+    pub fn rebuild_count(&self) -> u64 {
+        self.rebuilds
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(revision: u64, zoom: f64) -> SceneKey {
+        SceneKey {
+            revision,
+            center: Point2::origin(),
+            zoom,
+            rect: egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(800.0, 600.0)),
+            theme: Theme::default(),
+            style: 0,
+        }
+    }
+
+    fn segment() -> Vec<egui::Shape> {
+        vec![egui::Shape::line_segment(
+            [egui::pos2(0.0, 0.0), egui::pos2(10.0, 10.0)],
+            egui::Stroke::new(1.0, egui::Color32::WHITE),
+        )]
+    }
+
+    #[test]
+    fn test_rebuild_only_on_revision_or_camera_change() {
+        let mut cache = SceneCache::new();
+        assert_eq!(cache.shapes(key(1, 1.0), segment).len(), 1);
+        assert_eq!(cache.rebuild_count(), 1);
+
+        // 单纯重绘不重建
+        for _ in 0..3 {
+            cache.shapes(key(1, 1.0), || panic!("重绘不应重建缓存"));
+        }
+        assert_eq!(cache.rebuild_count(), 1);
+
+        // 文档修订号变化、相机缩放变化都会重建
+        cache.shapes(key(2, 1.0), segment);
+        assert_eq!(cache.rebuild_count(), 2);
+        cache.shapes(key(2, 2.0), Vec::new);
+        assert_eq!(cache.rebuild_count(), 3);
+        assert!(cache.shapes(key(2, 2.0), segment).is_empty());
+
+        cache.invalidate();
+        cache.shapes(key(2, 2.0), segment);
+        assert_eq!(cache.rebuild_count(), 4);
+    }
+}
@@ -0,0 +1,183 @@
+//! 用户设置
+//!
+//! 对象捕捉、网格、单位显示和界面偏好保存为 JSON 配置文件，启动时加载、退出时保存。
+//! 文件不存在、缺少字段或无法解析时使用默认值。
+
+use crate::state::UiState;
+use crate::theme::{Theme, ThemePreset};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use zcad_core::snap::{GridMode, SnapConfig};
+use zcad_core::units::DisplayFormat;
+
+/// 跨会话保存的用户设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Settings {
+    /// 对象捕捉配置（启用的类型、容差、捕捉步长、极轴追踪等）
+    pub snap: SnapConfig,
+    /// 是否启用对象捕捉
+    pub snap_enabled: bool,
+    /// 是否显示网格
+    pub show_grid: bool,
+    /// 显示网格的基准间距
+    pub grid_spacing: f64,
+    /// 网格模式
+    pub grid_mode: GridMode,
+    /// 长度/角度的显示格式
+    pub display_format: DisplayFormat,
+    /// 正交模式
+    pub ortho_mode: bool,
+    /// 圆/圆弧细分的最大弦高误差（屏幕像素）
+    pub arc_chord_error: f64,
+    /// 模型空间配色主题
+    pub theme: ThemePreset,
+    /// 布局空间是否使用白色图纸背景
+    pub white_paper: bool,
+    /// 标注文字背景遮罩
+    pub dim_text_mask: bool,
+    /// 线条抗锯齿
+    pub antialiasing: bool,
+    /// 是否显示图层面板
+    pub show_layers_panel: bool,
+    /// 是否显示属性面板
+    pub show_properties_panel: bool,
+}
+
+impl Settings {
+    /// 从界面状态提取设置
+    pub fn from_state(state: &UiState) -> Self {
+        Self {
+            snap: state.snap_state.config().clone(),
+            snap_enabled: state.snap_state.enabled,
+            show_grid: state.show_grid,
+            grid_spacing: state.grid_spacing,
+            grid_mode: state.grid_mode,
+            display_format: state.display_format,
+            ortho_mode: state.ortho_mode,
+            arc_chord_error: state.arc_chord_error,
+            theme: state.theme.preset,
+            white_paper: state.white_paper,
+            dim_text_mask: state.dim_text_mask,
+            antialiasing: state.antialiasing,
+            show_layers_panel: state.show_layers_panel,
+            show_properties_panel: state.show_properties_panel,
+        }
+    }
+
+    /// 把设置应用到界面状态
+    pub fn apply(&self, state: &mut UiState) {
+        *state.snap_state.config_mut() = self.snap.clone();
+        state.snap_state.enabled = self.snap_enabled;
+        state.show_grid = self.show_grid;
+        state.grid_spacing = self.grid_spacing;
+        state.set_grid_mode(self.grid_mode);
+        // UCS 不随配置保存，保留当前值并重新同步到捕捉配置
+        state.set_ucs(state.ucs);
+        state.display_format = self.display_format;
+        state.ortho_mode = self.ortho_mode;
+        state.arc_chord_error = self.arc_chord_error;
+        state.theme = Theme::from_preset(self.theme);
+        state.white_paper = self.white_paper;
+        state.dim_text_mask = self.dim_text_mask;
+        state.antialiasing = self.antialiasing;
+        state.show_layers_panel = self.show_layers_panel;
+        state.show_properties_panel = self.show_properties_panel;
+    }
+
+    /// 默认配置文件路径：`$XDG_CONFIG_HOME`、`%APPDATA%` 或 `~/.config` 下的 `zcad/settings.json`
+    pub fn default_path() -> Option<PathBuf> {
+        let base = std::env::var_os("XDG_CONFIG_HOME")
+            .or_else(|| std::env::var_os("APPDATA"))
+            .map(PathBuf::from)
+            .or_else(|| std::env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))?;
+        Some(base.join("zcad").join("settings.json"))
+    }
+
+    /// 从文件加载设置
+    pub fn load(path: &Path) -> Result<Self, std::io::Error> {
+        let content = std::fs::read_to_string(path)?;
+        serde_json::from_str(&content).map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// 从文件加载设置，文件不存在或无法解析时返回默认设置
+    pub fn load_or_default(path: &Path) -> Self {
+        match Self::load(path) {
+            Ok(settings) => settings,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Self::default(),
+            Err(e) => {
+                tracing::warn!("Failed to load settings from {}: {}", path.display(), e);
+                Self::default()
+            }
+        }
+    }
+
+    /// 保存设置到文件（必要时创建所在目录）
+    pub fn save(&self, path: &Path) -> Result<(), std::io::Error> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let content = serde_json::to_string_pretty(self)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+        std::fs::write(path, content)
+    }
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self::from_state(&UiState::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zcad_core::snap::SnapType;
+    use zcad_core::units::{AngleFormat, Unit};
+
+    #[test]
+    fn test_settings_roundtrip() {
+        let mut state = UiState::default();
+        state.snap_state.toggle_snap_type(SnapType::Nearest);
+        state.snap_state.config_mut().polar_tracking = true;
+        state.set_snap_step(2.5);
+        state.set_grid_mode(GridMode::Isometric);
+        state.grid_spacing = 25.0;
+        state.display_format.unit = Unit::Inch;
+        state.display_format.angle_format = AngleFormat::Radians;
+        state.theme = Theme::from_preset(ThemePreset::Light);
+        state.antialiasing = false;
+        state.ortho_mode = true;
+
+        let path = std::env::temp_dir().join("zcad_test_settings").join("settings.json");
+        Settings::from_state(&state).save(&path).unwrap();
+        let mut restored = UiState::default();
+        Settings::load_or_default(&path).apply(&mut restored);
+
+        assert!(restored.snap_state.is_snap_type_enabled(SnapType::Nearest));
+        assert!(restored.snap_state.config().polar_tracking);
+        assert_eq!(restored.snap_step(), 2.5);
+        assert_eq!(restored.grid_mode, GridMode::Isometric);
+        assert_eq!(restored.snap_state.config().grid_mode, GridMode::Isometric);
+        assert_eq!(restored.grid_spacing, 25.0);
+        assert_eq!(restored.display_format, state.display_format);
+        assert_eq!(restored.theme, Theme::from_preset(ThemePreset::Light));
+        assert!(!restored.antialiasing);
+        assert!(restored.ortho_mode);
+    }
+
+    #[test]
+    fn test_missing_or_partial_settings_use_defaults() {
+        let missing = std::env::temp_dir().join("zcad_missing_settings").join("settings.json");
+        let settings = Settings::load_or_default(&missing);
+        assert!(settings.snap_enabled);
+        assert_eq!(settings.grid_spacing, UiState::default().grid_spacing);
+
+        // 只写了部分字段（含部分捕捉配置）时，其余取默认值
+        let partial: Settings = serde_json::from_str(r#"{"show_grid": false, "snap": {"tolerance": 4.0}}"#).unwrap();
+        assert!(!partial.show_grid);
+        assert_eq!(partial.snap.tolerance, 4.0);
+        assert_eq!(partial.snap.grid_spacing, SnapConfig::default().grid_spacing);
+        assert!(partial.antialiasing);
+    }
+}
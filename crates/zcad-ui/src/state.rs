@@ -1,9 +1,15 @@
 //! UI状态管理
 
-use zcad_core::entity::EntityId;
+use crate::dynamic_input::DynamicInput;
+use crate::keymap::{KeyModifiers, Keymap};
+use crate::theme::{Theme, ThemePreset};
+use zcad_core::entity::{Entity, EntityId};
 use zcad_core::layout::{LayoutManager, LayoutId, ViewportId, SpaceType};
+use zcad_core::input_parser::{InputParser, ParseError};
 use zcad_core::math::Point2;
-use zcad_core::snap::{SnapConfig, SnapEngine, SnapPoint, SnapType};
+use zcad_core::snap::{GridMode, MidBetweenPoints, SnapConfig, SnapEngine, SnapPoint, SnapType};
+use zcad_core::ucs::Ucs;
+use zcad_core::units::DisplayFormat;
 
 /// 当前绘图工具
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -95,6 +101,8 @@ pub struct SnapState {
     pub current_snap: Option<SnapPoint>,
     /// 是否启用捕捉
     pub enabled: bool,
+    /// 进行中的两点之间的中点（M2P）拾取
+    mid_between: Option<MidBetweenPoints>,
 }
 
 impl SnapState {
@@ -103,9 +111,29 @@ impl SnapState {
             engine: SnapEngine::default(),
             current_snap: None,
             enabled: true,
+            mid_between: None,
         }
     }
 
+    /// 开始两点之间的中点（M2P）拾取，接下来的两次点击合成一个点
+    pub fn start_mid_between(&mut self) {
+        self.mid_between = Some(MidBetweenPoints::new());
+    }
+
+    /// 是否正在进行 M2P 拾取
+    pub fn is_mid_between_active(&self) -> bool {
+        self.mid_between.is_some()
+    }
+
+    /// 将一次点击交给 M2P：第二次点击返回中点并退出该模式
+    pub fn pick_mid_between(&mut self, point: Point2) -> Option<Point2> {
+        let midpoint = self.mid_between.as_mut()?.pick(point);
+        if midpoint.is_some() {
+            self.mid_between = None;
+        }
+        midpoint
+    }
+
     /// 获取捕捉引擎的可变引用
     pub fn engine_mut(&mut self) -> &mut SnapEngine {
         &mut self.engine
@@ -135,6 +163,24 @@ impl SnapState {
     pub fn is_snap_type_enabled(&self, snap_type: SnapType) -> bool {
         self.engine.config().enabled_types.is_enabled(snap_type)
     }
+
+    /// 根据按住的修饰键设置临时捕捉覆盖
+    ///
+    /// Alt 仅捕捉端点，Alt+Shift 仅捕捉中点，Alt+Ctrl 仅捕捉圆心；松开 Alt 恢复。
+    pub fn apply_modifier_override(&mut self, modifiers: KeyModifiers) {
+        let snap_type = match (modifiers.alt, modifiers.shift, modifiers.command) {
+            (true, true, false) => Some(SnapType::Midpoint),
+            (true, false, true) => Some(SnapType::Center),
+            (true, false, false) => Some(SnapType::Endpoint),
+            _ => None,
+        };
+        self.engine.set_override(snap_type);
+    }
+
+    /// 当前的临时捕捉覆盖
+    pub fn override_type(&self) -> Option<SnapType> {
+        self.engine.override_type()
+    }
 }
 
 impl Default for SnapState {
@@ -252,6 +298,17 @@ impl Default for EditState {
     }
 }
 
+/// 实体的绘制层次，后绘制的层次显示在上面
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum DrawLayer {
+    /// 普通实体
+    Normal,
+    /// 选中的实体
+    Selected,
+    /// 悬停预高亮的实体
+    Hovered,
+}
+
 /// UI状态
 #[derive(Debug)]
 pub struct UiState {
@@ -264,6 +321,9 @@ pub struct UiState {
     /// 选中的实体
     pub selected_entities: Vec<EntityId>,
 
+    /// 选择模式下光标悬停的实体（预高亮）
+    pub hovered_entity: Option<EntityId>,
+
     /// 鼠标在世界坐标中的位置（原始位置）
     pub mouse_world_pos: Point2,
 
@@ -279,12 +339,29 @@ pub struct UiState {
     /// 是否显示网格
     pub show_grid: bool,
 
-    /// 网格间距
+    /// 显示网格的基准间距，实际间距随缩放调整（见 `display_grid_spacing`），不影响网格捕捉
     pub grid_spacing: f64,
 
+    /// 网格模式（正交/等轴测/旋转），通过 `set_grid_mode` 修改以同步网格捕捉
+    pub grid_mode: GridMode,
+
+    /// 用户坐标系：键入的坐标、十字光标和网格按它解释，图形仍以世界坐标存储
+    ///
+    /// 通过 `set_ucs` 修改以同步网格捕捉和正交/极轴方向
+    pub ucs: Ucs,
+
+    /// 圆/圆弧细分的最大弦高误差（屏幕像素），越小曲线越平滑
+    pub arc_chord_error: f64,
+
+    /// 长度/角度的显示格式（捕捉提示、状态栏等）
+    pub display_format: DisplayFormat,
+
     /// 命令行输入
     pub command_input: String,
 
+    /// 光标旁的动态输入（长度/角度）
+    pub dynamic_input: DynamicInput,
+
     /// 命令历史
     pub command_history: Vec<String>,
 
@@ -300,6 +377,9 @@ pub struct UiState {
     /// 正交模式
     pub ortho_mode: bool,
 
+    /// 平移模式（左键拖动平移视图）
+    pub pan_mode: bool,
+
     /// 待处理的命令（由UI组件生成）
     pub pending_command: Option<Command>,
 
@@ -311,6 +391,21 @@ pub struct UiState {
     
     /// 布局管理器
     pub layout_manager: LayoutManager,
+
+    /// 快捷键映射
+    pub keymap: Keymap,
+
+    /// 模型空间配色主题
+    pub theme: Theme,
+
+    /// 布局空间是否使用白色图纸背景
+    pub white_paper: bool,
+
+    /// 是否在标注和引线文字下方填充背景色遮罩（对应 DIMTFILL）
+    pub dim_text_mask: bool,
+
+    /// 线条抗锯齿（egui 细线羽化）
+    pub antialiasing: bool,
 }
 
 impl UiState {
@@ -324,6 +419,39 @@ impl UiState {
         self.mouse_world_pos
     }
 
+    /// 将目标点按极轴或正交约束到从参考点出发的方向（极轴优先）
+    pub fn constrain_from(&self, reference: Point2, target: Point2) -> Point2 {
+        self.snap_state.engine().constrain_direction(target, reference, self.ortho_mode)
+    }
+
+    /// 正交约束是否实际生效（开启极轴追踪时正交被忽略）
+    pub fn ortho_active(&self) -> bool {
+        self.ortho_mode && !self.snap_state.config().polar_tracking
+    }
+
+    /// UCS 坐标转世界坐标
+    pub fn ucs_to_world(&self, point: Point2) -> Point2 {
+        self.ucs.to_world(point)
+    }
+
+    /// 世界坐标转 UCS 坐标
+    pub fn world_to_ucs(&self, point: Point2) -> Point2 {
+        self.ucs.to_ucs(point)
+    }
+
+    /// 解析键入的点坐标（绝对、`@` 相对或极坐标），按当前 UCS 解释，返回世界坐标
+    pub fn parse_point_input(&self, input: &str, reference: Option<Point2>) -> Result<Point2, ParseError> {
+        let reference = reference.map(|p| self.world_to_ucs(p));
+        InputParser::parse_point(input, reference).map(|p| self.ucs_to_world(p))
+    }
+
+    /// 提交光标旁的动态输入，长度和角度按当前 UCS 解释，返回世界坐标
+    pub fn commit_dynamic_input(&mut self, reference: Point2, cursor: Point2) -> Result<Point2, ParseError> {
+        let (reference, cursor) = (self.world_to_ucs(reference), self.world_to_ucs(cursor));
+        let point = self.dynamic_input.commit(reference, cursor)?;
+        Ok(self.ucs_to_world(point))
+    }
+
     /// 获取当前捕捉点信息
     pub fn current_snap(&self) -> Option<&SnapPoint> {
         if self.snap_state.enabled {
@@ -340,22 +468,34 @@ impl Default for UiState {
             current_tool: DrawingTool::Select,
             edit_state: EditState::Idle,
             selected_entities: Vec::new(),
+            hovered_entity: None,
             mouse_world_pos: Point2::origin(),
             snap_state: SnapState::default(),
             snap_point: None,
             snap_mode: SnapMode::default(),
             show_grid: true,
             grid_spacing: 10.0,
+            grid_mode: GridMode::Rectangular,
+            ucs: Ucs::world(),
+            arc_chord_error: 0.25,
+            display_format: DisplayFormat::default(),
             command_input: String::new(),
+            dynamic_input: DynamicInput::default(),
             command_history: Vec::new(),
             status_message: "Ready".to_string(),
             show_layers_panel: true,
             show_properties_panel: true,
             ortho_mode: false,
+            pan_mode: false,
             pending_command: None,
             last_command: None,
             should_focus_command_line: false,
             layout_manager: LayoutManager::new(),
+            keymap: Keymap::default(),
+            theme: Theme::default(),
+            white_paper: true,
+            dim_text_mask: false,
+            antialiasing: true,
         }
     }
 }
@@ -365,6 +505,7 @@ impl UiState {
     pub fn set_tool(&mut self, tool: DrawingTool) {
         self.current_tool = tool;
         self.edit_state = EditState::Idle;
+        self.dynamic_input.clear();
         self.status_message = match tool {
             DrawingTool::Dimension => "标注工具已选择。指定第一点或 [半径(R)/直径(D)]:".to_string(),
             DrawingTool::DimensionRadius => "半径标注工具已选择。请选择圆或圆弧:".to_string(),
@@ -376,6 +517,8 @@ impl UiState {
     /// 取消当前操作
     pub fn cancel(&mut self) {
         self.edit_state = EditState::Idle;
+        self.dynamic_input.clear();
+        self.snap_state.mid_between = None;
         // 如果当前有工具（非选择工具），则切换回选择工具
         if self.current_tool != DrawingTool::Select {
             self.current_tool = DrawingTool::Select;
@@ -391,6 +534,147 @@ impl UiState {
         }
     }
 
+    /// 动态输入的参考点
+    ///
+    /// 绘制直线或多段线时为已指定的最后一点；绘制圆时为圆心，输入的长度作为半径；
+    /// 绘制矩形时为第一个角点，输入 `宽,高` 得到对角点。
+    pub fn dynamic_input_reference(&self) -> Option<Point2> {
+        match &self.edit_state {
+            EditState::Drawing { tool: DrawingTool::Line | DrawingTool::Polyline, points, .. } => {
+                points.last().copied()
+            }
+            // 圆心确定后，输入的长度即半径
+            EditState::Drawing { tool: DrawingTool::Circle | DrawingTool::Rectangle, points, .. }
+                if points.len() == 1 =>
+            {
+                points.first().copied()
+            }
+            _ => None,
+        }
+    }
+
+    /// 显示网格的实际间距：从基准间距起按 5 倍细分或合并，使屏幕上的线距保持在 20~200 像素
+    pub fn display_grid_spacing(&self, zoom: f64) -> f64 {
+        let mut spacing = self.grid_spacing;
+        if spacing <= 0.0 || zoom <= 0.0 || !zoom.is_finite() {
+            return spacing;
+        }
+        while spacing * zoom < 20.0 {
+            spacing *= 5.0;
+        }
+        while spacing * zoom > 200.0 {
+            spacing /= 5.0;
+        }
+        spacing
+    }
+
+    /// 按显示缩放换算线宽
+    ///
+    /// `width` 为 1 倍缩放（96 DPI）下的像素宽度，返回 egui 逻辑点宽度。
+    /// 结果至少为 1 个物理像素；关闭抗锯齿时物理宽度取整到整像素，
+    /// 避免细线在不同位置时粗时细。
+    pub fn line_width(&self, width: f32, pixels_per_point: f32) -> f32 {
+        if pixels_per_point <= 0.0 || !pixels_per_point.is_finite() {
+            return width;
+        }
+        let physical = width * pixels_per_point;
+        let physical = if self.antialiasing { physical } else { physical.round() };
+        physical.max(1.0) / pixels_per_point
+    }
+
+    /// 网格捕捉步长（世界单位），不随显示网格的缩放调整变化
+    pub fn snap_step(&self) -> f64 {
+        self.snap_state.config().grid_spacing
+    }
+
+    /// 设置网格捕捉步长，非正值被忽略
+    pub fn set_snap_step(&mut self, step: f64) {
+        if step > 0.0 && step.is_finite() {
+            self.snap_state.config_mut().grid_spacing = step;
+        }
+    }
+
+    /// 设置用户坐标系（同时用于网格捕捉和正交/极轴约束）
+    pub fn set_ucs(&mut self, ucs: Ucs) {
+        self.ucs = ucs;
+        self.snap_state.config_mut().ucs = ucs;
+    }
+
+    /// 设置网格模式（同时用于网格显示和网格捕捉）
+    pub fn set_grid_mode(&mut self, mode: GridMode) {
+        self.grid_mode = mode;
+        self.snap_state.config_mut().grid_mode = mode;
+    }
+
+    /// 光标旁的捕捉提示文本
+    ///
+    /// 包含捕捉类型；正在绘制时附带当前线段（上一点到有效点）的长度和角度。
+    /// 既没有捕捉也没有进行中的线段时返回 `None`。
+    pub fn snap_tooltip(&self) -> Option<String> {
+        if !self.snap_state.config().show_tooltips {
+            return None;
+        }
+        let snap_name = self.current_snap().map(|s| s.snap_type.name());
+        let segment = match &self.edit_state {
+            EditState::Drawing { points, .. } => points
+                .last()
+                .map(|&last| self.display_format.segment(last, self.effective_point())),
+            _ => None,
+        };
+
+        match (snap_name, segment) {
+            (Some(name), Some(segment)) => Some(format!("{}  {}", name, segment)),
+            (Some(name), None) => Some(name.to_string()),
+            (None, Some(segment)) => Some(segment),
+            (None, None) => None,
+        }
+    }
+
+    /// 按绘制层次排列实体
+    ///
+    /// 先画普通实体，再画选中的实体，悬停高亮最后画，使选择不会被后绘制的图形遮挡。
+    /// 既选中又悬停的实体按选中绘制；同一层次内保持原有顺序。
+    pub fn draw_order<'a>(&self, entities: impl IntoIterator<Item = &'a Entity>) -> Vec<(&'a Entity, DrawLayer)> {
+        let mut ordered: Vec<(&Entity, DrawLayer)> = entities
+            .into_iter()
+            .map(|entity| {
+                let layer = if self.selected_entities.contains(&entity.id) {
+                    DrawLayer::Selected
+                } else if self.hovered_entity == Some(entity.id) {
+                    DrawLayer::Hovered
+                } else {
+                    DrawLayer::Normal
+                };
+                (entity, layer)
+            })
+            .collect();
+        ordered.sort_by_key(|(_, layer)| *layer);
+        ordered
+    }
+
+    /// 当前空间使用的配色：布局空间开启白色图纸时为图纸主题
+    pub fn active_theme(&self) -> Theme {
+        if self.white_paper && self.is_paper_space() {
+            Theme::from_preset(ThemePreset::Paper)
+        } else {
+            self.theme
+        }
+    }
+
+    /// 追踪基点：绘图时最后确定的点，作为垂足等捕捉的参考点
+    ///
+    /// 多段线、圆弧等连续输入的工具以上一个点为基准，而不是第一个点。
+    /// 移动、复制以基点为基准，旋转、缩放以中心点为基准，镜像以镜像线第一点为基准。
+    pub fn tracking_base(&self) -> Option<Point2> {
+        match &self.edit_state {
+            EditState::Drawing { points, .. } => points.last().copied(),
+            EditState::MoveOp { base_point, .. } | EditState::CopyOp { base_point, .. } => *base_point,
+            EditState::RotateOp { center, .. } | EditState::ScaleOp { center, .. } => *center,
+            EditState::MirrorOp { point1, .. } => *point1,
+            _ => None,
+        }
+    }
+
     /// 清空选择
     pub fn clear_selection(&mut self) {
         self.selected_entities.clear();
@@ -509,6 +793,13 @@ impl UiState {
         // 添加到历史
         self.command_history.push(command.to_string());
 
+        // 两点之间的中点是临时捕捉，不打断当前绘图（需在数据输入判断前处理，"M2P" 含数字）
+        if matches!(trimmed.to_uppercase().as_str(), "M2P" | "MTP") {
+            self.snap_state.start_mid_between();
+            self.status_message = "中点的第一点:".to_string();
+            return None;
+        }
+
         // 检查是否在绘图状态，如果是，优先尝试解析为数据输入
         if let EditState::Drawing { tool, .. } = &self.edit_state {
             // 在标注工具下，R和D是子命令，优先处理
@@ -598,3 +889,222 @@ pub enum Command {
     DataInput(String),
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use zcad_core::geometry::{Geometry, Line};
+    use zcad_core::snap::SnapMask;
+
+    #[test]
+    fn test_active_theme_follows_space() {
+        let mut state = UiState::default();
+        state.theme = Theme::from_preset(ThemePreset::Light);
+        assert_eq!(state.active_theme().preset, ThemePreset::Light);
+
+        state.switch_to_layout("Layout1");
+        assert_eq!(state.active_theme().preset, ThemePreset::Paper);
+        assert_eq!(state.active_theme().grid_line_color(0.0), Theme::from_preset(ThemePreset::Paper).axis);
+
+        state.white_paper = false;
+        assert_eq!(state.active_theme().preset, ThemePreset::Light);
+    }
+
+    #[test]
+    fn test_perpendicular_snap_uses_last_point() {
+        let wall = Entity::new(Geometry::Line(Line::new(Point2::new(-20.0, 0.0), Point2::new(20.0, 0.0))));
+        let entities = vec![&wall];
+        let mut state = UiState::default();
+        state.snap_state.engine_mut().set_override(Some(SnapType::Perpendicular));
+
+        // 画直线时以刚确定的点为基准，垂足落在其正下方
+        state.edit_state = EditState::Drawing {
+            tool: DrawingTool::Line,
+            points: vec![Point2::new(3.0, 5.0)],
+            expected_input: None,
+        };
+        let base = state.tracking_base();
+        assert_eq!(base, Some(Point2::new(3.0, 5.0)));
+        let snap = state
+            .snap_state
+            .engine_mut()
+            .find_snap_point(Point2::new(3.2, 0.1), &entities, 10.0, base)
+            .unwrap();
+        assert_eq!(snap.snap_type, SnapType::Perpendicular);
+        assert!((snap.point - Point2::new(3.0, 0.0)).norm() < 1e-9);
+
+        // 多段线以最后一个顶点而非起点为基准
+        state.edit_state = EditState::Drawing {
+            tool: DrawingTool::Polyline,
+            points: vec![Point2::new(-10.0, 8.0), Point2::new(3.0, 5.0)],
+            expected_input: None,
+        };
+        assert_eq!(state.tracking_base(), Some(Point2::new(3.0, 5.0)));
+
+        state.edit_state = EditState::Idle;
+        assert_eq!(state.tracking_base(), None);
+    }
+
+    #[test]
+    fn test_point_input_in_rotated_ucs() {
+        let mut state = UiState::default();
+        state.set_ucs(Ucs::new(Point2::new(50.0, 20.0), std::f64::consts::FRAC_PI_2));
+
+        // 绝对坐标相对 UCS 原点和轴向
+        let p = state.parse_point_input("10,0", None).unwrap();
+        assert!((p - Point2::new(50.0, 30.0)).norm() < 1e-9);
+
+        // 相对坐标和极坐标的角度从 UCS X 轴量起
+        let base = Point2::new(50.0, 30.0);
+        let p = state.parse_point_input("@0,5", Some(base)).unwrap();
+        assert!((p - Point2::new(45.0, 30.0)).norm() < 1e-9);
+        let p = state.parse_point_input("@10<0", Some(base)).unwrap();
+        assert!((p - Point2::new(50.0, 40.0)).norm() < 1e-9);
+
+        // 动态输入同样按 UCS 解释角度
+        state.dynamic_input.push_char('8');
+        state.dynamic_input.switch_field();
+        state.dynamic_input.push_char('0');
+        let p = state.commit_dynamic_input(base, Point2::new(0.0, 0.0)).unwrap();
+        assert!((p - Point2::new(50.0, 38.0)).norm() < 1e-9);
+
+        // 正交沿 UCS 轴向：UCS 的 X 轴为世界 Y 轴
+        state.ortho_mode = true;
+        let p = state.constrain_from(base, Point2::new(51.0, 40.0));
+        assert!((p - Point2::new(50.0, 40.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_rectangle_size_from_dynamic_input() {
+        let mut state = UiState::default();
+        let corner = Point2::new(10.0, 20.0);
+        state.edit_state = EditState::Drawing {
+            tool: DrawingTool::Rectangle,
+            points: vec![corner],
+            expected_input: None,
+        };
+        let reference = state.dynamic_input_reference().unwrap();
+        assert_eq!(reference, corner);
+
+        // 键入 `宽,高` 得到对角点，与光标方向无关
+        for c in "30,-15".chars() {
+            assert!(state.dynamic_input.push_char(c));
+        }
+        let p = state.commit_dynamic_input(reference, Point2::new(0.0, 100.0)).unwrap();
+        assert!((p - Point2::new(40.0, 5.0)).norm() < 1e-9);
+    }
+
+    #[test]
+    fn test_grid_snap_step_independent_of_display_grid() {
+        let mut state = UiState::default();
+        state.set_snap_step(2.5);
+        let config = state.snap_state.engine_mut().config_mut();
+        config.enabled_types = SnapMask::NONE;
+        config.enabled_types.set(SnapType::Grid, true);
+
+        // 显示网格随缩放细分或合并，捕捉点始终落在 2.5 的整数倍上
+        let mouse = Point2::new(5.1, 4.95);
+        for zoom in [0.05, 1.0, 40.0] {
+            let display = state.display_grid_spacing(zoom);
+            assert!((20.0..=200.0).contains(&(display * zoom)));
+            let snap = state
+                .snap_state
+                .engine_mut()
+                .find_snap_point(mouse, &[], zoom, None)
+                .unwrap();
+            assert_eq!(snap.snap_type, SnapType::Grid);
+            assert!((snap.point - Point2::new(5.0, 5.0)).norm() < 1e-9);
+        }
+        assert_ne!(state.display_grid_spacing(0.05), state.display_grid_spacing(40.0));
+
+        state.set_snap_step(-1.0);
+        assert_eq!(state.snap_step(), 2.5);
+    }
+
+    #[test]
+    fn test_line_width_scales_with_dpi() {
+        let mut state = UiState::default();
+        assert!(state.antialiasing);
+
+        // 逻辑宽度不随缩放变化，物理宽度随之放大
+        assert_eq!(state.line_width(1.5, 1.0), 1.5);
+        assert_eq!(state.line_width(1.5, 2.0), 1.5);
+        // 不细于 1 个物理像素
+        assert_eq!(state.line_width(0.25, 1.0), 1.0);
+        assert_eq!(state.line_width(0.25, 2.0), 0.5);
+        // 非法缩放原样返回
+        assert_eq!(state.line_width(1.5, 0.0), 1.5);
+
+        // 关闭抗锯齿时物理宽度取整
+        state.antialiasing = false;
+        assert_eq!(state.line_width(1.5, 1.0), 2.0);
+        assert_eq!(state.line_width(1.5, 1.25), 1.6);
+        assert_eq!(state.line_width(1.5, 2.0), 1.5);
+    }
+
+    #[test]
+    fn test_perpendicular_snap_during_move_uses_base_point() {
+        let wall = Entity::new(Geometry::Line(Line::new(Point2::new(-20.0, 0.0), Point2::new(20.0, 0.0))));
+        let entities = vec![&wall];
+        let mut state = UiState::default();
+        state.snap_state.engine_mut().set_override(Some(SnapType::Perpendicular));
+
+        // 尚未指定基点时没有参考点，垂足捕捉无效
+        state.edit_state = EditState::MoveOp { entity_ids: Vec::new(), base_point: None };
+        let base = state.tracking_base();
+        assert_eq!(base, None);
+        assert!(state
+            .snap_state
+            .engine_mut()
+            .find_snap_point(Point2::new(7.2, 0.1), &entities, 10.0, base)
+            .is_none());
+
+        // 以基点为参考，目标点捕捉到基点在墙线上的垂足
+        state.edit_state = EditState::MoveOp {
+            entity_ids: Vec::new(),
+            base_point: Some(Point2::new(7.0, 12.0)),
+        };
+        let base = state.tracking_base();
+        let snap = state
+            .snap_state
+            .engine_mut()
+            .find_snap_point(Point2::new(7.2, 0.1), &entities, 10.0, base)
+            .unwrap();
+        assert_eq!(snap.snap_type, SnapType::Perpendicular);
+        assert!((snap.point - Point2::new(7.0, 0.0)).norm() < 1e-9);
+
+        // 旋转以中心点为参考
+        state.edit_state = EditState::RotateOp {
+            entity_ids: Vec::new(),
+            center: Some(Point2::new(1.0, 2.0)),
+            start_angle: None,
+        };
+        assert_eq!(state.tracking_base(), Some(Point2::new(1.0, 2.0)));
+    }
+
+    #[test]
+    fn test_draw_order_puts_selection_on_top() {
+        let entities: Vec<Entity> = (0..4)
+            .map(|i| {
+                let y = i as f64;
+                Entity::new(Geometry::Line(Line::new(Point2::new(0.0, y), Point2::new(10.0, y))))
+            })
+            .collect();
+        let mut state = UiState::default();
+        state.add_to_selection(entities[0].id);
+        state.add_to_selection(entities[2].id);
+        state.hovered_entity = Some(entities[1].id);
+
+        let order: Vec<(EntityId, DrawLayer)> =
+            state.draw_order(&entities).into_iter().map(|(e, layer)| (e.id, layer)).collect();
+        assert_eq!(
+            order,
+            vec![
+                (entities[3].id, DrawLayer::Normal),
+                (entities[0].id, DrawLayer::Selected),
+                (entities[2].id, DrawLayer::Selected),
+                (entities[1].id, DrawLayer::Hovered),
+            ]
+        );
+    }
+}
@@ -0,0 +1,114 @@
+//! 绘图区配色主题
+//!
+//! 背景、网格线和坐标轴的颜色。模型空间使用可切换的深色/浅色主题，
+//! 布局（图纸空间）可使用白色图纸背景。
+
+use serde::{Deserialize, Serialize};
+use zcad_core::properties::Color;
+
+/// 主题预设
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ThemePreset {
+    /// 深色背景
+    Dark,
+    /// 浅色背景
+    Light,
+    /// 白色图纸（布局空间）
+    Paper,
+}
+
+/// 绘图区配色
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Theme {
+    /// 对应的预设
+    pub preset: ThemePreset,
+    /// 背景色
+    pub background: Color,
+    /// 普通网格线颜色
+    pub grid: Color,
+    /// 坐标轴（x = 0 / y = 0）网格线颜色
+    pub axis: Color,
+}
+
+impl Theme {
+    /// 按预设创建主题
+    pub fn from_preset(preset: ThemePreset) -> Self {
+        match preset {
+            ThemePreset::Dark => Self {
+                preset,
+                background: Color::new(30, 30, 46),
+                grid: Color::new(50, 50, 60),
+                axis: Color::new(80, 80, 100),
+            },
+            ThemePreset::Light => Self {
+                preset,
+                background: Color::new(242, 242, 245),
+                grid: Color::new(220, 220, 228),
+                axis: Color::new(170, 170, 190),
+            },
+            ThemePreset::Paper => Self {
+                preset,
+                background: Color::WHITE,
+                grid: Color::new(232, 232, 232),
+                axis: Color::new(190, 190, 190),
+            },
+        }
+    }
+
+    /// 背景是否为浅色
+    pub fn is_light(&self) -> bool {
+        let Color { r, g, b, .. } = self.background;
+        (r as u32 * 299 + g as u32 * 587 + b as u32 * 114) / 1000 > 128
+    }
+
+    /// 位于 `coordinate` 处的网格线颜色：坐标轴上的线用坐标轴颜色
+    pub fn grid_line_color(&self, coordinate: f64) -> Color {
+        if coordinate.abs() < 0.001 {
+            self.axis
+        } else {
+            self.grid
+        }
+    }
+
+    /// 实体在此背景上的显示颜色
+    ///
+    /// 与 AutoCAD 一致，白色（ACI 7）在浅色背景上显示为黑色，避免看不见。
+    pub fn display_color(&self, color: Color) -> Color {
+        if self.is_light() && color == Color::WHITE {
+            Color::BLACK
+        } else {
+            color
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::from_preset(ThemePreset::Dark)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grid_colors_follow_theme() {
+        let dark = Theme::from_preset(ThemePreset::Dark);
+        let light = Theme::from_preset(ThemePreset::Light);
+        assert_eq!(dark.grid_line_color(0.0), dark.axis);
+        assert_eq!(dark.grid_line_color(50.0), dark.grid);
+        assert_eq!(light.grid_line_color(0.0), light.axis);
+        assert_eq!(light.grid_line_color(-50.0), light.grid);
+        assert_ne!(dark.grid, light.grid);
+        assert_ne!(dark.axis, light.axis);
+    }
+
+    #[test]
+    fn test_white_entities_visible_on_light_background() {
+        assert!(!Theme::from_preset(ThemePreset::Dark).is_light());
+        assert_eq!(Theme::from_preset(ThemePreset::Dark).display_color(Color::WHITE), Color::WHITE);
+        assert_eq!(Theme::from_preset(ThemePreset::Paper).display_color(Color::WHITE), Color::BLACK);
+        assert_eq!(Theme::from_preset(ThemePreset::Light).display_color(Color::RED), Color::RED);
+    }
+}